@@ -2,8 +2,8 @@ use std::collections::HashMap;
 use std::io::{Read, Seek};
 
 use crate::model::{
-    Alignment, Block, CellBorder, CellBorders, CellMargins, Paragraph, Run, Table, TableCell,
-    TableRow,
+    Alignment, Block, CellBorder, CellBorders, CellMargins, Paragraph, PreferredWidth, Run, Table,
+    TableCell, TableRow, UnderlineStyle,
 };
 
 use super::parse_hex_color;
@@ -471,7 +471,11 @@ fn collect_runs(node: roxmltree::Node, ctx: &RunContext, runs: &mut Vec<Run>) {
                     font_name: ctx.font_name.to_string(),
                     bold: ctx.bold,
                     italic: ctx.italic,
-                    underline: ctx.underline,
+                    underline: if ctx.underline {
+                        UnderlineStyle::Single
+                    } else {
+                        UnderlineStyle::None
+                    },
                     color: ctx.color,
                     ..Run::default()
                 });
@@ -651,6 +655,7 @@ fn convert_table(
                 right: td_css.border_right.unwrap_or_default(),
                 bottom: td_css.border_bottom.unwrap_or_default(),
                 left: td_css.border_left.unwrap_or_default(),
+                ..CellBorders::default()
             };
 
             let v_align = match td_css.vertical_align.as_deref() {
@@ -688,7 +693,7 @@ fn convert_table(
             }
 
             cells.push(TableCell {
-                width: w_pt,
+                width: PreferredWidth::Points(w_pt),
                 paragraphs: cell_paras,
                 borders,
                 shading: None,
@@ -697,6 +702,7 @@ fn convert_table(
                 v_align,
                 text_direction: crate::model::TextDirection::default(),
                 cell_margins: None,
+                nested_tables: Vec::new(),
             });
         }
 
@@ -705,11 +711,15 @@ fn convert_table(
             height: None,
             height_exact: false,
             is_header: false,
+            cant_split: false,
+            cell_spacing: 0.0,
         });
     }
 
     Some(Table {
         col_widths,
+        width: PreferredWidth::Auto,
+        autofit: false,
         rows,
         table_indent: 0.0,
         cell_margins: CellMargins::default(),
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use crate::model::{Block, BookmarkLocation, Document, FieldCode, Paragraph, Section};
+
+use super::{WML_NS, paragraphs_mut};
+
+/// Word's hidden "last edit position" bookmark, never a cross-reference target.
+const GO_BACK: &str = "_GoBack";
+
+fn is_wml(node: &roxmltree::Node, name: &str) -> bool {
+    node.tag_name().name() == name && node.tag_name().namespace() == Some(WML_NS)
+}
+
+/// Name of a `w:bookmarkStart`, skipping `_GoBack`.
+pub(super) fn bookmark_start_name<'a>(node: roxmltree::Node<'a, '_>) -> Option<&'a str> {
+    if !is_wml(&node, "bookmarkStart") {
+        return None;
+    }
+    node.attribute((WML_NS, "name"))
+        .filter(|&name| name != GO_BACK)
+}
+
+/// Names of the bookmarks that start inside a paragraph.
+pub(super) fn paragraph_bookmarks(para_node: roxmltree::Node) -> Vec<String> {
+    para_node
+        .descendants()
+        .filter_map(bookmark_start_name)
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Block each body bookmark starts in. The first bookmark of a name wins, as in Word.
+pub(super) fn locate_bookmarks(sections: &[Section]) -> HashMap<String, BookmarkLocation> {
+    let mut locations = HashMap::new();
+    for (si, section) in sections.iter().enumerate() {
+        for (bi, block) in section.blocks.iter().enumerate() {
+            let paragraphs: Box<dyn Iterator<Item = &Paragraph>> = match block {
+                Block::Paragraph(p) => Box::new(std::iter::once(p)),
+                Block::Table(t) => t.paragraphs(),
+            };
+            let location = BookmarkLocation {
+                section: si,
+                block: bi,
+            };
+            for name in paragraphs.flat_map(|p| p.bookmarks.iter()) {
+                locations.entry(name.clone()).or_insert(location);
+            }
+        }
+    }
+    locations
+}
+
+/// Text enclosed by each bookmark of the body, in document order. Bookmarks may
+/// nest or overlap, so text is appended to every bookmark open at that point.
+pub(super) fn collect_bookmark_text(body: roxmltree::Node) -> HashMap<String, String> {
+    let mut open: Vec<(&str, &str)> = Vec::new();
+    let mut text: HashMap<String, String> = HashMap::new();
+    for node in body.descendants() {
+        if node.tag_name().namespace() != Some(WML_NS) {
+            continue;
+        }
+        match node.tag_name().name() {
+            "bookmarkStart" => {
+                if let Some(id) = node.attribute((WML_NS, "id"))
+                    && let Some(name) = bookmark_start_name(node)
+                {
+                    open.push((id, name));
+                    text.entry(name.to_string()).or_default();
+                }
+            }
+            "bookmarkEnd" => {
+                if let Some(id) = node.attribute((WML_NS, "id")) {
+                    open.retain(|&(open_id, _)| open_id != id);
+                }
+            }
+            "t" => {
+                if let Some(t) = node.text() {
+                    for (_, name) in &open {
+                        if let Some(s) = text.get_mut(*name) {
+                            s.push_str(t);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Replace cached REF results with the current text of their bookmark.
+/// References to missing bookmarks keep Word's cached result.
+pub(super) fn resolve_ref_fields(doc: &mut Document, bookmark_text: &HashMap<String, String>) {
+    if bookmark_text.is_empty() {
+        return;
+    }
+    for section in &mut doc.sections {
+        resolve_in_blocks(&mut section.blocks, bookmark_text);
+        let props = &mut section.properties;
+        for hf in [
+            &mut props.header_default,
+            &mut props.header_first,
+            &mut props.header_even,
+            &mut props.footer_default,
+            &mut props.footer_first,
+            &mut props.footer_even,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve_in_blocks(&mut hf.blocks, bookmark_text);
+        }
+    }
+    for footnote in doc.footnotes.values_mut() {
+        for para in &mut footnote.paragraphs {
+            resolve_in_paragraph(para, bookmark_text);
+        }
+    }
+}
+
+fn resolve_in_blocks(blocks: &mut [Block], bookmark_text: &HashMap<String, String>) {
+    for para in paragraphs_mut(blocks) {
+        resolve_in_paragraph(para, bookmark_text);
+    }
+}
+
+fn resolve_in_paragraph(para: &mut Paragraph, bookmark_text: &HashMap<String, String>) {
+    for run in &mut para.runs {
+        if let Some(FieldCode::Ref(ref name)) = run.field_code
+            && let Some(text) = bookmark_text.get(name)
+        {
+            run.text = text.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::docx::testing;
+    use crate::model::BookmarkLocation;
+
+    #[test]
+    fn test_bookmarks_are_located_by_section_and_block() {
+        let mark = |id: u32, name: &str| {
+            format!(r#"<w:bookmarkStart w:id="{id}" w:name="{name}"/><w:bookmarkEnd w:id="{id}"/>"#)
+        };
+        let body = format!(
+            concat!(
+                "<w:p><w:r><w:t>Intro</w:t></w:r></w:p>",
+                "<w:p><w:pPr><w:sectPr/></w:pPr>{}<w:r><w:t>Goals</w:t></w:r></w:p>",
+                "{}<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Cell</w:t></w:r></w:p></w:tc></w:tr></w:tbl>",
+                "<w:p>{}<w:r><w:t>Again</w:t></w:r></w:p>",
+            ),
+            mark(1, "goals"),
+            mark(2, "figures"),
+            mark(3, "goals"),
+        );
+        let doc = testing::parse_body(&body);
+
+        let at = |section, block| Some(BookmarkLocation { section, block });
+        // A bookmark opened before a table starts in it; a repeated name keeps
+        // its first location
+        assert_eq!(doc.bookmarks.get("goals").copied(), at(0, 1));
+        assert_eq!(doc.bookmarks.get("figures").copied(), at(1, 0));
+        assert_eq!(doc.bookmarks.len(), 2);
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::model::Comment;
+
+use super::{WML_NS, read_zip_text};
+
+/// Reads `word/comments.xml`: author, date and plain text of each review
+/// comment, one line per paragraph.
+pub(super) fn parse_comments<R: Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+) -> HashMap<u32, Comment> {
+    let mut comments = HashMap::new();
+    let Some(xml_text) = read_zip_text(zip, "word/comments.xml") else {
+        return comments;
+    };
+    let Ok(doc) = roxmltree::Document::parse(&xml_text) else {
+        return comments;
+    };
+    let is_wml = |n: roxmltree::Node, name: &str| {
+        n.tag_name().namespace() == Some(WML_NS) && n.tag_name().name() == name
+    };
+
+    for node in doc
+        .root_element()
+        .children()
+        .filter(|n| is_wml(*n, "comment"))
+    {
+        let Some(id) = node
+            .attribute((WML_NS, "id"))
+            .and_then(|v| v.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let text = node
+            .children()
+            .filter(|p| is_wml(*p, "p"))
+            .map(|p| {
+                p.descendants()
+                    .filter(|t| is_wml(*t, "t"))
+                    .filter_map(|t| t.text())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let attr = |name: &str| {
+            node.attribute((WML_NS, name))
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        };
+        comments.insert(
+            id,
+            Comment {
+                author: attr("author"),
+                date: attr("date"),
+                text,
+            },
+        );
+    }
+    comments
+}
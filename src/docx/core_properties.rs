@@ -0,0 +1,40 @@
+use std::io::Read;
+
+use crate::model::CoreProperties;
+
+use super::read_zip_text;
+
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+const DCTERMS_NS: &str = "http://purl.org/dc/terms/";
+const CP_NS: &str = "http://schemas.openxmlformats.org/package/2006/metadata/core-properties";
+
+/// Reads `docProps/core.xml` (Dublin Core metadata). Missing or empty entries stay `None`.
+pub(super) fn parse_core_properties<R: Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+) -> CoreProperties {
+    let Some(xml_text) = read_zip_text(zip, "docProps/core.xml") else {
+        return CoreProperties::default();
+    };
+    let Ok(doc) = roxmltree::Document::parse(&xml_text) else {
+        return CoreProperties::default();
+    };
+    let root = doc.root_element();
+    let text = |ns: &str, name: &str| {
+        root.children()
+            .find(|n| n.tag_name().namespace() == Some(ns) && n.tag_name().name() == name)
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+
+    CoreProperties {
+        title: text(DC_NS, "title"),
+        subject: text(DC_NS, "subject"),
+        creator: text(DC_NS, "creator"),
+        keywords: text(CP_NS, "keywords"),
+        last_modified_by: text(CP_NS, "lastModifiedBy"),
+        created: text(DCTERMS_NS, "created"),
+        modified: text(DCTERMS_NS, "modified"),
+    }
+}
@@ -4,6 +4,7 @@ use std::io::{Read, Seek};
 use crate::model::{FontFamily, FontTable, FontTableEntry};
 
 use super::relationships::parse_part_relationships;
+use super::woff::{woff_to_sfnt, woff2_to_sfnt};
 use super::{REL_NS, WML_NS, read_zip_text, wml};
 
 const EMBED_VARIANTS: &[(&str, bool, bool)] = &[
@@ -42,6 +43,20 @@ fn deobfuscate_font(data: &mut [u8], key: &[u8; 16]) {
     }
 }
 
+/// Font data usable by the font registrar: WOFF and WOFF2 are unpacked to
+/// SFNT, anything else is passed through.
+fn sfnt_font_data(data: Vec<u8>, font_name: &str) -> Option<Vec<u8>> {
+    let (format, sfnt) = match data.get(..4) {
+        Some(b"wOFF") => ("WOFF", woff_to_sfnt(&data)),
+        Some(b"wOF2") => ("WOFF2", woff2_to_sfnt(&data)),
+        _ => return Some(data),
+    };
+    if sfnt.is_none() {
+        log::warn!("Embedded font {font_name}: malformed or unsupported {format} data");
+    }
+    sfnt
+}
+
 struct EmbedInfo {
     font_name: String,
     bold: bool,
@@ -159,6 +174,9 @@ pub(super) fn parse_font_table<R: Read + Seek>(zip: &mut zip::ZipArchive<R>) ->
         {
             deobfuscate_font(&mut data, &key);
         }
+        let Some(data) = sfnt_font_data(data, &info.font_name) else {
+            continue;
+        };
 
         log::info!(
             "Extracted embedded font: {} bold={} italic={} ({} bytes)",
@@ -178,3 +196,25 @@ pub(super) fn parse_font_table<R: Read + Seek>(zip: &mut zip::ZipArchive<R>) ->
         font_table,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sfnt_font_data_unpacks_web_fonts() {
+        let woff2 = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/fonts/SourceCodePro-It.ttf.woff2"
+        ))
+        .unwrap();
+        let sfnt = sfnt_font_data(woff2, "Source Code Pro").expect("WOFF2 font data");
+        assert_eq!(sfnt[..4], [0, 1, 0, 0]);
+        assert_eq!(sfnt_font_data(b"wOF2rest".to_vec(), "Test"), None);
+        assert_eq!(sfnt_font_data(b"wOFFrest".to_vec(), "Test"), None);
+        assert_eq!(
+            sfnt_font_data(vec![0, 1, 0, 0, 9], "Test"),
+            Some(vec![0, 1, 0, 0, 9])
+        );
+    }
+}
@@ -0,0 +1,841 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::ConversionOptions;
+use crate::model::{Document, FieldCode};
+
+use super::numbering::format_number;
+use super::{for_each_paragraph, paragraphs_mut};
+
+fn field_keyword(instr: &str) -> &str {
+    instr.split_whitespace().next().unwrap_or("")
+}
+
+/// Fields whose cached result is replaced by a value computed at conversion time.
+pub(super) fn is_dynamic_field(instr: &str) -> bool {
+    let keyword = field_keyword(instr);
+    [
+        "PAGE",
+        "NUMPAGES",
+        "SECTION",
+        "SECTIONPAGES",
+        "STYLEREF",
+        "DATE",
+        "TIME",
+        "FILENAME",
+        "AUTHOR",
+        "TITLE",
+        "SUBJECT",
+        "KEYWORDS",
+        "LASTSAVEDBY",
+        "REF",
+        "PAGEREF",
+        "SEQ",
+        "SYMBOL",
+        "DOCVARIABLE",
+        "MERGEFIELD",
+    ]
+    .iter()
+    .any(|k| keyword.eq_ignore_ascii_case(k))
+}
+
+pub(super) fn parse_field_code(instr: &str) -> Option<FieldCode> {
+    let keyword = field_keyword(instr);
+    if keyword.eq_ignore_ascii_case("PAGE") {
+        Some(FieldCode::Page)
+    } else if keyword.eq_ignore_ascii_case("NUMPAGES") {
+        Some(FieldCode::NumPages)
+    } else if keyword.eq_ignore_ascii_case("SECTION") {
+        Some(FieldCode::Section)
+    } else if keyword.eq_ignore_ascii_case("SECTIONPAGES") {
+        Some(FieldCode::SectionPages)
+    } else if keyword.eq_ignore_ascii_case("STYLEREF") {
+        first_argument(instr).map(|style_name| FieldCode::StyleRef {
+            style_name,
+            last_on_page: has_switch(instr, 'l'),
+        })
+    } else if keyword.eq_ignore_ascii_case("DATE") {
+        Some(FieldCode::Date(
+            format_switch(instr).unwrap_or_else(|| "M/d/yyyy".to_string()),
+        ))
+    } else if keyword.eq_ignore_ascii_case("TIME") {
+        Some(FieldCode::Time(
+            format_switch(instr).unwrap_or_else(|| "h:mm am/pm".to_string()),
+        ))
+    } else if keyword.eq_ignore_ascii_case("FILENAME") {
+        Some(FieldCode::FileName {
+            full_path: has_switch(instr, 'p'),
+        })
+    } else if keyword.eq_ignore_ascii_case("AUTHOR") {
+        Some(FieldCode::Author)
+    } else if keyword.eq_ignore_ascii_case("TITLE") {
+        Some(FieldCode::Title)
+    } else if keyword.eq_ignore_ascii_case("SUBJECT") {
+        Some(FieldCode::Subject)
+    } else if keyword.eq_ignore_ascii_case("KEYWORDS") {
+        Some(FieldCode::Keywords)
+    } else if keyword.eq_ignore_ascii_case("LASTSAVEDBY") {
+        Some(FieldCode::LastSavedBy)
+    } else if keyword.eq_ignore_ascii_case("REF") {
+        first_argument(instr).map(FieldCode::Ref)
+    } else if keyword.eq_ignore_ascii_case("PAGEREF") {
+        first_argument(instr).map(FieldCode::PageRef)
+    } else if keyword.eq_ignore_ascii_case("DOCVARIABLE") {
+        first_argument(instr).map(FieldCode::DocVariable)
+    } else if keyword.eq_ignore_ascii_case("MERGEFIELD") {
+        first_argument(instr).map(FieldCode::MergeField)
+    } else if keyword.eq_ignore_ascii_case("SEQ") {
+        parse_seq_field(instr)
+    } else if keyword.eq_ignore_ascii_case("TOC") {
+        Some(parse_toc_field(instr))
+    } else if keyword.eq_ignore_ascii_case("SYMBOL") {
+        parse_symbol_field(instr)
+    } else {
+        None
+    }
+}
+
+/// Target URL of a `HYPERLINK "url" [\l "anchor"]` field. Anchor-only links
+/// target a bookmark within the document and come back as "#anchor".
+pub(super) fn parse_hyperlink_field(instr: &str) -> Option<String> {
+    let keyword = field_keyword(instr);
+    if !keyword.eq_ignore_ascii_case("HYPERLINK") {
+        return None;
+    }
+    let args = field_arguments(&instr.trim_start()[keyword.len()..]);
+    let mut url = None;
+    let mut anchor = None;
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg.eq_ignore_ascii_case("\\l") {
+            anchor = iter.next();
+        } else if arg.starts_with('\\') {
+            // \o tooltip, \t target frame, ... carry a value; \m and \n do not
+            if matches!(arg.to_ascii_lowercase().as_str(), "\\o" | "\\t") {
+                iter.next();
+            }
+        } else if url.is_none() {
+            url = Some(arg);
+        }
+    }
+    match (url.filter(|u| !u.is_empty()), anchor) {
+        (Some(url), Some(anchor)) => Some(format!("{url}#{anchor}")),
+        (Some(url), None) => Some(url),
+        (None, Some(anchor)) => Some(format!("#{anchor}")),
+        (None, None) => None,
+    }
+}
+
+/// First argument that isn't a switch: the bookmark of REF/PAGEREF, the style of
+/// STYLEREF, the variable of DOCVARIABLE/MERGEFIELD.
+fn first_argument(instr: &str) -> Option<String> {
+    let keyword = field_keyword(instr);
+    field_arguments(&instr.trim_start()[keyword.len()..])
+        .into_iter()
+        .find(|arg| !arg.is_empty() && !arg.starts_with('\\'))
+}
+
+fn parse_seq_field(instr: &str) -> Option<FieldCode> {
+    let keyword = field_keyword(instr);
+    let args = field_arguments(&instr.trim_start()[keyword.len()..]);
+    let mut identifier = None;
+    let mut restart = None;
+    let mut repeat = false;
+    let mut hidden = false;
+    let mut num_fmt = "decimal";
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.to_ascii_lowercase().as_str() {
+            "\\r" => restart = iter.next().and_then(|v| v.parse().ok()),
+            "\\c" => repeat = true,
+            "\\h" => hidden = true,
+            "\\s" => {
+                iter.next();
+            }
+            "\\*" => {
+                if let Some(fmt) = iter.next() {
+                    num_fmt = general_number_format(fmt);
+                }
+            }
+            _ if arg.starts_with('\\') => {}
+            _ if identifier.is_none() => identifier = Some(arg.clone()),
+            _ => {}
+        }
+    }
+    Some(FieldCode::Seq {
+        identifier: identifier?,
+        restart,
+        repeat,
+        hidden,
+        num_fmt: num_fmt.to_string(),
+    })
+}
+
+/// `SYMBOL 61623 \f "Symbol"`: a decimal or `0x` hex character code and an
+/// optional font. `\s` (size) and `\h` (don't affect line spacing) are ignored.
+fn parse_symbol_field(instr: &str) -> Option<FieldCode> {
+    let keyword = field_keyword(instr);
+    let args = field_arguments(&instr.trim_start()[keyword.len()..]);
+    let mut char_code = None;
+    let mut font = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.to_ascii_lowercase().as_str() {
+            "\\f" => font = iter.next().filter(|f| !f.is_empty()).cloned(),
+            "\\s" => {
+                iter.next();
+            }
+            _ if arg.starts_with('\\') => {}
+            _ if char_code.is_none() => {
+                char_code = match arg.strip_prefix("0x").or_else(|| arg.strip_prefix("0X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => arg.parse().ok(),
+                };
+            }
+            _ => {}
+        }
+    }
+    Some(FieldCode::Symbol {
+        char_code: char_code?,
+        font,
+    })
+}
+
+/// Heading range of a TOC field's `\o "1-3"` switch; all levels without one.
+fn parse_toc_field(instr: &str) -> FieldCode {
+    let keyword = field_keyword(instr);
+    let args = field_arguments(&instr.trim_start()[keyword.len()..]);
+    let range = args
+        .iter()
+        .position(|a| a.eq_ignore_ascii_case("\\o"))
+        .and_then(|i| args.get(i + 1))
+        .and_then(|r| {
+            let (lo, hi) = r.split_once('-')?;
+            Some((lo.trim().parse::<u8>().ok()?, hi.trim().parse::<u8>().ok()?))
+        });
+    let (min_level, max_level) = range.unwrap_or((1, 9));
+    FieldCode::Toc {
+        min_level: min_level.clamp(1, 9),
+        max_level: max_level.clamp(1, 9),
+    }
+}
+
+/// Maps a `\*` general formatting switch to its `w:numFmt` equivalent. The case of
+/// the switch picks the case of roman numerals and letters.
+fn general_number_format(switch: &str) -> &'static str {
+    let upper = switch.starts_with(|c: char| c.is_ascii_uppercase());
+    if switch.eq_ignore_ascii_case("roman") {
+        if upper { "upperRoman" } else { "lowerRoman" }
+    } else if switch.eq_ignore_ascii_case("alphabetic") {
+        if upper { "upperLetter" } else { "lowerLetter" }
+    } else {
+        "decimal"
+    }
+}
+
+/// Numbers SEQ fields in document order, keeping one counter per identifier.
+pub(super) fn resolve_seq_fields(doc: &mut Document) {
+    let mut counters: HashMap<String, u32> = HashMap::new();
+    for para in doc
+        .sections
+        .iter_mut()
+        .flat_map(|s| paragraphs_mut(&mut s.blocks))
+    {
+        for run in &mut para.runs {
+            if let Some(FieldCode::Seq {
+                identifier,
+                restart,
+                repeat,
+                hidden,
+                num_fmt,
+            }) = &run.field_code
+            {
+                let counter = counters.entry(identifier.clone()).or_insert(0);
+                if let Some(n) = restart {
+                    *counter = *n;
+                } else if !*repeat {
+                    *counter += 1;
+                }
+                run.text = if *hidden {
+                    String::new()
+                } else {
+                    format_number(*counter, num_fmt)
+                };
+            }
+        }
+    }
+}
+
+/// Fills DOCVARIABLE fields from the document's variables and MERGEFIELD fields
+/// from the caller's values, matching names case-insensitively as Word does.
+/// Unknown variables keep Word's cached result; unmapped merge fields show
+/// their name in chevrons, as in Word's merge preview.
+pub(super) fn resolve_variable_fields(
+    doc: &mut Document,
+    doc_vars: &HashMap<String, String>,
+    merge_fields: &HashMap<String, String>,
+) {
+    let lookup = |values: &HashMap<String, String>, name: &str| {
+        values.get(name).cloned().or_else(|| {
+            values
+                .iter()
+                .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+                .min_by_key(|(key, _)| key.as_str())
+                .map(|(_, value)| value.clone())
+        })
+    };
+    for_each_paragraph(doc, &mut |para| {
+        for run in &mut para.runs {
+            match &run.field_code {
+                Some(FieldCode::DocVariable(name)) => {
+                    if let Some(value) = lookup(doc_vars, name) {
+                        run.text = value;
+                    }
+                }
+                Some(FieldCode::MergeField(name)) => {
+                    run.text = lookup(merge_fields, name).unwrap_or_else(|| format!("«{name}»"));
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Fills AUTHOR, TITLE, SUBJECT, KEYWORDS and LASTSAVEDBY fields from the
+/// core properties wherever they appear; a missing property leaves the field
+/// empty, as in Word.
+pub(super) fn resolve_property_fields(doc: &mut Document) {
+    let props = doc.core_properties.clone();
+    for_each_paragraph(doc, &mut |para| {
+        for run in &mut para.runs {
+            if let Some(
+                code @ (FieldCode::Author
+                | FieldCode::Title
+                | FieldCode::Subject
+                | FieldCode::Keywords
+                | FieldCode::LastSavedBy),
+            ) = &run.field_code
+            {
+                run.text = props.field_value(code).unwrap_or_default().to_string();
+            }
+        }
+    });
+}
+
+/// Fills FILENAME fields from the path the document was read from. Documents
+/// read from bytes have no path and keep Word's cached result.
+pub(super) fn resolve_file_name_fields(doc: &mut Document, path: &Path) {
+    let full = path.display().to_string();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    for_each_paragraph(doc, &mut |para| {
+        for run in &mut para.runs {
+            if let Some(FieldCode::FileName { full_path }) = &run.field_code {
+                run.text = if *full_path {
+                    full.clone()
+                } else {
+                    name.clone()
+                };
+            }
+        }
+    });
+}
+
+/// Splits field arguments on whitespace, keeping double-quoted strings together.
+fn field_arguments(args: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut chars = args.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            out.push(chars.by_ref().take_while(|&ch| ch != '"').collect());
+        } else {
+            let mut word = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() {
+                    break;
+                }
+                word.push(ch);
+                chars.next();
+            }
+            out.push(word);
+        }
+    }
+    out
+}
+
+/// Whether a general switch like `\p` is present (case-insensitive).
+fn has_switch(instr: &str, switch: char) -> bool {
+    instr.split_whitespace().any(|word| {
+        let mut chars = word.chars();
+        chars.next() == Some('\\')
+            && chars
+                .next()
+                .is_some_and(|c| c.eq_ignore_ascii_case(&switch))
+            && chars.next().is_none()
+    })
+}
+
+/// Fills DATE and TIME fields with the current time at the caller's UTC offset.
+/// Deterministic output instead shows when the document was last saved (or
+/// created), so converting the same file always gives the same text.
+pub(super) fn resolve_date_fields(doc: &mut Document, options: &ConversionOptions) {
+    let fixed = options.creation_date.as_deref().and_then(w3cdtf_epoch_secs);
+    let epoch_secs = if let Some(secs) = fixed {
+        secs
+    } else if options.deterministic {
+        let props = &doc.core_properties;
+        [props.modified.as_deref(), props.created.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(w3cdtf_epoch_secs)
+            .unwrap_or(0)
+    } else {
+        now_epoch_secs()
+    };
+    let local_secs = epoch_secs + i64::from(options.utc_offset_minutes) * 60;
+    for_each_paragraph(doc, &mut |para| {
+        for run in &mut para.runs {
+            if let Some(FieldCode::Date(format) | FieldCode::Time(format)) = &run.field_code {
+                run.text = format_date_time(format, local_secs);
+            }
+        }
+    });
+}
+
+/// Value of the `\@` date-time picture switch, quoted or bare.
+fn format_switch(instr: &str) -> Option<String> {
+    let rest = instr[instr.find("\\@")? + 2..].trim_start();
+    if let Some(quoted) = rest.strip_prefix('"') {
+        Some(quoted[..quoted.find('"')?].to_string())
+    } else {
+        rest.split_whitespace().next().map(String::from)
+    }
+}
+
+fn now_epoch_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Seconds since 1970 of a `YYYY-MM-DDThh:mm:ss` timestamp, read as UTC.
+fn w3cdtf_epoch_secs(s: &str) -> Option<i64> {
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, min, sec) = (
+        num(11..13).unwrap_or(0),
+        num(14..16).unwrap_or(0),
+        num(17..19).unwrap_or(0),
+    );
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3600 + min * 60 + sec)
+}
+
+/// (year, month 1-12, day 1-31) → days since 1970-01-01; inverse of `civil_from_days`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Days since 1970-01-01 → (year, month 1-12, day 1-31), proleptic Gregorian.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats a UTC timestamp with Word's date-time picture tokens
+/// (d/dd/ddd/dddd, M/MM/MMM/MMMM, yy/yyyy, h/hh/H/HH, m/mm, s/ss, am/pm, 'literal').
+fn format_date_time(format: &str, epoch_secs: i64) -> String {
+    let days = epoch_secs.div_euclid(86_400);
+    let secs_of_day = epoch_secs.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    let weekday = WEEKDAYS[(days + 4).rem_euclid(7) as usize];
+    let month_name = MONTHS[month as usize - 1];
+    let hour = (secs_of_day / 3600) as u32;
+    let minute = (secs_of_day / 60 % 60) as u32;
+    let second = (secs_of_day % 60) as u32;
+    let hour12 = match hour % 12 {
+        0 => 12,
+        h => h,
+    };
+
+    let chars: Vec<char> = format.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&ch| ch == '\'')
+                .map_or(chars.len(), |p| i + 1 + p);
+            out.extend(&chars[i + 1..end]);
+            i = end + 1;
+            continue;
+        }
+        let rest: String = chars[i..].iter().take(5).collect();
+        if rest.eq_ignore_ascii_case("am/pm") {
+            let upper = rest.starts_with('A');
+            out.push_str(match (hour < 12, upper) {
+                (true, true) => "AM",
+                (false, true) => "PM",
+                (true, false) => "am",
+                (false, false) => "pm",
+            });
+            i += 5;
+            continue;
+        }
+        let run = chars[i..].iter().take_while(|&&ch| ch == c).count();
+        match c {
+            'd' => match run {
+                1 => out.push_str(&day.to_string()),
+                2 => out.push_str(&format!("{day:02}")),
+                3 => out.push_str(&weekday[..3]),
+                _ => out.push_str(weekday),
+            },
+            'M' => match run {
+                1 => out.push_str(&month.to_string()),
+                2 => out.push_str(&format!("{month:02}")),
+                3 => out.push_str(&month_name[..3]),
+                _ => out.push_str(month_name),
+            },
+            'y' | 'Y' => match run {
+                1 | 2 => out.push_str(&format!("{:02}", year.rem_euclid(100))),
+                _ => out.push_str(&year.to_string()),
+            },
+            'h' => match run {
+                1 => out.push_str(&hour12.to_string()),
+                _ => out.push_str(&format!("{hour12:02}")),
+            },
+            'H' => match run {
+                1 => out.push_str(&hour.to_string()),
+                _ => out.push_str(&format!("{hour:02}")),
+            },
+            'm' => match run {
+                1 => out.push_str(&minute.to_string()),
+                _ => out.push_str(&format!("{minute:02}")),
+            },
+            's' | 'S' => match run {
+                1 => out.push_str(&second.to_string()),
+                _ => out.push_str(&format!("{second:02}")),
+            },
+            _ => out.extend(std::iter::repeat_n(c, run)),
+        }
+        i += run;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::testing::{self, field};
+
+    // 2024-02-29 13:05:09 UTC, a Thursday
+    const LEAP_DAY: i64 = 1_709_211_909;
+
+    #[test]
+    fn test_styleref_field() {
+        assert_eq!(
+            parse_field_code(r#" STYLEREF "Heading 1" \* MERGEFORMAT "#),
+            Some(FieldCode::StyleRef {
+                style_name: "Heading 1".into(),
+                last_on_page: false,
+            })
+        );
+        assert_eq!(
+            parse_field_code(r"STYLEREF \l Heading2"),
+            Some(FieldCode::StyleRef {
+                style_name: "Heading2".into(),
+                last_on_page: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_toc_field() {
+        assert_eq!(
+            parse_field_code(r#" TOC \o "1-3" \h \z \u "#),
+            Some(FieldCode::Toc {
+                min_level: 1,
+                max_level: 3,
+            })
+        );
+        assert_eq!(
+            parse_field_code("TOC"),
+            Some(FieldCode::Toc {
+                min_level: 1,
+                max_level: 9,
+            })
+        );
+    }
+
+    #[test]
+    fn test_symbol_field() {
+        assert_eq!(
+            parse_field_code(r#" SYMBOL 252 \f "Wingdings" \s 12 "#),
+            Some(FieldCode::Symbol {
+                char_code: 252,
+                font: Some("Wingdings".into()),
+            })
+        );
+        assert_eq!(
+            parse_field_code("SYMBOL 0xB7 \\u"),
+            Some(FieldCode::Symbol {
+                char_code: 0xB7,
+                font: None,
+            })
+        );
+        assert_eq!(parse_field_code("SYMBOL"), None);
+    }
+
+    #[test]
+    fn test_seq_field() {
+        assert_eq!(
+            parse_field_code(" SEQ Figure \\* ARABIC "),
+            Some(FieldCode::Seq {
+                identifier: "Figure".into(),
+                restart: None,
+                repeat: false,
+                hidden: false,
+                num_fmt: "decimal".into(),
+            })
+        );
+        assert_eq!(
+            parse_field_code("SEQ Table \\r 5 \\* roman"),
+            Some(FieldCode::Seq {
+                identifier: "Table".into(),
+                restart: Some(5),
+                repeat: false,
+                hidden: false,
+                num_fmt: "lowerRoman".into(),
+            })
+        );
+        assert!(matches!(
+            parse_field_code("SEQ Figure \\c"),
+            Some(FieldCode::Seq { repeat: true, .. })
+        ));
+        assert!(matches!(
+            parse_field_code("SEQ Figure \\h"),
+            Some(FieldCode::Seq { hidden: true, .. })
+        ));
+        assert_eq!(parse_field_code("SEQ \\h"), None);
+    }
+
+    #[test]
+    fn test_hidden_seq_advances_the_counter() {
+        let paragraph = [
+            field(" SEQ Figure ", "9"),
+            field(" SEQ Figure \\h ", "9"),
+            field(" SEQ Figure ", "9"),
+        ]
+        .concat();
+        let doc = testing::parse_body(&format!("<w:p>{paragraph}</w:p>"));
+        assert_eq!(testing::first_paragraph_texts(&doc), ["1", "", "3"]);
+    }
+
+    #[test]
+    fn test_date_formats() {
+        assert_eq!(format_date_time("M/d/yyyy", LEAP_DAY), "2/29/2024");
+        assert_eq!(
+            format_date_time("MMMM d, yyyy", LEAP_DAY),
+            "February 29, 2024"
+        );
+        assert_eq!(
+            format_date_time("dddd, dd MMM yy", LEAP_DAY),
+            "Thursday, 29 Feb 24"
+        );
+        assert_eq!(format_date_time("yyyy-MM-dd", 0), "1970-01-01");
+    }
+
+    #[test]
+    fn test_w3cdtf_timestamp() {
+        assert_eq!(w3cdtf_epoch_secs("2024-02-29T13:05:09Z"), Some(LEAP_DAY));
+        assert_eq!(w3cdtf_epoch_secs("1970-01-01"), Some(0));
+        assert_eq!(w3cdtf_epoch_secs("not a date"), None);
+    }
+
+    #[test]
+    fn test_time_formats() {
+        assert_eq!(format_date_time("h:mm am/pm", LEAP_DAY), "1:05 pm");
+        assert_eq!(format_date_time("HH:mm:ss", LEAP_DAY), "13:05:09");
+        assert_eq!(
+            format_date_time("hh 'o''clock' AM/PM", LEAP_DAY),
+            "01 oclock PM"
+        );
+    }
+
+    #[test]
+    fn test_format_switch() {
+        assert_eq!(
+            format_switch(r#" DATE \@ "MMMM d, yyyy" "#).as_deref(),
+            Some("MMMM d, yyyy")
+        );
+        assert_eq!(format_switch(r" TIME \@ HH:mm ").as_deref(), Some("HH:mm"));
+        assert_eq!(format_switch(" DATE "), None);
+    }
+
+    #[test]
+    fn test_hyperlink_field() {
+        assert_eq!(
+            parse_hyperlink_field(r#" HYPERLINK "https://example.com/a b" \o "tip" "#).as_deref(),
+            Some("https://example.com/a b")
+        );
+        assert_eq!(
+            parse_hyperlink_field(r#"HYPERLINK https://example.com \l "part2""#).as_deref(),
+            Some("https://example.com#part2")
+        );
+        assert_eq!(
+            parse_hyperlink_field(r#" HYPERLINK \l "_Toc1" "#).as_deref(),
+            Some("#_Toc1")
+        );
+        assert_eq!(parse_hyperlink_field(" HYPERLINK "), None);
+    }
+
+    /// Run texts of a one-paragraph document with the given settings.xml body.
+    fn parsed_run_texts(
+        paragraph: &str,
+        settings: &str,
+        options: &crate::ConversionOptions,
+    ) -> Vec<String> {
+        let settings = testing::part("w:settings", settings);
+        let doc = testing::parse_with(
+            &format!("<w:p>{paragraph}</w:p>"),
+            &[("word/settings.xml", settings.as_bytes())],
+            options,
+        );
+        testing::first_paragraph_texts(&doc)
+    }
+
+    #[test]
+    fn test_variable_fields_are_filled() {
+        let options = crate::ConversionOptions {
+            merge_fields: HashMap::from([("first name".to_string(), "Kari".to_string())]),
+            ..crate::ConversionOptions::default()
+        };
+        let paragraph = [
+            field(" DOCVARIABLE Client ", "old client"),
+            field(r#" MERGEFIELD "First Name" \* MERGEFORMAT "#, "old name"),
+            field(" MERGEFIELD City ", "old city"),
+        ]
+        .concat();
+        let settings = r#"<w:docVars><w:docVar w:name="client" w:val="Acme AS"/></w:docVars>"#;
+        assert_eq!(
+            parsed_run_texts(&paragraph, settings, &options),
+            ["Acme AS", "Kari", "«City»"]
+        );
+    }
+
+    #[test]
+    fn test_date_fields_show_time_at_the_utc_offset() {
+        let options = crate::ConversionOptions {
+            creation_date: Some("2024-02-29T23:30:00Z".to_string()),
+            utc_offset_minutes: 90,
+            ..crate::ConversionOptions::default()
+        };
+        let paragraph = [
+            field(r#" DATE \@ "yyyy-MM-dd" "#, "old date"),
+            field(r#" TIME \@ "HH:mm" "#, "old time"),
+        ]
+        .concat();
+        assert_eq!(
+            parsed_run_texts(&paragraph, "", &options),
+            ["2024-03-01", "01:00"]
+        );
+    }
+
+    #[test]
+    fn test_unevaluated_fields_keep_cached_result() {
+        let paragraph = [
+            field(r#" QUOTE "Ship it" "#, "Ship it"),
+            field(" REF ", "See above"),
+        ]
+        .concat();
+        let texts = parsed_run_texts(&paragraph, "", &crate::ConversionOptions::default());
+        assert_eq!(texts, ["Ship it", "See above"]);
+    }
+
+    #[test]
+    fn test_property_fields_resolve_in_body_and_tables() {
+        let core = r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:creator>Ada Lovelace</dc:creator><dc:title>Notes on the Engine</dc:title></cp:coreProperties>"#;
+        let body = format!(
+            "<w:p>{}{}</w:p><w:tbl><w:tr><w:tc><w:p>{}</w:p></w:tc></w:tr></w:tbl>",
+            field(" AUTHOR ", "old author"),
+            field(" SUBJECT ", "old subject"),
+            field(r" TITLE \* MERGEFORMAT ", "old title"),
+        );
+        let doc = testing::parse_with(
+            &body,
+            &[("docProps/core.xml", core.as_bytes())],
+            &crate::ConversionOptions::default(),
+        );
+        assert_eq!(testing::first_paragraph_texts(&doc), ["Ada Lovelace", ""]);
+        let crate::model::Block::Table(table) = &doc.sections[0].blocks[1] else {
+            panic!("expected a table");
+        };
+        let cell = &table.rows[0].cells[0].paragraphs[0];
+        assert_eq!(cell.runs[0].text, "Notes on the Engine");
+    }
+
+    #[test]
+    fn test_file_name_field_resolves_from_the_source_path() {
+        let body = format!(
+            "<w:p>{}{}</w:p>",
+            field(" FILENAME ", "old.docx"),
+            field(r" FILENAME \p ", "C:\\old.docx"),
+        );
+        let document = testing::part("w:document", &format!("<w:body>{body}</w:body>"));
+        let package = testing::package(&[("word/document.xml", document.as_bytes())]);
+        let path = std::env::temp_dir().join(format!("filename-field-{}.docx", std::process::id()));
+        std::fs::write(&path, package).unwrap();
+        let doc = crate::docx::parse(&path, &crate::ConversionOptions::default());
+        std::fs::remove_file(&path).unwrap();
+
+        let texts = testing::first_paragraph_texts(&doc.unwrap());
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert_eq!(texts, [name.as_ref(), path.to_str().unwrap()]);
+    }
+}
@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 use std::io::Read;
 
-use crate::model::{Alignment, Block, Footnote, HeaderFooter, LineSpacing, Paragraph};
+use crate::model::{
+    Alignment, Block, Footnote, HeaderFooter, LineSpacing, NoteProperties, Paragraph, Section,
+};
 
 use super::numbering::NumberingInfo;
 use super::parse_table_node;
 use super::runs::parse_runs;
-use super::styles::{ParagraphStyle, StylesInfo, ThemeFonts, parse_alignment};
+use super::styles::{
+    ParagraphStyle, StylesInfo, ThemeFonts, bidi_alignment, parse_alignment, resolve_bidi,
+};
+use super::watermark::parse_watermark;
 use super::{
-    WML_NS, parse_paragraph_borders, parse_paragraph_spacing, parse_tab_stops, wml, wml_attr,
+    WML_NS, paragraphs_mut, parse_paragraph_borders, parse_paragraph_spacing, parse_tab_stops, wml,
+    wml_attr,
 };
 
 fn is_wml_element(node: roxmltree::Node, name: &str) -> bool {
@@ -23,10 +29,12 @@ fn resolve_alignment(
     ppr: Option<roxmltree::Node>,
     para_style: Option<&ParagraphStyle>,
 ) -> Alignment {
-    ppr.and_then(|ppr| wml_attr(ppr, "jc"))
+    let alignment = ppr
+        .and_then(|ppr| wml_attr(ppr, "jc"))
         .map(parse_alignment)
         .or_else(|| para_style.and_then(|s| s.alignment))
-        .unwrap_or(Alignment::Left)
+        .unwrap_or(Alignment::Left);
+    bidi_alignment(alignment, resolve_bidi(ppr, para_style))
 }
 
 pub(super) fn parse_header_footer_xml<R: Read + std::io::Seek>(
@@ -81,12 +89,14 @@ pub(super) fn parse_header_footer_xml<R: Read + std::io::Seek>(
                 let para_style = styles.paragraph_styles.get(para_style_id);
 
                 let alignment = resolve_alignment(ppr, para_style);
+                let rtl = resolve_bidi(ppr, para_style);
                 let (sp_before, sp_after, line_spacing) = parse_paragraph_spacing(ppr, para_style);
                 let parsed = parse_runs(node, styles, theme, rels, zip, &numbering);
 
                 blocks.push(Block::Paragraph(Paragraph {
                     runs: parsed.runs,
                     alignment,
+                    rtl,
                     line_spacing,
                     space_before: sp_before.unwrap_or(0.0),
                     space_after: sp_after.unwrap_or(0.0),
@@ -101,16 +111,50 @@ pub(super) fn parse_header_footer_xml<R: Read + std::io::Seek>(
         }
     }
 
-    (!blocks.is_empty()).then(|| HeaderFooter { blocks })
+    let watermark = parse_watermark(root, rels, zip);
+    (!blocks.is_empty() || watermark.is_some()).then(|| HeaderFooter { blocks, watermark })
 }
 
 pub(super) fn parse_footnotes<R: Read + std::io::Seek>(
     zip: &mut zip::ZipArchive<R>,
     styles: &StylesInfo,
     theme: &ThemeFonts,
+) -> HashMap<u32, Footnote> {
+    parse_notes(
+        zip,
+        styles,
+        theme,
+        "word/footnotes.xml",
+        "footnote",
+        "FootnoteText",
+    )
+}
+
+pub(super) fn parse_endnotes<R: Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    styles: &StylesInfo,
+    theme: &ThemeFonts,
+) -> HashMap<u32, Footnote> {
+    parse_notes(
+        zip,
+        styles,
+        theme,
+        "word/endnotes.xml",
+        "endnote",
+        "EndnoteText",
+    )
+}
+
+fn parse_notes<R: Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    styles: &StylesInfo,
+    theme: &ThemeFonts,
+    part: &str,
+    element: &str,
+    default_style: &str,
 ) -> HashMap<u32, Footnote> {
     let mut footnotes = HashMap::new();
-    let Some(xml_text) = super::read_zip_text(zip, "word/footnotes.xml") else {
+    let Some(xml_text) = super::read_zip_text(zip, part) else {
         return footnotes;
     };
     let Ok(xml) = roxmltree::Document::parse(&xml_text) else {
@@ -121,7 +165,7 @@ pub(super) fn parse_footnotes<R: Read + std::io::Seek>(
     let numbering = NumberingInfo::default();
 
     for node in root.children() {
-        if !is_wml_element(node, "footnote") {
+        if !is_wml_element(node, element) {
             continue;
         }
         // Skip separator/continuationSeparator footnotes (type attribute, IDs 0 and 1)
@@ -140,10 +184,11 @@ pub(super) fn parse_footnotes<R: Read + std::io::Seek>(
             let ppr = wml(p, "pPr");
             let para_style_id = ppr
                 .and_then(|ppr| wml_attr(ppr, "pStyle"))
-                .unwrap_or("FootnoteText");
+                .unwrap_or(default_style);
             let para_style = styles.paragraph_styles.get(para_style_id);
 
             let alignment = resolve_alignment(ppr, para_style);
+            let rtl = resolve_bidi(ppr, para_style);
             let parsed = parse_runs(p, styles, theme, &empty_rels, zip, &numbering);
             let (sp_before, sp_after, ls) = parse_paragraph_spacing(ppr, para_style);
 
@@ -152,6 +197,7 @@ pub(super) fn parse_footnotes<R: Read + std::io::Seek>(
                 space_before: sp_before.unwrap_or(0.0),
                 space_after: sp_after.unwrap_or(0.0),
                 alignment,
+                rtl,
                 line_spacing: ls.or(Some(LineSpacing::Auto(1.0))),
                 ..Paragraph::default()
             });
@@ -164,3 +210,86 @@ pub(super) fn parse_footnotes<R: Read + std::io::Seek>(
 
     footnotes
 }
+
+/// Numbers endnote references in document order, in the `w:endnotePr` format
+/// (lower-roman by default), and writes each number into the note's own reference mark.
+pub(super) fn number_endnotes(
+    sections: &mut [Section],
+    endnotes: &mut HashMap<u32, Footnote>,
+    props: NoteProperties,
+) {
+    if endnotes.is_empty() {
+        return;
+    }
+    let mut numbers: HashMap<u32, String> = HashMap::new();
+    for para in sections
+        .iter_mut()
+        .flat_map(|s| paragraphs_mut(&mut s.blocks))
+    {
+        for run in &mut para.runs {
+            if let Some(id) = run.endnote_id {
+                let next = props.num_start + numbers.len() as u32;
+                run.text = numbers
+                    .entry(id)
+                    .or_insert_with(|| props.num_fmt.format(next))
+                    .clone();
+            }
+        }
+    }
+    for (id, note) in endnotes.iter_mut() {
+        let Some(number) = numbers.get(id) else {
+            continue;
+        };
+        for run in note
+            .paragraphs
+            .iter_mut()
+            .flat_map(|p| p.runs.iter_mut())
+            .filter(|r| r.is_footnote_ref_mark)
+        {
+            run.text = number.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConversionOptions;
+    use crate::docx::testing;
+
+    fn endnote(id: u32, text: &str) -> String {
+        format!(
+            r#"<w:endnote w:id="{id}"><w:p><w:r><w:endnoteRef/></w:r><w:r><w:t xml:space="preserve"> {text}</w:t></w:r></w:p></w:endnote>"#
+        )
+    }
+
+    #[test]
+    fn test_endnotes_are_numbered_in_reference_order() {
+        let endnotes = testing::part(
+            "w:endnotes",
+            &format!(
+                r#"<w:endnote w:type="separator" w:id="-1"><w:p><w:r><w:separator/></w:r></w:p></w:endnote>{}{}"#,
+                endnote(1, "Second"),
+                endnote(2, "First")
+            ),
+        );
+        let body = r#"<w:p><w:r><w:t>A</w:t></w:r><w:r><w:endnoteReference w:id="2"/></w:r></w:p>
+            <w:p><w:r><w:t>B</w:t></w:r><w:r><w:endnoteReference w:id="1"/></w:r></w:p>"#;
+        let doc = testing::parse_with(
+            body,
+            &[("word/endnotes.xml", endnotes.as_bytes())],
+            &ConversionOptions::default(),
+        );
+
+        let marks: Vec<&str> = testing::paragraphs(&doc)
+            .iter()
+            .flat_map(|p| &p.runs)
+            .filter(|r| r.endnote_id.is_some())
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(marks, ["i", "ii"]);
+        assert_eq!(doc.endnotes.len(), 2);
+        let own_mark = |id: u32| doc.endnotes[&id].paragraphs[0].runs[0].text.clone();
+        assert_eq!(own_mark(2), "i");
+        assert_eq!(own_mark(1), "ii");
+    }
+}
@@ -2,8 +2,8 @@ use std::collections::HashMap;
 use std::io::Read;
 
 use crate::model::{
-    EmbeddedImage, FloatingImage, HRelativeFrom, HorizontalPosition, ImageFormat, InlineChart,
-    SmartArtDiagram, VRelativeFrom, VerticalPosition, WrapType,
+    EmbeddedImage, FloatingImage, HRelativeFrom, HorizontalPosition, ImageCrop, ImageFormat,
+    InlineChart, SmartArtDiagram, VRelativeFrom, VerticalPosition, WrapSide, WrapType,
 };
 
 use super::charts::parse_chart_from_zip;
@@ -72,9 +72,112 @@ pub(super) fn image_dimensions(data: &[u8]) -> Option<(u32, u32, ImageFormat, u8
         return Some((width, height, ImageFormat::Png, 3));
     }
 
+    // GIF87a/GIF89a: logical screen width/height follow the 6-byte signature
+    if data.len() >= 10 && (data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) {
+        let width = u16::from_le_bytes([data[6], data[7]]) as u32;
+        let height = u16::from_le_bytes([data[8], data[9]]) as u32;
+        return Some((width, height, ImageFormat::Gif, 3));
+    }
+
+    if data.len() >= 18 && data.starts_with(b"BM") {
+        return parse_bmp_dimensions(data);
+    }
+
+    if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        return parse_tiff_dimensions(data);
+    }
+
+    // EMF: EMR_HEADER record (type 1) with " EMF" signature. rclFrame at
+    // 24..40 is the picture's size in 0.01 mm, taken as pixels at 96 DPI;
+    // rclBounds before it only covers what was drawn, in device pixels.
+    if data.len() >= 44 && data[0..4] == [1, 0, 0, 0] && &data[40..44] == b" EMF" {
+        let rect = |i: usize| i32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        let to_px = |hundredths_mm: u32| (hundredths_mm as f32 * 96.0 / 2540.0).round() as u32;
+        let width = to_px((rect(32) - rect(24)).unsigned_abs()).max(1);
+        let height = to_px((rect(36) - rect(28)).unsigned_abs()).max(1);
+        return Some((width, height, ImageFormat::Emf, 3));
+    }
+
+    // Placeable WMF: bounding box (left, top, right, bottom) at 6..14
+    if data.len() >= 22 && data[0..4] == [0xD7, 0xCD, 0xC6, 0x9A] {
+        let coord = |i: usize| i16::from_le_bytes([data[i], data[i + 1]]) as i32;
+        let width = (coord(10) - coord(6)).unsigned_abs().max(1);
+        let height = (coord(12) - coord(8)).unsigned_abs().max(1);
+        return Some((width, height, ImageFormat::Wmf, 3));
+    }
+
+    // Non-placeable WMF carries no bounds (zero here); the caller sizes it
+    // from the drawing extent
+    if data.len() >= 4 && matches!(data[0..4], [1 | 2, 0, 9, 0]) {
+        return Some((0, 0, ImageFormat::Wmf, 3));
+    }
+
     None
 }
 
+/// Reads the size from the DIB header after the 14-byte file header. OS/2
+/// BITMAPCOREHEADER (12 bytes) stores u16 width/height at 18/20; the
+/// BITMAPINFOHEADER family stores i32s at 18/22, a negative height meaning
+/// top-down rows.
+fn parse_bmp_dimensions(data: &[u8]) -> Option<(u32, u32, ImageFormat, u8)> {
+    let header_size = u32::from_le_bytes(data.get(14..18)?.try_into().ok()?);
+    let (width, height) = if header_size == 12 {
+        let dims = data.get(18..22)?;
+        (
+            u16::from_le_bytes([dims[0], dims[1]]) as u32,
+            u16::from_le_bytes([dims[2], dims[3]]) as u32,
+        )
+    } else {
+        let dims = data.get(18..26)?;
+        (
+            i32::from_le_bytes([dims[0], dims[1], dims[2], dims[3]]).unsigned_abs(),
+            i32::from_le_bytes([dims[4], dims[5], dims[6], dims[7]]).unsigned_abs(),
+        )
+    };
+    Some((width, height, ImageFormat::Bmp, 3))
+}
+
+/// Reads ImageWidth (256) / ImageLength (257) from the first IFD.
+fn parse_tiff_dimensions(data: &[u8]) -> Option<(u32, u32, ImageFormat, u8)> {
+    let le = data[0] == b'I';
+    let u16_at = |i: usize| -> Option<u16> {
+        let b = [*data.get(i)?, *data.get(i + 1)?];
+        Some(if le { u16::from_le_bytes(b) } else { u16::from_be_bytes(b) })
+    };
+    let u32_at = |i: usize| -> Option<u32> {
+        let b = [
+            *data.get(i)?,
+            *data.get(i + 1)?,
+            *data.get(i + 2)?,
+            *data.get(i + 3)?,
+        ];
+        Some(if le { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) })
+    };
+
+    let ifd = u32_at(4)? as usize;
+    let count = u16_at(ifd)? as usize;
+    let mut width = None;
+    let mut height = None;
+    for i in 0..count {
+        let entry = ifd + 2 + i * 12;
+        let tag = u16_at(entry)?;
+        if tag != 256 && tag != 257 {
+            continue;
+        }
+        // Field type 3 = SHORT, 4 = LONG; value stored inline
+        let value = match u16_at(entry + 2)? {
+            3 => u16_at(entry + 8)? as u32,
+            _ => u32_at(entry + 8)?,
+        };
+        if tag == 256 {
+            width = Some(value);
+        } else {
+            height = Some(value);
+        }
+    }
+    Some((width?, height?, ImageFormat::Tiff, 3))
+}
+
 fn parse_jpeg_dimensions(data: &[u8]) -> Option<(u32, u32, ImageFormat, u8)> {
     let mut i = 2;
     while i + 4 < data.len() {
@@ -97,14 +200,111 @@ fn parse_jpeg_dimensions(data: &[u8]) -> Option<(u32, u32, ImageFormat, u8)> {
     None
 }
 
-pub(super) fn read_image_from_zip<R: Read + std::io::Seek>(
-    embed_id: &str,
+/// Returns the APP14 "Adobe" segment's color transform flag (0 = CMYK/RGB, 1 = YCbCr, 2 = YCCK).
+fn jpeg_adobe_transform(data: &[u8]) -> Option<u8> {
+    let mut i = 2;
+    while i + 4 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        // Stop at start of scan / end of image: APP segments precede them
+        if matches!(marker, 0xD9 | 0xDA) {
+            break;
+        }
+        let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        if marker == 0xEE && len >= 12 && data.get(i + 4..i + 9) == Some(b"Adobe".as_slice()) {
+            return data.get(i + 15).copied();
+        }
+        i += 2 + len;
+    }
+    None
+}
+
+/// Reads the picture referenced by the container's `a:blip`, applying `a:srcRect` cropping.
+fn read_blip_image<R: Read + std::io::Seek>(
+    container: roxmltree::Node,
     rels: &HashMap<String, String>,
     zip: &mut zip::ZipArchive<R>,
     display_w: f32,
     display_h: f32,
+    layout_extra_height: f32,
 ) -> Option<EmbeddedImage> {
-    read_image_from_zip_extra(embed_id, rels, zip, display_w, display_h, 0.0)
+    let embed_id = find_blip_embed(container)?;
+    let mut img = read_image_from_zip_extra(
+        embed_id,
+        rels,
+        zip,
+        display_w,
+        display_h,
+        layout_extra_height,
+    )?;
+    img.crop = parse_src_rect(container);
+    img.rotation = parse_xfrm_rotation(container);
+    img.alt_text = parse_alt_text(container);
+    img.hyperlink_url = parse_image_link(container, rels);
+    if img.rotation != 0.0 {
+        // Rotated pictures occupy their rotated bounding box; effectExtent usually
+        // covers this already, so only grow the reserved height when it falls short.
+        let (sin, cos) = img.rotation.to_radians().sin_cos();
+        let rotated_h = (display_w * sin).abs() + (display_h * cos).abs();
+        img.layout_extra_height = img.layout_extra_height.max(rotated_h - display_h);
+    }
+    Some(img)
+}
+
+fn parse_alt_text(container: roxmltree::Node) -> Option<String> {
+    let doc_pr = wpd(container, "docPr")?;
+    [doc_pr.attribute("descr"), doc_pr.attribute("title")]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Target of the picture's `a:hlinkClick`, on `wp:docPr` or `pic:cNvPr`.
+fn parse_image_link(container: roxmltree::Node, rels: &HashMap<String, String>) -> Option<String> {
+    container
+        .descendants()
+        .find(|n| n.tag_name().name() == "hlinkClick" && n.tag_name().namespace() == Some(DML_NS))
+        .and_then(|n| n.attribute((REL_NS, "id")))
+        .and_then(|rid| rels.get(rid))
+        .cloned()
+}
+
+/// `a:xfrm@rot` is in 60000ths of a degree, clockwise.
+fn parse_xfrm_rotation(container: roxmltree::Node) -> f32 {
+    container
+        .descendants()
+        .find(|n| n.tag_name().name() == "xfrm" && n.tag_name().namespace() == Some(DML_NS))
+        .and_then(|n| n.attribute("rot"))
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|rot| (rot / 60000.0) % 360.0)
+        .unwrap_or(0.0)
+}
+
+/// `a:srcRect` insets are in 1000ths of a percent of the source image.
+fn parse_src_rect(container: roxmltree::Node) -> ImageCrop {
+    let Some(src_rect) = container
+        .descendants()
+        .find(|n| n.tag_name().name() == "srcRect" && n.tag_name().namespace() == Some(DML_NS))
+    else {
+        return ImageCrop::default();
+    };
+    let frac = |attr: &str| {
+        src_rect
+            .attribute(attr)
+            .and_then(|v| v.parse::<f32>().ok())
+            .map(|v| v / 100_000.0)
+            .unwrap_or(0.0)
+    };
+    ImageCrop {
+        left: frac("l"),
+        top: frac("t"),
+        right: frac("r"),
+        bottom: frac("b"),
+    }
 }
 
 pub(super) fn read_image_from_zip_extra<R: Read + std::io::Seek>(
@@ -123,7 +323,16 @@ pub(super) fn read_image_from_zip_extra<R: Read + std::io::Seek>(
     let mut entry = zip.by_name(&zip_path).ok()?;
     let mut data = Vec::new();
     entry.read_to_end(&mut data).ok()?;
-    let (pw, ph, fmt, components) = image_dimensions(&data)?;
+    let (mut pw, mut ph, fmt, components) = image_dimensions(&data)?;
+    if pw == 0 || ph == 0 {
+        // A metafile without bounds of its own, at 96 pixels per inch
+        let px = |pts: f32| ((pts * 96.0 / 72.0).round() as u32).max(1);
+        (pw, ph) = (px(display_w), px(display_h));
+    }
+    // Adobe writes 4-component JPEGs inverted regardless of the transform flag;
+    // the flag itself (CMYK vs YCCK) is honored by the DCTDecode filter.
+    let jpeg_adobe_inverted =
+        fmt == ImageFormat::Jpeg && components == 4 && jpeg_adobe_transform(&data).is_some();
     Some(EmbeddedImage {
         data: std::sync::Arc::new(data),
         format: fmt,
@@ -132,7 +341,12 @@ pub(super) fn read_image_from_zip_extra<R: Read + std::io::Seek>(
         display_width: display_w,
         display_height: display_h,
         jpeg_components: components,
+        jpeg_adobe_inverted,
         layout_extra_height,
+        crop: ImageCrop::default(),
+        rotation: 0.0,
+        alt_text: None,
+        hyperlink_url: None,
     })
 }
 
@@ -214,6 +428,23 @@ pub(super) fn parse_wrap_type(container: roxmltree::Node) -> WrapType {
     WrapType::None
 }
 
+/// Sides of a square, tight or through wrap that text flows on.
+fn parse_wrap_side(container: roxmltree::Node) -> WrapSide {
+    let wrap = container.children().find(|n| {
+        n.tag_name().namespace() == Some(WPD_NS)
+            && matches!(
+                n.tag_name().name(),
+                "wrapSquare" | "wrapTight" | "wrapThrough"
+            )
+    });
+    match wrap.and_then(|n| n.attribute("wrapText")) {
+        Some("left") => WrapSide::Left,
+        Some("right") => WrapSide::Right,
+        Some("largest") => WrapSide::Largest,
+        _ => WrapSide::BothSides,
+    }
+}
+
 pub(super) enum RunDrawingResult {
     Inline(EmbeddedImage),
     Floating(FloatingImage),
@@ -282,33 +513,36 @@ pub(super) fn parse_run_drawing<R: Read + std::io::Seek>(
             if let Some(conn) = parse_connector_from_wsp(container, theme) {
                 return Some(RunDrawingResult::Connector(conn));
             }
-            if let Some(embed_id) = find_blip_embed(container) {
-                if let Some(img) = read_image_from_zip(embed_id, rels, zip, display_w, display_h) {
-                    let (h_position, h_relative, v_position, v_relative) =
-                        parse_anchor_position(container);
-                    let wrap_type = parse_wrap_type(container);
-                    let behind_doc = container.attribute("behindDoc") == Some("1");
-                    return Some(RunDrawingResult::Floating(FloatingImage {
-                        image: img,
-                        h_position,
-                        h_relative_from: h_relative,
-                        v_position,
-                        v_relative_from: v_relative,
-                        wrap_type,
-                        behind_doc,
-                    }));
-                }
+            if let Some(img) = read_blip_image(container, rels, zip, display_w, display_h, 0.0) {
+                let (h_position, h_relative, v_position, v_relative) =
+                    parse_anchor_position(container);
+                let wrap_type = parse_wrap_type(container);
+                let behind_doc = container.attribute("behindDoc") == Some("1");
+                return Some(RunDrawingResult::Floating(FloatingImage {
+                    image: img,
+                    h_position,
+                    h_relative_from: h_relative,
+                    v_position,
+                    v_relative_from: v_relative,
+                    wrap_type,
+                    wrap_side: parse_wrap_side(container),
+                    dist_top: emu_attr(container, "distT"),
+                    dist_bottom: emu_attr(container, "distB"),
+                    dist_left: emu_attr(container, "distL"),
+                    dist_right: emu_attr(container, "distR"),
+                    behind_doc,
+                    z_order: container
+                        .attribute("relativeHeight")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(0),
+                }));
             }
             continue;
         }
 
-        if let Some(embed_id) = find_blip_embed(container) {
-            let extra_h = inline_extra_height(container);
-            if let Some(img) =
-                read_image_from_zip_extra(embed_id, rels, zip, display_w, display_h, extra_h)
-            {
-                return Some(RunDrawingResult::Inline(img));
-            }
+        let extra_h = inline_extra_height(container);
+        if let Some(img) = read_blip_image(container, rels, zip, display_w, display_h, extra_h) {
+            return Some(RunDrawingResult::Inline(img));
         }
 
         if let Some(chart_rid) = find_chart_ref(container) {
@@ -374,10 +608,7 @@ pub(super) fn compute_drawing_info<R: Read + std::io::Seek>(
             max_height = max_height.max(display_h + extra_h);
 
             if image.is_none() {
-                if let Some(embed_id) = find_blip_embed(container) {
-                    image =
-                        read_image_from_zip_extra(embed_id, rels, zip, display_w, display_h, extra_h);
-                }
+                image = read_blip_image(container, rels, zip, display_w, display_h, extra_h);
             }
         }
     }
@@ -387,3 +618,157 @@ pub(super) fn compute_drawing_info<R: Read + std::io::Seek>(
         floating_images: Vec::new(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::testing;
+
+    /// A BMP file header followed by the first `dib` bytes of a DIB header.
+    fn bmp(dib: &[u8]) -> Vec<u8> {
+        let mut data = b"BM".to_vec();
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(dib);
+        data
+    }
+
+    fn encoded(image: image::DynamicImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut data = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut data), format)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn gif_dimensions_come_from_the_logical_screen() {
+        let gif = encoded(image::DynamicImage::new_rgb8(3, 2), image::ImageFormat::Gif);
+        assert_eq!(image_dimensions(&gif), Some((3, 2, ImageFormat::Gif, 3)));
+    }
+
+    #[test]
+    fn tiff_dimensions_come_from_the_first_ifd() {
+        let tiff = encoded(
+            image::DynamicImage::new_luma8(5, 7),
+            image::ImageFormat::Tiff,
+        );
+        assert_eq!(image_dimensions(&tiff), Some((5, 7, ImageFormat::Tiff, 3)));
+    }
+
+    #[test]
+    fn src_rect_insets_are_fractions_of_the_source() {
+        let xml = format!(
+            r#"<pic xmlns:a="{DML_NS}"><a:blip/><a:srcRect l="25000" t="12500" r="10000"/></pic>"#
+        );
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        assert_eq!(
+            parse_src_rect(doc.root_element()),
+            ImageCrop {
+                left: 0.25,
+                top: 0.125,
+                right: 0.1,
+                bottom: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn xfrm_rotation_is_in_degrees_clockwise() {
+        let rotation = |rot: &str| {
+            let xml = format!(r#"<pic xmlns:a="{DML_NS}"><a:xfrm rot="{rot}"/></pic>"#);
+            parse_xfrm_rotation(roxmltree::Document::parse(&xml).unwrap().root_element())
+        };
+        assert_eq!(rotation("5400000"), 90.0);
+        assert_eq!(rotation("22200000"), 10.0);
+        assert_eq!(rotation("junk"), 0.0);
+    }
+
+    #[test]
+    fn bmp_core_header_dimensions() {
+        // BITMAPCOREHEADER: size 12, u16 width 300, u16 height 200
+        let data = bmp(&[12, 0, 0, 0, 0x2C, 0x01, 0xC8, 0x00, 1, 0, 24, 0]);
+        assert_eq!(
+            image_dimensions(&data),
+            Some((300, 200, ImageFormat::Bmp, 3))
+        );
+    }
+
+    #[test]
+    fn bmp_info_header_dimensions() {
+        // BITMAPINFOHEADER: size 40, i32 width 640, i32 height -480 (top-down)
+        let mut dib = vec![40, 0, 0, 0];
+        dib.extend_from_slice(&640i32.to_le_bytes());
+        dib.extend_from_slice(&(-480i32).to_le_bytes());
+        let data = bmp(&dib);
+        assert_eq!(
+            image_dimensions(&data),
+            Some((640, 480, ImageFormat::Bmp, 3))
+        );
+    }
+
+    /// Markers of a 3x2 baseline JPEG with `components`, optionally with an
+    /// Adobe APP14 segment carrying `transform`. No scan data.
+    fn jpeg_headers(components: u8, adobe_transform: Option<u8>) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        if let Some(transform) = adobe_transform {
+            data.extend_from_slice(&[0xFF, 0xEE, 0, 14]);
+            data.extend_from_slice(b"Adobe");
+            data.extend_from_slice(&[0, 100, 0, 0, 0, 0, transform]);
+        }
+        let sof_len = 8 + 3 * components as u16;
+        data.extend_from_slice(&[0xFF, 0xC0]);
+        data.extend_from_slice(&sof_len.to_be_bytes());
+        data.extend_from_slice(&[8, 0, 2, 0, 3, components]);
+        for id in 1..=components {
+            data.extend_from_slice(&[id, 0x11, 0]);
+        }
+        data.extend_from_slice(&[0xFF, 0xD9]);
+        data
+    }
+
+    #[test]
+    fn adobe_cmyk_jpeg_is_marked_inverted() {
+        let read = |jpeg: &[u8]| {
+            let package = testing::package(&[("word/media/image1.jpeg", jpeg)]);
+            let mut zip = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+            let rels = HashMap::from([("rId1".to_string(), "media/image1.jpeg".to_string())]);
+            read_image_from_zip_extra("rId1", &rels, &mut zip, 30.0, 20.0, 0.0).unwrap()
+        };
+        for transform in [0, 2] {
+            let image = read(&jpeg_headers(4, Some(transform)));
+            assert_eq!(image.jpeg_components, 4);
+            assert!(image.jpeg_adobe_inverted, "transform {transform}");
+        }
+        assert!(!read(&jpeg_headers(4, None)).jpeg_adobe_inverted);
+        assert!(!read(&jpeg_headers(3, Some(1))).jpeg_adobe_inverted);
+    }
+
+    #[test]
+    fn non_placeable_wmf_takes_drawing_extent() {
+        // METAHEADER of a memory metafile: type 1, header size 9 words
+        let wmf = [1, 0, 9, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let package = testing::package(&[("word/media/image1.wmf", &wmf)]);
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+        let rels = HashMap::from([("rId1".to_string(), "media/image1.wmf".to_string())]);
+        let image = read_image_from_zip_extra("rId1", &rels, &mut zip, 72.0, 36.0, 0.0).unwrap();
+        assert_eq!(image.format, ImageFormat::Wmf);
+        assert_eq!((image.pixel_width, image.pixel_height), (96, 48));
+    }
+
+    #[test]
+    fn emf_size_comes_from_the_frame() {
+        let mut emf = vec![0u8; 88];
+        let mut put = |at: usize, v: i32| emf[at..at + 4].copy_from_slice(&v.to_le_bytes());
+        put(0, 1);
+        // rclBounds: only the drawn part, in device pixels
+        for (i, v) in [10, 10, 20, 20].into_iter().enumerate() {
+            put(8 + 4 * i, v);
+        }
+        // rclFrame: 2 by 1 inches in 0.01 mm
+        for (i, v) in [0, 0, 5080, 2540].into_iter().enumerate() {
+            put(24 + 4 * i, v);
+        }
+        emf[40..44].copy_from_slice(b" EMF");
+        assert_eq!(image_dimensions(&emf), Some((192, 96, ImageFormat::Emf, 3)));
+    }
+}
@@ -0,0 +1,289 @@
+// Office Math (OMML) is drawn in Word's linear format: fractions become a/b,
+// scripts become superscript/subscript runs and structures without a linear
+// form fall back to the text of their runs.
+
+use crate::model::{Alignment, Run, VertAlign};
+
+use super::{WML_NS, parse_text_color, wml, wml_attr};
+
+const MATH_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/math";
+const MATH_FONT: &str = "Cambria Math";
+
+fn is_math(node: roxmltree::Node, name: &str) -> bool {
+    node.tag_name().namespace() == Some(MATH_NS) && node.tag_name().name() == name
+}
+
+fn math_child<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<roxmltree::Node<'a, 'a>> {
+    node.children().find(|n| is_math(*n, name))
+}
+
+/// `m:val` of a child of a property element such as `m:naryPr`.
+fn math_prop<'a>(node: roxmltree::Node<'a, 'a>, props: &str, name: &str) -> Option<&'a str> {
+    math_child(node, props)
+        .and_then(|p| math_child(p, name))
+        .and_then(|n| n.attribute((MATH_NS, "val")))
+}
+
+/// An inline equation (`m:oMath`) or a display equation (`m:oMathPara`).
+pub(super) fn is_math_node(node: roxmltree::Node) -> bool {
+    is_math(node, "oMath") || is_math(node, "oMathPara")
+}
+
+/// Alignment of a paragraph holding a display equation: `m:jc`, centered by default.
+pub(super) fn display_math_alignment(para: roxmltree::Node) -> Option<Alignment> {
+    let math_para = math_child(para, "oMathPara")?;
+    Some(match math_prop(math_para, "oMathParaPr", "jc") {
+        Some("left") => Alignment::Left,
+        Some("right") => Alignment::Right,
+        _ => Alignment::Center,
+    })
+}
+
+/// Runs for an `m:oMath`/`m:oMathPara`, formatted from `base` (size, color).
+pub(super) fn math_runs(node: roxmltree::Node, base: &Run) -> Vec<Run> {
+    let base = Run {
+        font_name: MATH_FONT.to_string(),
+        ..base.clone()
+    };
+    let mut runs = Vec::new();
+    if is_math(node, "oMathPara") {
+        for (i, eq) in node.children().filter(|n| is_math(*n, "oMath")).enumerate() {
+            if i > 0 {
+                runs.push(Run {
+                    is_line_break: true,
+                    ..base.clone()
+                });
+            }
+            convert_children(eq, &base, &mut runs);
+        }
+    } else {
+        convert_children(node, &base, &mut runs);
+    }
+    runs
+}
+
+fn convert_children(node: roxmltree::Node, base: &Run, out: &mut Vec<Run>) {
+    for child in node.children().filter(|n| n.is_element()) {
+        convert(child, base, out);
+    }
+}
+
+fn text(base: &Run, s: &str) -> Run {
+    Run {
+        text: s.to_string(),
+        ..base.clone()
+    }
+}
+
+/// Runs of an argument element (`m:e`, `m:num`, `m:sup`, ...).
+fn arg(node: roxmltree::Node, name: &str, base: &Run) -> Vec<Run> {
+    let mut runs = Vec::new();
+    if let Some(n) = math_child(node, name) {
+        convert_children(n, base, &mut runs);
+    }
+    runs
+}
+
+/// Wraps a compound argument in parentheses so the linear form stays unambiguous.
+fn grouped(mut runs: Vec<Run>, base: &Run) -> Vec<Run> {
+    let text: String = runs.iter().map(|r| r.text.as_str()).collect();
+    if text.chars().count() > 1 && !text.chars().all(char::is_alphanumeric) {
+        runs.insert(0, text_like(&runs[0], base, "("));
+        runs.push(text_like(&runs[runs.len() - 1], base, ")"));
+    }
+    runs
+}
+
+/// An upright symbol at the script level of `neighbor`.
+fn text_like(neighbor: &Run, base: &Run, s: &str) -> Run {
+    Run {
+        vertical_align: neighbor.vertical_align,
+        ..text(base, s)
+    }
+}
+
+fn scripted(mut runs: Vec<Run>, align: VertAlign) -> Vec<Run> {
+    for run in &mut runs {
+        run.vertical_align = align;
+    }
+    runs
+}
+
+fn convert(node: roxmltree::Node, base: &Run, out: &mut Vec<Run>) {
+    if node.tag_name().namespace() != Some(MATH_NS) {
+        return;
+    }
+    match node.tag_name().name() {
+        "r" => out.extend(math_text_run(node, base)),
+        "f" => {
+            out.extend(grouped(arg(node, "num", base), base));
+            out.push(text(base, "/"));
+            out.extend(grouped(arg(node, "den", base), base));
+        }
+        "sSup" => {
+            out.extend(arg(node, "e", base));
+            out.extend(scripted(arg(node, "sup", base), VertAlign::Superscript));
+        }
+        "sSub" => {
+            out.extend(arg(node, "e", base));
+            out.extend(scripted(arg(node, "sub", base), VertAlign::Subscript));
+        }
+        "sSubSup" => {
+            out.extend(arg(node, "e", base));
+            out.extend(scripted(arg(node, "sub", base), VertAlign::Subscript));
+            out.extend(scripted(arg(node, "sup", base), VertAlign::Superscript));
+        }
+        "sPre" => {
+            out.extend(scripted(arg(node, "sub", base), VertAlign::Subscript));
+            out.extend(scripted(arg(node, "sup", base), VertAlign::Superscript));
+            out.extend(arg(node, "e", base));
+        }
+        "rad" => {
+            let deg_hidden = math_child(node, "radPr")
+                .and_then(|p| math_child(p, "degHide"))
+                .is_some_and(|n| !matches!(n.attribute((MATH_NS, "val")), Some("0" | "off")));
+            if !deg_hidden {
+                out.extend(scripted(arg(node, "deg", base), VertAlign::Superscript));
+            }
+            out.push(text(base, "\u{221A}"));
+            out.extend(grouped(arg(node, "e", base), base));
+        }
+        "nary" => {
+            let chr = math_prop(node, "naryPr", "chr").unwrap_or("\u{222B}");
+            out.push(text(base, chr));
+            out.extend(scripted(arg(node, "sub", base), VertAlign::Subscript));
+            out.extend(scripted(arg(node, "sup", base), VertAlign::Superscript));
+            out.extend(arg(node, "e", base));
+        }
+        "d" => {
+            let beg = math_prop(node, "dPr", "begChr").unwrap_or("(");
+            let end = math_prop(node, "dPr", "endChr").unwrap_or(")");
+            let sep = math_prop(node, "dPr", "sepChr").unwrap_or("|");
+            out.push(text(base, beg));
+            for (i, e) in node.children().filter(|n| is_math(*n, "e")).enumerate() {
+                if i > 0 {
+                    out.push(text(base, sep));
+                }
+                convert_children(e, base, out);
+            }
+            out.push(text(base, end));
+        }
+        "func" => {
+            out.extend(arg(node, "fName", base));
+            out.push(text(base, " "));
+            out.extend(arg(node, "e", base));
+        }
+        "limLow" => {
+            out.extend(arg(node, "e", base));
+            out.extend(scripted(arg(node, "lim", base), VertAlign::Subscript));
+        }
+        "limUpp" => {
+            out.extend(arg(node, "e", base));
+            out.extend(scripted(arg(node, "lim", base), VertAlign::Superscript));
+        }
+        "eqArr" => {
+            for (i, e) in node.children().filter(|n| is_math(*n, "e")).enumerate() {
+                if i > 0 {
+                    out.push(Run {
+                        is_line_break: true,
+                        ..base.clone()
+                    });
+                }
+                convert_children(e, base, out);
+            }
+        }
+        // Property elements carry no content
+        name if name.ends_with("Pr") => {}
+        // Accents, bars, boxes, matrices, ...: the text of their arguments
+        _ => convert_children(node, base, out),
+    }
+}
+
+/// An `m:r`: letters in math italic unless the run is plain text (`m:nor`) or
+/// upright (`m:sty="p"`), digits and operators upright.
+fn math_text_run(node: roxmltree::Node, base: &Run) -> Vec<Run> {
+    let content: String = node
+        .children()
+        .filter(|n| is_math(*n, "t"))
+        .filter_map(|n| n.text())
+        .collect();
+    if content.is_empty() {
+        return Vec::new();
+    }
+    let mrpr = math_child(node, "rPr");
+    let upright = mrpr.is_some_and(|p| {
+        math_child(p, "nor").is_some()
+            || math_child(p, "sty")
+                .and_then(|s| s.attribute((MATH_NS, "val")))
+                .is_some_and(|v| v == "p" || v == "b")
+    });
+    let bold = mrpr
+        .and_then(|p| math_child(p, "sty"))
+        .and_then(|s| s.attribute((MATH_NS, "val")))
+        .is_some_and(|v| v == "b" || v == "bi");
+    let rpr = node
+        .children()
+        .find(|n| n.tag_name().namespace() == Some(WML_NS) && n.tag_name().name() == "rPr");
+    let run = Run {
+        font_size: rpr
+            .and_then(|n| wml_attr(n, "sz"))
+            .and_then(|v| v.parse::<f32>().ok())
+            .map_or(base.font_size, |hp| hp / 2.0),
+        color: rpr
+            .and_then(|n| wml(n, "color"))
+            .and_then(|n| n.attribute((WML_NS, "val")))
+            .and_then(parse_text_color)
+            .or(base.color),
+        bold: bold || base.bold,
+        ..base.clone()
+    };
+
+    // Split into alternating letter / non-letter runs so only letters slant
+    let mut runs: Vec<Run> = Vec::new();
+    for ch in content.chars() {
+        let italic = !upright && ch.is_alphabetic();
+        match runs.last_mut() {
+            Some(last) if last.italic == italic => last.text.push(ch),
+            _ => runs.push(Run {
+                text: ch.to_string(),
+                italic,
+                ..run.clone()
+            }),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear(xml: &str) -> String {
+        let xml = format!(r#"<m:oMath xmlns:m="{MATH_NS}">{xml}</m:oMath>"#);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        math_runs(doc.root_element(), &Run::default())
+            .iter()
+            .map(|r| match r.vertical_align {
+                VertAlign::Superscript => format!("^{}", r.text),
+                VertAlign::Subscript => format!("_{}", r.text),
+                VertAlign::Baseline => r.text.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_fraction_and_sum() {
+        assert_eq!(
+            linear(
+                "<m:f><m:num><m:r><m:t>a+b</m:t></m:r></m:num><m:den><m:r><m:t>2</m:t></m:r></m:den></m:f>"
+            ),
+            "(a+b)/2"
+        );
+        assert_eq!(
+            linear(
+                r#"<m:nary><m:naryPr><m:chr m:val="∑"/></m:naryPr><m:sub><m:r><m:t>i=1</m:t></m:r></m:sub><m:sup><m:r><m:t>n</m:t></m:r></m:sup><m:e><m:r><m:t>i</m:t></m:r></m:e></m:nary>"#
+            ),
+            "∑_i_=1^ni"
+        );
+    }
+}
@@ -1,38 +1,69 @@
 mod alt_chunk;
+mod bookmarks;
 mod charts;
+mod comments;
+mod core_properties;
 mod embedded_fonts;
+mod fields;
 mod headers_footers;
 mod images;
+mod math;
 mod numbering;
+mod revisions;
 mod runs;
 mod sections;
 mod settings;
 pub(crate) mod smartart;
 mod styles;
+mod symbols;
 mod tables;
 mod textbox;
+mod toc;
+mod watermark;
+mod woff;
+
+#[cfg(test)]
+pub(crate) mod testing;
 
 use std::collections::HashMap;
 use std::io::Read;
 
+use crate::ConversionOptions;
 use crate::error::Error;
 use crate::model::{
-    Alignment, Block, Document, LineSpacing, Paragraph, ParagraphBorders, Section,
-    SectionBreakType, SectionProperties, TabAlignment, TabStop,
+    Alignment, Block, BorderLineStyle, CharBorder, Document, DropCap, LineSpacing, NumberFormat,
+    PageVAlign, Paragraph, ParagraphBorders, Run, Section, SectionBreakType, SectionProperties,
+    TabAlignment, TabStop,
 };
 
-use styles::{ParagraphStyle, parse_alignment, parse_line_spacing, parse_styles, parse_theme};
+use styles::{
+    ParagraphStyle, bidi_alignment, parse_alignment, parse_line_spacing, parse_styles, parse_theme,
+    resolve_bidi,
+};
 
+use bookmarks::{
+    bookmark_start_name, collect_bookmark_text, locate_bookmarks, paragraph_bookmarks,
+    resolve_ref_fields,
+};
+use comments::parse_comments;
+use core_properties::parse_core_properties;
 use embedded_fonts::parse_font_table;
-use headers_footers::parse_footnotes;
+use fields::{
+    resolve_date_fields, resolve_file_name_fields, resolve_property_fields, resolve_seq_fields,
+    resolve_variable_fields,
+};
+use headers_footers::{number_endnotes, parse_endnotes, parse_footnotes};
 use images::compute_drawing_info;
+use math::display_math_alignment;
 use numbering::{ListLabelInfo, parse_list_info, parse_numbering};
 use relationships::parse_relationships;
+use revisions::apply_revisions;
 use runs::parse_runs;
 use sections::parse_section_properties;
 use settings::parse_settings;
 use tables::parse_table_node;
 use textbox::collect_textboxes_from_paragraph;
+use toc::{generate_tocs, outline_level};
 
 pub(super) const WML_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
 pub(super) const DML_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
@@ -136,7 +167,7 @@ pub(super) fn twips_attr(node: roxmltree::Node, attr: &str) -> Option<f32> {
         .map(twips_to_pts)
 }
 
-fn parse_one_border(node: roxmltree::Node) -> Option<crate::model::ParagraphBorder> {
+pub(super) fn parse_one_border(node: roxmltree::Node) -> Option<crate::model::ParagraphBorder> {
     let val = node.attribute((WML_NS, "val")).unwrap_or("none");
     if val == "none" || val == "nil" {
         return None;
@@ -161,6 +192,48 @@ fn parse_one_border(node: roxmltree::Node) -> Option<crate::model::ParagraphBord
     })
 }
 
+/// Character border (`w:bdr`), with the line style drawn closest to `w:val`.
+pub(super) fn parse_char_border(node: roxmltree::Node) -> Option<CharBorder> {
+    let line = parse_one_border(node)?;
+    let style = match node.attribute((WML_NS, "val")) {
+        Some("double") => BorderLineStyle::Double,
+        Some("dotted" | "dotDash" | "dotDotDash") => BorderLineStyle::Dotted,
+        Some(v) if v.starts_with("dash") => BorderLineStyle::Dashed,
+        _ => BorderLineStyle::Single,
+    };
+    Some(CharBorder {
+        width_pt: line.width_pt,
+        space_pt: line.space_pt,
+        color: line.color,
+        style,
+    })
+}
+
+/// Drop cap settings of a frame paragraph (`w:framePr` with `w:dropCap` "drop"
+/// or "margin"), without its runs. Frames holding nothing but whitespace are
+/// left as ordinary paragraphs.
+fn parse_drop_cap(ppr: roxmltree::Node, runs: &[Run]) -> Option<DropCap> {
+    let frame = wml(ppr, "framePr")?;
+    let in_margin = match frame.attribute((WML_NS, "dropCap"))? {
+        "drop" => false,
+        "margin" => true,
+        _ => return None,
+    };
+    if runs.iter().all(|r| r.text.trim().is_empty()) {
+        return None;
+    }
+    Some(DropCap {
+        runs: Vec::new(),
+        lines: frame
+            .attribute((WML_NS, "lines"))
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(3)
+            .max(1),
+        in_margin,
+        h_space: twips_attr(frame, "hSpace").unwrap_or(0.0),
+    })
+}
+
 pub(super) fn parse_paragraph_borders(ppr: roxmltree::Node) -> ParagraphBorders {
     let Some(pbdr) = wml(ppr, "pBdr") else {
         return ParagraphBorders::default();
@@ -309,6 +382,64 @@ pub(super) fn collect_block_nodes<'a>(
     nodes
 }
 
+/// Paragraphs of a block list in document order, including those in table cells.
+pub(super) fn paragraphs_mut(blocks: &mut [Block]) -> impl Iterator<Item = &mut Paragraph> {
+    blocks
+        .iter_mut()
+        .flat_map(|block| -> Box<dyn Iterator<Item = &mut Paragraph>> {
+            match block {
+                Block::Paragraph(p) => Box::new(std::iter::once(p)),
+                Block::Table(t) => t.paragraphs_mut(),
+            }
+        })
+}
+
+/// Every paragraph of the document: body, headers and footers, notes and the
+/// text boxes inside them.
+pub(super) fn for_each_paragraph(doc: &mut Document, f: &mut impl FnMut(&mut Paragraph)) {
+    fn visit(para: &mut Paragraph, f: &mut impl FnMut(&mut Paragraph)) {
+        f(para);
+        for tb in &mut para.textboxes {
+            for p in &mut tb.paragraphs {
+                visit(p, f);
+            }
+        }
+    }
+
+    for section in &mut doc.sections {
+        for para in paragraphs_mut(&mut section.blocks) {
+            visit(para, f);
+        }
+        let props = &mut section.properties;
+        for hf in [
+            &mut props.header_default,
+            &mut props.header_first,
+            &mut props.header_even,
+            &mut props.footer_default,
+            &mut props.footer_first,
+            &mut props.footer_even,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for para in paragraphs_mut(&mut hf.blocks) {
+                visit(para, f);
+            }
+        }
+    }
+    for notes in [&mut doc.footnotes, &mut doc.endnotes] {
+        let mut ids: Vec<u32> = notes.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            if let Some(note) = notes.get_mut(&id) {
+                for para in &mut note.paragraphs {
+                    visit(para, f);
+                }
+            }
+        }
+    }
+}
+
 pub(super) fn read_zip_text<R: Read + std::io::Seek>(
     zip: &mut zip::ZipArchive<R>,
     name: &str,
@@ -369,7 +500,7 @@ mod relationships {
     }
 }
 
-pub fn parse(path: &std::path::Path) -> Result<Document, Error> {
+pub fn parse(path: &std::path::Path, options: &ConversionOptions) -> Result<Document, Error> {
     let file = std::fs::File::open(path).map_err(|e| match e.kind() {
         std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied => Error::Io(
             std::io::Error::new(e.kind(), format!("{}: {}", e, path.display())),
@@ -380,26 +511,46 @@ pub fn parse(path: &std::path::Path) -> Result<Document, Error> {
     let mut zip = zip::ZipArchive::new(file)
         .map_err(|_| Error::InvalidDocx("file is not a ZIP archive".into()))?;
 
-    parse_zip(&mut zip)
+    let mut doc = parse_zip(&mut zip, options)?;
+    resolve_file_name_fields(&mut doc, path);
+    doc.file_path = Some(path.to_path_buf());
+    Ok(doc)
 }
 
-pub fn parse_bytes(bytes: &[u8]) -> Result<Document, Error> {
-    let cursor = std::io::Cursor::new(bytes);
-    let mut zip = zip::ZipArchive::new(cursor)
+pub fn parse_bytes(bytes: &[u8], options: &ConversionOptions) -> Result<Document, Error> {
+    parse_reader(std::io::Cursor::new(bytes), options)
+}
+
+/// Parses a .docx from any seekable source, such as a cursor over an upload.
+pub fn parse_reader<R: Read + std::io::Seek>(
+    reader: R,
+    options: &ConversionOptions,
+) -> Result<Document, Error> {
+    let mut zip = zip::ZipArchive::new(reader)
         .map_err(|_| Error::InvalidDocx("data is not a valid ZIP/DOCX archive".into()))?;
 
-    parse_zip(&mut zip)
+    parse_zip(&mut zip, options)
 }
 
-fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Document, Error> {
+fn parse_zip<R: Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    options: &ConversionOptions,
+) -> Result<Document, Error> {
     let settings = parse_settings(zip);
-    let theme = parse_theme(zip, settings.east_asia_lang.as_deref());
+    let core_properties = parse_core_properties(zip);
+    let theme = parse_theme(
+        zip,
+        settings.east_asia_lang.as_deref(),
+        options.default_font.as_deref(),
+    );
     let styles = parse_styles(zip, &theme);
     let numbering = parse_numbering(zip);
     let rels = parse_relationships(zip);
     let ft = parse_font_table(zip);
     let (embedded_fonts, font_table) = (ft.embedded_fonts, ft.font_table);
     let footnotes = parse_footnotes(zip, &styles, &theme);
+    let mut endnotes = parse_endnotes(zip, &styles, &theme);
+    let comments = parse_comments(zip);
 
     let mut xml_content = String::new();
     zip.by_name("word/document.xml")
@@ -410,6 +561,12 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
     let root = xml.root_element();
 
     let body = wml(root, "body").ok_or_else(|| Error::InvalidDocx("Missing w:body".into()))?;
+    // A fill-effect background (a `v:background` shape) is only shown when
+    // settings ask for background shapes; a plain page color always is.
+    let background = wml(root, "background")
+        .filter(|bg| settings.display_background_shape || !bg.children().any(|n| n.is_element()))
+        .and_then(|bg| bg.attribute((WML_NS, "color")))
+        .and_then(parse_hex_color);
 
     let default_line_pitch = styles.defaults.font_size * 1.2;
 
@@ -417,6 +574,10 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
     let mut blocks = Vec::new();
     let mut counters: HashMap<(String, u8), u32> = HashMap::new();
     let mut last_seen_level: HashMap<String, u8> = HashMap::new();
+    // Bookmarks starting between paragraphs belong to the next paragraph
+    let mut pending_bookmarks: Vec<String> = Vec::new();
+    // A drop cap frame paragraph belongs to the paragraph after it
+    let mut pending_drop_cap: Option<DropCap> = None;
 
     for node in collect_block_nodes(body) {
         if node.tag_name().namespace() != Some(WML_NS) {
@@ -424,7 +585,7 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
         }
         match node.tag_name().name() {
             "tbl" => {
-                let table = parse_table_node(
+                let mut table = parse_table_node(
                     node,
                     &styles,
                     &theme,
@@ -434,6 +595,16 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     &mut counters,
                     &mut last_seen_level,
                 );
+                // Bookmarks opened just before a table start at its first cell
+                if !pending_bookmarks.is_empty()
+                    && let Some(first) = table
+                        .rows
+                        .first_mut()
+                        .and_then(|row| row.cells.first_mut())
+                        .and_then(|cell| cell.paragraphs.first_mut())
+                {
+                    first.bookmarks.splice(0..0, pending_bookmarks.drain(..));
+                }
                 blocks.push(Block::Table(table));
             }
             "p" => {
@@ -478,6 +649,9 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     .map(parse_alignment)
                     .or_else(|| para_style.and_then(|s| s.alignment))
                     .unwrap_or(Alignment::Left);
+                let alignment = display_math_alignment(node).unwrap_or(alignment);
+                let rtl = resolve_bidi(ppr, para_style);
+                let alignment = bidi_alignment(alignment, rtl);
 
                 let contextual_spacing = ppr
                     .and_then(|ppr| wml_bool(ppr, "contextualSpacing"))
@@ -491,6 +665,10 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     .and_then(|ppr| wml_bool(ppr, "keepLines"))
                     .unwrap_or_else(|| para_style.is_some_and(|s| s.keep_lines));
 
+                let suppress_auto_hyphens = ppr
+                    .and_then(|ppr| wml_bool(ppr, "suppressAutoHyphens"))
+                    .unwrap_or_else(|| para_style.is_some_and(|s| s.suppress_auto_hyphens));
+
                 let num_pr = ppr.and_then(|ppr| wml(ppr, "numPr"));
                 let style_num = para_style.and_then(|s| s.num_id.as_deref());
                 let style_ilvl = para_style.and_then(|s| s.num_ilvl);
@@ -502,6 +680,7 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     font_size: list_label_font_size,
                     bold: list_label_bold,
                     color: list_label_color,
+                    suffix: list_label_suffix,
                 } = parse_list_info(
                     num_pr,
                     style_num,
@@ -541,6 +720,8 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     indent_first_line = v;
                 }
 
+                let outline_level = outline_level(ppr, para_style, para_style_id, &styles);
+
                 let parsed = parse_runs(node, &styles, &theme, &rels, zip, &numbering);
                 let mut runs = parsed.runs;
 
@@ -550,6 +731,15 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     }
                 }
 
+                if let Some(drop_cap) = ppr.and_then(|ppr| parse_drop_cap(ppr, &runs)) {
+                    pending_bookmarks.extend(paragraph_bookmarks(node));
+                    pending_drop_cap = Some(DropCap { runs, ..drop_cap });
+                    continue;
+                }
+
+                let mut bookmarks = std::mem::take(&mut pending_bookmarks);
+                bookmarks.extend(paragraph_bookmarks(node));
+
                 let mut tab_stops = ppr.map(parse_tab_stops).unwrap_or_default();
                 if tab_stops.is_empty()
                     && let Some(s) = para_style
@@ -604,6 +794,8 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     space_after,
                     content_height,
                     alignment,
+                    rtl,
+                    outline_level,
                     indent_left,
                     indent_right,
                     indent_hanging,
@@ -613,9 +805,11 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     list_label_font_size,
                     list_label_bold,
                     list_label_color,
+                    list_label_suffix,
                     contextual_spacing,
                     keep_next,
                     keep_lines,
+                    suppress_auto_hyphens,
                     line_spacing,
                     image: para_image,
                     borders,
@@ -637,6 +831,8 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     inline_chart: parsed.inline_chart,
                     smartart: parsed.smartart,
                     is_section_break: false,
+                    bookmarks,
+                    drop_cap: pending_drop_cap.take(),
                 }));
 
                 // Mid-document section break: sectPr inside pPr ends the current section
@@ -651,6 +847,7 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                         &theme,
                         zip,
                         default_line_pitch,
+                        settings.gutter_at_top,
                     );
                     sections.push(Section {
                         properties: props,
@@ -658,6 +855,11 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
                     });
                 }
             }
+            "bookmarkStart" => {
+                if let Some(name) = bookmark_start_name(node) {
+                    pending_bookmarks.push(name.to_string());
+                }
+            }
             "altChunk" => {
                 if let Some(id) = node.attribute((REL_NS, "id")) {
                     blocks.extend(alt_chunk::parse_alt_chunk(id, &rels, zip));
@@ -669,7 +871,15 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
 
     // Final section: body-level sectPr
     let final_props = if let Some(sect_node) = wml(body, "sectPr") {
-        parse_section_properties(sect_node, &rels, &styles, &theme, zip, default_line_pitch)
+        parse_section_properties(
+            sect_node,
+            &rels,
+            &styles,
+            &theme,
+            zip,
+            default_line_pitch,
+            settings.gutter_at_top,
+        )
     } else {
         SectionProperties {
             page_width: 612.0,
@@ -691,20 +901,45 @@ fn parse_zip<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> Result<Do
             break_type: SectionBreakType::NextPage,
             columns: None,
             page_num_start: None,
+            page_num_format: NumberFormat::Decimal,
+            page_borders: None,
+            line_numbering: None,
+            v_align: PageVAlign::Top,
         }
     };
     sections.push(Section {
         properties: final_props,
         blocks,
     });
+    generate_tocs(&mut sections, &styles);
+    let bookmarks = locate_bookmarks(&sections);
+    number_endnotes(&mut sections, &mut endnotes, settings.endnote_properties);
 
-    Ok(Document {
+    let mut doc = Document {
         sections,
         line_spacing: styles.defaults.line_spacing,
         embedded_fonts,
         footnotes,
+        endnotes,
+        footnote_properties: settings.footnote_properties,
+        comments,
+        background,
         font_table,
         even_and_odd_headers: settings.even_and_odd_headers,
+        default_tab_stop: settings.default_tab_stop,
+        mirror_margins: settings.mirror_margins,
+        auto_hyphenation: settings.auto_hyphenation,
+        lang: settings.lang,
         style_id_to_name: styles.style_id_to_name,
-    })
+        file_path: None,
+        core_properties,
+        bookmarks,
+    };
+    resolve_seq_fields(&mut doc);
+    resolve_ref_fields(&mut doc, &collect_bookmark_text(body));
+    resolve_variable_fields(&mut doc, &settings.doc_vars, &options.merge_fields);
+    resolve_property_fields(&mut doc);
+    resolve_date_fields(&mut doc, options);
+    apply_revisions(&mut doc, options.accept_changes);
+    Ok(doc)
 }
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use crate::model::{LabelSuffix, NumberFormat};
+
+use super::symbols::is_symbol_font;
 use super::{WML_NS, parse_hex_color, twips_attr, wml, wml_attr, wml_bool};
 
 #[derive(Clone)]
@@ -9,10 +12,13 @@ pub(super) struct LevelDef {
     pub(super) indent_left: f32,
     pub(super) indent_hanging: f32,
     pub(super) start: u32,
-    pub(super) bullet_font: Option<String>,
+    pub(super) label_font: Option<String>,
     pub(super) label_font_size: Option<f32>,
     pub(super) label_bold: bool,
     pub(super) label_color: Option<[u8; 3]>,
+    /// Legal numbering (`w:isLgl`): every number in the label is decimal
+    pub(super) is_lgl: bool,
+    pub(super) suffix: LabelSuffix,
 }
 
 #[derive(Default)]
@@ -24,6 +30,7 @@ pub(super) struct ListLabelInfo {
     pub(super) font_size: Option<f32>,
     pub(super) bold: bool,
     pub(super) color: Option<[u8; 3]>,
+    pub(super) suffix: LabelSuffix,
 }
 
 #[derive(Default)]
@@ -31,6 +38,84 @@ pub(super) struct NumberingInfo {
     pub(super) abstract_nums: HashMap<String, HashMap<u8, LevelDef>>,
     pub(super) num_to_abstract: HashMap<String, String>,
     pub(super) start_overrides: HashMap<String, HashMap<u8, u32>>,
+    /// Levels a `w:num` redefines in a `w:lvlOverride/w:lvl`
+    pub(super) level_overrides: HashMap<String, HashMap<u8, LevelDef>>,
+}
+
+impl NumberingInfo {
+    /// Definition of a list level, with the `w:num`'s own override first.
+    fn level(&self, num_id: &str, ilvl: u8) -> Option<&LevelDef> {
+        self.level_overrides
+            .get(num_id)
+            .and_then(|m| m.get(&ilvl))
+            .or_else(|| {
+                let abs_id = self.num_to_abstract.get(num_id)?;
+                self.abstract_nums.get(abs_id.as_str())?.get(&ilvl)
+            })
+    }
+
+    /// First number of a list level: `w:startOverride`, else the level's `w:start`.
+    fn start(&self, num_id: &str, ilvl: u8) -> Option<u32> {
+        self.start_overrides
+            .get(num_id)
+            .and_then(|m| m.get(&ilvl))
+            .copied()
+            .or_else(|| self.level(num_id, ilvl).map(|d| d.start))
+    }
+}
+
+/// A `w:lvl`, in an abstract numbering or a level override.
+fn parse_level(lvl: roxmltree::Node) -> Option<(u8, LevelDef)> {
+    let ilvl = lvl
+        .attribute((WML_NS, "ilvl"))
+        .and_then(|v| v.parse::<u8>().ok())?;
+    let num_fmt = wml_attr(lvl, "numFmt").unwrap_or("bullet").to_string();
+    let lvl_text = wml_attr(lvl, "lvlText").unwrap_or("").to_string();
+    let start = wml_attr(lvl, "start")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(1);
+    let ind = wml(lvl, "pPr").and_then(|ppr| wml(ppr, "ind"));
+    let indent_left = ind.and_then(|n| twips_attr(n, "left")).unwrap_or(0.0);
+    let indent_hanging = ind.and_then(|n| twips_attr(n, "hanging")).unwrap_or(0.0);
+    let rpr = wml(lvl, "rPr");
+    // Bullets and numbers alike are drawn in the level's font when it names one
+    let label_font = rpr
+        .and_then(|r| wml(r, "rFonts"))
+        .and_then(|rf| {
+            rf.attribute((WML_NS, "ascii"))
+                .or_else(|| rf.attribute((WML_NS, "hAnsi")))
+        })
+        .map(|s| s.to_string());
+    let label_font_size = rpr
+        .and_then(|r| wml_attr(r, "sz"))
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|hp| hp / 2.0);
+    let label_bold = rpr.and_then(|r| wml_bool(r, "b")).unwrap_or(false);
+    let label_color = rpr
+        .and_then(|r| wml_attr(r, "color"))
+        .and_then(parse_hex_color);
+    let is_lgl = wml_bool(lvl, "isLgl").unwrap_or(false);
+    let suffix = match wml_attr(lvl, "suff") {
+        Some("space") => LabelSuffix::Space,
+        Some("nothing") => LabelSuffix::Nothing,
+        _ => LabelSuffix::Tab,
+    };
+    Some((
+        ilvl,
+        LevelDef {
+            num_fmt,
+            lvl_text,
+            indent_left,
+            indent_hanging,
+            start,
+            label_font,
+            label_font_size,
+            label_bold,
+            label_color,
+            is_lgl,
+            suffix,
+        },
+    ))
 }
 
 pub(super) fn parse_numbering<R: std::io::Read + std::io::Seek>(
@@ -48,6 +133,7 @@ pub(super) fn parse_numbering<R: std::io::Read + std::io::Seek>(
     let mut num_style_link: HashMap<String, String> = HashMap::new();
     let mut style_link_target: HashMap<String, String> = HashMap::new();
     let mut start_overrides: HashMap<String, HashMap<u8, u32>> = HashMap::new();
+    let mut level_overrides: HashMap<String, HashMap<u8, LevelDef>> = HashMap::new();
 
     let root = xml.root_element();
 
@@ -60,53 +146,11 @@ pub(super) fn parse_numbering<R: std::io::Read + std::io::Seek>(
                 let Some(abs_id) = node.attribute((WML_NS, "abstractNumId")) else {
                     continue;
                 };
-                let mut levels: HashMap<u8, LevelDef> = HashMap::new();
-                for lvl in node.children().filter(|n| n.has_tag_name((WML_NS, "lvl"))) {
-                    let Some(ilvl) = lvl
-                        .attribute((WML_NS, "ilvl"))
-                        .and_then(|v| v.parse::<u8>().ok())
-                    else {
-                        continue;
-                    };
-                    let num_fmt = wml_attr(lvl, "numFmt").unwrap_or("bullet").to_string();
-                    let lvl_text = wml_attr(lvl, "lvlText").unwrap_or("").to_string();
-                    let start = wml_attr(lvl, "start")
-                        .and_then(|v| v.parse::<u32>().ok())
-                        .unwrap_or(1);
-                    let ind = wml(lvl, "pPr").and_then(|ppr| wml(ppr, "ind"));
-                    let indent_left = ind.and_then(|n| twips_attr(n, "left")).unwrap_or(0.0);
-                    let indent_hanging = ind.and_then(|n| twips_attr(n, "hanging")).unwrap_or(0.0);
-                    let rpr = wml(lvl, "rPr");
-                    let bullet_font = rpr
-                        .and_then(|r| wml(r, "rFonts"))
-                        .and_then(|rf| {
-                            rf.attribute((WML_NS, "ascii"))
-                                .or_else(|| rf.attribute((WML_NS, "hAnsi")))
-                        })
-                        .map(|s| s.to_string());
-                    let label_font_size = rpr
-                        .and_then(|r| wml_attr(r, "sz"))
-                        .and_then(|v| v.parse::<f32>().ok())
-                        .map(|hp| hp / 2.0);
-                    let label_bold = rpr.and_then(|r| wml_bool(r, "b")).unwrap_or(false);
-                    let label_color = rpr
-                        .and_then(|r| wml_attr(r, "color"))
-                        .and_then(parse_hex_color);
-                    levels.insert(
-                        ilvl,
-                        LevelDef {
-                            num_fmt,
-                            lvl_text,
-                            indent_left,
-                            indent_hanging,
-                            start,
-                            bullet_font,
-                            label_font_size,
-                            label_bold,
-                            label_color,
-                        },
-                    );
-                }
+                let levels: HashMap<u8, LevelDef> = node
+                    .children()
+                    .filter(|n| n.has_tag_name((WML_NS, "lvl")))
+                    .filter_map(parse_level)
+                    .collect();
                 abstract_nums.insert(abs_id.to_string(), levels);
                 if let Some(link) = wml_attr(node, "numStyleLink") {
                     num_style_link.insert(abs_id.to_string(), link.to_string());
@@ -123,21 +167,34 @@ pub(super) fn parse_numbering<R: std::io::Read + std::io::Seek>(
                     continue;
                 };
                 num_to_abstract.insert(num_id.to_string(), abs_id.to_string());
-                let overrides: HashMap<u8, u32> = node
+                let mut overrides: HashMap<u8, u32> = HashMap::new();
+                let mut levels: HashMap<u8, LevelDef> = HashMap::new();
+                for ovr in node
                     .children()
                     .filter(|n| n.has_tag_name((WML_NS, "lvlOverride")))
-                    .filter_map(|ovr| {
-                        let ilvl = ovr
-                            .attribute((WML_NS, "ilvl"))
-                            .and_then(|v| v.parse::<u8>().ok())?;
-                        let val =
-                            wml_attr(ovr, "startOverride").and_then(|v| v.parse::<u32>().ok())?;
-                        Some((ilvl, val))
-                    })
-                    .collect();
+                {
+                    let Some(ilvl) = ovr
+                        .attribute((WML_NS, "ilvl"))
+                        .and_then(|v| v.parse::<u8>().ok())
+                    else {
+                        continue;
+                    };
+                    if let Some(val) =
+                        wml_attr(ovr, "startOverride").and_then(|v| v.parse::<u32>().ok())
+                    {
+                        overrides.insert(ilvl, val);
+                    }
+                    // The redefined level applies at the override's ilvl
+                    if let Some((_, def)) = wml(ovr, "lvl").and_then(parse_level) {
+                        levels.insert(ilvl, def);
+                    }
+                }
                 if !overrides.is_empty() {
                     start_overrides.insert(num_id.to_string(), overrides);
                 }
+                if !levels.is_empty() {
+                    level_overrides.insert(num_id.to_string(), levels);
+                }
             }
             _ => {}
         }
@@ -164,70 +221,20 @@ pub(super) fn parse_numbering<R: std::io::Read + std::io::Seek>(
         abstract_nums,
         num_to_abstract,
         start_overrides,
+        level_overrides,
     }
 }
 
-fn to_roman(mut n: u32) -> String {
-    const TABLE: &[(u32, &str)] = &[
-        (1000, "m"),
-        (900, "cm"),
-        (500, "d"),
-        (400, "cd"),
-        (100, "c"),
-        (90, "xc"),
-        (50, "l"),
-        (40, "xl"),
-        (10, "x"),
-        (9, "ix"),
-        (5, "v"),
-        (4, "iv"),
-        (1, "i"),
-    ];
-    let mut result = String::new();
-    for &(value, numeral) in TABLE {
-        while n >= value {
-            result.push_str(numeral);
-            n -= value;
-        }
-    }
-    result
-}
-
-fn to_letter(value: u32, base: u8) -> String {
-    if value == 0 {
-        return String::new();
-    }
-    let mut n = value - 1;
-    let mut result = String::new();
-    loop {
-        result.insert(0, (base + (n % 26) as u8) as char);
-        if n < 26 {
-            break;
-        }
-        n = n / 26 - 1;
-    }
-    result
-}
-
-fn format_number(value: u32, num_fmt: &str) -> String {
-    match num_fmt {
-        "decimal" => value.to_string(),
-        "decimalZero" => format!("{value:02}"),
-        "lowerLetter" => to_letter(value, b'a'),
-        "upperLetter" => to_letter(value, b'A'),
-        "lowerRoman" => to_roman(value),
-        "upperRoman" => to_roman(value).to_uppercase(),
-        "none" => String::new(),
-        _ => value.to_string(),
-    }
+pub(super) fn format_number(value: u32, num_fmt: &str) -> String {
+    NumberFormat::from_ooxml(num_fmt).format(value)
 }
 
-fn normalize_bullet_text(text: &str) -> String {
+fn normalize_bullet_text(text: &str, label_font: Option<&str>) -> String {
     text.chars()
         .map(|c| {
             let cp = c as u32;
             if (0xF000..=0xF0FF).contains(&cp) {
-                symbol_pua_to_unicode(cp).unwrap_or(c)
+                symbol_pua_to_unicode(cp, label_font).unwrap_or(c)
             } else {
                 c
             }
@@ -235,10 +242,18 @@ fn normalize_bullet_text(text: &str) -> String {
         .collect()
 }
 
-fn symbol_pua_to_unicode(cp: u32) -> Option<char> {
+/// The usual Symbol bullet becomes U+2022 in the text font. Other glyphs of a
+/// known symbol font (Wingdings arrows, squares, ...) stay in the private use
+/// range so the label is drawn from the symbol font itself.
+fn symbol_pua_to_unicode(cp: u32, label_font: Option<&str>) -> Option<char> {
     let sym = cp - 0xF000;
+    if sym == 0xB7 {
+        return Some('\u{2022}'); // bullet •
+    }
+    if label_font.is_some_and(is_symbol_font) {
+        return char::from_u32(cp);
+    }
     let mapped = match sym {
-        0xB7 => '\u{2022}', // bullet •
         0xA7 => '\u{25A0}', // black square ■ (Wingdings §)
         0xA8 => '\u{25CB}', // white circle ○
         0xD8 => '\u{2666}', // diamond ◆
@@ -273,13 +288,7 @@ pub(super) fn parse_list_info(
     if num_id == "0" {
         return ListLabelInfo::default();
     }
-    let Some(abs_id) = numbering.num_to_abstract.get(num_id) else {
-        return ListLabelInfo::default();
-    };
-    let Some(levels) = numbering.abstract_nums.get(abs_id.as_str()) else {
-        return ListLabelInfo::default();
-    };
-    let Some(def) = levels.get(&ilvl) else {
+    let Some(def) = numbering.level(num_id, ilvl) else {
         return ListLabelInfo::default();
     };
 
@@ -292,12 +301,7 @@ pub(super) fn parse_list_info(
     last_seen_level.insert(num_id.to_string(), ilvl);
 
     // Increment or initialize counter using the level's start value
-    let start = numbering
-        .start_overrides
-        .get(num_id)
-        .and_then(|m| m.get(&ilvl))
-        .copied()
-        .unwrap_or(def.start);
+    let start = numbering.start(num_id, ilvl).unwrap_or(def.start);
     let current_counter = *counters
         .entry((num_id.to_string(), ilvl))
         .and_modify(|c| *c += 1)
@@ -305,7 +309,7 @@ pub(super) fn parse_list_info(
 
     let is_bullet = def.num_fmt == "bullet";
     let label = if is_bullet {
-        let text = normalize_bullet_text(&def.lvl_text);
+        let text = normalize_bullet_text(&def.lvl_text, def.label_font.as_deref());
         if text.is_empty() {
             "\u{2022}".to_string()
         } else {
@@ -322,11 +326,12 @@ pub(super) fn parse_list_info(
                     counters
                         .get(&(num_id.to_string(), lvl_idx))
                         .copied()
-                        .unwrap_or(levels.get(&lvl_idx).map(|d| d.start).unwrap_or(1))
+                        .unwrap_or(numbering.start(num_id, lvl_idx).unwrap_or(1))
                 };
-                let lvl_fmt = levels
-                    .get(&lvl_idx)
+                let lvl_fmt = numbering
+                    .level(num_id, lvl_idx)
                     .map(|d| d.num_fmt.as_str())
+                    .filter(|_| !def.is_lgl)
                     .unwrap_or("decimal");
                 label = label.replace(&placeholder, &format_number(lvl_counter, lvl_fmt));
             }
@@ -340,13 +345,135 @@ pub(super) fn parse_list_info(
         indent_left: def.indent_left,
         indent_hanging: def.indent_hanging,
         label,
-        font: if is_bullet && has_pua {
-            def.bullet_font.clone()
-        } else {
-            None
-        },
+        // A symbol font only suits the label while it still holds raw symbol codes
+        font: def
+            .label_font
+            .clone()
+            .filter(|f| has_pua || !is_symbol_font(f)),
         font_size: def.label_font_size,
         bold: def.label_bold,
         color: def.label_color,
+        suffix: def.suffix,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConversionOptions;
+    use crate::docx::testing;
+    use crate::model::Paragraph;
+
+    /// Parses one list paragraph per `(numId, ilvl)` against `numbering`.
+    fn list_paragraphs(numbering: &str, items: &[(&str, u8)]) -> Vec<Paragraph> {
+        let numbering = testing::part("w:numbering", numbering);
+        let body: String = items
+            .iter()
+            .map(|(num_id, ilvl)| {
+                format!(
+                    r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="{ilvl}"/><w:numId w:val="{num_id}"/></w:numPr></w:pPr><w:r><w:t>Item</w:t></w:r></w:p>"#
+                )
+            })
+            .collect();
+        let doc = testing::parse_with(
+            &body,
+            &[("word/numbering.xml", numbering.as_bytes())],
+            &ConversionOptions::default(),
+        );
+        doc.sections
+            .into_iter()
+            .flat_map(|s| s.blocks)
+            .filter_map(|b| match b {
+                crate::model::Block::Paragraph(p) => Some(p),
+                crate::model::Block::Table(_) => None,
+            })
+            .collect()
+    }
+
+    fn level(num_fmt: &str, lvl_text: &str, is_lgl: bool) -> LevelDef {
+        LevelDef {
+            num_fmt: num_fmt.to_string(),
+            lvl_text: lvl_text.to_string(),
+            indent_left: 0.0,
+            indent_hanging: 0.0,
+            start: 1,
+            label_font: None,
+            label_font_size: None,
+            label_bold: false,
+            label_color: None,
+            is_lgl,
+            suffix: LabelSuffix::Tab,
+        }
+    }
+
+    #[test]
+    fn test_legal_numbering_makes_every_level_decimal() {
+        let levels = HashMap::from([
+            (0, level("upperRoman", "%1.", false)),
+            (1, level("lowerLetter", "%1.%2", true)),
+        ]);
+        let numbering = NumberingInfo {
+            abstract_nums: HashMap::from([("0".to_string(), levels)]),
+            num_to_abstract: HashMap::from([("1".to_string(), "0".to_string())]),
+            ..NumberingInfo::default()
+        };
+        let mut counters = HashMap::new();
+        let mut last_seen = HashMap::new();
+        let mut label = |ilvl: u8| {
+            parse_list_info(
+                None,
+                Some("1"),
+                Some(ilvl),
+                &numbering,
+                &mut counters,
+                &mut last_seen,
+            )
+            .label
+        };
+        assert_eq!(label(0), "I.");
+        assert_eq!(label(0), "II.");
+        assert_eq!(label(1), "2.1");
+    }
+
+    #[test]
+    fn test_ordinal_and_text_formats() {
+        assert_eq!(format_number(2, "ordinal"), "2nd");
+        assert_eq!(format_number(13, "ordinal"), "13th");
+        assert_eq!(format_number(21, "cardinalText"), "Twenty-one");
+        assert_eq!(format_number(112, "ordinalText"), "One hundred twelfth");
+        assert_eq!(format_number(40, "ordinalText"), "Fortieth");
+    }
+
+    #[test]
+    fn test_level_override_replaces_the_abstract_level() {
+        let numbering = r#"<w:abstractNum w:abstractNumId="0"><w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="decimal"/><w:lvlText w:val="%1."/></w:lvl></w:abstractNum>
+            <w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>
+            <w:num w:numId="2"><w:abstractNumId w:val="0"/><w:lvlOverride w:ilvl="0"><w:lvl w:ilvl="0"><w:start w:val="3"/><w:numFmt w:val="upperLetter"/><w:lvlText w:val="%1)"/></w:lvl></w:lvlOverride></w:num>"#;
+        let labels: Vec<String> = list_paragraphs(numbering, &[("1", 0), ("2", 0), ("2", 0)])
+            .into_iter()
+            .map(|p| p.list_label)
+            .collect();
+        assert_eq!(labels, ["1.", "C)", "D)"]);
+    }
+
+    #[test]
+    fn test_labels_use_the_level_font() {
+        let numbering = r#"<w:abstractNum w:abstractNumId="0"><w:lvl w:ilvl="0"><w:numFmt w:val="decimal"/><w:lvlText w:val="%1."/><w:rPr><w:rFonts w:ascii="Courier New" w:hAnsi="Courier New"/></w:rPr></w:lvl><w:lvl w:ilvl="1"><w:numFmt w:val="bullet"/><w:lvlText w:val="&#xF0B7;"/><w:rPr><w:rFonts w:ascii="Symbol" w:hAnsi="Symbol"/></w:rPr></w:lvl><w:lvl w:ilvl="2"><w:numFmt w:val="bullet"/><w:lvlText w:val="&#xF0A7;"/><w:rPr><w:rFonts w:ascii="Wingdings" w:hAnsi="Wingdings"/></w:rPr></w:lvl></w:abstractNum>
+            <w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>"#;
+        let labels: Vec<(String, Option<String>)> =
+            list_paragraphs(numbering, &[("1", 0), ("1", 1), ("1", 2)])
+                .into_iter()
+                .map(|p| (p.list_label, p.list_label_font))
+                .collect();
+        assert_eq!(
+            labels,
+            [
+                ("1.".to_string(), Some("Courier New".to_string())),
+                // The Symbol bullet becomes U+2022 in the text font
+                ("\u{2022}".to_string(), None),
+                // Other symbols are drawn from their own font
+                ("\u{F0A7}".to_string(), Some("Wingdings".to_string())),
+            ]
+        );
     }
 }
@@ -0,0 +1,113 @@
+use crate::model::{Document, Paragraph, RevisionKind, Run, UnderlineStyle};
+
+use super::for_each_paragraph;
+
+/// Markup colors, assigned to reviewers in order of their first change.
+const AUTHOR_COLORS: [[u8; 3]; 8] = [
+    [0xC0, 0x00, 0x00],
+    [0x00, 0x4E, 0xC8],
+    [0x00, 0x80, 0x00],
+    [0x80, 0x00, 0x80],
+    [0xCC, 0x66, 0x00],
+    [0x00, 0x80, 0x80],
+    [0x99, 0x33, 0x66],
+    [0x66, 0x66, 0x00],
+];
+
+/// Resolves tracked changes. Accepting drops deleted runs and keeps insertions
+/// as plain text; otherwise they show as Word's markup view does, insertions
+/// underlined and deletions struck through in a color per reviewer.
+pub fn apply_revisions(doc: &mut Document, accept: bool) {
+    let mut authors: Vec<Option<String>> = Vec::new();
+    for_each_paragraph(doc, &mut |para| {
+        if accept {
+            accept_in_paragraph(para);
+            return;
+        }
+        for run in &mut para.runs {
+            let Some(revision) = &run.revision else {
+                continue;
+            };
+            let idx = match authors.iter().position(|a| *a == revision.author) {
+                Some(idx) => idx,
+                None => {
+                    authors.push(revision.author.clone());
+                    authors.len() - 1
+                }
+            };
+            run.color = Some(AUTHOR_COLORS[idx % AUTHOR_COLORS.len()]);
+            match revision.kind {
+                RevisionKind::Insertion => run.underline = UnderlineStyle::Single,
+                RevisionKind::Deletion => run.strikethrough = true,
+            }
+        }
+    });
+}
+
+fn accept_in_paragraph(para: &mut Paragraph) {
+    let is_deleted = |r: &Run| {
+        r.revision
+            .as_ref()
+            .is_some_and(|rev| rev.kind == RevisionKind::Deletion)
+    };
+    if !para.runs.iter().any(is_deleted) {
+        for run in &mut para.runs {
+            run.revision = None;
+        }
+        return;
+    }
+    // A paragraph that loses all its text keeps one empty run for its line height
+    let placeholder = Run {
+        text: String::new(),
+        revision: None,
+        ..para.runs[0].clone()
+    };
+    para.runs.retain(|r| !is_deleted(r));
+    for run in &mut para.runs {
+        run.revision = None;
+    }
+    if para.runs.is_empty() {
+        para.runs.push(placeholder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConversionOptions;
+    use crate::docx::testing;
+
+    const BODY: &str = r#"<w:p><w:r><w:t xml:space="preserve">Keep </w:t></w:r><w:ins w:id="1" w:author="Ann"><w:r><w:t>new</w:t></w:r></w:ins><w:del w:id="2" w:author="Bob"><w:r><w:delText>old</w:delText></w:r></w:del></w:p>"#;
+
+    fn parse(accept_changes: bool) -> Document {
+        let options = ConversionOptions {
+            accept_changes,
+            ..ConversionOptions::default()
+        };
+        testing::parse_with(BODY, &[], &options)
+    }
+
+    #[test]
+    fn test_tracked_changes_show_as_markup_per_author() {
+        let doc = parse(false);
+        let runs = &testing::paragraphs(&doc)[0].runs;
+        let texts: Vec<&str> = runs.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, ["Keep ", "new", "old"]);
+
+        assert_eq!(runs[0].color, None);
+        assert_eq!(runs[1].underline, UnderlineStyle::Single);
+        assert_eq!(runs[1].color, Some(AUTHOR_COLORS[0]));
+        assert!(runs[2].strikethrough);
+        assert_eq!(runs[2].color, Some(AUTHOR_COLORS[1]));
+    }
+
+    #[test]
+    fn test_accepting_changes_drops_deletions() {
+        let doc = parse(true);
+        let runs = &testing::paragraphs(&doc)[0].runs;
+        let texts: Vec<&str> = runs.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, ["Keep ", "new"]);
+        assert!(runs.iter().all(|r| r.revision.is_none()));
+        assert_eq!(runs[1].underline, UnderlineStyle::None);
+    }
+}
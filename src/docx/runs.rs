@@ -2,43 +2,30 @@ use std::collections::HashMap;
 use std::io::Read;
 
 use crate::model::{
-    ConnectorShape, FieldCode, FloatingImage, InlineChart, Run, SmartArtDiagram, Textbox, VertAlign,
+    CharBorder, ConnectorShape, FieldCode, FloatingImage, InlineChart, Revision, RevisionKind, Run,
+    SmartArtDiagram, TextEffects, Textbox, UnderlineStyle, VertAlign,
 };
 
+use super::fields::{is_dynamic_field, parse_field_code, parse_hyperlink_field};
 use super::images::{RunDrawingResult, parse_run_drawing};
 use super::is_east_asian_char;
+use super::math::{is_math_node, math_runs};
 use super::numbering::NumberingInfo;
 use super::styles::{
-    StylesInfo, ThemeFonts, resolve_east_asia_font_from_node, resolve_font_from_node,
+    StylesInfo, ThemeFonts, parse_cs_font_size, parse_lang, parse_position, parse_text_effects,
+    parse_text_scale, parse_underline, parse_underline_color, resolve_cs_font_from_node,
+    resolve_east_asia_font_from_node, resolve_font_from_node,
 };
+use super::symbols::resolve_symbol;
 use super::textbox::parse_textbox_from_vml;
-use super::{WML_NS, highlight_color, parse_text_color, twips_to_pts, wml, wml_attr, wml_bool};
+use super::{
+    WML_NS, highlight_color, parse_char_border, parse_text_color, twips_to_pts, wml, wml_attr,
+    wml_bool,
+};
 
 const MC_NS: &str = "http://schemas.openxmlformats.org/markup-compatibility/2006";
 const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
 
-fn is_dynamic_field(instr: &str) -> bool {
-    let keyword = instr.split_whitespace().next().unwrap_or("");
-    keyword.eq_ignore_ascii_case("PAGE")
-        || keyword.eq_ignore_ascii_case("NUMPAGES")
-        || keyword.eq_ignore_ascii_case("STYLEREF")
-}
-
-fn parse_styleref_arg(instr: &str) -> Option<String> {
-    let trimmed = instr.trim();
-    let kw = trimmed.split_whitespace().next()?;
-    if !kw.eq_ignore_ascii_case("styleref") {
-        return None;
-    }
-    let rest = trimmed[kw.len()..].trim();
-    if let Some(quoted) = rest.strip_prefix('"') {
-        let end = quoted.find('"')?;
-        Some(quoted[..end].to_string())
-    } else {
-        Some(rest.split_whitespace().next()?.to_string())
-    }
-}
-
 fn mc_choice_or_fallback<'a>(node: roxmltree::Node<'a, 'a>) -> Option<roxmltree::Node<'a, 'a>> {
     let choice = node
         .children()
@@ -66,19 +53,26 @@ struct RunFormat {
     font_size: f32,
     font_name: String,
     east_asia_font_name: Option<String>,
+    cs_font_name: Option<String>,
+    cs_font_size: Option<f32>,
     bold: bool,
     italic: bool,
-    underline: bool,
+    underline: UnderlineStyle,
+    underline_color: Option<[u8; 3]>,
     strikethrough: bool,
     dstrike: bool,
     char_spacing: f32,
     text_scale: f32,
+    position: f32,
+    lang: Option<String>,
     caps: bool,
     small_caps: bool,
     vanish: bool,
+    effects: TextEffects,
     color: Option<[u8; 3]>,
     vertical_align: VertAlign,
     highlight: Option<[u8; 3]>,
+    char_border: Option<CharBorder>,
     kern_threshold: Option<f32>,
     char_style_id: Option<String>,
 }
@@ -91,19 +85,26 @@ impl RunFormat {
             font_size: self.font_size,
             font_name: self.font_name.clone(),
             east_asia_font_name: self.east_asia_font_name.clone(),
+            cs_font_name: self.cs_font_name.clone(),
+            cs_font_size: self.cs_font_size,
             bold: self.bold,
             italic: self.italic,
             underline: self.underline,
+            underline_color: self.underline_color,
             strikethrough: self.strikethrough,
             dstrike: self.dstrike,
             char_spacing: self.char_spacing,
             text_scale: self.text_scale,
+            position: self.position,
+            lang: self.lang.clone(),
             caps: self.caps,
             small_caps: self.small_caps,
             vanish: self.vanish,
+            effects: self.effects,
             color: self.color,
             vertical_align: self.vertical_align,
             highlight: self.highlight,
+            char_border: self.char_border,
             kern_threshold: self.kern_threshold,
             char_style_id: self.char_style_id.clone(),
             hyperlink_url,
@@ -139,124 +140,212 @@ impl RunFormat {
     }
 }
 
-fn split_run_by_script(run: Run) -> Vec<Run> {
-    let ea_font = match &run.east_asia_font_name {
-        Some(f) if f != &run.font_name => f.clone(),
-        _ => return vec![run],
-    };
+/// Characters Word draws with the eastAsia font when `w:rFonts@w:hint` is
+/// "eastAsia": punctuation, symbols and Greek/Cyrillic shared with CJK fonts.
+fn is_east_asia_hint_char(ch: char) -> bool {
+    matches!(ch as u32,
+        0x00A1..=0x00BF | 0x00D7 | 0x00F7
+        | 0x0370..=0x04FF // Greek, Cyrillic
+        | 0x2000..=0x27BF // punctuation, letterlike, arrows, math, shapes
+    )
+}
 
-    let text = &run.text;
-    if text.is_empty() {
-        return vec![run];
+/// Hebrew, Arabic, Syriac, Thaana, Indic and Thai text, which Word draws with
+/// the run's complex-script font and size.
+fn is_complex_script_char(ch: char) -> bool {
+    matches!(ch as u32,
+        0x0590..=0x07BF   // Hebrew, Arabic, Syriac, Arabic Supplement, Thaana
+        | 0x0860..=0x08FF // Syriac Supplement, Arabic Extended
+        | 0x0900..=0x0E7F // Indic scripts, Thai
+        | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms A
+        | 0xFE70..=0xFEFF // Arabic presentation forms B
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    Latin,
+    EastAsian,
+    Complex,
+}
+
+/// Splits a run into Latin, East Asian and complex-script sub-runs, giving
+/// each the run's eastAsia or cs font (and szCs size for complex scripts).
+fn split_run_by_script(run: Run, east_asia_hint: bool) -> Vec<Run> {
+    let ea_font = run
+        .east_asia_font_name
+        .clone()
+        .filter(|f| f != &run.font_name);
+    let has_cs = run.cs_font_name.is_some() || run.cs_font_size.is_some();
+    if (ea_font.is_none() && !has_cs) || run.text.is_empty() {
+        let mut r = run;
+        r.east_asia_font_name = None;
+        r.cs_font_name = None;
+        r.cs_font_size = None;
+        return vec![r];
     }
 
+    let script_of = |ch: char| {
+        if is_east_asian_char(ch) || (east_asia_hint && is_east_asia_hint_char(ch)) {
+            Script::EastAsian
+        } else if is_complex_script_char(ch) {
+            Script::Complex
+        } else {
+            Script::Latin
+        }
+    };
+    let make_sub = |text: &str, script: Script| {
+        let mut sub = run.clone();
+        sub.text = text.to_string();
+        match script {
+            Script::EastAsian => {
+                if let Some(f) = &ea_font {
+                    sub.font_name = f.clone();
+                }
+            }
+            Script::Complex => {
+                if let Some(f) = &run.cs_font_name {
+                    sub.font_name = f.clone();
+                }
+                if let Some(sz) = run.cs_font_size {
+                    sub.font_size = sz;
+                }
+            }
+            Script::Latin => {}
+        }
+        sub.east_asia_font_name = None;
+        sub.cs_font_name = None;
+        sub.cs_font_size = None;
+        sub
+    };
+
+    let text = &run.text;
     let mut result: Vec<Run> = Vec::new();
     let mut segment_start = 0;
-    let mut in_ea = false;
-    let mut first = true;
+    let mut current: Option<Script> = None;
 
     for (i, ch) in text.char_indices() {
-        let ch_is_ea = if ch.is_whitespace() {
-            // Whitespace inherits current script context
-            in_ea
-        } else {
-            is_east_asian_char(ch)
+        // Whitespace inherits current script context
+        let script = match current {
+            Some(cur) if ch.is_whitespace() => cur,
+            _ => script_of(ch),
         };
-
-        if first {
-            in_ea = ch_is_ea;
-            first = false;
-            continue;
-        }
-
-        if ch_is_ea != in_ea {
-            let segment = &text[segment_start..i];
-            if !segment.is_empty() {
-                let mut sub = run.clone();
-                sub.text = segment.to_string();
-                if in_ea {
-                    sub.font_name = ea_font.clone();
-                }
-                sub.east_asia_font_name = None;
-                result.push(sub);
-            }
+        if let Some(cur) = current
+            && script != cur
+        {
+            result.push(make_sub(&text[segment_start..i], cur));
             segment_start = i;
-            in_ea = ch_is_ea;
         }
+        current = Some(script);
     }
-
-    let segment = &text[segment_start..];
-    if !segment.is_empty() {
-        let mut sub = run.clone();
-        sub.text = segment.to_string();
-        if in_ea {
-            sub.font_name = ea_font;
-        }
-        sub.east_asia_font_name = None;
-        result.push(sub);
+    if let Some(cur) = current {
+        result.push(make_sub(&text[segment_start..], cur));
     }
+    result
+}
 
-    if result.is_empty() {
-        let mut r = run;
-        r.east_asia_font_name = None;
-        vec![r]
+/// A run node with its hyperlink target, whether that target is an anchor-only
+/// link, and the tracked change enclosing it.
+type RunNode<'a> = (
+    roxmltree::Node<'a, 'a>,
+    Option<String>,
+    bool,
+    Option<Revision>,
+);
+
+/// Text of a `w:t`. Word drops the whitespace around it unless the element
+/// has `xml:space="preserve"`.
+fn run_text<'a>(t: roxmltree::Node<'a, '_>) -> Option<&'a str> {
+    let text = t.text()?;
+    if t.attribute((roxmltree::NS_XML_URI, "space")) == Some("preserve") {
+        Some(text)
     } else {
-        result
+        Some(text.trim_matches([' ', '\t', '\r', '\n']))
+    }
+}
+
+fn revision_of(node: roxmltree::Node, kind: RevisionKind) -> Revision {
+    Revision {
+        kind,
+        author: node.attribute((WML_NS, "author")).map(String::from),
     }
 }
 
 fn collect_run_nodes<'a>(
     parent: roxmltree::Node<'a, 'a>,
     rels: &HashMap<String, String>,
-    out: &mut Vec<(roxmltree::Node<'a, 'a>, Option<String>, bool)>,
+    revision: Option<&Revision>,
+    out: &mut Vec<RunNode<'a>>,
 ) {
     for child in parent.children() {
         let name = child.tag_name().name();
         let ns = child.tag_name().namespace();
         let is_wml = ns == Some(WML_NS);
         if is_wml && name == "r" {
-            out.push((child, None, false));
+            out.push((child, None, false, revision.cloned()));
         } else if is_wml && name == "hyperlink" {
             let has_rid = child.attribute((REL_NS, "id")).is_some();
-            let has_anchor = child.attribute((WML_NS, "anchor")).is_some();
-            let is_anchor_only = has_anchor && !has_rid;
-            let url = child
+            let anchor = child.attribute((WML_NS, "anchor"));
+            let is_anchor_only = anchor.is_some() && !has_rid;
+            let target = child
                 .attribute((REL_NS, "id"))
-                .and_then(|rid| rels.get(rid))
-                .cloned();
+                .and_then(|rid| rels.get(rid));
+            // "#name" targets a bookmark in this document, like the HYPERLINK \l switch
+            let url = match (target, anchor) {
+                (Some(url), Some(anchor)) => Some(format!("{url}#{anchor}")),
+                (Some(url), None) => Some(url.clone()),
+                (None, Some(anchor)) => Some(format!("#{anchor}")),
+                (None, None) => None,
+            };
             for n in child
                 .children()
                 .filter(|n| n.tag_name().name() == "r" && n.tag_name().namespace() == Some(WML_NS))
             {
-                out.push((n, url.clone(), is_anchor_only));
+                out.push((n, url.clone(), is_anchor_only, revision.cloned()));
             }
-        } else if is_wml && matches!(name, "ins" | "smartTag") {
-            collect_run_nodes(child, rels, out);
-        } else if is_wml && name == "del" {
-            // Final mode: skip deleted content entirely
+        } else if is_wml && matches!(name, "ins" | "moveTo") {
+            let ins = revision_of(child, RevisionKind::Insertion);
+            collect_run_nodes(child, rels, Some(&ins), out);
+        } else if is_wml && matches!(name, "del" | "moveFrom") {
+            let del = revision_of(child, RevisionKind::Deletion);
+            collect_run_nodes(child, rels, Some(&del), out);
+        } else if is_math_node(child) {
+            out.push((child, None, false, revision.cloned()));
+        } else if is_wml && name == "smartTag" {
+            collect_run_nodes(child, rels, revision, out);
         } else if is_wml && name == "sdt" {
             if let Some(content) = wml(child, "sdtContent") {
-                collect_run_nodes(content, rels, out);
+                collect_run_nodes(content, rels, revision, out);
             }
         } else if ns == Some(MC_NS) && name == "AlternateContent" {
             if let Some(branch) = mc_choice_or_fallback(child) {
-                collect_run_nodes(branch, rels, out);
+                collect_run_nodes(branch, rels, revision, out);
             }
         }
     }
 }
 
+/// Pictures without a link of their own link to the enclosing `w:hyperlink`.
 macro_rules! handle_drawing_result {
-    ($result:expr, $fmt:expr, $runs:expr, $floating_images:expr, $textboxes:expr,
-     $inline_chart:expr, $smartart:expr, $connectors:expr) => {
+    ($result:expr, $fmt:expr, $hyperlink_url:expr, $runs:expr, $floating_images:expr,
+     $textboxes:expr, $inline_chart:expr, $smartart:expr, $connectors:expr) => {
         match $result {
-            Some(RunDrawingResult::Inline(img)) => {
+            Some(RunDrawingResult::Inline(mut img)) => {
+                img.hyperlink_url = img.hyperlink_url.or_else(|| $hyperlink_url.clone());
                 $runs.push(Run {
+                    hyperlink_url: img.hyperlink_url.clone(),
                     inline_image: Some(img),
                     ..$fmt.minimal_run()
                 });
             }
-            Some(RunDrawingResult::Floating(fi)) => $floating_images.push(fi),
+            Some(RunDrawingResult::Floating(mut fi)) => {
+                fi.image.hyperlink_url = fi
+                    .image
+                    .hyperlink_url
+                    .take()
+                    .or_else(|| $hyperlink_url.clone());
+                $floating_images.push(fi);
+            }
             Some(RunDrawingResult::TextBox(tb)) => $textboxes.push(tb),
             Some(RunDrawingResult::Chart(ic)) => $inline_chart = Some(ic),
             Some(RunDrawingResult::SmartArt(diagram)) => $smartart = Some(diagram),
@@ -302,9 +391,13 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
     let style_vanish = para_style
         .and_then(|s| s.vanish)
         .unwrap_or(styles.defaults.vanish);
+    let style_effects = para_style.and_then(|s| s.effects).unwrap_or_default();
     let style_underline = para_style
         .and_then(|s| s.underline)
         .unwrap_or(styles.defaults.underline);
+    let style_underline_color = para_style
+        .and_then(|s| s.underline_color)
+        .or(styles.defaults.underline_color);
     let style_strikethrough = para_style
         .and_then(|s| s.strikethrough)
         .unwrap_or(styles.defaults.strikethrough);
@@ -315,15 +408,30 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
     let style_char_spacing = para_style
         .and_then(|s| s.char_spacing)
         .unwrap_or(styles.defaults.char_spacing);
+    let style_position = para_style
+        .and_then(|s| s.position)
+        .unwrap_or(styles.defaults.position);
+    let style_lang = para_style
+        .and_then(|s| s.lang.as_deref())
+        .or(styles.defaults.lang.as_deref());
+    let style_text_scale = para_style
+        .and_then(|s| s.text_scale)
+        .unwrap_or(styles.defaults.text_scale);
     let style_kern_threshold: Option<f32> = para_style
         .and_then(|s| s.kern_threshold)
         .or(styles.defaults.kern_threshold);
     let style_east_asia_font: Option<&str> = para_style
         .and_then(|s| s.east_asia_font.as_deref())
         .or(styles.defaults.east_asia_font.as_deref());
+    let style_cs_font: Option<&str> = para_style
+        .and_then(|s| s.cs_font.as_deref())
+        .or(styles.defaults.cs_font.as_deref());
+    let style_cs_font_size: Option<f32> = para_style
+        .and_then(|s| s.cs_font_size)
+        .or(styles.defaults.cs_font_size);
 
-    let mut run_nodes: Vec<(roxmltree::Node, Option<String>, bool)> = Vec::new();
-    collect_run_nodes(para_node, rels, &mut run_nodes);
+    let mut run_nodes: Vec<RunNode> = Vec::new();
+    collect_run_nodes(para_node, rels, None, &mut run_nodes);
 
     let mut runs = Vec::new();
     let mut floating_images: Vec<FloatingImage> = Vec::new();
@@ -337,8 +445,31 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
     let mut in_field_result = false;
     let mut field_instr = String::new();
     let mut field_result_text = String::new();
+    // Fields open in this paragraph, counting nested ones. An end at depth 0
+    // closes a field begun in an earlier paragraph.
+    let mut field_depth = 0usize;
+    // Marker for a TOC field's result and where it starts, plus the TOC's depth
+    // while it is still open
+    let mut toc_start: Option<(usize, Run)> = None;
+    let mut toc_depth: Option<usize> = None;
+    // Target of an enclosing HYPERLINK field, applied to its result runs
+    let mut field_hyperlink: Option<String> = None;
+    // Comment reference with no text before it in the paragraph
+    let mut unanchored_comment: Option<u32> = None;
 
-    for (run_node, hyperlink_url, is_anchor_hyperlink) in run_nodes {
+    for (run_node, hyperlink_url, is_anchor_hyperlink, revision) in run_nodes {
+        if is_math_node(run_node) {
+            let base = Run {
+                font_size: style_font_size,
+                color: style_color,
+                bold: style_bold,
+                ..Run::default()
+            };
+            runs.extend(math_runs(run_node, &base));
+            continue;
+        }
+        let hyperlink_url = hyperlink_url.or_else(|| field_hyperlink.clone());
+        let first_new_run = runs.len();
         let rpr = wml(run_node, "rPr");
 
         let char_style_id_str = rpr.and_then(|n| wml_attr(n, "rStyle"));
@@ -348,7 +479,15 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
             char_style_id_str.and_then(|id| styles.character_styles.get(id))
         };
 
+        let effects_base = char_style
+            .and_then(|cs| cs.effects)
+            .unwrap_or(style_effects);
+        let effects = rpr
+            .and_then(|n| parse_text_effects(n, effects_base))
+            .unwrap_or(effects_base);
         let rfonts_node = rpr.and_then(|n| wml(n, "rFonts"));
+        let east_asia_hint =
+            rfonts_node.and_then(|n| n.attribute((WML_NS, "hint"))) == Some("eastAsia");
         let fmt = RunFormat {
             font_size: rpr
                 .and_then(|n| wml_attr(n, "sz"))
@@ -364,6 +503,14 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                 .and_then(|rfonts| resolve_east_asia_font_from_node(rfonts, theme))
                 .or_else(|| char_style.and_then(|cs| cs.east_asia_font.clone()))
                 .or_else(|| style_east_asia_font.map(|s| s.to_string())),
+            cs_font_name: rfonts_node
+                .and_then(|rfonts| resolve_cs_font_from_node(rfonts, theme))
+                .or_else(|| char_style.and_then(|cs| cs.cs_font.clone()))
+                .or_else(|| style_cs_font.map(|s| s.to_string())),
+            cs_font_size: rpr
+                .and_then(parse_cs_font_size)
+                .or_else(|| char_style.and_then(|cs| cs.cs_font_size))
+                .or(style_cs_font_size),
             bold: rpr
                 .and_then(|n| wml_bool(n, "b"))
                 .or_else(|| char_style.and_then(|cs| cs.bold))
@@ -373,30 +520,40 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                 .or_else(|| char_style.and_then(|cs| cs.italic))
                 .unwrap_or(style_italic),
             underline: rpr
-                .and_then(|n| {
-                    wml(n, "u")
-                        .and_then(|u| u.attribute((WML_NS, "val")))
-                        .map(|v| v != "none")
-                })
+                .and_then(parse_underline)
                 .or_else(|| char_style.and_then(|cs| cs.underline))
                 .unwrap_or(style_underline),
+            underline_color: rpr
+                .and_then(parse_underline_color)
+                .or_else(|| char_style.and_then(|cs| cs.underline_color))
+                .or(style_underline_color),
             strikethrough: rpr
                 .and_then(|n| wml_bool(n, "strike"))
                 .or_else(|| char_style.and_then(|cs| cs.strikethrough))
                 .unwrap_or(style_strikethrough),
             dstrike: rpr
                 .and_then(|n| wml_bool(n, "dstrike"))
+                .or_else(|| char_style.and_then(|cs| cs.dstrike))
                 .unwrap_or(style_dstrike),
             char_spacing: rpr
                 .and_then(|n| wml(n, "spacing"))
                 .and_then(|n| n.attribute((WML_NS, "val")))
                 .and_then(|v| v.parse::<f32>().ok())
                 .map(twips_to_pts)
+                .or_else(|| char_style.and_then(|cs| cs.char_spacing))
                 .unwrap_or(style_char_spacing),
             text_scale: rpr
-                .and_then(|n| wml_attr(n, "w"))
-                .and_then(|v| v.trim_end_matches('%').parse::<f32>().ok())
-                .unwrap_or(100.0),
+                .and_then(parse_text_scale)
+                .or_else(|| char_style.and_then(|cs| cs.text_scale))
+                .unwrap_or(style_text_scale),
+            position: rpr
+                .and_then(parse_position)
+                .or_else(|| char_style.and_then(|cs| cs.position))
+                .unwrap_or(style_position),
+            lang: rpr
+                .and_then(parse_lang)
+                .or_else(|| char_style.and_then(|cs| cs.lang.clone()))
+                .or_else(|| style_lang.map(String::from)),
             caps: rpr
                 .and_then(|n| wml_bool(n, "caps"))
                 .or_else(|| char_style.and_then(|cs| cs.caps))
@@ -409,6 +566,7 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                 .and_then(|n| wml_bool(n, "vanish"))
                 .or_else(|| char_style.and_then(|cs| cs.vanish))
                 .unwrap_or(style_vanish),
+            effects,
             color: rpr
                 .and_then(|n| wml_attr(n, "color"))
                 .and_then(parse_text_color)
@@ -425,6 +583,10 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
             highlight: rpr
                 .and_then(|n| wml_attr(n, "highlight"))
                 .and_then(highlight_color),
+            char_border: rpr
+                .and_then(|n| wml(n, "bdr"))
+                .and_then(parse_char_border)
+                .or_else(|| char_style.and_then(|cs| cs.char_border)),
             kern_threshold: rpr
                 .and_then(|n| wml_attr(n, "kern"))
                 .and_then(|v| v.parse::<f32>().ok())
@@ -437,7 +599,7 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
         let flush_pending = |pending: &mut String, runs: &mut Vec<Run>| {
             if !pending.is_empty() {
                 let run = fmt.text_run(std::mem::take(pending), hyperlink_url.clone());
-                runs.extend(split_run_by_script(run));
+                runs.extend(split_run_by_script(run, east_asia_hint));
             }
         };
 
@@ -457,6 +619,7 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                         handle_drawing_result!(
                             result,
                             fmt,
+                            hyperlink_url,
                             runs,
                             floating_images,
                             textboxes,
@@ -483,10 +646,14 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
             if child_ns != Some(WML_NS) {
                 continue;
             }
+            // Outside fields, and in the cached result of a field that isn't
+            // recomputed, content shows as written
+            let literal = !in_field || (in_field_result && !is_dynamic_field(&field_instr));
             match child.tag_name().name() {
                 "fldChar" => match child.attribute((WML_NS, "fldCharType")) {
                     Some("begin") => {
                         flush_pending(&mut pending_text, &mut runs);
+                        field_depth += 1;
                         in_field = true;
                         in_field_result = false;
                         field_instr.clear();
@@ -494,58 +661,107 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                     }
                     Some("separate") => {
                         in_field_result = true;
+                        field_hyperlink = parse_hyperlink_field(&field_instr);
+                        if in_field
+                            && let Some(code @ FieldCode::Toc { .. }) =
+                                parse_field_code(&field_instr)
+                        {
+                            let marker = Run {
+                                field_code: Some(code),
+                                ..fmt.styled_run()
+                            };
+                            toc_start = Some((runs.len(), marker));
+                            toc_depth = Some(field_depth);
+                        }
                     }
                     Some("end") => {
-                        if in_field {
-                            let keyword = field_instr.split_whitespace().next().unwrap_or("");
-                            let fc = if keyword.eq_ignore_ascii_case("PAGE") {
-                                Some(FieldCode::Page)
-                            } else if keyword.eq_ignore_ascii_case("NUMPAGES") {
-                                Some(FieldCode::NumPages)
-                            } else if keyword.eq_ignore_ascii_case("STYLEREF") {
-                                parse_styleref_arg(&field_instr).map(FieldCode::StyleRef)
-                            } else {
-                                None
+                        // A TOC without a result
+                        if in_field
+                            && toc_depth.is_none()
+                            && let Some(code @ FieldCode::Toc { .. }) =
+                                parse_field_code(&field_instr)
+                        {
+                            let marker = Run {
+                                field_code: Some(code),
+                                ..fmt.styled_run()
                             };
-                            if let Some(code) = fc {
-                                let text = std::mem::take(&mut field_result_text);
+                            toc_start = Some((runs.len(), marker));
+                            toc_depth = Some(field_depth);
+                        }
+                        if field_depth == 0 || toc_depth == Some(field_depth) {
+                            // Generated TOCs replace the runs up to here
+                            flush_pending(&mut pending_text, &mut runs);
+                            runs.push(Run {
+                                field_code: Some(FieldCode::FieldEnd),
+                                ..fmt.styled_run()
+                            });
+                            toc_depth = None;
+                        } else if in_field {
+                            if let Some(code) = parse_field_code(&field_instr) {
+                                let cached = std::mem::take(&mut field_result_text);
+                                let mut text = cached;
+                                // Never-updated PAGEREF: reserve roughly a page number's width
+                                if text.is_empty() && matches!(code, FieldCode::PageRef(_)) {
+                                    text = "0".to_string();
+                                }
+                                let mut font_name = fmt.font_name.clone();
+                                if let FieldCode::Symbol { char_code, font } = &code
+                                    && let Some((ch, font)) = resolve_symbol(
+                                        font.as_deref().unwrap_or(&fmt.font_name),
+                                        *char_code,
+                                        &fmt.font_name,
+                                    )
+                                {
+                                    text = ch.to_string();
+                                    font_name = font.to_string();
+                                }
                                 runs.push(Run {
                                     text,
+                                    font_name,
                                     field_code: Some(code),
                                     hyperlink_url: hyperlink_url.clone(),
                                     ..fmt.styled_run()
                                 });
+                            } else {
+                                // A field we can't evaluate (a REF without a bookmark,
+                                // say) still shows the result Word last computed
+                                pending_text.push_str(&std::mem::take(&mut field_result_text));
                             }
-                            in_field = false;
-                            in_field_result = false;
-                            field_instr.clear();
                         }
+                        field_depth = field_depth.saturating_sub(1);
+                        in_field = false;
+                        in_field_result = false;
+                        field_instr.clear();
+                        field_hyperlink = None;
                     }
                     _ => {}
                 },
-                "instrText" if in_field && !in_field_result => {
+                "instrText" | "delInstrText" if in_field && !in_field_result => {
                     if let Some(t) = child.text() {
                         field_instr.push_str(t);
                     }
                 }
-                "t" if !in_field || (in_field_result && !is_dynamic_field(&field_instr)) => {
-                    if let Some(t) = child.text() {
+                "t" | "delText" if literal => {
+                    if let Some(t) = run_text(child) {
                         pending_text.push_str(&t.replace('\n', " "));
                     }
                 }
-                "t" if in_field_result && is_dynamic_field(&field_instr) => {
-                    if let Some(t) = child.text() {
+                "t" | "delText" if in_field_result => {
+                    if let Some(t) = run_text(child) {
                         field_result_text.push_str(t);
                     }
                 }
-                "tab" if !in_field => {
+                // Lines break only at spaces, so a plain hyphen stays with its word
+                "noBreakHyphen" if literal => pending_text.push('-'),
+                "softHyphen" if literal => pending_text.push('\u{AD}'),
+                "tab" if literal => {
                     flush_pending(&mut pending_text, &mut runs);
                     runs.push(Run {
                         is_tab: true,
                         ..fmt.minimal_run()
                     });
                 }
-                "br" if !in_field => match child.attribute((WML_NS, "type")) {
+                "br" if literal => match child.attribute((WML_NS, "type")) {
                     Some("page") => has_page_break_after = true,
                     Some("column") => has_column_break = true,
                     _ => {
@@ -563,6 +779,7 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                     handle_drawing_result!(
                         result,
                         fmt,
+                        hyperlink_url,
                         runs,
                         floating_images,
                         textboxes,
@@ -590,7 +807,32 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                         });
                     }
                 }
-                "footnoteRef" if !in_field => {
+                "endnoteReference" if !in_field => {
+                    flush_pending(&mut pending_text, &mut runs);
+                    if let Some(id) = child
+                        .attribute((WML_NS, "id"))
+                        .and_then(|v| v.parse::<u32>().ok())
+                    {
+                        runs.push(Run {
+                            endnote_id: Some(id),
+                            ..fmt.superscript_run()
+                        });
+                    }
+                }
+                "commentReference" if !in_field => {
+                    flush_pending(&mut pending_text, &mut runs);
+                    // The reference follows the commented range; anchor on its last run
+                    if let Some(id) = child
+                        .attribute((WML_NS, "id"))
+                        .and_then(|v| v.parse::<u32>().ok())
+                    {
+                        match runs.iter_mut().rev().find(|r| !r.text.is_empty()) {
+                            Some(run) => run.comment_id = Some(id),
+                            None => unanchored_comment = Some(id),
+                        }
+                    }
+                }
+                "footnoteRef" | "endnoteRef" if !in_field => {
                     flush_pending(&mut pending_text, &mut runs);
                     runs.push(Run {
                         is_footnote_ref_mark: true,
@@ -600,21 +842,23 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
                 "sym" if !in_field => {
                     flush_pending(&mut pending_text, &mut runs);
                     let sym_font = child.attribute((WML_NS, "font")).unwrap_or(&fmt.font_name);
-                    if let Some(ch) = child
+                    if let Some((ch, font)) = child
                         .attribute((WML_NS, "char"))
                         .and_then(|hex| u32::from_str_radix(hex, 16).ok())
-                        .and_then(char::from_u32)
+                        .and_then(|code| resolve_symbol(sym_font, code, &fmt.font_name))
                     {
                         runs.push(Run {
                             text: ch.to_string(),
-                            font_name: sym_font.to_string(),
+                            font_name: font.to_string(),
                             font_size: fmt.font_size,
                             bold: fmt.bold,
                             italic: fmt.italic,
                             color: fmt.color,
                             underline: fmt.underline,
+                            underline_color: fmt.underline_color,
                             strikethrough: fmt.strikethrough,
                             char_spacing: fmt.char_spacing,
+                            text_scale: fmt.text_scale,
                             ..Run::default()
                         });
                     }
@@ -624,10 +868,28 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
         }
         if !pending_text.is_empty() {
             let run = fmt.text_run(pending_text, hyperlink_url.clone());
-            runs.extend(split_run_by_script(run));
+            runs.extend(split_run_by_script(run, east_asia_hint));
+        }
+        if revision.is_some() {
+            for run in &mut runs[first_new_run..] {
+                run.revision = revision.clone();
+            }
         }
     }
 
+    // A TOC's start is marked last so that indexes recorded while parsing stay put
+    if let Some((start, marker)) = toc_start {
+        runs.insert(start, marker);
+    }
+
+    if let Some(id) = unanchored_comment
+        && let Some(run) = runs
+            .iter_mut()
+            .find(|r| !r.text.is_empty() && r.comment_id.is_none())
+    {
+        run.comment_id = Some(id);
+    }
+
     let has_page_break_before = ppr
         .and_then(|ppr| wml_bool(ppr, "pageBreakBefore"))
         .unwrap_or(false);
@@ -679,3 +941,124 @@ pub(super) fn parse_runs<R: Read + std::io::Seek>(
         smartart,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::docx::testing;
+
+    fn run_fonts(body: &str) -> Vec<(String, String)> {
+        testing::paragraphs(&testing::parse_body(body))[0]
+            .runs
+            .iter()
+            .map(|r| (r.text.clone(), r.font_name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_east_asia_hint_moves_shared_symbols_to_the_east_asia_font() {
+        let run = |hint: &str| {
+            format!(
+                r#"<w:p><w:r><w:rPr><w:rFonts w:ascii="Arial" w:hAnsi="Arial" w:eastAsia="MS Mincho"{hint}/></w:rPr><w:t>A→B</w:t></w:r></w:p>"#
+            )
+        };
+        let owned = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(t, f)| (t.to_string(), f.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            run_fonts(&run(r#" w:hint="eastAsia""#)),
+            owned(&[("A", "Arial"), ("→", "MS Mincho"), ("B", "Arial")])
+        );
+        assert_eq!(run_fonts(&run("")), owned(&[("A→B", "Arial")]));
+    }
+
+    #[test]
+    fn test_complex_script_text_uses_the_cs_font_and_size() {
+        let doc = testing::parse_body(
+            r#"<w:p><w:r><w:rPr><w:rFonts w:ascii="Arial" w:hAnsi="Arial" w:cs="Traditional Arabic"/><w:sz w:val="20"/><w:szCs w:val="28"/></w:rPr><w:t>Hi مرحبا</w:t></w:r></w:p>"#,
+        );
+        let runs: Vec<_> = testing::paragraphs(&doc)[0]
+            .runs
+            .iter()
+            .map(|r| (r.text.as_str(), r.font_name.as_str(), r.font_size))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                ("Hi ", "Arial", 10.0),
+                ("مرحبا", "Traditional Arabic", 14.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_double_strike_comes_from_the_run_or_its_character_style() {
+        let styles = testing::part(
+            "w:styles",
+            r#"<w:style w:type="character" w:styleId="Void"><w:rPr><w:dstrike/></w:rPr></w:style>"#,
+        );
+        let body = concat!(
+            r#"<w:p><w:r><w:t>plain</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:dstrike/></w:rPr><w:t>direct</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:rStyle w:val="Void"/></w:rPr><w:t>styled</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:rStyle w:val="Void"/><w:dstrike w:val="0"/></w:rPr><w:t>off</w:t></w:r></w:p>"#,
+        );
+        let doc = testing::parse_with(
+            body,
+            &[("word/styles.xml", styles.as_bytes())],
+            &crate::ConversionOptions::default(),
+        );
+        let struck: Vec<(&str, bool)> = testing::paragraphs(&doc)[0]
+            .runs
+            .iter()
+            .map(|r| (r.text.as_str(), r.dstrike))
+            .collect();
+        assert_eq!(
+            struck,
+            [
+                ("plain", false),
+                ("direct", true),
+                ("styled", true),
+                ("off", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hyperlink_anchor_targets_a_bookmark_or_a_fragment() {
+        let rels = concat!(
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"<Relationship Id="rIdGuide" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/guide" TargetMode="External"/>"#,
+            r#"</Relationships>"#,
+        );
+        let link = |attrs: &str, text: &str| {
+            format!(r#"<w:hyperlink {attrs}><w:r><w:t>{text}</w:t></w:r></w:hyperlink>"#)
+        };
+        let body = format!(
+            "<w:p>{}{}{}</w:p>",
+            link(r#"w:anchor="Summary""#, "inside"),
+            link(r#"r:id="rIdGuide" w:anchor="install""#, "fragment"),
+            link(r#"r:id="rIdGuide""#, "page"),
+        );
+        let doc = testing::parse_with(
+            &body,
+            &[("word/_rels/document.xml.rels", rels.as_bytes())],
+            &crate::ConversionOptions::default(),
+        );
+        let targets: Vec<Option<&str>> = testing::paragraphs(&doc)[0]
+            .runs
+            .iter()
+            .map(|r| r.hyperlink_url.as_deref())
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                Some("#Summary"),
+                Some("https://example.com/guide#install"),
+                Some("https://example.com/guide")
+            ]
+        );
+    }
+}
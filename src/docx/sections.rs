@@ -1,12 +1,84 @@
 use std::collections::HashMap;
 use std::io::Read;
 
-use crate::model::{ColumnDef, ColumnsConfig, HeaderFooter, SectionBreakType, SectionProperties};
+use crate::model::{
+    ColumnDef, ColumnsConfig, HeaderFooter, LineNumberRestart, LineNumbering, NumberFormat,
+    PageBorderDisplay, PageBorderOffset, PageBorders, PageVAlign, Run, SectionBreakType,
+    SectionProperties,
+};
 
 use super::headers_footers::parse_header_footer_xml;
 use super::relationships::parse_part_relationships;
 use super::styles::{StylesInfo, ThemeFonts};
-use super::{REL_NS, WML_NS, read_zip_text, twips_attr, twips_to_pts, wml};
+use super::{REL_NS, WML_NS, parse_one_border, read_zip_text, twips_attr, twips_to_pts, wml};
+
+fn parse_page_borders(pg_borders: roxmltree::Node) -> Option<PageBorders> {
+    let borders = PageBorders {
+        top: wml(pg_borders, "top").and_then(parse_one_border),
+        bottom: wml(pg_borders, "bottom").and_then(parse_one_border),
+        left: wml(pg_borders, "left").and_then(parse_one_border),
+        right: wml(pg_borders, "right").and_then(parse_one_border),
+        offset_from: match pg_borders.attribute((WML_NS, "offsetFrom")) {
+            Some("page") => PageBorderOffset::Page,
+            _ => PageBorderOffset::Text,
+        },
+        display: match pg_borders.attribute((WML_NS, "display")) {
+            Some("firstPage") => PageBorderDisplay::FirstPage,
+            Some("notFirstPage") => PageBorderDisplay::NotFirstPage,
+            _ => PageBorderDisplay::AllPages,
+        },
+    };
+    let any = borders.top.is_some()
+        || borders.bottom.is_some()
+        || borders.left.is_some()
+        || borders.right.is_some();
+    any.then_some(borders)
+}
+
+/// Word's "Auto" distance between line numbers and text.
+const DEFAULT_LINE_NUMBER_DISTANCE: f32 = 18.0;
+
+fn parse_line_numbering(ln_num_type: roxmltree::Node, styles: &StylesInfo) -> LineNumbering {
+    let count_by = ln_num_type
+        .attribute((WML_NS, "countBy"))
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(1)
+        .max(1);
+    // `w:start` is zero-based: Word's "Start at 1" is written as 0
+    let start = ln_num_type
+        .attribute((WML_NS, "start"))
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0)
+        + 1;
+    let restart = match ln_num_type.attribute((WML_NS, "restart")) {
+        Some("newSection") => LineNumberRestart::NewSection,
+        Some("continuous") => LineNumberRestart::Continuous,
+        _ => LineNumberRestart::NewPage,
+    };
+    let distance = twips_attr(ln_num_type, "distance").unwrap_or(DEFAULT_LINE_NUMBER_DISTANCE);
+
+    let style = styles.character_styles.get("LineNumber");
+    let defaults = &styles.defaults;
+    let run = Run {
+        font_name: style
+            .and_then(|s| s.font_name.clone())
+            .unwrap_or_else(|| defaults.font_name.clone()),
+        font_size: style
+            .and_then(|s| s.font_size)
+            .unwrap_or(defaults.font_size),
+        bold: style.and_then(|s| s.bold).unwrap_or(defaults.bold),
+        italic: style.and_then(|s| s.italic).unwrap_or(defaults.italic),
+        color: style.and_then(|s| s.color).or(defaults.color),
+        ..Run::default()
+    };
+    LineNumbering {
+        count_by,
+        start,
+        restart,
+        distance,
+        run,
+    }
+}
 
 pub(super) fn parse_section_properties<R: Read + std::io::Seek>(
     sect_node: roxmltree::Node,
@@ -15,28 +87,65 @@ pub(super) fn parse_section_properties<R: Read + std::io::Seek>(
     theme: &ThemeFonts,
     zip: &mut zip::ZipArchive<R>,
     default_line_pitch: f32,
+    gutter_at_top: bool,
 ) -> SectionProperties {
     let pg_sz = wml(sect_node, "pgSz");
     let pg_mar = wml(sect_node, "pgMar");
     let doc_grid = wml(sect_node, "docGrid");
 
-    let page_width = pg_sz.and_then(|n| twips_attr(n, "w")).unwrap_or(612.0);
-    let page_height = pg_sz.and_then(|n| twips_attr(n, "h")).unwrap_or(792.0);
-    let margin_top = pg_mar.and_then(|n| twips_attr(n, "top")).unwrap_or(72.0);
+    let mut page_width = pg_sz.and_then(|n| twips_attr(n, "w")).unwrap_or(612.0);
+    let mut page_height = pg_sz.and_then(|n| twips_attr(n, "h")).unwrap_or(792.0);
+    // Some producers write orient="landscape" with portrait w/h (or vice versa);
+    // the orient attribute wins.
+    let landscape = match pg_sz.and_then(|n| n.attribute((WML_NS, "orient"))) {
+        Some("landscape") => Some(true),
+        Some("portrait") => Some(false),
+        _ => None,
+    };
+    if let Some(landscape) = landscape
+        && landscape != (page_width > page_height)
+        && page_width != page_height
+    {
+        std::mem::swap(&mut page_width, &mut page_height);
+    }
+    let mut margin_top = pg_mar.and_then(|n| twips_attr(n, "top")).unwrap_or(72.0);
     let margin_bottom = pg_mar.and_then(|n| twips_attr(n, "bottom")).unwrap_or(72.0);
-    let margin_left = pg_mar.and_then(|n| twips_attr(n, "left")).unwrap_or(72.0);
+    let mut margin_left = pg_mar.and_then(|n| twips_attr(n, "left")).unwrap_or(72.0);
     let margin_right = pg_mar.and_then(|n| twips_attr(n, "right")).unwrap_or(72.0);
     let header_margin = pg_mar.and_then(|n| twips_attr(n, "header")).unwrap_or(36.0);
     let footer_margin = pg_mar.and_then(|n| twips_attr(n, "footer")).unwrap_or(36.0);
+    // The gutter is binding space on top of the inside margin, so it becomes
+    // part of the left (or top) margin; mirror margins move it to the right
+    // on even pages along with the left margin.
+    let gutter = pg_mar.and_then(|n| twips_attr(n, "gutter")).unwrap_or(0.0);
+    if gutter_at_top {
+        margin_top += gutter;
+    } else {
+        margin_left += gutter;
+    }
     let line_pitch = doc_grid
         .and_then(|n| twips_attr(n, "linePitch"))
         .unwrap_or(default_line_pitch);
 
     let different_first_page = wml(sect_node, "titlePg").is_some();
 
-    let page_num_start = wml(sect_node, "pgNumType")
+    let pg_num_type = wml(sect_node, "pgNumType");
+    let page_num_start = pg_num_type
         .and_then(|n| n.attribute((WML_NS, "start")))
         .and_then(|v| v.parse::<u32>().ok());
+    let page_num_format = pg_num_type
+        .and_then(|n| n.attribute((WML_NS, "fmt")))
+        .map(NumberFormat::from_ooxml)
+        .unwrap_or_default();
+
+    let page_borders = wml(sect_node, "pgBorders").and_then(parse_page_borders);
+    let line_numbering = wml(sect_node, "lnNumType").map(|n| parse_line_numbering(n, styles));
+    let v_align = match wml(sect_node, "vAlign").and_then(|n| n.attribute((WML_NS, "val"))) {
+        Some("center") => PageVAlign::Center,
+        Some("both") => PageVAlign::Both,
+        Some("bottom") => PageVAlign::Bottom,
+        _ => PageVAlign::Top,
+    };
 
     let break_type = wml(sect_node, "type")
         .and_then(|n| n.attribute((WML_NS, "val")))
@@ -153,5 +262,52 @@ pub(super) fn parse_section_properties<R: Read + std::io::Seek>(
         break_type,
         columns,
         page_num_start,
+        page_num_format,
+        page_borders,
+        line_numbering,
+        v_align,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::docx::testing;
+
+    #[test]
+    fn test_landscape_section_between_portrait_sections() {
+        let section = |pg_sz: &str| {
+            format!(r#"<w:p><w:pPr><w:sectPr><w:pgSz {pg_sz}/></w:sectPr></w:pPr></w:p>"#)
+        };
+        let body = [
+            section(r#"w:w="12240" w:h="15840""#),
+            // Portrait width and height, but the orientation says landscape
+            section(r#"w:w="12240" w:h="15840" w:orient="landscape""#),
+            r#"<w:p/><w:sectPr><w:pgSz w:w="12240" w:h="15840"/></w:sectPr>"#.to_string(),
+        ]
+        .concat();
+        let doc = testing::parse_body(&body);
+        let sizes: Vec<(f32, f32)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.properties.page_width, s.properties.page_height))
+            .collect();
+        assert_eq!(sizes, [(612.0, 792.0), (792.0, 612.0), (612.0, 792.0)]);
+    }
+
+    #[test]
+    fn test_gutter_widens_the_left_or_top_margin() {
+        let body = r#"<w:p/><w:sectPr><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:gutter="720"/></w:sectPr>"#;
+        let margins = |settings: &str| {
+            let settings = testing::part("w:settings", settings);
+            let doc = testing::parse_with(
+                body,
+                &[("word/settings.xml", settings.as_bytes())],
+                &crate::ConversionOptions::default(),
+            );
+            let sp = &doc.sections[0].properties;
+            (sp.margin_left, sp.margin_top)
+        };
+        assert_eq!(margins(""), (108.0, 72.0));
+        assert_eq!(margins("<w:gutterAtTop/>"), (72.0, 108.0));
     }
 }
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::io::Read;
 
+use crate::model::{NoteProperties, NoteRestart, NumberFormat};
+
 use super::{WML_NS, read_zip_text, twips_to_pts, wml, wml_attr, wml_bool};
 
 pub(super) struct DocumentSettings {
@@ -7,6 +10,14 @@ pub(super) struct DocumentSettings {
     pub default_tab_stop: f32,
     pub mirror_margins: bool,
     pub east_asia_lang: Option<String>,
+    pub lang: Option<String>,
+    pub footnote_properties: NoteProperties,
+    pub endnote_properties: NoteProperties,
+    pub display_background_shape: bool,
+    pub gutter_at_top: bool,
+    pub auto_hyphenation: bool,
+    /// Document variables (`w:docVars`) read by DOCVARIABLE fields
+    pub doc_vars: HashMap<String, String>,
 }
 
 impl Default for DocumentSettings {
@@ -16,10 +27,53 @@ impl Default for DocumentSettings {
             default_tab_stop: 36.0, // 0.5 inches = 720 twips = 36pt
             mirror_margins: false,
             east_asia_lang: None,
+            lang: None,
+            footnote_properties: NoteProperties::default(),
+            endnote_properties: default_endnote_properties(),
+            display_background_shape: false,
+            gutter_at_top: false,
+            auto_hyphenation: false,
+            doc_vars: HashMap::new(),
         }
     }
 }
 
+/// Word numbers endnotes i, ii, iii unless told otherwise.
+fn default_endnote_properties() -> NoteProperties {
+    NoteProperties {
+        num_fmt: NumberFormat::LowerRoman,
+        ..NoteProperties::default()
+    }
+}
+
+/// Reads `w:numFmt`, `w:numStart` and `w:numRestart` from a `w:footnotePr` or
+/// `w:endnotePr` element, keeping `defaults` for anything absent.
+fn parse_note_properties(
+    node: Option<roxmltree::Node>,
+    defaults: NoteProperties,
+) -> NoteProperties {
+    let Some(node) = node else {
+        return defaults;
+    };
+    let num_fmt = wml_attr(node, "numFmt")
+        .map(NumberFormat::from_ooxml)
+        .unwrap_or(defaults.num_fmt);
+    let num_start = wml_attr(node, "numStart")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(defaults.num_start);
+    let restart = match wml_attr(node, "numRestart") {
+        Some("eachSect") => NoteRestart::EachSection,
+        Some("eachPage") => NoteRestart::EachPage,
+        Some(_) => NoteRestart::Continuous,
+        None => defaults.restart,
+    };
+    NoteProperties {
+        num_fmt,
+        num_start,
+        restart,
+    }
+}
+
 pub(super) fn parse_settings<R: Read + std::io::Seek>(
     zip: &mut zip::ZipArchive<R>,
 ) -> DocumentSettings {
@@ -34,16 +88,69 @@ pub(super) fn parse_settings<R: Read + std::io::Seek>(
     let default_tab_stop = wml_attr(root, "defaultTabStop")
         .and_then(|v| v.parse::<f32>().ok())
         .map(twips_to_pts)
+        .filter(|&pt| pt > 0.0)
         .unwrap_or(36.0);
 
-    let east_asia_lang = wml(root, "themeFontLang")
+    let theme_font_lang = wml(root, "themeFontLang");
+    let east_asia_lang = theme_font_lang
         .and_then(|n| n.attribute((WML_NS, "eastAsia")))
         .map(|s| s.to_string());
+    let lang = theme_font_lang
+        .and_then(|n| n.attribute((WML_NS, "val")))
+        .map(|s| s.to_string());
+
+    let footnote_properties =
+        parse_note_properties(wml(root, "footnotePr"), NoteProperties::default());
+    let endnote_properties =
+        parse_note_properties(wml(root, "endnotePr"), default_endnote_properties());
+
+    let doc_vars = wml(root, "docVars")
+        .into_iter()
+        .flat_map(|vars| vars.children())
+        .filter(|n| n.tag_name().namespace() == Some(WML_NS) && n.tag_name().name() == "docVar")
+        .filter_map(|var| {
+            let name = var.attribute((WML_NS, "name"))?;
+            let value = var.attribute((WML_NS, "val"))?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect();
 
     DocumentSettings {
         even_and_odd_headers: wml_bool(root, "evenAndOddHeaders").unwrap_or(false),
         default_tab_stop,
         mirror_margins: wml_bool(root, "mirrorMargins").unwrap_or(false),
         east_asia_lang,
+        lang,
+        footnote_properties,
+        endnote_properties,
+        display_background_shape: wml_bool(root, "displayBackgroundShape").unwrap_or(false),
+        gutter_at_top: wml_bool(root, "gutterAtTop").unwrap_or(false),
+        auto_hyphenation: wml_bool(root, "autoHyphenation").unwrap_or(false),
+        doc_vars,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings_reads_fixture_defaults() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/samples/samtale/input.docx"
+        );
+        let file = std::fs::File::open(path).expect("open fixture");
+        let mut zip = zip::ZipArchive::new(file).expect("zip");
+        let settings = parse_settings(&mut zip);
+        assert!(settings.even_and_odd_headers);
+        assert_eq!(settings.default_tab_stop, twips_to_pts(708.0));
+        assert_eq!(settings.lang.as_deref(), Some("nb-NO"));
+        assert!(!settings.mirror_margins);
+        assert!(!settings.auto_hyphenation);
+        assert_eq!(
+            settings.endnote_properties.num_fmt,
+            NumberFormat::LowerRoman
+        );
     }
 }
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
-use crate::model::{Alignment, CellBorder, LineSpacing, TabStop};
+use crate::model::{
+    Alignment, CellBorder, CharBorder, LineSpacing, TabStop, TextEffects, UnderlineStyle,
+};
 
 use super::{
     DML_NS, WML_NS, parse_cell_border, parse_cell_border_left, parse_cell_border_right,
-    parse_paragraph_borders, parse_tab_stops, parse_text_color, read_zip_text, twips_attr,
-    twips_to_pts, wml, wml_attr, wml_bool,
+    parse_char_border, parse_hex_color, parse_paragraph_borders, parse_tab_stops, parse_text_color,
+    read_zip_text, twips_attr, twips_to_pts, wml, wml_attr, wml_bool,
 };
 
 fn dml<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<roxmltree::Node<'a, 'a>> {
@@ -72,6 +74,8 @@ pub(super) struct ThemeFonts {
     pub(super) minor: String,
     pub(super) major_east_asia: String,
     pub(super) minor_east_asia: String,
+    pub(super) major_cs: String,
+    pub(super) minor_cs: String,
     pub(super) colors: HashMap<String, [u8; 3]>,
     pub(super) fill_styles: Vec<ThemeFillStyle>,
 }
@@ -80,6 +84,8 @@ pub(super) struct StyleDefaults {
     pub(super) font_size: f32,
     pub(super) font_name: String,
     pub(super) east_asia_font: Option<String>,
+    pub(super) cs_font: Option<String>,
+    pub(super) cs_font_size: Option<f32>,
     pub(super) space_after: f32,
     pub(super) line_spacing: LineSpacing,
     pub(super) kern_threshold: Option<f32>,
@@ -90,9 +96,13 @@ pub(super) struct StyleDefaults {
     pub(super) vanish: bool,
     pub(super) strikethrough: bool,
     pub(super) dstrike: bool,
-    pub(super) underline: bool,
+    pub(super) underline: UnderlineStyle,
+    pub(super) underline_color: Option<[u8; 3]>,
     pub(super) color: Option<[u8; 3]>,
     pub(super) char_spacing: f32,
+    pub(super) text_scale: f32,
+    pub(super) position: f32,
+    pub(super) lang: Option<String>,
 }
 
 #[derive(Default)]
@@ -100,23 +110,33 @@ pub(super) struct ParagraphStyle {
     pub(super) font_size: Option<f32>,
     pub(super) font_name: Option<String>,
     pub(super) east_asia_font: Option<String>,
+    pub(super) cs_font: Option<String>,
+    pub(super) cs_font_size: Option<f32>,
     pub(super) bold: Option<bool>,
     pub(super) italic: Option<bool>,
     pub(super) caps: Option<bool>,
     pub(super) small_caps: Option<bool>,
     pub(super) vanish: Option<bool>,
-    pub(super) underline: Option<bool>,
+    pub(super) effects: Option<TextEffects>,
+    pub(super) underline: Option<UnderlineStyle>,
+    pub(super) underline_color: Option<[u8; 3]>,
     pub(super) strikethrough: Option<bool>,
     pub(super) dstrike: Option<bool>,
     pub(super) color: Option<[u8; 3]>,
     pub(super) char_spacing: Option<f32>,
+    pub(super) text_scale: Option<f32>,
+    pub(super) position: Option<f32>,
+    pub(super) lang: Option<String>,
     pub(super) space_before: Option<f32>,
     pub(super) space_after: Option<f32>,
     pub(super) alignment: Option<Alignment>,
     pub(super) contextual_spacing: bool,
     pub(super) keep_next: bool,
     pub(super) keep_lines: bool,
+    pub(super) suppress_auto_hyphens: bool,
     pub(super) page_break_before: bool,
+    pub(super) bidi: Option<bool>,
+    pub(super) outline_level: Option<u8>,
     pub(super) line_spacing: Option<LineSpacing>,
     pub(super) indent_left: Option<f32>,
     pub(super) indent_right: Option<f32>,
@@ -134,17 +154,28 @@ pub(super) struct CharacterStyle {
     pub(super) font_size: Option<f32>,
     pub(super) font_name: Option<String>,
     pub(super) east_asia_font: Option<String>,
+    pub(super) cs_font: Option<String>,
+    pub(super) cs_font_size: Option<f32>,
     pub(super) bold: Option<bool>,
     pub(super) italic: Option<bool>,
-    pub(super) underline: Option<bool>,
+    pub(super) underline: Option<UnderlineStyle>,
+    pub(super) underline_color: Option<[u8; 3]>,
     pub(super) strikethrough: Option<bool>,
+    pub(super) dstrike: Option<bool>,
     pub(super) caps: Option<bool>,
     pub(super) small_caps: Option<bool>,
     pub(super) vanish: Option<bool>,
+    pub(super) effects: Option<TextEffects>,
     pub(super) color: Option<[u8; 3]>,
+    pub(super) char_border: Option<CharBorder>,
+    pub(super) char_spacing: Option<f32>,
+    pub(super) text_scale: Option<f32>,
+    pub(super) position: Option<f32>,
+    pub(super) lang: Option<String>,
     pub(super) kern_threshold: Option<f32>,
 }
 
+#[derive(Clone, Copy)]
 pub(super) struct TableBordersDef {
     pub(super) top: CellBorder,
     pub(super) bottom: CellBorder,
@@ -154,11 +185,31 @@ pub(super) struct TableBordersDef {
     pub(super) inside_v: CellBorder,
 }
 
+/// Formatting a table style gives one region of the table (`w:tblStylePr`),
+/// or the whole table.
+#[derive(Clone, Default)]
+pub(super) struct ConditionalFormat {
+    pub(super) shading: Option<[u8; 3]>,
+    pub(super) borders: Option<TableBordersDef>,
+    pub(super) bold: Option<bool>,
+    pub(super) italic: Option<bool>,
+    pub(super) color: Option<[u8; 3]>,
+}
+
+pub(super) struct TableStyleDef {
+    pub(super) borders: Option<TableBordersDef>,
+    pub(super) whole_table: ConditionalFormat,
+    /// Keyed by `w:tblStylePr/@w:type`: "firstRow", "band1Horz", "nwCell", ...
+    pub(super) conditional: HashMap<String, ConditionalFormat>,
+    pub(super) row_band_size: usize,
+    pub(super) col_band_size: usize,
+}
+
 pub(super) struct StylesInfo {
     pub(super) defaults: StyleDefaults,
     pub(super) paragraph_styles: HashMap<String, ParagraphStyle>,
     pub(super) character_styles: HashMap<String, CharacterStyle>,
-    pub(super) table_border_styles: HashMap<String, TableBordersDef>,
+    pub(super) table_styles: HashMap<String, TableStyleDef>,
     /// Maps style ID → display name (for STYLEREF resolution)
     pub(super) style_id_to_name: HashMap<String, String>,
     /// The styleId of the default paragraph style (w:default="1" w:type="paragraph").
@@ -166,31 +217,143 @@ pub(super) struct StylesInfo {
     pub(super) default_paragraph_style_id: String,
 }
 
+pub(super) fn parse_table_borders(tbl_borders: roxmltree::Node) -> TableBordersDef {
+    TableBordersDef {
+        top: parse_cell_border(tbl_borders, "top"),
+        bottom: parse_cell_border(tbl_borders, "bottom"),
+        left: parse_cell_border_left(tbl_borders),
+        right: parse_cell_border_right(tbl_borders),
+        inside_h: parse_cell_border(tbl_borders, "insideH"),
+        inside_v: parse_cell_border(tbl_borders, "insideV"),
+    }
+}
+
+/// Cell shading, cell borders and run formatting of a table style or one of
+/// its `w:tblStylePr` elements.
+fn parse_conditional_format(node: roxmltree::Node) -> ConditionalFormat {
+    let tc_pr = wml(node, "tcPr");
+    let rpr = wml(node, "rPr");
+    ConditionalFormat {
+        shading: tc_pr
+            .and_then(|pr| wml(pr, "shd"))
+            .and_then(|shd| shd.attribute((WML_NS, "fill")))
+            .filter(|f| *f != "none" && *f != "auto")
+            .and_then(parse_hex_color),
+        borders: tc_pr
+            .and_then(|pr| wml(pr, "tcBorders"))
+            .map(parse_table_borders),
+        bold: rpr.and_then(|r| wml_bool(r, "b")),
+        italic: rpr.and_then(|r| wml_bool(r, "i")),
+        color: rpr
+            .and_then(|r| wml_attr(r, "color"))
+            .and_then(parse_text_color),
+    }
+}
+
 pub(super) fn parse_alignment(val: &str) -> Alignment {
     match val {
         "center" => Alignment::Center,
         "right" | "end" => Alignment::Right,
         "both" => Alignment::Justify,
+        "distribute" | "thaiDistribute" => Alignment::Distribute,
         _ => Alignment::Left,
     }
 }
 
+/// Whether a paragraph runs right to left (`w:bidi`), directly or via its style.
+pub(super) fn resolve_bidi(
+    ppr: Option<roxmltree::Node>,
+    para_style: Option<&ParagraphStyle>,
+) -> bool {
+    ppr.and_then(|ppr| wml_bool(ppr, "bidi"))
+        .or_else(|| para_style.and_then(|s| s.bidi))
+        .unwrap_or(false)
+}
+
+/// Raw `w:outlineLvl` value: 0–8 are outline levels, 9 is body text.
+pub(super) fn parse_outline_level(ppr: roxmltree::Node) -> Option<u8> {
+    wml_attr(ppr, "outlineLvl")
+        .and_then(|v| v.parse::<u8>().ok())
+        .map(|v| v.min(9))
+}
+
+/// `w:jc` is logical in right-to-left paragraphs: left (start) is the right edge.
+pub(super) fn bidi_alignment(alignment: Alignment, rtl: bool) -> Alignment {
+    match alignment {
+        Alignment::Left if rtl => Alignment::Right,
+        Alignment::Right if rtl => Alignment::Left,
+        _ => alignment,
+    }
+}
+
 fn parse_font_size(rpr: roxmltree::Node) -> Option<f32> {
     wml_attr(rpr, "sz")
         .and_then(|v| v.parse::<f32>().ok())
         .map(|hp| hp / 2.0)
 }
 
+pub(super) fn parse_cs_font_size(rpr: roxmltree::Node) -> Option<f32> {
+    wml_attr(rpr, "szCs")
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|hp| hp / 2.0)
+}
+
 fn parse_kern(rpr: roxmltree::Node) -> Option<f32> {
     wml_attr(rpr, "kern")
         .and_then(|v| v.parse::<f32>().ok())
         .map(|hp| hp / 2.0)
 }
 
-fn parse_underline(rpr: roxmltree::Node) -> Option<bool> {
+pub(super) fn parse_underline(rpr: roxmltree::Node) -> Option<UnderlineStyle> {
     wml(rpr, "u")
         .and_then(|u| u.attribute((WML_NS, "val")))
-        .map(|v| v != "none")
+        .map(UnderlineStyle::from_ooxml)
+}
+
+/// `w:u@color`; "auto" follows the text color.
+pub(super) fn parse_underline_color(rpr: roxmltree::Node) -> Option<[u8; 3]> {
+    wml(rpr, "u")
+        .and_then(|u| u.attribute((WML_NS, "color")))
+        .and_then(parse_hex_color)
+}
+
+/// Overlays the outline/shadow/emboss/imprint toggles present in `rpr` onto
+/// `base`; `None` when `rpr` sets none of them.
+pub(super) fn parse_text_effects(rpr: roxmltree::Node, base: TextEffects) -> Option<TextEffects> {
+    let outline = wml_bool(rpr, "outline");
+    let shadow = wml_bool(rpr, "shadow");
+    let emboss = wml_bool(rpr, "emboss");
+    let imprint = wml_bool(rpr, "imprint");
+    if outline.is_none() && shadow.is_none() && emboss.is_none() && imprint.is_none() {
+        return None;
+    }
+    Some(TextEffects {
+        outline: outline.unwrap_or(base.outline),
+        shadow: shadow.unwrap_or(base.shadow),
+        emboss: emboss.unwrap_or(base.emboss),
+        imprint: imprint.unwrap_or(base.imprint),
+    })
+}
+
+/// `w:position` baseline shift in points, positive for raised text.
+pub(super) fn parse_position(rpr: roxmltree::Node) -> Option<f32> {
+    wml_attr(rpr, "position")
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|hp| hp / 2.0)
+}
+
+/// Language of Latin text (`w:lang/@w:val`), e.g. "de-DE".
+pub(super) fn parse_lang(rpr: roxmltree::Node) -> Option<String> {
+    wml_attr(rpr, "lang")
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+}
+
+/// `w:w` horizontal scale in percent, within Word's 1-600% range.
+pub(super) fn parse_text_scale(rpr: roxmltree::Node) -> Option<f32> {
+    wml_attr(rpr, "w")
+        .and_then(|v| v.trim_end_matches('%').parse::<f32>().ok())
+        .map(|pct| pct.clamp(1.0, 600.0))
 }
 
 fn parse_char_spacing(rpr: roxmltree::Node) -> Option<f32> {
@@ -203,11 +366,15 @@ fn parse_char_spacing(rpr: roxmltree::Node) -> Option<f32> {
 pub(super) fn parse_theme<R: std::io::Read + std::io::Seek>(
     zip: &mut zip::ZipArchive<R>,
     east_asia_lang: Option<&str>,
+    default_font: Option<&str>,
 ) -> ThemeFonts {
-    let mut major = String::from("Aptos Display");
-    let mut minor = String::from("Aptos");
+    // Without theme fonts, Word's own defaults unless the caller names one
+    let mut major = default_font.unwrap_or("Aptos Display").to_string();
+    let mut minor = default_font.unwrap_or("Aptos").to_string();
     let mut major_east_asia = String::new();
     let mut minor_east_asia = String::new();
+    let mut major_cs = String::new();
+    let mut minor_cs = String::new();
     let mut colors = HashMap::new();
     let mut fill_styles = Vec::new();
 
@@ -235,6 +402,7 @@ pub(super) fn parse_theme<R: std::io::Read + std::io::Seek>(
                         .or_else(|| script_font_typeface(node, script))
                         .unwrap_or("")
                         .to_string();
+                    major_cs = dml_typeface(node, "cs").unwrap_or("").to_string();
                 }
                 "minorFont" => {
                     if let Some(tf) = dml_typeface(node, "latin") {
@@ -244,6 +412,7 @@ pub(super) fn parse_theme<R: std::io::Read + std::io::Seek>(
                         .or_else(|| script_font_typeface(node, script))
                         .unwrap_or("")
                         .to_string();
+                    minor_cs = dml_typeface(node, "cs").unwrap_or("").to_string();
                 }
                 "clrScheme" => {
                     for child in node.children() {
@@ -297,6 +466,8 @@ pub(super) fn parse_theme<R: std::io::Read + std::io::Seek>(
         minor,
         major_east_asia,
         minor_east_asia,
+        major_cs,
+        minor_cs,
         colors,
         fill_styles,
     }
@@ -411,6 +582,24 @@ pub(super) fn resolve_east_asia_font_from_node(
     resolve_east_asia_font(east_asia, east_asia_theme, theme)
 }
 
+/// Complex-script font (`w:cs`/`w:csTheme`), used for Arabic, Hebrew and Thai text.
+pub(super) fn resolve_cs_font_from_node(
+    rfonts: roxmltree::Node,
+    theme: &ThemeFonts,
+) -> Option<String> {
+    let from_theme = match rfonts.attribute((WML_NS, "csTheme")) {
+        Some("majorBidi") if !theme.major_cs.is_empty() => Some(theme.major_cs.clone()),
+        Some("minorBidi") if !theme.minor_cs.is_empty() => Some(theme.minor_cs.clone()),
+        _ => None,
+    };
+    from_theme.or_else(|| {
+        rfonts
+            .attribute((WML_NS, "cs"))
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    })
+}
+
 pub(super) fn parse_line_spacing(spacing_node: roxmltree::Node, line_val: f32) -> LineSpacing {
     match spacing_node.attribute((WML_NS, "lineRule")) {
         Some("exact") => LineSpacing::Exact(line_val / 20.0),
@@ -427,6 +616,8 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
         font_size: 10.0,
         font_name: theme.minor.clone(),
         east_asia_font: None,
+        cs_font: None,
+        cs_font_size: None,
         space_after: 0.0,
         line_spacing: LineSpacing::Auto(1.0),
         kern_threshold: None,
@@ -437,9 +628,13 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
         vanish: false,
         strikethrough: false,
         dstrike: false,
-        underline: false,
+        underline: UnderlineStyle::None,
+        underline_color: None,
         color: None,
         char_spacing: 0.0,
+        text_scale: 100.0,
+        position: 0.0,
+        lang: None,
     };
     let mut paragraph_styles = HashMap::new();
     let mut character_styles = HashMap::new();
@@ -451,7 +646,7 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
             defaults,
             paragraph_styles,
             character_styles,
-            table_border_styles: HashMap::new(),
+            table_styles: HashMap::new(),
             style_id_to_name,
             default_paragraph_style_id,
         };
@@ -461,7 +656,7 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
             defaults,
             paragraph_styles,
             character_styles,
-            table_border_styles: HashMap::new(),
+            table_styles: HashMap::new(),
             style_id_to_name,
             default_paragraph_style_id,
         };
@@ -477,7 +672,9 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
             if let Some(rfonts) = wml(rpr, "rFonts") {
                 defaults.font_name = resolve_font_from_node(rfonts, theme, &theme.minor);
                 defaults.east_asia_font = resolve_east_asia_font_from_node(rfonts, theme);
+                defaults.cs_font = resolve_cs_font_from_node(rfonts, theme);
             }
+            defaults.cs_font_size = parse_cs_font_size(rpr);
             defaults.kern_threshold = parse_kern(rpr);
             defaults.bold = wml_bool(rpr, "b").unwrap_or(false);
             defaults.italic = wml_bool(rpr, "i").unwrap_or(false);
@@ -486,9 +683,13 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
             defaults.vanish = wml_bool(rpr, "vanish").unwrap_or(false);
             defaults.strikethrough = wml_bool(rpr, "strike").unwrap_or(false);
             defaults.dstrike = wml_bool(rpr, "dstrike").unwrap_or(false);
-            defaults.underline = parse_underline(rpr).unwrap_or(false);
+            defaults.underline = parse_underline(rpr).unwrap_or_default();
+            defaults.underline_color = parse_underline_color(rpr);
             defaults.color = wml_attr(rpr, "color").and_then(parse_text_color);
             defaults.char_spacing = parse_char_spacing(rpr).unwrap_or(0.0);
+            defaults.text_scale = parse_text_scale(rpr).unwrap_or(100.0);
+            defaults.position = parse_position(rpr).unwrap_or(0.0);
+            defaults.lang = parse_lang(rpr);
         }
         let default_spacing = wml(doc_defaults, "pPrDefault")
             .and_then(|n| wml(n, "pPr"))
@@ -506,7 +707,7 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
         }
     }
 
-    let mut table_border_styles = HashMap::new();
+    let mut table_styles = HashMap::new();
 
     for style_node in root.children() {
         if style_node.tag_name().name() != "style"
@@ -546,16 +747,24 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
                     .map(|rfonts| resolve_font_from_node(rfonts, theme, &defaults.font_name));
                 let east_asia_font =
                     rfonts_node.and_then(|rfonts| resolve_east_asia_font_from_node(rfonts, theme));
+                let cs_font =
+                    rfonts_node.and_then(|rfonts| resolve_cs_font_from_node(rfonts, theme));
+                let cs_font_size = rpr.and_then(parse_cs_font_size);
 
                 let bold = rpr.and_then(|n| wml_bool(n, "b"));
                 let italic = rpr.and_then(|n| wml_bool(n, "i"));
                 let caps = rpr.and_then(|n| wml_bool(n, "caps"));
                 let small_caps = rpr.and_then(|n| wml_bool(n, "smallCaps"));
                 let vanish = rpr.and_then(|n| wml_bool(n, "vanish"));
+                let effects = rpr.and_then(|n| parse_text_effects(n, TextEffects::default()));
                 let underline = rpr.and_then(parse_underline);
+                let underline_color = rpr.and_then(parse_underline_color);
                 let strikethrough = rpr.and_then(|n| wml_bool(n, "strike"));
                 let dstrike = rpr.and_then(|n| wml_bool(n, "dstrike"));
                 let char_spacing = rpr.and_then(parse_char_spacing);
+                let text_scale = rpr.and_then(parse_text_scale);
+                let position = rpr.and_then(parse_position);
+                let lang = rpr.and_then(parse_lang);
                 let kern_threshold = rpr.and_then(parse_kern);
                 let color = rpr
                     .and_then(|n| wml_attr(n, "color"))
@@ -573,9 +782,14 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
                 let keep_lines = ppr
                     .and_then(|ppr| wml_bool(ppr, "keepLines"))
                     .unwrap_or(false);
+                let suppress_auto_hyphens = ppr
+                    .and_then(|ppr| wml_bool(ppr, "suppressAutoHyphens"))
+                    .unwrap_or(false);
                 let page_break_before = ppr
                     .and_then(|ppr| wml_bool(ppr, "pageBreakBefore"))
                     .unwrap_or(false);
+                let bidi = ppr.and_then(|ppr| wml_bool(ppr, "bidi"));
+                let outline_level = ppr.and_then(parse_outline_level);
 
                 let line_spacing = spacing.and_then(|n| {
                     n.attribute((WML_NS, "line"))
@@ -608,23 +822,33 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
                         font_size,
                         font_name,
                         east_asia_font,
+                        cs_font,
+                        cs_font_size,
                         bold,
                         italic,
                         caps,
                         small_caps,
                         vanish,
+                        effects,
                         underline,
+                        underline_color,
                         strikethrough,
                         dstrike,
                         color,
                         char_spacing,
+                        text_scale,
+                        position,
+                        lang,
                         space_before,
                         space_after,
                         alignment,
                         contextual_spacing,
                         keep_next,
                         keep_lines,
+                        suppress_auto_hyphens,
                         page_break_before,
+                        bidi,
+                        outline_level,
                         line_spacing,
                         indent_left,
                         indent_right,
@@ -649,14 +873,25 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
                     .map(|rfonts| resolve_font_from_node(rfonts, theme, &defaults.font_name));
                 let east_asia_font =
                     rfonts_node.and_then(|rfonts| resolve_east_asia_font_from_node(rfonts, theme));
+                let cs_font =
+                    rfonts_node.and_then(|rfonts| resolve_cs_font_from_node(rfonts, theme));
+                let cs_font_size = parse_cs_font_size(rpr);
                 let bold = wml_bool(rpr, "b");
                 let italic = wml_bool(rpr, "i");
                 let underline = parse_underline(rpr);
+                let underline_color = parse_underline_color(rpr);
                 let strikethrough = wml_bool(rpr, "strike");
+                let dstrike = wml_bool(rpr, "dstrike");
                 let caps = wml_bool(rpr, "caps");
                 let small_caps = wml_bool(rpr, "smallCaps");
                 let vanish = wml_bool(rpr, "vanish");
+                let effects = parse_text_effects(rpr, TextEffects::default());
                 let color = wml_attr(rpr, "color").and_then(parse_text_color);
+                let char_border = wml(rpr, "bdr").and_then(parse_char_border);
+                let char_spacing = parse_char_spacing(rpr);
+                let text_scale = parse_text_scale(rpr);
+                let position = parse_position(rpr);
+                let lang = parse_lang(rpr);
                 let kern_threshold = parse_kern(rpr);
 
                 character_styles.insert(
@@ -665,34 +900,60 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
                         font_size,
                         font_name,
                         east_asia_font,
+                        cs_font,
+                        cs_font_size,
                         bold,
                         italic,
                         underline,
+                        underline_color,
                         strikethrough,
+                        dstrike,
                         caps,
                         small_caps,
                         vanish,
+                        effects,
                         color,
+                        char_border,
+                        char_spacing,
+                        text_scale,
+                        position,
+                        lang,
                         kern_threshold,
                     },
                 );
             }
             Some("table") => {
-                if let Some(tbl_borders) =
-                    wml(style_node, "tblPr").and_then(|pr| wml(pr, "tblBorders"))
-                {
-                    table_border_styles.insert(
-                        style_id.to_string(),
-                        TableBordersDef {
-                            top: parse_cell_border(tbl_borders, "top"),
-                            bottom: parse_cell_border(tbl_borders, "bottom"),
-                            left: parse_cell_border_left(tbl_borders),
-                            right: parse_cell_border_right(tbl_borders),
-                            inside_h: parse_cell_border(tbl_borders, "insideH"),
-                            inside_v: parse_cell_border(tbl_borders, "insideV"),
-                        },
-                    );
-                }
+                let tbl_pr = wml(style_node, "tblPr");
+                let band_size = |name: &str| {
+                    tbl_pr
+                        .and_then(|pr| wml_attr(pr, name))
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .filter(|&n| n > 0)
+                        .unwrap_or(1)
+                };
+                let conditional = style_node
+                    .children()
+                    .filter(|n| {
+                        n.tag_name().name() == "tblStylePr"
+                            && n.tag_name().namespace() == Some(WML_NS)
+                    })
+                    .filter_map(|n| {
+                        let kind = n.attribute((WML_NS, "type"))?;
+                        Some((kind.to_string(), parse_conditional_format(n)))
+                    })
+                    .collect();
+                table_styles.insert(
+                    style_id.to_string(),
+                    TableStyleDef {
+                        borders: tbl_pr
+                            .and_then(|pr| wml(pr, "tblBorders"))
+                            .map(parse_table_borders),
+                        whole_table: parse_conditional_format(style_node),
+                        conditional,
+                        row_band_size: band_size("tblStyleRowBandSize"),
+                        col_band_size: band_size("tblStyleColBandSize"),
+                    },
+                );
             }
             _ => {}
         }
@@ -712,7 +973,7 @@ pub(super) fn parse_styles<R: std::io::Read + std::io::Seek>(
         defaults,
         paragraph_styles,
         character_styles,
-        table_border_styles,
+        table_styles,
         style_id_to_name,
         default_paragraph_style_id,
     }
@@ -751,18 +1012,27 @@ fn resolve_based_on(styles: &mut HashMap<String, ParagraphStyle>) {
                     s,
                     font_name,
                     east_asia_font,
+                    cs_font,
+                    cs_font_size,
                     font_size,
                     bold,
                     italic,
                     caps,
                     small_caps,
                     vanish,
+                    effects,
                     underline,
+                    underline_color,
                     strikethrough,
                     dstrike,
                     color,
                     char_spacing,
+                    text_scale,
+                    position,
+                    lang,
                     alignment,
+                    bidi,
+                    outline_level,
                     space_before,
                     space_after,
                     line_spacing,
@@ -794,14 +1064,27 @@ fn resolve_based_on(styles: &mut HashMap<String, ParagraphStyle>) {
         if let Some(s) = styles.get_mut(&id) {
             s.font_name = s.font_name.take().or(inh.font_name);
             s.east_asia_font = s.east_asia_font.take().or(inh.east_asia_font);
+            s.cs_font = s.cs_font.take().or(inh.cs_font);
+            s.cs_font_size = s.cs_font_size.or(inh.cs_font_size);
             s.font_size = s.font_size.or(inh.font_size);
             s.bold = s.bold.or(inh.bold);
             s.italic = s.italic.or(inh.italic);
             s.caps = s.caps.or(inh.caps);
             s.small_caps = s.small_caps.or(inh.small_caps);
             s.vanish = s.vanish.or(inh.vanish);
+            s.effects = s.effects.or(inh.effects);
+            s.underline = s.underline.or(inh.underline);
+            s.underline_color = s.underline_color.or(inh.underline_color);
+            s.strikethrough = s.strikethrough.or(inh.strikethrough);
+            s.dstrike = s.dstrike.or(inh.dstrike);
             s.color = s.color.or(inh.color);
+            s.char_spacing = s.char_spacing.or(inh.char_spacing);
+            s.text_scale = s.text_scale.or(inh.text_scale);
+            s.position = s.position.or(inh.position);
+            s.lang = s.lang.take().or(inh.lang);
             s.alignment = s.alignment.or(inh.alignment);
+            s.bidi = s.bidi.or(inh.bidi);
+            s.outline_level = s.outline_level.or(inh.outline_level);
             s.space_before = s.space_before.or(inh.space_before);
             s.space_after = s.space_after.or(inh.space_after);
             s.line_spacing = s.line_spacing.or(inh.line_spacing);
@@ -818,3 +1101,89 @@ fn resolve_based_on(styles: &mut HashMap<String, ParagraphStyle>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::testing;
+
+    #[test]
+    fn test_hebrew_paragraph_aligns_from_the_right() {
+        let styles = testing::part(
+            "w:styles",
+            r#"<w:style w:type="paragraph" w:styleId="Hebrew"><w:pPr><w:bidi/></w:pPr></w:style>"#,
+        );
+        let body = concat!(
+            r#"<w:p><w:pPr><w:pStyle w:val="Hebrew"/><w:jc w:val="left"/></w:pPr><w:r><w:t>שלום עולם</w:t></w:r></w:p>"#,
+            r#"<w:p><w:pPr><w:bidi/><w:jc w:val="right"/></w:pPr><w:r><w:t>סוף</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Latin</w:t></w:r></w:p>"#,
+        );
+        let doc = testing::parse_with(
+            body,
+            &[("word/styles.xml", styles.as_bytes())],
+            &crate::ConversionOptions::default(),
+        );
+        let paras = testing::paragraphs(&doc);
+        let rtl: Vec<bool> = paras.iter().map(|p| p.rtl).collect();
+        assert_eq!(rtl, [true, true, false]);
+        // `w:jc` is logical: start is the right edge of a right-to-left paragraph
+        assert!(matches!(paras[0].alignment, Alignment::Right));
+        assert!(matches!(paras[1].alignment, Alignment::Left));
+        assert!(matches!(paras[2].alignment, Alignment::Left));
+    }
+
+    #[test]
+    fn test_position_inherits_through_styles() {
+        let styles = testing::part(
+            "w:styles",
+            concat!(
+                r#"<w:style w:type="paragraph" w:styleId="Base"><w:rPr><w:position w:val="6"/></w:rPr></w:style>"#,
+                r#"<w:style w:type="paragraph" w:styleId="Child"><w:basedOn w:val="Base"/></w:style>"#,
+                r#"<w:style w:type="character" w:styleId="Lowered"><w:rPr><w:position w:val="-4"/></w:rPr></w:style>"#,
+            ),
+        );
+        let body = concat!(
+            r#"<w:p><w:pPr><w:pStyle w:val="Child"/></w:pPr><w:r><w:t>a</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:rStyle w:val="Lowered"/></w:rPr><w:t>b</w:t></w:r></w:p>"#,
+        );
+        let doc = testing::parse_with(
+            body,
+            &[("word/styles.xml", styles.as_bytes())],
+            &crate::ConversionOptions::default(),
+        );
+        let positions: Vec<f32> = testing::paragraphs(&doc)[0]
+            .runs
+            .iter()
+            .map(|r| r.position)
+            .collect();
+        assert_eq!(positions, [3.0, -2.0]);
+    }
+
+    #[test]
+    fn test_lang_falls_back_from_run_to_style_to_defaults() {
+        let styles = testing::part(
+            "w:styles",
+            concat!(
+                r#"<w:docDefaults><w:rPrDefault><w:rPr><w:lang w:val="en-US"/></w:rPr></w:rPrDefault></w:docDefaults>"#,
+                r#"<w:style w:type="paragraph" w:styleId="Base"><w:rPr><w:lang w:val="de-DE"/></w:rPr></w:style>"#,
+                r#"<w:style w:type="paragraph" w:styleId="Quote"><w:basedOn w:val="Base"/></w:style>"#,
+            ),
+        );
+        let body = concat!(
+            r#"<w:p><w:r><w:t>a</w:t></w:r></w:p>"#,
+            r#"<w:p><w:pPr><w:pStyle w:val="Quote"/></w:pPr><w:r><w:t>b</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:lang w:val="fr-FR"/></w:rPr><w:t>c</w:t></w:r></w:p>"#,
+        );
+        let doc = testing::parse_with(
+            body,
+            &[("word/styles.xml", styles.as_bytes())],
+            &crate::ConversionOptions::default(),
+        );
+        let langs: Vec<Option<&str>> = testing::paragraphs(&doc)
+            .iter()
+            .flat_map(|p| &p.runs)
+            .map(|r| r.lang.as_deref())
+            .collect();
+        assert_eq!(langs, [Some("en-US"), Some("de-DE"), Some("fr-FR")]);
+    }
+}
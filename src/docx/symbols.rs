@@ -0,0 +1,418 @@
+// Word's symbol fonts (Symbol, Wingdings 1-3, Webdings) address glyphs by byte
+// value, stored either directly or in the U+F020..U+F0FF private use range.
+
+/// Adobe Symbol encoding; printable ASCII not listed here maps to itself.
+const SYMBOL: &[(u8, char)] = &[
+    (0x22, '\u{2200}'),
+    (0x24, '\u{2203}'),
+    (0x27, '\u{220B}'),
+    (0x2A, '\u{2217}'),
+    (0x2D, '\u{2212}'),
+    (0x40, '\u{2245}'),
+    (0x41, 'Α'),
+    (0x42, 'Β'),
+    (0x43, 'Χ'),
+    (0x44, 'Δ'),
+    (0x45, 'Ε'),
+    (0x46, 'Φ'),
+    (0x47, 'Γ'),
+    (0x48, 'Η'),
+    (0x49, 'Ι'),
+    (0x4A, 'ϑ'),
+    (0x4B, 'Κ'),
+    (0x4C, 'Λ'),
+    (0x4D, 'Μ'),
+    (0x4E, 'Ν'),
+    (0x4F, 'Ο'),
+    (0x50, 'Π'),
+    (0x51, 'Θ'),
+    (0x52, 'Ρ'),
+    (0x53, 'Σ'),
+    (0x54, 'Τ'),
+    (0x55, 'Υ'),
+    (0x56, 'ς'),
+    (0x57, 'Ω'),
+    (0x58, 'Ξ'),
+    (0x59, 'Ψ'),
+    (0x5A, 'Ζ'),
+    (0x5C, '\u{2234}'),
+    (0x5E, '\u{22A5}'),
+    (0x60, '\u{203E}'),
+    (0x61, 'α'),
+    (0x62, 'β'),
+    (0x63, 'χ'),
+    (0x64, 'δ'),
+    (0x65, 'ε'),
+    (0x66, 'φ'),
+    (0x67, 'γ'),
+    (0x68, 'η'),
+    (0x69, 'ι'),
+    (0x6A, 'ϕ'),
+    (0x6B, 'κ'),
+    (0x6C, 'λ'),
+    (0x6D, 'μ'),
+    (0x6E, 'ν'),
+    (0x6F, 'ο'),
+    (0x70, 'π'),
+    (0x71, 'θ'),
+    (0x72, 'ρ'),
+    (0x73, 'σ'),
+    (0x74, 'τ'),
+    (0x75, 'υ'),
+    (0x76, 'ϖ'),
+    (0x77, 'ω'),
+    (0x78, 'ξ'),
+    (0x79, 'ψ'),
+    (0x7A, 'ζ'),
+    (0x7E, '\u{223C}'),
+    (0xA0, '€'),
+    (0xA1, 'ϒ'),
+    (0xA2, '′'),
+    (0xA3, '≤'),
+    (0xA4, '⁄'),
+    (0xA5, '∞'),
+    (0xA6, 'ƒ'),
+    (0xA7, '♣'),
+    (0xA8, '♦'),
+    (0xA9, '♥'),
+    (0xAA, '♠'),
+    (0xAB, '↔'),
+    (0xAC, '←'),
+    (0xAD, '↑'),
+    (0xAE, '→'),
+    (0xAF, '↓'),
+    (0xB0, '°'),
+    (0xB1, '±'),
+    (0xB2, '″'),
+    (0xB3, '≥'),
+    (0xB4, '×'),
+    (0xB5, '∝'),
+    (0xB6, '∂'),
+    (0xB7, '•'),
+    (0xB8, '÷'),
+    (0xB9, '≠'),
+    (0xBA, '≡'),
+    (0xBB, '≈'),
+    (0xBC, '…'),
+    (0xBF, '↵'),
+    (0xC0, 'ℵ'),
+    (0xC1, 'ℑ'),
+    (0xC2, 'ℜ'),
+    (0xC3, '℘'),
+    (0xC4, '⊗'),
+    (0xC5, '⊕'),
+    (0xC6, '∅'),
+    (0xC7, '∩'),
+    (0xC8, '∪'),
+    (0xC9, '⊃'),
+    (0xCA, '⊇'),
+    (0xCB, '⊄'),
+    (0xCC, '⊂'),
+    (0xCD, '⊆'),
+    (0xCE, '∈'),
+    (0xCF, '∉'),
+    (0xD0, '∠'),
+    (0xD1, '∇'),
+    (0xD2, '®'),
+    (0xD3, '©'),
+    (0xD4, '™'),
+    (0xD5, '∏'),
+    (0xD6, '√'),
+    (0xD7, '⋅'),
+    (0xD8, '¬'),
+    (0xD9, '∧'),
+    (0xDA, '∨'),
+    (0xDB, '⇔'),
+    (0xDC, '⇐'),
+    (0xDD, '⇑'),
+    (0xDE, '⇒'),
+    (0xDF, '⇓'),
+    (0xE0, '◊'),
+    (0xE1, '〈'),
+    (0xE2, '®'),
+    (0xE3, '©'),
+    (0xE4, '™'),
+    (0xE5, '∑'),
+    (0xF1, '〉'),
+    (0xF2, '∫'),
+];
+
+/// Wingdings glyphs with a Unicode counterpart in the Basic Multilingual Plane,
+/// which text fonts are far likelier to cover than the emoji planes.
+const WINGDINGS: &[(u8, char)] = &[
+    (0x20, ' '),
+    (0x21, '✏'),
+    (0x22, '✂'),
+    (0x23, '✁'),
+    (0x28, '☎'),
+    (0x29, '✆'),
+    (0x2A, '✉'),
+    (0x36, '⌛'),
+    (0x37, '⌨'),
+    (0x3E, '✇'),
+    (0x3F, '✍'),
+    (0x41, '✌'),
+    (0x45, '☜'),
+    (0x46, '☞'),
+    (0x47, '☝'),
+    (0x48, '☟'),
+    (0x4A, '☺'),
+    (0x4C, '☹'),
+    (0x4E, '☠'),
+    (0x4F, '⚐'),
+    (0x51, '✈'),
+    (0x52, '☼'),
+    (0x54, '❄'),
+    (0x56, '✞'),
+    (0x58, '✠'),
+    (0x59, '✡'),
+    (0x5A, '☪'),
+    (0x5B, '☯'),
+    (0x5C, 'ॐ'),
+    (0x5D, '☸'),
+    (0x5E, '♈'),
+    (0x5F, '♉'),
+    (0x60, '♊'),
+    (0x61, '♋'),
+    (0x62, '♌'),
+    (0x63, '♍'),
+    (0x64, '♎'),
+    (0x65, '♏'),
+    (0x66, '♐'),
+    (0x67, '♑'),
+    (0x68, '♒'),
+    (0x69, '♓'),
+    (0x6C, '●'),
+    (0x6D, '❍'),
+    (0x6E, '■'),
+    (0x6F, '□'),
+    (0x70, '◻'),
+    (0x71, '❑'),
+    (0x72, '❒'),
+    (0x73, '⬧'),
+    (0x74, '⧫'),
+    (0x75, '◆'),
+    (0x76, '❖'),
+    (0x77, '⬥'),
+    (0x78, '⌧'),
+    (0x79, '⮹'),
+    (0x7A, '⌘'),
+    (0x7B, '❀'),
+    (0x7C, '✿'),
+    (0x7D, '❝'),
+    (0x7E, '❞'),
+    (0x80, '⓪'),
+    (0x81, '①'),
+    (0x82, '②'),
+    (0x83, '③'),
+    (0x84, '④'),
+    (0x85, '⑤'),
+    (0x86, '⑥'),
+    (0x87, '⑦'),
+    (0x88, '⑧'),
+    (0x89, '⑨'),
+    (0x8A, '⑩'),
+    (0x8B, '⓿'),
+    (0x8C, '❶'),
+    (0x8D, '❷'),
+    (0x8E, '❸'),
+    (0x8F, '❹'),
+    (0x90, '❺'),
+    (0x91, '❻'),
+    (0x92, '❼'),
+    (0x93, '❽'),
+    (0x94, '❾'),
+    (0x95, '❿'),
+    (0x9E, '·'),
+    (0x9F, '•'),
+    (0xA0, '▪'),
+    (0xA1, '○'),
+    (0xA4, '◉'),
+    (0xA5, '◎'),
+    (0xA7, '▪'),
+    (0xA8, '◻'),
+    (0xAA, '✦'),
+    (0xAB, '★'),
+    (0xAC, '✶'),
+    (0xAD, '✴'),
+    (0xAE, '✹'),
+    (0xAF, '✵'),
+    (0xB0, '⯐'),
+    (0xB1, '⌖'),
+    (0xB2, '⟡'),
+    (0xB3, '⌑'),
+    (0xB4, '⯑'),
+    (0xB5, '✪'),
+    (0xB6, '✰'),
+    (0xC3, '⮰'),
+    (0xC4, '⮱'),
+    (0xC5, '⮲'),
+    (0xC6, '⮳'),
+    (0xC7, '⮴'),
+    (0xC8, '⮵'),
+    (0xC9, '⮶'),
+    (0xCA, '⮷'),
+    (0xD5, '⌫'),
+    (0xD6, '⌦'),
+    (0xD7, '⮘'),
+    (0xD8, '➢'),
+    (0xD9, '⮙'),
+    (0xDA, '⮛'),
+    (0xDB, '⮈'),
+    (0xDC, '⮊'),
+    (0xDD, '⮉'),
+    (0xDE, '⮋'),
+    (0xDF, '←'),
+    (0xE0, '→'),
+    (0xE1, '↑'),
+    (0xE2, '↓'),
+    (0xE3, '↖'),
+    (0xE4, '↗'),
+    (0xE5, '↙'),
+    (0xE6, '↘'),
+    (0xE7, '⬅'),
+    (0xE8, '➔'),
+    (0xE9, '⬆'),
+    (0xEA, '⬇'),
+    (0xEB, '⬉'),
+    (0xEC, '⬈'),
+    (0xED, '⬋'),
+    (0xEE, '⬊'),
+    (0xEF, '⇦'),
+    (0xF0, '⇨'),
+    (0xF1, '⇧'),
+    (0xF2, '⇩'),
+    (0xF3, '⬄'),
+    (0xF4, '⇳'),
+    (0xF5, '⬀'),
+    (0xF6, '⬁'),
+    (0xF7, '⬃'),
+    (0xF8, '⬂'),
+    (0xFB, '✗'),
+    (0xFC, '✓'),
+    (0xFD, '☒'),
+    (0xFE, '☑'),
+];
+
+/// Webdings glyphs with a reasonably common Unicode counterpart.
+const WEBDINGS: &[(u8, char)] = &[
+    (0x20, ' '),
+    (0x33, '◀'),
+    (0x34, '▶'),
+    (0x35, '▲'),
+    (0x36, '▼'),
+    (0x59, '♥'),
+    (0x61, '✓'),
+    (0x67, '■'),
+    (0x6E, '●'),
+    (0x72, '✕'),
+];
+
+/// Wingdings 2 check marks and ballot boxes.
+const WINGDINGS_2: &[(u8, char)] = &[
+    (0x20, ' '),
+    (0x4F, '✗'),
+    (0x50, '✓'),
+    (0x51, '☒'),
+    (0x52, '☑'),
+    (0x54, '☒'),
+    (0xA3, '☐'),
+];
+
+/// Wingdings 3 triangle arrowheads.
+const WINGDINGS_3: &[(u8, char)] = &[
+    (0x20, ' '),
+    (0x70, '▲'),
+    (0x71, '▼'),
+    (0x74, '◀'),
+    (0x75, '▶'),
+];
+
+fn symbol_table(font: &str) -> Option<&'static [(u8, char)]> {
+    let font = font.trim();
+    if font.eq_ignore_ascii_case("Symbol") {
+        Some(SYMBOL)
+    } else if font.eq_ignore_ascii_case("Wingdings") {
+        Some(WINGDINGS)
+    } else if font.eq_ignore_ascii_case("Wingdings 2") {
+        Some(WINGDINGS_2)
+    } else if font.eq_ignore_ascii_case("Wingdings 3") {
+        Some(WINGDINGS_3)
+    } else if font.eq_ignore_ascii_case("Webdings") {
+        Some(WEBDINGS)
+    } else {
+        None
+    }
+}
+
+pub(super) fn is_symbol_font(font: &str) -> bool {
+    symbol_table(font).is_some()
+}
+
+/// Unicode equivalent of `code` (a byte or its U+F0xx alias) in a symbol font.
+/// Returns `None` for other fonts and for glyphs without a counterpart.
+pub(super) fn symbol_to_unicode(font: &str, code: u32) -> Option<char> {
+    let byte = match code {
+        0xF000..=0xF0FF => (code - 0xF000) as u8,
+        0..=0xFF => code as u8,
+        _ => return None,
+    };
+    let table = symbol_table(font)?;
+    let mapped = table.iter().find(|&&(b, _)| b == byte).map(|&(_, ch)| ch);
+    // Symbol keeps ASCII punctuation and digits at their usual positions.
+    let ascii = font.trim().eq_ignore_ascii_case("Symbol") && (0x20..=0x7E).contains(&byte);
+    mapped.or_else(|| ascii.then_some(byte as char))
+}
+
+/// Text and font for a symbol character (`w:sym`, SYMBOL field): its Unicode
+/// equivalent in the surrounding text font when one exists, otherwise the raw
+/// code drawn from the symbol font itself.
+pub(super) fn resolve_symbol<'a>(
+    symbol_font: &'a str,
+    code: u32,
+    text_font: &'a str,
+) -> Option<(char, &'a str)> {
+    match symbol_to_unicode(symbol_font, code) {
+        Some(ch) => Some((ch, text_font)),
+        None => char::from_u32(code).map(|ch| (ch, symbol_font)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_to_unicode() {
+        assert_eq!(symbol_to_unicode("Wingdings", 0xF0FC), Some('✓'));
+        assert_eq!(symbol_to_unicode("wingdings", 0xFC), Some('✓'));
+        assert_eq!(symbol_to_unicode("Symbol", 0xF061), Some('α'));
+        assert_eq!(symbol_to_unicode("Symbol", 0x28), Some('('));
+        assert_eq!(symbol_to_unicode("Webdings", 0x61), Some('✓'));
+        assert_eq!(symbol_to_unicode("Wingdings", 0x42), None);
+        assert_eq!(symbol_to_unicode("Calibri", 0xB7), None);
+    }
+
+    #[test]
+    fn test_wingdings_phone_envelope_and_arrows() {
+        assert_eq!(symbol_to_unicode("Wingdings", 0xF028), Some('☎'));
+        assert_eq!(symbol_to_unicode("Wingdings", 0xF02A), Some('✉'));
+        assert_eq!(symbol_to_unicode("Wingdings", 0xF0E4), Some('↗'));
+        assert_eq!(symbol_to_unicode("Wingdings", 0xF0C3), Some('⮰'));
+        assert_eq!(symbol_to_unicode("Wingdings 2", 0xF050), Some('✓'));
+        assert_eq!(symbol_to_unicode("Wingdings 3", 0x75), Some('▶'));
+        assert!(is_symbol_font("Wingdings 3"));
+    }
+
+    #[test]
+    fn test_resolve_symbol() {
+        assert_eq!(
+            resolve_symbol("Symbol", 0xF0B7, "Calibri"),
+            Some(('•', "Calibri"))
+        );
+        assert_eq!(
+            resolve_symbol("Wingdings", 0xF042, "Calibri"),
+            Some(('\u{F042}', "Wingdings"))
+        );
+    }
+}
@@ -3,16 +3,20 @@ use std::io::Read;
 
 use crate::model::{
     Alignment, CellBorder, CellBorders, CellMargins, CellVAlign, HorizontalPosition, LineSpacing,
-    Paragraph, Table, TableCell, TablePosition, TableRow, TextDirection, VMerge,
+    NestedTable, Paragraph, PreferredWidth, Table, TableCell, TablePosition, TableRow,
+    TextDirection, VMerge,
 };
 
+use super::bookmarks::paragraph_bookmarks;
 use super::numbering::{self, ListLabelInfo, parse_list_info};
 use super::runs::parse_runs;
-use super::styles::{self, TableBordersDef, parse_alignment};
+use super::styles::{
+    self, ConditionalFormat, TableBordersDef, bidi_alignment, parse_alignment, resolve_bidi,
+};
 use super::{
     WML_NS, collect_block_nodes, extract_indents, parse_cell_border, parse_cell_border_left,
     parse_cell_border_right, parse_hex_color, parse_paragraph_spacing, twips_attr, twips_to_pts,
-    wml, wml_attr,
+    wml, wml_attr, wml_bool,
 };
 
 fn is_wml(node: &roxmltree::Node, name: &str) -> bool {
@@ -25,86 +29,290 @@ fn margin_twips(mar: roxmltree::Node, primary: &str, fallback: &str) -> Option<f
         .and_then(|n| twips_attr(n, "w"))
 }
 
-fn border_or_fallback(inline: CellBorder, fallback: CellBorder) -> CellBorder {
-    if inline.present { inline } else { fallback }
+/// A `w:tblW`/`w:tcW`: twips for `dxa` (the default), fiftieths of a percent
+/// or a "50%" string for `pct`; `auto` and `nil` leave the width to the grid.
+fn preferred_width(node: roxmltree::Node) -> PreferredWidth {
+    let w = node.attribute((WML_NS, "w")).unwrap_or("0");
+    let parsed = match node.attribute((WML_NS, "type")).unwrap_or("dxa") {
+        "pct" => match w.strip_suffix('%') {
+            Some(pct) => pct.parse::<f32>().ok(),
+            None => w.parse::<f32>().ok().map(|v| v / 50.0),
+        }
+        .map(PreferredWidth::Percent),
+        "dxa" => w
+            .parse::<f32>()
+            .ok()
+            .map(|v| PreferredWidth::Points(twips_to_pts(v))),
+        _ => None,
+    };
+    parsed.unwrap_or_default()
 }
 
-struct AnnotatedNode<'a> {
-    node: roxmltree::Node<'a, 'a>,
-    extra_space_before: f32,
-    extra_space_after: f32,
+/// `w:tblCellSpacing` of a `w:tblPr` or `w:trPr`, in points.
+fn cell_spacing(pr: roxmltree::Node) -> Option<f32> {
+    wml(pr, "tblCellSpacing")
+        .filter(|n| n.attribute((WML_NS, "type")) != Some("nil"))
+        .and_then(|n| twips_attr(n, "w"))
 }
 
-/// Flatten nested table content: extract all w:p nodes from a w:tbl's cells,
-/// skipping vMerge=continue cells to avoid duplicating merged content.
-/// Preserves nested table cell margins as extra spacing on first/last paragraphs.
-fn collect_nested_table_paragraphs<'a>(
-    tbl: roxmltree::Node<'a, 'a>,
-    out: &mut Vec<AnnotatedNode<'a>>,
-) {
-    let tbl_pr = wml(tbl, "tblPr");
-    let (margin_top, margin_bottom) = tbl_pr
-        .and_then(|pr| wml(pr, "tblCellMar"))
-        .map(|mar| {
-            let top = wml(mar, "top")
-                .and_then(|n| twips_attr(n, "w"))
-                .unwrap_or(0.0);
-            let bottom = wml(mar, "bottom")
-                .and_then(|n| twips_attr(n, "w"))
-                .unwrap_or(0.0);
-            (top, bottom)
-        })
-        .unwrap_or((0.0, 0.0));
+/// `w:tblLook`: which conditional formats of the table style apply.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+struct TableLook {
+    first_row: bool,
+    last_row: bool,
+    first_column: bool,
+    last_column: bool,
+    h_band: bool,
+    v_band: bool,
+}
 
-    for tr in collect_block_nodes(tbl)
-        .into_iter()
-        .filter(|n| is_wml(n, "tr"))
+/// Reads the on/off attributes, or the older hex bitmask in `w:val`.
+fn parse_table_look(node: roxmltree::Node) -> TableLook {
+    let mask = node
+        .attribute((WML_NS, "val"))
+        .and_then(|v| u16::from_str_radix(v, 16).ok())
+        .unwrap_or(0);
+    let flag = |name: &str, bit: u16| match node.attribute((WML_NS, name)) {
+        Some(v) => matches!(v, "1" | "true" | "on"),
+        None => mask & bit != 0,
+    };
+    TableLook {
+        first_row: flag("firstRow", 0x0020),
+        last_row: flag("lastRow", 0x0040),
+        first_column: flag("firstColumn", 0x0080),
+        last_column: flag("lastColumn", 0x0100),
+        h_band: !flag("noHBand", 0x0200),
+        v_band: !flag("noVBand", 0x0400),
+    }
+}
+
+/// Where a cell sits in its table, in rows and grid columns.
+struct CellRegion {
+    row: usize,
+    col: usize,
+    span_end: usize,
+    num_rows: usize,
+    num_cols: usize,
+}
+
+/// Odd (`band1`) or even (`band2`) band of `index`, counted after any header
+/// row or column; `None` for the header and footer lines themselves.
+fn band_type(
+    index: usize,
+    last: bool,
+    skip_first: bool,
+    skip_last: bool,
+    band_size: usize,
+) -> Option<bool> {
+    if (skip_first && index == 0) || (skip_last && last) {
+        return None;
+    }
+    let index = index - skip_first as usize;
+    Some((index / band_size).is_multiple_of(2))
+}
+
+/// Merges the table style's formats that apply to a cell, from lowest to
+/// highest priority: whole table, column bands, row bands, first/last column,
+/// first/last row and the corner cells.
+fn resolve_conditional_format(
+    style: &styles::TableStyleDef,
+    look: TableLook,
+    cell: CellRegion,
+) -> ConditionalFormat {
+    let first_row = look.first_row && cell.row == 0;
+    let last_row = look.last_row && cell.row + 1 == cell.num_rows;
+    let first_col = look.first_column && cell.col == 0;
+    let last_col = look.last_column && cell.span_end >= cell.num_cols;
+
+    let mut kinds = Vec::new();
+    if look.v_band
+        && let Some(odd) = band_type(
+            cell.col,
+            cell.span_end >= cell.num_cols,
+            look.first_column,
+            look.last_column,
+            style.col_band_size,
+        )
     {
-        for tc in collect_block_nodes(tr)
-            .into_iter()
-            .filter(|n| is_wml(n, "tc"))
-        {
-            let tc_pr = wml(tc, "tcPr");
-            let is_continue = tc_pr
-                .and_then(|pr| wml(pr, "vMerge"))
-                .is_some_and(|n| n.attribute((WML_NS, "val")) != Some("restart"));
-            if is_continue {
-                continue;
-            }
-            let (cell_margin_top, cell_margin_bottom) = tc_pr
-                .and_then(|pr| wml(pr, "tcMar"))
-                .map(|mar| {
-                    let top = wml(mar, "top")
-                        .and_then(|n| twips_attr(n, "w"))
-                        .unwrap_or(margin_top);
-                    let bottom = wml(mar, "bottom")
-                        .and_then(|n| twips_attr(n, "w"))
-                        .unwrap_or(margin_bottom);
-                    (top, bottom)
-                })
-                .unwrap_or((margin_top, margin_bottom));
+        kinds.push(if odd { "band1Vert" } else { "band2Vert" });
+    }
+    if look.h_band
+        && let Some(odd) = band_type(
+            cell.row,
+            cell.row + 1 == cell.num_rows,
+            look.first_row,
+            look.last_row,
+            style.row_band_size,
+        )
+    {
+        kinds.push(if odd { "band1Horz" } else { "band2Horz" });
+    }
+    if first_col {
+        kinds.push("firstCol");
+    }
+    if last_col {
+        kinds.push("lastCol");
+    }
+    if first_row {
+        kinds.push("firstRow");
+    }
+    if last_row {
+        kinds.push("lastRow");
+    }
+    match (first_row, last_row, first_col, last_col) {
+        (true, _, true, _) => kinds.push("nwCell"),
+        (true, _, _, true) => kinds.push("neCell"),
+        (_, true, true, _) => kinds.push("swCell"),
+        (_, true, _, true) => kinds.push("seCell"),
+        _ => {}
+    }
 
-            let start_idx = out.len();
-            for n in collect_block_nodes(tc) {
-                if is_wml(&n, "p") {
-                    out.push(AnnotatedNode {
-                        node: n,
-                        extra_space_before: 0.0,
-                        extra_space_after: 0.0,
-                    });
-                } else if is_wml(&n, "tbl") {
-                    collect_nested_table_paragraphs(n, out);
-                }
-            }
-            let end_idx = out.len();
-            if start_idx < end_idx {
-                out[start_idx].extra_space_before += cell_margin_top;
-                out[end_idx - 1].extra_space_after += cell_margin_bottom;
-            }
+    let mut format = style.whole_table.clone();
+    for cond in kinds.iter().filter_map(|k| style.conditional.get(*k)) {
+        format.shading = cond.shading.or(format.shading);
+        format.borders = match (format.borders, cond.borders) {
+            (Some(base), Some(over)) => Some(TableBordersDef {
+                top: border_or_fallback(over.top, base.top),
+                bottom: border_or_fallback(over.bottom, base.bottom),
+                left: border_or_fallback(over.left, base.left),
+                right: border_or_fallback(over.right, base.right),
+                inside_h: border_or_fallback(over.inside_h, base.inside_h),
+                inside_v: border_or_fallback(over.inside_v, base.inside_v),
+            }),
+            (base, over) => over.or(base),
+        };
+        format.bold = cond.bold.or(format.bold);
+        format.italic = cond.italic.or(format.italic);
+        format.color = cond.color.or(format.color);
+    }
+    format
+}
+
+/// Run formatting from the table style, below anything the runs set themselves:
+/// it can turn bold and italic on and color runs that have no color.
+fn apply_conditional_run_format(para: &mut Paragraph, format: &ConditionalFormat) {
+    for run in &mut para.runs {
+        run.bold |= format.bold == Some(true);
+        run.italic |= format.italic == Some(true);
+        if run.color.is_none() {
+            run.color = format.color;
         }
     }
 }
 
+fn border_or_fallback(inline: CellBorder, fallback: CellBorder) -> CellBorder {
+    if inline.present { inline } else { fallback }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn parse_cell_paragraph<R: Read + std::io::Seek>(
+    p: roxmltree::Node,
+    styles: &styles::StylesInfo,
+    theme: &styles::ThemeFonts,
+    rels: &HashMap<String, String>,
+    zip: &mut zip::ZipArchive<R>,
+    numbering: &numbering::NumberingInfo,
+    counters: &mut HashMap<(String, u8), u32>,
+    last_seen_level: &mut HashMap<String, u8>,
+    has_tbl_style: bool,
+) -> Paragraph {
+    let parsed = parse_runs(p, styles, theme, rels, zip, numbering);
+    let mut runs = parsed.runs;
+    let has_text = runs.iter().any(|r| !r.text.is_empty() || r.is_tab);
+    let has_inline_images = runs.iter().any(|r| r.inline_image.is_some());
+    let (para_image, content_height) = if has_inline_images && !has_text {
+        let idx = runs.iter().position(|r| r.inline_image.is_some());
+        let img = idx.and_then(|i| runs[i].inline_image.take());
+        let h = img
+            .as_ref()
+            .map(|i| i.display_height + i.layout_extra_height)
+            .unwrap_or(0.0);
+        (img, h)
+    } else {
+        (None, 0.0)
+    };
+    let ppr = wml(p, "pPr");
+    let para_style_id = ppr
+        .and_then(|ppr| wml_attr(ppr, "pStyle"))
+        .unwrap_or(&styles.default_paragraph_style_id);
+    let para_style = styles.paragraph_styles.get(para_style_id);
+    let alignment = ppr
+        .and_then(|ppr| wml_attr(ppr, "jc"))
+        .map(parse_alignment)
+        .or_else(|| para_style.and_then(|s| s.alignment))
+        .unwrap_or(Alignment::Left);
+    let rtl = resolve_bidi(ppr, para_style);
+    let alignment = bidi_alignment(alignment, rtl);
+    let (sp_before, sp_after, ls) = parse_paragraph_spacing(ppr, para_style);
+    let line_spacing = ls.or_else(|| has_tbl_style.then_some(LineSpacing::Auto(1.0)));
+    let num_pr = ppr.and_then(|ppr| wml(ppr, "numPr"));
+    let style_num = para_style.and_then(|s| s.num_id.as_deref());
+    let style_ilvl = para_style.and_then(|s| s.num_ilvl);
+    let ListLabelInfo {
+        mut indent_left,
+        mut indent_hanging,
+        label: list_label,
+        font: list_label_font,
+        font_size: list_label_font_size,
+        bold: list_label_bold,
+        color: list_label_color,
+        suffix: list_label_suffix,
+    } = parse_list_info(
+        num_pr,
+        style_num,
+        style_ilvl,
+        numbering,
+        counters,
+        last_seen_level,
+    );
+    let mut indent_first_line = 0.0f32;
+    let mut indent_right = 0.0f32;
+    if let Some(ind) = ppr.and_then(|ppr| wml(ppr, "ind")) {
+        let (left, right, hanging, first) = extract_indents(ind);
+        if let Some(v) = left {
+            indent_left = v;
+        }
+        if let Some(v) = right {
+            indent_right = v;
+        }
+        if let Some(v) = hanging {
+            indent_hanging = v;
+        }
+        if let Some(v) = first {
+            indent_first_line = v;
+        }
+    }
+    let space_before = sp_before.unwrap_or(0.0);
+    let space_after = sp_after.unwrap_or(if has_tbl_style {
+        0.0
+    } else {
+        styles.defaults.space_after
+    });
+    Paragraph {
+        runs,
+        style_id: Some(para_style_id.to_string()),
+        alignment,
+        rtl,
+        indent_left,
+        indent_right,
+        indent_hanging,
+        indent_first_line,
+        list_label,
+        list_label_font,
+        list_label_font_size,
+        list_label_bold,
+        list_label_color,
+        list_label_suffix,
+        line_spacing,
+        space_before,
+        space_after,
+        image: para_image,
+        content_height,
+        bookmarks: paragraph_bookmarks(p),
+        ..Paragraph::default()
+    }
+}
+
 pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
     node: roxmltree::Node,
     styles: &styles::StylesInfo,
@@ -142,6 +350,16 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
         })
         .unwrap_or_default();
 
+    let table_cell_spacing = tbl_pr.and_then(cell_spacing).unwrap_or(0.0);
+    let width = tbl_pr
+        .and_then(|pr| wml(pr, "tblW"))
+        .map(preferred_width)
+        .unwrap_or_default();
+    let autofit = tbl_pr.and_then(|pr| wml(pr, "tblLayout")).and_then(|n| {
+        n.attribute((WML_NS, "type"))
+            .or_else(|| n.attribute((WML_NS, "val")))
+    }) == Some("autofit");
+
     let table_position = tbl_pr.and_then(|pr| wml(pr, "tblpPr")).map(|tblp| {
         let v_anchor = match tblp.attribute((WML_NS, "vertAnchor")) {
             Some("page") => "page",
@@ -179,25 +397,22 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
         }
     });
 
-    let tbl_style_borders = tbl_pr
+    let tbl_style = tbl_pr
         .and_then(|pr| wml_attr(pr, "tblStyle"))
-        .and_then(|id| styles.table_border_styles.get(id));
+        .and_then(|id| styles.table_styles.get(id));
+    let tbl_style_borders = tbl_style.and_then(|s| s.borders.as_ref());
     let has_tbl_style = tbl_style_borders.is_some();
 
-    let inline_tbl_borders =
-        tbl_pr
-            .and_then(|pr| wml(pr, "tblBorders"))
-            .map(|bdr_node| TableBordersDef {
-                top: parse_cell_border(bdr_node, "top"),
-                bottom: parse_cell_border(bdr_node, "bottom"),
-                left: parse_cell_border_left(bdr_node),
-                right: parse_cell_border_right(bdr_node),
-                inside_h: parse_cell_border(bdr_node, "insideH"),
-                inside_v: parse_cell_border(bdr_node, "insideV"),
-            });
+    let inline_tbl_borders = tbl_pr
+        .and_then(|pr| wml(pr, "tblBorders"))
+        .map(styles::parse_table_borders);
 
     let effective_tbl_borders: Option<&TableBordersDef> =
         inline_tbl_borders.as_ref().or(tbl_style_borders);
+    let look = tbl_pr
+        .and_then(|pr| wml(pr, "tblLook"))
+        .map(parse_table_look)
+        .unwrap_or_default();
 
     let tbl_rows: Vec<_> = collect_block_nodes(node)
         .into_iter()
@@ -218,6 +433,10 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
             })
             .unwrap_or((None, false));
         let is_header = tr_pr.and_then(|pr| wml(pr, "tblHeader")).is_some();
+        let cant_split = tr_pr
+            .and_then(|pr| wml_bool(pr, "cantSplit"))
+            .unwrap_or(false);
+        let row_cell_spacing = tr_pr.and_then(cell_spacing).unwrap_or(table_cell_spacing);
 
         let mut cells = Vec::new();
         let mut grid_col = 0usize;
@@ -229,8 +448,8 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
             let tc_pr = wml(tc, "tcPr");
             let cell_width = tc_pr
                 .and_then(|pr| wml(pr, "tcW"))
-                .and_then(|w| twips_attr(w, "w"))
-                .unwrap_or_else(|| col_widths.get(ci).copied().unwrap_or(72.0));
+                .map(preferred_width)
+                .unwrap_or_default();
 
             let grid_span = tc_pr
                 .and_then(|pr| wml_attr(pr, "gridSpan"))
@@ -272,8 +491,45 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
                 } else {
                     tb.inside_v
                 },
+                ..CellBorders::default()
             });
 
+            let format = tbl_style
+                .map(|style| {
+                    let region = CellRegion {
+                        row: ri,
+                        col: ci,
+                        span_end,
+                        num_rows,
+                        num_cols,
+                    };
+                    resolve_conditional_format(style, look, region)
+                })
+                .unwrap_or_default();
+            let style_borders = match format.borders {
+                Some(cond) => {
+                    let base = style_borders.unwrap_or_default();
+                    let left = if ci == 0 {
+                        cond.left
+                    } else {
+                        border_or_fallback(cond.inside_v, cond.left)
+                    };
+                    let right = if span_end >= num_cols {
+                        cond.right
+                    } else {
+                        border_or_fallback(cond.inside_v, cond.right)
+                    };
+                    Some(CellBorders {
+                        top: border_or_fallback(cond.top, base.top),
+                        bottom: border_or_fallback(cond.bottom, base.bottom),
+                        left: border_or_fallback(left, base.left),
+                        right: border_or_fallback(right, base.right),
+                        ..CellBorders::default()
+                    })
+                }
+                None => style_borders,
+            };
+
             let borders = tc_pr
                 .and_then(|pr| wml(pr, "tcBorders"))
                 .map(|bdr| {
@@ -286,6 +542,8 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
                         ),
                         left: border_or_fallback(parse_cell_border_left(bdr), fallback.left),
                         right: border_or_fallback(parse_cell_border_right(bdr), fallback.right),
+                        tl2br: parse_cell_border(bdr, "tl2br"),
+                        tr2bl: parse_cell_border(bdr, "tr2bl"),
                     }
                 })
                 .unwrap_or_else(|| style_borders.unwrap_or_default());
@@ -294,7 +552,8 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
                 .and_then(|pr| wml(pr, "shd"))
                 .and_then(|shd| shd.attribute((WML_NS, "fill")))
                 .filter(|f| *f != "none")
-                .and_then(parse_hex_color);
+                .and_then(parse_hex_color)
+                .or(format.shading);
 
             let per_cell_margins = tc_pr
                 .and_then(|pr| wml(pr, "tcMar"))
@@ -312,110 +571,39 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
                 });
 
             let mut cell_paras = Vec::new();
-            let block_nodes = collect_block_nodes(tc);
-            let mut p_nodes: Vec<AnnotatedNode> = Vec::new();
-            for n in &block_nodes {
-                if is_wml(n, "p") {
-                    p_nodes.push(AnnotatedNode {
-                        node: *n,
-                        extra_space_before: 0.0,
-                        extra_space_after: 0.0,
+            let mut nested_tables = Vec::new();
+            for n in collect_block_nodes(tc) {
+                if is_wml(&n, "p") {
+                    let mut para = parse_cell_paragraph(
+                        n,
+                        styles,
+                        theme,
+                        rels,
+                        zip,
+                        numbering,
+                        counters,
+                        last_seen_level,
+                        has_tbl_style,
+                    );
+                    apply_conditional_run_format(&mut para, &format);
+                    cell_paras.push(para);
+                } else if is_wml(&n, "tbl") {
+                    let table = parse_table_node(
+                        n,
+                        styles,
+                        theme,
+                        rels,
+                        zip,
+                        numbering,
+                        counters,
+                        last_seen_level,
+                    );
+                    nested_tables.push(NestedTable {
+                        para_idx: cell_paras.len(),
+                        table,
                     });
-                } else if is_wml(n, "tbl") {
-                    collect_nested_table_paragraphs(*n, &mut p_nodes);
                 }
             }
-            for ap in p_nodes {
-                let p = ap.node;
-                let parsed = parse_runs(p, styles, theme, rels, zip, numbering);
-                let mut runs = parsed.runs;
-                let has_text = runs.iter().any(|r| !r.text.is_empty() || r.is_tab);
-                let has_inline_images = runs.iter().any(|r| r.inline_image.is_some());
-                let (para_image, content_height) = if has_inline_images && !has_text {
-                    let idx = runs.iter().position(|r| r.inline_image.is_some());
-                    let img = idx.and_then(|i| runs[i].inline_image.take());
-                    let h = img
-                        .as_ref()
-                        .map(|i| i.display_height + i.layout_extra_height)
-                        .unwrap_or(0.0);
-                    (img, h)
-                } else {
-                    (None, 0.0)
-                };
-                let ppr = wml(p, "pPr");
-                let para_style_id = ppr
-                    .and_then(|ppr| wml_attr(ppr, "pStyle"))
-                    .unwrap_or(&styles.default_paragraph_style_id);
-                let para_style = styles.paragraph_styles.get(para_style_id);
-                let alignment = ppr
-                    .and_then(|ppr| wml_attr(ppr, "jc"))
-                    .map(parse_alignment)
-                    .or_else(|| para_style.and_then(|s| s.alignment))
-                    .unwrap_or(Alignment::Left);
-                let (sp_before, sp_after, ls) = parse_paragraph_spacing(ppr, para_style);
-                let line_spacing = ls.or_else(|| has_tbl_style.then_some(LineSpacing::Auto(1.0)));
-                let num_pr = ppr.and_then(|ppr| wml(ppr, "numPr"));
-                let style_num = para_style.and_then(|s| s.num_id.as_deref());
-                let style_ilvl = para_style.and_then(|s| s.num_ilvl);
-                let ListLabelInfo {
-                    mut indent_left,
-                    mut indent_hanging,
-                    label: list_label,
-                    font: list_label_font,
-                    font_size: list_label_font_size,
-                    bold: list_label_bold,
-                    color: list_label_color,
-                } = parse_list_info(
-                    num_pr,
-                    style_num,
-                    style_ilvl,
-                    numbering,
-                    counters,
-                    last_seen_level,
-                );
-                let mut indent_first_line = 0.0f32;
-                let mut indent_right = 0.0f32;
-                if let Some(ind) = ppr.and_then(|ppr| wml(ppr, "ind")) {
-                    let (left, right, hanging, first) = extract_indents(ind);
-                    if let Some(v) = left {
-                        indent_left = v;
-                    }
-                    if let Some(v) = right {
-                        indent_right = v;
-                    }
-                    if let Some(v) = hanging {
-                        indent_hanging = v;
-                    }
-                    if let Some(v) = first {
-                        indent_first_line = v;
-                    }
-                }
-                let space_before = sp_before.unwrap_or(0.0) + ap.extra_space_before;
-                let space_after = sp_after.unwrap_or(if has_tbl_style {
-                    0.0
-                } else {
-                    styles.defaults.space_after
-                }) + ap.extra_space_after;
-                cell_paras.push(Paragraph {
-                    runs,
-                    alignment,
-                    indent_left,
-                    indent_right,
-                    indent_hanging,
-                    indent_first_line,
-                    list_label,
-                    list_label_font,
-                    list_label_font_size,
-                    list_label_bold,
-                    list_label_color,
-                    line_spacing,
-                    space_before,
-                    space_after,
-                    image: para_image,
-                    content_height,
-                    ..Paragraph::default()
-                });
-            }
             cells.push(TableCell {
                 width: cell_width,
                 paragraphs: cell_paras,
@@ -426,6 +614,7 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
                 v_align,
                 cell_margins: per_cell_margins,
                 text_direction,
+                nested_tables,
             });
             grid_col += grid_span as usize;
         }
@@ -434,13 +623,54 @@ pub(in crate::docx) fn parse_table_node<R: Read + std::io::Seek>(
             height: row_height,
             height_exact,
             is_header,
+            cant_split,
+            cell_spacing: row_cell_spacing,
         });
     }
     Table {
         col_widths,
+        width,
+        autofit,
         rows,
         table_indent,
         cell_margins,
         position: table_position,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn look(attrs: &str) -> TableLook {
+        let xml = format!(r#"<w:tblLook xmlns:w="{WML_NS}" {attrs}/>"#);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        parse_table_look(doc.root_element())
+    }
+
+    #[test]
+    fn test_table_look_bitmask_and_attributes() {
+        let expected = TableLook {
+            first_row: true,
+            first_column: true,
+            h_band: true,
+            ..TableLook::default()
+        };
+        assert_eq!(look(r#"w:val="04A0""#), expected);
+        assert_eq!(
+            look(r#"w:firstRow="1" w:lastRow="0" w:firstColumn="1" w:noVBand="1""#),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_row_bands_skip_header_row() {
+        let bands: Vec<_> = (0..5)
+            .map(|row| band_type(row, row == 4, true, false, 1))
+            .collect();
+        assert_eq!(
+            bands,
+            [None, Some(true), Some(false), Some(true), Some(false)]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! In-memory packages for parser and layout unit tests.
+
+use std::io::Write;
+
+use crate::ConversionOptions;
+use crate::model::{Block, Document, Paragraph};
+
+/// Namespace declarations for the prefixes test markup uses.
+const NAMESPACES: &str = concat!(
+    r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+    r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+    r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+    r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture" "#,
+    r#"xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math""#,
+);
+
+/// A zip of `parts` under their package names. The parser opens parts by
+/// name, so content types and package relationships can be left out.
+pub(crate) fn package(parts: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in parts {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// `content` as the root `tag` element of a WordprocessingML part, e.g.
+/// `w:styles` or `w:ftr`, with the test namespaces declared.
+pub(crate) fn part(tag: &str, content: &str) -> String {
+    format!("<{tag} {NAMESPACES}>{content}</{tag}>")
+}
+
+/// Parses a document whose body is `body`, next to the extra `parts`.
+pub(crate) fn parse_with(
+    body: &str,
+    parts: &[(&str, &[u8])],
+    options: &ConversionOptions,
+) -> Document {
+    let document = part("w:document", &format!("<w:body>{body}</w:body>"));
+    let mut all = vec![("word/document.xml", document.as_bytes())];
+    all.extend_from_slice(parts);
+    super::parse_bytes(&package(&all), options).expect("parse test document")
+}
+
+/// Parses a document whose body is `body` and that has no other parts.
+pub(crate) fn parse_body(body: &str) -> Document {
+    parse_with(body, &[], &ConversionOptions::default())
+}
+
+/// The body paragraphs of every section, in order, leaving out tables.
+pub(crate) fn paragraphs(doc: &Document) -> Vec<&Paragraph> {
+    doc.sections
+        .iter()
+        .flat_map(|s| &s.blocks)
+        .filter_map(|b| match b {
+            Block::Paragraph(p) => Some(p),
+            Block::Table(_) => None,
+        })
+        .collect()
+}
+
+/// Text of each run of the first body paragraph.
+pub(crate) fn first_paragraph_texts(doc: &Document) -> Vec<String> {
+    paragraphs(doc)[0]
+        .runs
+        .iter()
+        .map(|r| r.text.clone())
+        .collect()
+}
+
+/// A complex field whose result is `cached`, as Word leaves it when a
+/// document is saved without updating fields.
+pub(crate) fn field(instr: &str, cached: &str) -> String {
+    format!(
+        r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve">{instr}</w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>{cached}</w:t></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r>"#
+    )
+}
@@ -7,12 +7,13 @@ use crate::model::{
     ShapeGeometry, TextAnchor, Textbox, VRelativeFrom, WrapType,
 };
 
+use super::bookmarks::paragraph_bookmarks;
 use super::images::{extent_dimensions, parse_anchor_position};
 use super::numbering::{ListLabelInfo, NumberingInfo};
 use super::runs::parse_runs;
 use super::styles::{
-    ColorTransforms, StylesInfo, ThemeFillStyle, ThemeFonts, parse_alignment,
-    parse_color_transforms,
+    ColorTransforms, StylesInfo, ThemeFillStyle, ThemeFonts, bidi_alignment, parse_alignment,
+    parse_color_transforms, resolve_bidi,
 };
 use super::{
     DML_NS, MC_NS_TOP, WML_NS, WPD_NS, WPS_NS, extract_indents, parse_paragraph_spacing,
@@ -114,6 +115,8 @@ pub(super) fn parse_txbx_content_paragraphs<R: Read + std::io::Seek>(
             .map(parse_alignment)
             .or_else(|| para_style.and_then(|s| s.alignment))
             .unwrap_or(Alignment::Left);
+        let rtl = resolve_bidi(ppr, para_style);
+        let alignment = bidi_alignment(alignment, rtl);
         let (sp_before, sp_after, ls) = parse_paragraph_spacing(ppr, para_style);
         let space_before = sp_before.unwrap_or(0.0);
         let space_after = sp_after.unwrap_or(styles.defaults.space_after);
@@ -128,6 +131,7 @@ pub(super) fn parse_txbx_content_paragraphs<R: Read + std::io::Seek>(
             font_size: list_label_font_size,
             bold: list_label_bold,
             color: list_label_color,
+            suffix: list_label_suffix,
         } = super::numbering::parse_list_info(
             num_pr,
             None,
@@ -173,6 +177,7 @@ pub(super) fn parse_txbx_content_paragraphs<R: Read + std::io::Seek>(
             space_before,
             space_after,
             alignment,
+            rtl,
             indent_left,
             indent_right,
             indent_hanging,
@@ -182,10 +187,12 @@ pub(super) fn parse_txbx_content_paragraphs<R: Read + std::io::Seek>(
             list_label_font_size,
             list_label_bold,
             list_label_color,
+            list_label_suffix,
             line_spacing,
             tab_stops,
             floating_images: parsed.floating_images,
             textboxes: parsed.textboxes,
+            bookmarks: paragraph_bookmarks(p),
             ..Paragraph::default()
         });
     }
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use crate::model::{Alignment, Block, FieldCode, Paragraph, Run, Section, TabAlignment, TabStop};
+
+use super::paragraphs_mut;
+use super::styles::{ParagraphStyle, StylesInfo, parse_outline_level};
+
+/// Word indents each TOC level by 0.15" when no TOC style says otherwise.
+const TOC_LEVEL_INDENT: f32 = 11.0;
+
+struct TocHeading {
+    level: u8,
+    text: String,
+    bookmark: String,
+}
+
+/// Heading level of a paragraph style, by built-in style name or English style id.
+fn heading_level(style_id: &str, style_id_to_name: &HashMap<String, String>) -> Option<u8> {
+    let name = style_id_to_name
+        .get(style_id)
+        .map(|n| n.to_ascii_lowercase())
+        .unwrap_or_default();
+    let digits = name
+        .strip_prefix("heading ")
+        .or_else(|| style_id.strip_prefix("Heading"))?;
+    let level = digits.trim().parse::<u8>().ok()?;
+    (1..=9).contains(&level).then_some(level)
+}
+
+/// Outline level (1–9) of a paragraph: `w:outlineLvl`, directly or from its style,
+/// else the level of a built-in heading style. Level 9 in the XML means body text.
+pub(super) fn outline_level(
+    ppr: Option<roxmltree::Node>,
+    para_style: Option<&ParagraphStyle>,
+    style_id: &str,
+    styles: &StylesInfo,
+) -> Option<u8> {
+    match ppr
+        .and_then(parse_outline_level)
+        .or_else(|| para_style.and_then(|s| s.outline_level))
+    {
+        Some(lvl) => (lvl < 9).then_some(lvl + 1),
+        None => heading_level(style_id, &styles.style_id_to_name),
+    }
+}
+
+/// Run index and level range of the TOC field starting in `para`.
+fn toc_start(para: &Paragraph) -> Option<(usize, u8, u8)> {
+    para.runs
+        .iter()
+        .enumerate()
+        .find_map(|(i, r)| match r.field_code {
+            Some(FieldCode::Toc {
+                min_level,
+                max_level,
+            }) => Some((i, min_level, max_level)),
+            _ => None,
+        })
+}
+
+/// Block and run index of the end of a field whose result starts after run
+/// `start` of block `first`. Word writes a populated TOC as one paragraph per
+/// entry, so the end is usually several paragraphs on.
+fn field_end(blocks: &[Block], first: usize, start: usize) -> Option<(usize, usize)> {
+    blocks[first..]
+        .iter()
+        .enumerate()
+        .find_map(|(offset, block)| {
+            let Block::Paragraph(p) = block else {
+                return None;
+            };
+            let from = if offset == 0 { start + 1 } else { 0 };
+            p.runs
+                .iter()
+                .enumerate()
+                .skip(from)
+                .find(|(_, r)| matches!(r.field_code, Some(FieldCode::FieldEnd)))
+                .map(|(i, _)| (first + offset, i))
+        })
+}
+
+fn has_content(runs: &[Run]) -> bool {
+    runs.iter()
+        .any(|r| r.field_code.is_none() && (!r.text.trim().is_empty() || r.is_tab))
+}
+
+/// An empty paragraph with `para`'s own paragraph properties and trailing
+/// break, for text that follows a field ending inside `para`.
+fn paragraph_properties(para: &Paragraph) -> Paragraph {
+    Paragraph {
+        style_id: para.style_id.clone(),
+        space_before: para.space_before,
+        space_after: para.space_after,
+        alignment: para.alignment,
+        indent_left: para.indent_left,
+        indent_right: para.indent_right,
+        indent_hanging: para.indent_hanging,
+        indent_first_line: para.indent_first_line,
+        contextual_spacing: para.contextual_spacing,
+        keep_next: para.keep_next,
+        keep_lines: para.keep_lines,
+        suppress_auto_hyphens: para.suppress_auto_hyphens,
+        line_spacing: para.line_spacing,
+        borders: para.borders.clone(),
+        shading: para.shading,
+        tab_stops: para.tab_stops.clone(),
+        rtl: para.rtl,
+        is_section_break: para.is_section_break,
+        page_break_after: para.page_break_after,
+        ..Paragraph::default()
+    }
+}
+
+/// Replaces TOC fields with one entry per heading: heading text, a dot-leader
+/// tab and a PAGEREF to a bookmark on the heading, linked back to it. Page
+/// numbers resolve with the other PAGEREF fields. Only the field's result is
+/// replaced; text around it stays in paragraphs of its own.
+pub(super) fn generate_tocs(sections: &mut [Section], styles: &StylesInfo) {
+    let has_toc = sections
+        .iter()
+        .flat_map(|s| s.blocks.iter())
+        .any(|b| matches!(b, Block::Paragraph(p) if toc_start(p).is_some()));
+    if !has_toc {
+        return;
+    }
+
+    let mut headings = Vec::new();
+    for para in sections
+        .iter_mut()
+        .flat_map(|s| paragraphs_mut(&mut s.blocks))
+    {
+        let Some(level) = para
+            .style_id
+            .as_deref()
+            .and_then(|id| heading_level(id, &styles.style_id_to_name))
+        else {
+            continue;
+        };
+        let mut text = para.list_label.clone();
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.extend(
+            para.runs
+                .iter()
+                .filter(|r| !r.vanish && r.field_code.is_none())
+                .map(|r| r.text.as_str()),
+        );
+        if text.trim().is_empty() {
+            continue;
+        }
+        let bookmark = format!("_TocHeading{}", headings.len() + 1);
+        para.bookmarks.push(bookmark.clone());
+        headings.push(TocHeading {
+            level,
+            text: text.trim().to_string(),
+            bookmark,
+        });
+    }
+    if headings.is_empty() {
+        return;
+    }
+
+    for section in sections.iter_mut() {
+        let sp = &section.properties;
+        let text_width = sp.page_width - sp.margin_left - sp.margin_right;
+        let mut i = 0;
+        while i < section.blocks.len() {
+            let range = match &section.blocks[i] {
+                Block::Paragraph(p) => toc_start(p),
+                Block::Table(_) => None,
+            };
+            let Some((start, min_level, max_level)) = range else {
+                i += 1;
+                continue;
+            };
+            let Some((last, end)) = field_end(&section.blocks, i, start) else {
+                i += 1;
+                continue;
+            };
+            let mut entries: Vec<Paragraph> = headings
+                .iter()
+                .filter(|h| (min_level..=max_level).contains(&h.level))
+                .map(|h| toc_entry(h, styles, text_width))
+                .collect();
+            if entries.is_empty() {
+                i += 1;
+                continue;
+            }
+
+            let mut replaced = section.blocks.drain(i..=last).filter_map(|b| match b {
+                Block::Paragraph(p) => Some(p),
+                Block::Table(_) => None,
+            });
+            let mut host_first = replaced.next().expect("the TOC paragraph");
+            // A field ending in its own paragraph hands the text after it
+            // that paragraph's properties and break
+            let (mut host_last, after) = match replaced.next_back() {
+                Some(mut last) => {
+                    let after = last.runs.split_off(end + 1);
+                    (last, after)
+                }
+                None => {
+                    let after = host_first.runs.split_off(end + 1);
+                    (paragraph_properties(&host_first), after)
+                }
+            };
+            drop(replaced);
+
+            let mut blocks = Vec::new();
+            let before: Vec<Run> = host_first.runs.drain(..start).collect();
+            if has_content(&before) {
+                blocks.push(Block::Paragraph(Paragraph {
+                    runs: before,
+                    is_section_break: false,
+                    page_break_after: false,
+                    ..host_first
+                }));
+            } else {
+                let first = &mut entries[0];
+                first.page_break_before = host_first.page_break_before;
+                first.column_break_before = host_first.column_break_before;
+                first.bookmarks = std::mem::take(&mut host_first.bookmarks);
+            }
+            let tail = if has_content(&after) {
+                Some(Paragraph {
+                    runs: after,
+                    page_break_before: false,
+                    column_break_before: false,
+                    bookmarks: Vec::new(),
+                    ..host_last
+                })
+            } else {
+                let entry = entries.last_mut().expect("at least one entry");
+                entry.is_section_break = host_last.is_section_break;
+                entry.page_break_after = host_last.page_break_after;
+                entry.bookmarks.append(&mut host_last.bookmarks);
+                None
+            };
+            blocks.extend(entries.into_iter().map(Block::Paragraph));
+            blocks.extend(tail.map(Block::Paragraph));
+
+            let count = blocks.len();
+            section.blocks.splice(i..i, blocks);
+            i += count;
+        }
+    }
+}
+
+fn toc_entry(heading: &TocHeading, styles: &StylesInfo, text_width: f32) -> Paragraph {
+    let style_id = format!("TOC{}", heading.level);
+    let style = styles.paragraph_styles.get(&style_id);
+    let indent_right = style.and_then(|s| s.indent_right).unwrap_or(0.0);
+
+    let base = Run {
+        font_size: style
+            .and_then(|s| s.font_size)
+            .unwrap_or(styles.defaults.font_size),
+        font_name: style
+            .and_then(|s| s.font_name.clone())
+            .unwrap_or_else(|| styles.defaults.font_name.clone()),
+        bold: style.and_then(|s| s.bold).unwrap_or(styles.defaults.bold),
+        italic: style
+            .and_then(|s| s.italic)
+            .unwrap_or(styles.defaults.italic),
+        caps: style.and_then(|s| s.caps).unwrap_or(styles.defaults.caps),
+        small_caps: style
+            .and_then(|s| s.small_caps)
+            .unwrap_or(styles.defaults.small_caps),
+        color: style.and_then(|s| s.color).or(styles.defaults.color),
+        hyperlink_url: Some(format!("#{}", heading.bookmark)),
+        ..Run::default()
+    };
+
+    let mut tab_stops = style.map(|s| s.tab_stops.clone()).unwrap_or_default();
+    tab_stops.push(TabStop {
+        position: text_width - indent_right,
+        alignment: TabAlignment::Right,
+        leader: Some('.'),
+    });
+    tab_stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+    Paragraph {
+        runs: vec![
+            Run {
+                text: heading.text.clone(),
+                ..base.clone()
+            },
+            Run {
+                is_tab: true,
+                ..base.clone()
+            },
+            Run {
+                text: "0".to_string(),
+                field_code: Some(FieldCode::PageRef(heading.bookmark.clone())),
+                ..base
+            },
+        ],
+        style_id: Some(style_id),
+        space_before: style.and_then(|s| s.space_before).unwrap_or(0.0),
+        space_after: style
+            .and_then(|s| s.space_after)
+            .unwrap_or(styles.defaults.space_after),
+        alignment: Alignment::Left,
+        indent_left: style
+            .and_then(|s| s.indent_left)
+            .unwrap_or((heading.level - 1) as f32 * TOC_LEVEL_INDENT),
+        indent_right,
+        line_spacing: style.and_then(|s| s.line_spacing),
+        tab_stops,
+        ..Paragraph::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::testing;
+
+    fn text(para: &Paragraph) -> String {
+        para.runs.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_cached_toc_over_several_paragraphs_is_replaced() {
+        let heading = |style: &str, text: &str| {
+            format!(
+                r#"<w:p><w:pPr><w:pStyle w:val="{style}"/></w:pPr><w:r><w:t>{text}</w:t></w:r></w:p>"#
+            )
+        };
+        let body = format!(
+            concat!(
+                r#"<w:p><w:r><w:t>Contents</w:t></w:r><w:r><w:fldChar w:fldCharType="begin"/></w:r>"#,
+                r#"<w:r><w:instrText xml:space="preserve"> TOC \o "1-2" \h </w:instrText></w:r>"#,
+                r#"<w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>Stale one</w:t></w:r></w:p>"#,
+                r#"<w:p><w:r><w:t>Stale two</w:t></w:r></w:p>"#,
+                r#"<w:p><w:pPr><w:sectPr/></w:pPr><w:r><w:t>Stale three</w:t></w:r>"#,
+                r#"<w:r><w:fldChar w:fldCharType="end"/></w:r></w:p>"#,
+                "{}<w:tbl><w:tr><w:tc>{}</w:tc></w:tr></w:tbl>{}"
+            ),
+            heading("Heading1", "Alpha"),
+            heading("Heading2", "Beta"),
+            heading("Heading3", "Gamma"),
+        );
+        let doc = testing::parse_body(&body);
+
+        let toc: Vec<&Paragraph> = doc.sections[0]
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                Block::Paragraph(p) => Some(p),
+                Block::Table(_) => None,
+            })
+            .collect();
+        let texts: Vec<String> = toc.iter().map(|p| text(p)).collect();
+        assert_eq!(texts, ["Contents", "Alpha0", "Beta0"]);
+        assert!(toc[2].is_section_break);
+        assert_eq!(
+            toc[2].runs[2].field_code,
+            Some(FieldCode::PageRef("_TocHeading2".into()))
+        );
+    }
+}
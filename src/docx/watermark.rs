@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::model::{Run, Watermark};
+
+use super::images::read_image_from_zip_extra;
+
+const VML_NS: &str = "urn:schemas-microsoft-com:vml";
+const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Opacity Word gives a washed-out picture watermark.
+const WASHOUT_OPACITY: f32 = 0.3;
+
+fn vml_child<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<roxmltree::Node<'a, 'a>> {
+    node.children()
+        .find(|n| n.tag_name().namespace() == Some(VML_NS) && n.tag_name().name() == name)
+}
+
+/// Length in a VML style (`468pt`, `6.5in`, ...) in points.
+fn vml_length(val: &str) -> Option<f32> {
+    let val = val.trim();
+    let (num, scale) = if let Some(v) = val.strip_suffix("pt") {
+        (v, 1.0)
+    } else if let Some(v) = val.strip_suffix("in") {
+        (v, 72.0)
+    } else if let Some(v) = val.strip_suffix("cm") {
+        (v, 72.0 / 2.54)
+    } else if let Some(v) = val.strip_suffix("mm") {
+        (v, 72.0 / 25.4)
+    } else {
+        (val, 1.0)
+    };
+    num.parse::<f32>().ok().map(|n| n * scale)
+}
+
+/// VML fractions are either plain (`.5`) or in 1/65536 units (`32768f`).
+fn vml_fraction(val: &str) -> Option<f32> {
+    match val.strip_suffix('f') {
+        Some(fixed) => fixed.parse::<f32>().ok().map(|v| v / 65536.0),
+        None => val.parse::<f32>().ok(),
+    }
+}
+
+/// VML colors: `#rrggbb`, `#rgb` or a named color, optionally followed by `[index]`.
+fn vml_color(val: &str) -> Option<[u8; 3]> {
+    let val = val.split_whitespace().next()?;
+    if let Some(hex) = val.strip_prefix('#') {
+        let hex: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            _ => hex.to_string(),
+        };
+        let v = u32::from_str_radix(hex.get(..6)?, 16).ok()?;
+        return Some([(v >> 16) as u8, (v >> 8) as u8, v as u8]);
+    }
+    Some(match val.to_ascii_lowercase().as_str() {
+        "black" => [0x00, 0x00, 0x00],
+        "white" => [0xFF, 0xFF, 0xFF],
+        "silver" => [0xC0, 0xC0, 0xC0],
+        "gray" | "grey" => [0x80, 0x80, 0x80],
+        "red" => [0xFF, 0x00, 0x00],
+        "maroon" => [0x80, 0x00, 0x00],
+        "blue" => [0x00, 0x00, 0xFF],
+        "navy" => [0x00, 0x00, 0x80],
+        "green" => [0x00, 0x80, 0x00],
+        "lime" => [0x00, 0xFF, 0x00],
+        "yellow" => [0xFF, 0xFF, 0x00],
+        "olive" => [0x80, 0x80, 0x00],
+        "teal" => [0x00, 0x80, 0x80],
+        "aqua" => [0x00, 0xFF, 0xFF],
+        "purple" => [0x80, 0x00, 0x80],
+        "fuchsia" => [0xFF, 0x00, 0xFF],
+        _ => return None,
+    })
+}
+
+fn style_value<'a>(style: &'a str, key: &str) -> Option<&'a str> {
+    style.split(';').find_map(|part| {
+        let (k, v) = part.split_once(':')?;
+        (k.trim() == key).then(|| v.trim())
+    })
+}
+
+/// The watermark of a header part: a VML WordArt shape (`v:textpath`) or a
+/// picture shape Word names `WordPictureWatermark…`.
+pub(super) fn parse_watermark<R: Read + std::io::Seek>(
+    root: roxmltree::Node,
+    rels: &HashMap<String, String>,
+    zip: &mut zip::ZipArchive<R>,
+) -> Option<Watermark> {
+    let shape = root.descendants().find(|n| {
+        n.tag_name().namespace() == Some(VML_NS)
+            && n.tag_name().name() == "shape"
+            && (vml_child(*n, "textpath").is_some()
+                || n.attribute("id")
+                    .is_some_and(|id| id.contains("Watermark") || id.contains("WaterMark")))
+    })?;
+    let style = shape.attribute("style").unwrap_or("");
+    let width = style_value(style, "width").and_then(vml_length)?;
+    let height = style_value(style, "height").and_then(vml_length)?;
+
+    if let Some(textpath) = vml_child(shape, "textpath") {
+        let text = textpath
+            .attribute("string")
+            .filter(|s| !s.trim().is_empty())?;
+        let font_name = textpath
+            .attribute("style")
+            .and_then(|s| style_value(s, "font-family"))
+            .map(|f| f.trim_matches(|c| c == '"' || c == '\'').to_string())
+            .unwrap_or_else(|| "Calibri".to_string());
+        let opacity = vml_child(shape, "fill")
+            .and_then(|f| f.attribute("opacity"))
+            .and_then(vml_fraction)
+            .unwrap_or(1.0);
+        let rotation = style_value(style, "rotation")
+            .and_then(|v| v.parse::<f32>().ok())
+            .unwrap_or(0.0);
+        return Some(Watermark::Text {
+            run: Box::new(Run {
+                text: text.to_string(),
+                font_name,
+                font_size: height,
+                color: shape
+                    .attribute("fillcolor")
+                    .and_then(vml_color)
+                    .or(Some([0xC0, 0xC0, 0xC0])),
+                ..Run::default()
+            }),
+            width,
+            rotation,
+            opacity,
+        });
+    }
+
+    let imagedata = vml_child(shape, "imagedata")?;
+    let rid = imagedata.attribute((REL_NS, "id"))?;
+    let image = read_image_from_zip_extra(rid, rels, zip, width, height, 0.0)?;
+    // Washout is written as reduced gain plus a raised black level
+    let washout =
+        imagedata.attribute("gain").is_some() && imagedata.attribute("blacklevel").is_some();
+    Some(Watermark::Image {
+        image,
+        opacity: if washout { WASHOUT_OPACITY } else { 1.0 },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vml_values() {
+        assert_eq!(vml_length("468pt"), Some(468.0));
+        assert_eq!(vml_length("1in"), Some(72.0));
+        assert_eq!(vml_fraction(".5"), Some(0.5));
+        assert_eq!(vml_fraction("32768f"), Some(0.5));
+        assert_eq!(vml_color("silver"), Some([0xC0, 0xC0, 0xC0]));
+        assert_eq!(vml_color("#c0c0c0 [3212]"), Some([0xC0, 0xC0, 0xC0]));
+        assert_eq!(vml_color("#f00"), Some([0xFF, 0x00, 0x00]));
+    }
+}
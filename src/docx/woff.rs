@@ -0,0 +1,745 @@
+//! Unpacking of WOFF and WOFF2 web fonts into the TrueType/OpenType (SFNT)
+//! files the font registrar reads.
+
+use std::io::Read;
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Sum of a table's big-endian u32 words, zero-padded to a whole word.
+fn table_checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// An SFNT file of `flavor` holding `tables` as (tag, checksum, data), in
+/// the order given. None when there are too many tables for the directory
+/// header's u16 search fields.
+fn write_sfnt(flavor: u32, tables: &[(u32, u32, Vec<u8>)]) -> Option<Vec<u8>> {
+    let num_tables = u16::try_from(tables.len()).ok()?;
+    let n = u32::from(num_tables.max(1));
+    let entry_selector = n.ilog2();
+    let search_range = (1u32 << entry_selector) * 16;
+    let range_shift = (u32::from(num_tables) * 16).saturating_sub(search_range);
+    let mut sfnt = flavor.to_be_bytes().to_vec();
+    for field in [
+        u32::from(num_tables),
+        search_range,
+        entry_selector,
+        range_shift,
+    ] {
+        sfnt.extend_from_slice(&u16::try_from(field).ok()?.to_be_bytes());
+    }
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, checksum, table) in tables {
+        sfnt.extend_from_slice(&tag.to_be_bytes());
+        sfnt.extend_from_slice(&checksum.to_be_bytes());
+        sfnt.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        sfnt.extend_from_slice(&u32::try_from(table.len()).ok()?.to_be_bytes());
+        offset += table.len().next_multiple_of(4);
+    }
+    for (_, _, table) in tables {
+        sfnt.extend_from_slice(table);
+        sfnt.resize(sfnt.len().next_multiple_of(4), 0);
+    }
+    Some(sfnt)
+}
+
+/// Rebuilds the TrueType/OpenType file packed in a WOFF 1.0 font: every table
+/// inflated (or copied, when stored) behind a regular SFNT table directory.
+pub(super) fn woff_to_sfnt(data: &[u8]) -> Option<Vec<u8>> {
+    const HEADER_LEN: usize = 44;
+    const ENTRY_LEN: usize = 20;
+    let flavor = be_u32(data, 4)?;
+    let num_tables = be_u16(data, 12)? as usize;
+    // Every directory entry has to fit in the data before any is read
+    if HEADER_LEN + num_tables * ENTRY_LEN > data.len() {
+        return None;
+    }
+
+    let mut tables = Vec::with_capacity(num_tables);
+    for i in 0..num_tables {
+        let at = HEADER_LEN + i * ENTRY_LEN;
+        let tag = be_u32(data, at)?;
+        let offset = be_u32(data, at + 4)? as usize;
+        let comp_len = be_u32(data, at + 8)? as usize;
+        let orig_len = be_u32(data, at + 12)? as usize;
+        let checksum = be_u32(data, at + 16)?;
+        let stored = data.get(offset..offset.checked_add(comp_len)?)?;
+        let table = if comp_len < orig_len {
+            miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(stored, orig_len).ok()?
+        } else {
+            stored.to_vec()
+        };
+        if table.len() != orig_len {
+            return None;
+        }
+        tables.push((tag, checksum, table));
+    }
+    write_sfnt(flavor, &tables)
+}
+
+/// Tags of WOFF2's known-table index, in flag order (§4.2 of the WOFF2 spec).
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+const GLYF: u32 = u32::from_be_bytes(*b"glyf");
+const LOCA: u32 = u32::from_be_bytes(*b"loca");
+const HMTX: u32 = u32::from_be_bytes(*b"hmtx");
+const HHEA: u32 = u32::from_be_bytes(*b"hhea");
+const HEAD: u32 = u32::from_be_bytes(*b"head");
+
+/// A forward-only reader over one of WOFF2's data streams.
+struct Stream<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Stream<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Stream { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        Some(i16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    /// UIntBase128: up to five 7-bit groups, most significant first.
+    fn base128(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            // No leading zero groups, and no overflow past 32 bits
+            if (i == 0 && byte == 0x80) || value & 0xFE00_0000 != 0 {
+                return None;
+            }
+            value = (value << 7) | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// 255UInt16: one byte, or a marker byte followed by one or two more.
+    fn u255_16(&mut self) -> Option<u16> {
+        match self.u8()? {
+            253 => self.u16(),
+            254 => Some(u16::from(self.u8()?) + 506),
+            255 => Some(u16::from(self.u8()?) + 253),
+            code => Some(u16::from(code)),
+        }
+    }
+}
+
+struct Woff2Table {
+    tag: u32,
+    orig_len: usize,
+    /// Length in the decompressed stream
+    stored_len: usize,
+    transformed: bool,
+}
+
+/// Rebuilds the TrueType/OpenType file packed in a WOFF2 font: the Brotli
+/// stream is decompressed and the transformed `glyf`, `loca` and `hmtx`
+/// tables are reconstructed. Font collections are not supported.
+pub(super) fn woff2_to_sfnt(data: &[u8]) -> Option<Vec<u8>> {
+    let mut header = Stream::new(data);
+    header.bytes(4)?; // signature
+    let flavor = header.u32()?;
+    if flavor == u32::from_be_bytes(*b"ttcf") {
+        return None;
+    }
+    header.u32()?; // length
+    let num_tables = header.u16()?;
+    header.bytes(6)?; // reserved, totalSfntSize
+    let compressed_len = header.u32()? as usize;
+    header.bytes(24)?; // versions, metadata and private blocks
+
+    let mut directory = Vec::with_capacity(usize::from(num_tables).min(header.remaining()));
+    for _ in 0..num_tables {
+        let flags = header.u8()?;
+        let tag = match flags & 0x3F {
+            63 => header.u32()?,
+            index => u32::from_be_bytes(*KNOWN_TAGS[usize::from(index)]),
+        };
+        let version = flags >> 6;
+        let orig_len = header.base128()? as usize;
+        // glyf and loca are transformed unless version 3; other tables only
+        // when the version is non-zero
+        let transformed = if tag == GLYF || tag == LOCA {
+            version != 3
+        } else {
+            version != 0
+        };
+        let stored_len = if transformed {
+            header.base128()? as usize
+        } else {
+            orig_len
+        };
+        directory.push(Woff2Table {
+            tag,
+            orig_len,
+            stored_len,
+            transformed,
+        });
+    }
+
+    let compressed = header.bytes(compressed_len)?;
+    let total: usize = directory.iter().map(|t| t.stored_len).sum();
+    let mut stream = Vec::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(total as u64 + 1)
+        .read_to_end(&mut stream)
+        .ok()?;
+    if stream.len() != total {
+        return None;
+    }
+
+    let mut stored: Vec<(&Woff2Table, &[u8])> = Vec::with_capacity(directory.len());
+    let mut offset = 0;
+    for table in &directory {
+        stored.push((table, &stream[offset..offset + table.stored_len]));
+        offset += table.stored_len;
+    }
+    let find = |tag: u32| stored.iter().find(|(t, _)| t.tag == tag);
+
+    // glyf and loca come out of the glyf transform together
+    let mut glyf_loca = None;
+    let mut x_mins = Vec::new();
+    if let Some((table, data)) = find(GLYF)
+        && table.transformed
+    {
+        let rebuilt = rebuild_glyf(data)?;
+        x_mins = rebuilt.x_mins;
+        glyf_loca = Some((rebuilt.glyf, rebuilt.loca));
+    }
+
+    let mut tables = Vec::with_capacity(stored.len());
+    for (table, data) in &stored {
+        let rebuilt = match table.tag {
+            GLYF if table.transformed => glyf_loca.as_ref()?.0.clone(),
+            LOCA if table.transformed => {
+                let loca = glyf_loca.as_ref()?.1.clone();
+                if loca.len() != table.orig_len {
+                    return None;
+                }
+                loca
+            }
+            HMTX if table.transformed => {
+                let hhea = find(HHEA)?.1;
+                let num_h_metrics = be_u16(hhea, 34)?;
+                rebuild_hmtx(data, num_h_metrics, &x_mins)?
+            }
+            _ if table.transformed => return None,
+            _ => data.to_vec(),
+        };
+        tables.push((table.tag, rebuilt));
+    }
+
+    // SFNT table directories are sorted by tag
+    tables.sort_by_key(|(tag, _)| *tag);
+    let tables: Vec<(u32, u32, Vec<u8>)> = tables
+        .into_iter()
+        .map(|(tag, mut table)| {
+            if tag == HEAD && table.len() >= 12 {
+                // checkSumAdjustment is not part of the head checksum
+                table[8..12].fill(0);
+            }
+            (tag, table_checksum(&table), table)
+        })
+        .collect();
+    write_sfnt(flavor, &tables)
+}
+
+struct RebuiltGlyf {
+    glyf: Vec<u8>,
+    loca: Vec<u8>,
+    /// Each glyph's xMin, for the hmtx transform's left side bearings
+    x_mins: Vec<i16>,
+}
+
+/// Reverses the WOFF2 `glyf` transform (§5.1), writing standard TrueType
+/// glyph records and the matching `loca` table.
+fn rebuild_glyf(data: &[u8]) -> Option<RebuiltGlyf> {
+    let mut header = Stream::new(data);
+    header.u16()?; // reserved
+    let option_flags = header.u16()?;
+    let num_glyphs = usize::from(header.u16()?);
+    let long_loca = header.u16()? == 1;
+    let mut sizes = [0usize; 7];
+    for size in &mut sizes {
+        *size = header.u32()? as usize;
+    }
+    let [
+        contours,
+        points,
+        flags,
+        glyphs,
+        composites,
+        bboxes,
+        instructions,
+    ] = sizes;
+    let mut n_contour_stream = Stream::new(header.bytes(contours)?);
+    let mut n_points_stream = Stream::new(header.bytes(points)?);
+    let mut flag_stream = Stream::new(header.bytes(flags)?);
+    let mut glyph_stream = Stream::new(header.bytes(glyphs)?);
+    let mut composite_stream = Stream::new(header.bytes(composites)?);
+    let bbox_data = header.bytes(bboxes)?;
+    let mut instruction_stream = Stream::new(header.bytes(instructions)?);
+    let bitmap_len = num_glyphs.div_ceil(32) * 4;
+    let bbox_bitmap = bbox_data.get(..bitmap_len)?;
+    let mut bbox_stream = Stream::new(&bbox_data[bitmap_len..]);
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(header.bytes(num_glyphs.div_ceil(8))?)
+    } else {
+        None
+    };
+    let bit = |bitmap: &[u8], i: usize| bitmap[i / 8] & (0x80 >> (i % 8)) != 0;
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(num_glyphs + 1);
+    let mut x_mins = Vec::with_capacity(num_glyphs);
+    for i in 0..num_glyphs {
+        offsets.push(glyf.len());
+        let n_contours = n_contour_stream.i16()?;
+        let explicit_bbox = bit(bbox_bitmap, i);
+        if n_contours == 0 {
+            if explicit_bbox {
+                return None;
+            }
+            x_mins.push(0);
+            continue;
+        }
+        if n_contours > 0 {
+            let mut end_points = Vec::with_capacity(n_contours as usize);
+            let mut total = 0usize;
+            for _ in 0..n_contours {
+                total += usize::from(n_points_stream.u255_16()?);
+                end_points.push(u16::try_from(total.checked_sub(1)?).ok()?);
+            }
+            let point_flags = flag_stream.bytes(total)?;
+            let points = decode_triplets(point_flags, &mut glyph_stream)?;
+            let instruction_len = glyph_stream.u255_16()?;
+            let code = instruction_stream.bytes(usize::from(instruction_len))?;
+
+            let bbox = if explicit_bbox {
+                read_bbox(&mut bbox_stream)?
+            } else {
+                points_bbox(&points)
+            };
+            glyf.extend_from_slice(&n_contours.to_be_bytes());
+            for v in bbox {
+                glyf.extend_from_slice(&v.to_be_bytes());
+            }
+            for end in end_points {
+                glyf.extend_from_slice(&end.to_be_bytes());
+            }
+            glyf.extend_from_slice(&instruction_len.to_be_bytes());
+            glyf.extend_from_slice(code);
+            let overlap = overlap_bitmap.is_some_and(|b| bit(b, i));
+            write_simple_points(&mut glyf, &points, overlap);
+            x_mins.push(bbox[0]);
+        } else {
+            // Composite glyphs always carry an explicit bounding box
+            if !explicit_bbox {
+                return None;
+            }
+            let bbox = read_bbox(&mut bbox_stream)?;
+            let (components, has_instructions) = read_composite(&mut composite_stream)?;
+            glyf.extend_from_slice(&(-1i16).to_be_bytes());
+            for v in bbox {
+                glyf.extend_from_slice(&v.to_be_bytes());
+            }
+            glyf.extend_from_slice(components);
+            if has_instructions {
+                let instruction_len = glyph_stream.u255_16()?;
+                glyf.extend_from_slice(&instruction_len.to_be_bytes());
+                glyf.extend_from_slice(instruction_stream.bytes(usize::from(instruction_len))?);
+            }
+            x_mins.push(bbox[0]);
+        }
+        glyf.resize(glyf.len().next_multiple_of(4), 0);
+    }
+    offsets.push(glyf.len());
+
+    let mut loca = Vec::with_capacity(offsets.len() * if long_loca { 4 } else { 2 });
+    for offset in offsets {
+        if long_loca {
+            loca.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        } else {
+            loca.extend_from_slice(&u16::try_from(offset / 2).ok()?.to_be_bytes());
+        }
+    }
+    Some(RebuiltGlyf { glyf, loca, x_mins })
+}
+
+fn read_bbox(stream: &mut Stream) -> Option<[i16; 4]> {
+    Some([stream.i16()?, stream.i16()?, stream.i16()?, stream.i16()?])
+}
+
+fn points_bbox(points: &[(i32, i32, bool)]) -> [i16; 4] {
+    let Some(&(x, y, _)) = points.first() else {
+        return [0; 4];
+    };
+    let (mut x_min, mut y_min, mut x_max, mut y_max) = (x, y, x, y);
+    for &(x, y, _) in points {
+        x_min = x_min.min(x);
+        y_min = y_min.min(y);
+        x_max = x_max.max(x);
+        y_max = y_max.max(y);
+    }
+    [x_min, y_min, x_max, y_max].map(|v| v as i16)
+}
+
+/// Absolute points of a simple glyph from its flag bytes and the triplet-
+/// encoded deltas in the glyph stream (§5.2).
+fn decode_triplets(flags: &[u8], stream: &mut Stream) -> Option<Vec<(i32, i32, bool)>> {
+    let with_sign = |flag: u8, value: i32| if flag & 1 != 0 { value } else { -value };
+    let mut points = Vec::with_capacity(flags.len());
+    let (mut x, mut y) = (0i32, 0i32);
+    for &flag in flags {
+        let on_curve = flag & 0x80 == 0;
+        let flag = flag & 0x7F;
+        let (dx, dy) = if flag < 10 {
+            let b = i32::from(stream.u8()?);
+            (0, with_sign(flag, (i32::from(flag & 14) << 7) + b))
+        } else if flag < 20 {
+            let b = i32::from(stream.u8()?);
+            (with_sign(flag, (i32::from((flag - 10) & 14) << 7) + b), 0)
+        } else if flag < 84 {
+            let b0 = i32::from(flag - 20);
+            let b1 = i32::from(stream.u8()?);
+            (
+                with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+                with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+            )
+        } else if flag < 120 {
+            let b0 = i32::from(flag - 84);
+            let bytes = stream.bytes(2)?;
+            (
+                with_sign(flag, 1 + ((b0 / 12) << 8) + i32::from(bytes[0])),
+                with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + i32::from(bytes[1])),
+            )
+        } else if flag < 124 {
+            let bytes = stream.bytes(3)?;
+            let b2 = i32::from(bytes[1]);
+            (
+                with_sign(flag, (i32::from(bytes[0]) << 4) + (b2 >> 4)),
+                with_sign(flag >> 1, ((b2 & 0x0F) << 8) + i32::from(bytes[2])),
+            )
+        } else {
+            let bytes = stream.bytes(4)?;
+            (
+                with_sign(flag, (i32::from(bytes[0]) << 8) + i32::from(bytes[1])),
+                with_sign(flag >> 1, (i32::from(bytes[2]) << 8) + i32::from(bytes[3])),
+            )
+        };
+        x += dx;
+        y += dy;
+        points.push((x, y, on_curve));
+    }
+    Some(points)
+}
+
+/// Appends the flags and x/y coordinate arrays of a simple glyph, using the
+/// short forms where deltas fit in a byte.
+fn write_simple_points(out: &mut Vec<u8>, points: &[(i32, i32, bool)], overlap: bool) {
+    const ON_CURVE: u8 = 0x01;
+    const X_SHORT: u8 = 0x02;
+    const Y_SHORT: u8 = 0x04;
+    const X_SAME_OR_POSITIVE: u8 = 0x10;
+    const Y_SAME_OR_POSITIVE: u8 = 0x20;
+    const OVERLAP_SIMPLE: u8 = 0x40;
+
+    let mut flags = Vec::with_capacity(points.len());
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let (mut last_x, mut last_y) = (0i32, 0i32);
+    for (i, &(x, y, on_curve)) in points.iter().enumerate() {
+        let mut flag = if on_curve { ON_CURVE } else { 0 };
+        if i == 0 && overlap {
+            flag |= OVERLAP_SIMPLE;
+        }
+        for (delta, short, same_or_positive, coords) in [
+            (x - last_x, X_SHORT, X_SAME_OR_POSITIVE, &mut xs),
+            (y - last_y, Y_SHORT, Y_SAME_OR_POSITIVE, &mut ys),
+        ] {
+            if delta == 0 {
+                flag |= same_or_positive;
+            } else if delta.abs() < 256 {
+                flag |= short;
+                if delta > 0 {
+                    flag |= same_or_positive;
+                }
+                coords.push(delta.unsigned_abs() as u8);
+            } else {
+                coords.extend_from_slice(&(delta as i16).to_be_bytes());
+            }
+        }
+        flags.push(flag);
+        (last_x, last_y) = (x, y);
+    }
+    out.extend_from_slice(&flags);
+    out.extend_from_slice(&xs);
+    out.extend_from_slice(&ys);
+}
+
+/// The component records of one composite glyph, copied as stored, and
+/// whether they are followed by instructions.
+fn read_composite<'a>(stream: &mut Stream<'a>) -> Option<(&'a [u8], bool)> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+    let start = stream.pos;
+    let mut has_instructions = false;
+    loop {
+        let flags = stream.u16()?;
+        has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+        let mut len = 2; // glyph index
+        len += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            len += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            len += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            len += 8;
+        }
+        stream.bytes(len)?;
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    Some((&stream.data[start..stream.pos], has_instructions))
+}
+
+/// Reverses the WOFF2 `hmtx` transform (§5.4): left side bearings left out
+/// of the stream are the glyphs' xMin.
+fn rebuild_hmtx(data: &[u8], num_h_metrics: u16, x_mins: &[i16]) -> Option<Vec<u8>> {
+    let mut stream = Stream::new(data);
+    let flags = stream.u8()?;
+    let num_h_metrics = usize::from(num_h_metrics);
+    let num_glyphs = x_mins.len();
+    if num_h_metrics == 0 || num_h_metrics > num_glyphs {
+        return None;
+    }
+    let advances = (0..num_h_metrics)
+        .map(|_| stream.u16())
+        .collect::<Option<Vec<_>>>()?;
+    let mut lsbs = Vec::with_capacity(num_glyphs);
+    for (i, &x_min) in x_mins.iter().enumerate() {
+        let explicit = if i < num_h_metrics {
+            flags & 1 == 0
+        } else {
+            flags & 2 == 0
+        };
+        lsbs.push(if explicit { stream.i16()? } else { x_min });
+    }
+
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 4 + (num_glyphs - num_h_metrics) * 2);
+    for (i, lsb) in lsbs.iter().enumerate() {
+        if let Some(advance) = advances.get(i) {
+            hmtx.extend_from_slice(&advance.to_be_bytes());
+        }
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+    Some(hmtx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An SFNT with the given tables, laid out as `write_sfnt` writes it.
+    fn sfnt(tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let n = tables.len() as u16;
+        let mut out = vec![0, 1, 0, 0];
+        out.extend_from_slice(&n.to_be_bytes());
+        out.extend_from_slice(&[0, 32, 0, 1, 0, 0]); // two tables: 32, 1, 0
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, data) in tables {
+            out.extend_from_slice(*tag);
+            out.extend_from_slice(&7u32.to_be_bytes());
+            out.extend_from_slice(&(offset as u32).to_be_bytes());
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len().next_multiple_of(4);
+        }
+        for (_, data) in tables {
+            out.extend_from_slice(data);
+            out.resize(out.len().next_multiple_of(4), 0);
+        }
+        out
+    }
+
+    /// The same tables packed as WOFF, the first compressed and the second stored.
+    fn woff(tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let packed: Vec<Vec<u8>> = tables
+            .iter()
+            .enumerate()
+            .map(|(i, (_, data))| {
+                if i == 0 {
+                    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+                } else {
+                    data.to_vec()
+                }
+            })
+            .collect();
+        let mut out = b"wOFF".to_vec();
+        out.extend_from_slice(&[0, 1, 0, 0]);
+        out.extend_from_slice(&0u32.to_be_bytes()); // length, unchecked
+        out.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        out.resize(44, 0);
+        let mut offset = 44 + tables.len() * 20;
+        for ((tag, data), packed) in tables.iter().zip(&packed) {
+            out.extend_from_slice(*tag);
+            out.extend_from_slice(&(offset as u32).to_be_bytes());
+            out.extend_from_slice(&(packed.len() as u32).to_be_bytes());
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            out.extend_from_slice(&7u32.to_be_bytes());
+            offset += packed.len().next_multiple_of(4);
+        }
+        for packed in &packed {
+            out.extend_from_slice(packed);
+            out.resize(out.len().next_multiple_of(4), 0);
+        }
+        out
+    }
+
+    #[test]
+    fn test_woff_unpacks_to_sfnt() {
+        let glyf = [0xAB; 300];
+        let tables: [(&[u8; 4], &[u8]); 2] = [(b"glyf", &glyf), (b"head", b"headdata!")];
+        assert_eq!(woff_to_sfnt(&woff(&tables)), Some(sfnt(&tables)));
+    }
+
+    #[test]
+    fn test_woff_table_count_bounded_by_data() {
+        // A directory claiming 0xFFFF tables in a header-sized file
+        let mut data = b"wOFF".to_vec();
+        data.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF]);
+        data.resize(64, 0);
+        assert_eq!(woff_to_sfnt(&data), None);
+
+        // 4096 tables push searchRange past u16
+        let tables: Vec<(u32, u32, Vec<u8>)> = (0..4096).map(|tag| (tag, 0, Vec::new())).collect();
+        assert!(write_sfnt(0x0001_0000, &tables[..4095]).is_some());
+        assert_eq!(write_sfnt(0x0001_0000, &tables), None);
+    }
+
+    #[test]
+    fn test_woff2_fixture_unpacks_to_truetype() {
+        let data = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/fonts/SourceCodePro-It.ttf.woff2"
+        ))
+        .unwrap();
+        let sfnt = woff2_to_sfnt(&data).expect("decode WOFF2");
+        let face = ttf_parser::Face::parse(&sfnt, 0).expect("parse unpacked font");
+        assert_eq!(face.number_of_glyphs(), 1271);
+
+        let a = face.glyph_index('A').unwrap();
+        assert_eq!(face.glyph_hor_advance(a), Some(600));
+        assert_eq!(face.glyph_hor_side_bearing(a), Some(-23));
+        let bbox = face.glyph_bounding_box(a).unwrap();
+        assert_eq!(
+            (bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max),
+            (-23, 0, 503, 656)
+        );
+
+        // Every rebuilt outline stays inside the box stored with it
+        struct Extent(f32, f32, f32, f32);
+        impl ttf_parser::OutlineBuilder for Extent {
+            fn move_to(&mut self, x: f32, y: f32) {
+                self.line_to(x, y);
+            }
+            fn line_to(&mut self, x: f32, y: f32) {
+                self.0 = self.0.min(x);
+                self.1 = self.1.min(y);
+                self.2 = self.2.max(x);
+                self.3 = self.3.max(y);
+            }
+            fn quad_to(&mut self, _: f32, _: f32, x: f32, y: f32) {
+                self.line_to(x, y);
+            }
+            fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, x: f32, y: f32) {
+                self.line_to(x, y);
+            }
+            fn close(&mut self) {}
+        }
+        for id in 0..face.number_of_glyphs() {
+            let mut extent = Extent(f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+            if let Some(bbox) = face.outline_glyph(ttf_parser::GlyphId(id), &mut extent) {
+                assert!(extent.0 >= f32::from(bbox.x_min), "glyph {id}");
+                assert!(extent.1 >= f32::from(bbox.y_min), "glyph {id}");
+                assert!(extent.2 <= f32::from(bbox.x_max), "glyph {id}");
+                assert!(extent.3 <= f32::from(bbox.y_max), "glyph {id}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_woff2_hmtx_transform_derives_side_bearings() {
+        // Two long metrics, whose side bearings are left out for xMin, and
+        // a trailing glyph with its side bearing stored
+        let data = [0x01, 0x01, 0xF4, 0x02, 0x58, 0xFF, 0xF6];
+        let hmtx = rebuild_hmtx(&data, 2, &[-10, 15, 30]).unwrap();
+        assert_eq!(
+            hmtx,
+            [0x01, 0xF4, 0xFF, 0xF6, 0x02, 0x58, 0x00, 0x0F, 0xFF, 0xF6]
+        );
+        assert_eq!(rebuild_hmtx(&data, 4, &[-10, 15, 30]), None);
+    }
+
+    #[test]
+    fn test_woff2_font_collections_are_rejected() {
+        let mut data = b"wOF2ttcf".to_vec();
+        data.resize(64, 0);
+        assert_eq!(woff2_to_sfnt(&data), None);
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 
 use memmap2::Mmap;
 use ttf_parser::Face;
@@ -11,6 +11,8 @@ use super::cache::{CachedFace, CachedFile, FontCache, dir_mtime, load_cache, sav
 type FontLookup = HashMap<(String, bool, bool), (PathBuf, u32)>;
 
 static FONT_INDEX: OnceLock<FontLookup> = OnceLock::new();
+/// Indexes of caller-supplied font directories, built on first use.
+static EXTRA_INDEXES: OnceLock<Mutex<HashMap<PathBuf, FontLookup>>> = OnceLock::new();
 
 fn font_family_name(face: &Face) -> Option<String> {
     // Use ID 1 (Family) — matches what DOCX references and distinguishes
@@ -218,15 +220,44 @@ fn get_font_index() -> &'static FontLookup {
     FONT_INDEX.get_or_init(scan_font_dirs)
 }
 
-/// Look up a font file by family name and style using the OS/2 table metadata index.
-/// Falls back to the regular variant if the requested bold/italic is not available.
-/// Returns `(path, face_index, exact_style_match)`.
-pub(super) fn find_font_file(
+/// Every face in the font files under `dir`, without the disk cache.
+fn index_dir(dir: &Path) -> FontLookup {
+    let mut index = FontLookup::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            if !is_font_file(&path) {
+                continue;
+            }
+            let Ok(data) = std::fs::read(&path) else {
+                continue;
+            };
+            let face_count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+            for face_idx in 0..face_count {
+                if let Some((family, bold, italic)) = read_font_style(&data, face_idx) {
+                    index
+                        .entry((family.to_lowercase(), bold, italic))
+                        .or_insert((path.clone(), face_idx));
+                }
+            }
+        }
+    }
+    index
+}
+
+fn lookup(
+    index: &FontLookup,
     font_name: &str,
     bold: bool,
     italic: bool,
 ) -> Option<(PathBuf, u32, bool)> {
-    let index = get_font_index();
     let key = font_name.to_lowercase();
     if let Some((path, face_index)) = index.get(&(key.clone(), bold, italic)) {
         return Some((path.clone(), *face_index, true));
@@ -239,3 +270,47 @@ pub(super) fn find_font_file(
         None
     }
 }
+
+/// Like `find_font_file`, in a font directory given by the caller.
+pub(super) fn find_font_file_in(
+    dir: &Path,
+    font_name: &str,
+    bold: bool,
+    italic: bool,
+) -> Option<(PathBuf, u32, bool)> {
+    let indexes = EXTRA_INDEXES.get_or_init(Default::default);
+    let mut indexes = indexes.lock().unwrap_or_else(|e| e.into_inner());
+    let index = indexes
+        .entry(dir.to_path_buf())
+        .or_insert_with(|| index_dir(dir));
+    lookup(index, font_name, bold, italic)
+}
+
+/// Look up a font file by family name and style using the OS/2 table metadata index.
+/// Falls back to the regular variant if the requested bold/italic is not available.
+/// Returns `(path, face_index, exact_style_match)`.
+pub(super) fn find_font_file(
+    font_name: &str,
+    bold: bool,
+    italic: bool,
+) -> Option<(PathBuf, u32, bool)> {
+    lookup(get_font_index(), font_name, bold, italic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_font_file_in_caller_dir() {
+        // The committed fixture font, which the system index need not have
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fonts");
+        let (path, face_index, exact) =
+            find_font_file_in(&dir, "Source Code Pro", false, false).unwrap();
+        assert_eq!(
+            (path, face_index, exact),
+            (dir.join("SourceCodePro-Regular.ttf"), 0, true)
+        );
+        assert!(find_font_file_in(&dir, "No Such Family", false, false).is_none());
+    }
+}
@@ -1,24 +1,26 @@
 use std::collections::{HashMap, HashSet};
 
 use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
-use pdf_writer::{Name, Pdf, Rect, Ref, Str};
+use pdf_writer::{Chunk, Filter, Name, Rect, Str};
 use ttf_parser::Face;
 use ttf_parser::gpos::{PairAdjustment, PositioningSubtable};
 
-use super::FontMetrics;
+use crate::ConversionOptions;
+
 use super::encoding::winansi_to_char;
+use super::{FontMetrics, FontRefs};
 
 pub(super) fn embed_truetype(
-    pdf: &mut Pdf,
-    font_ref: Ref,
-    descriptor_ref: Ref,
-    data_ref: Ref,
+    pdf: &mut Chunk,
+    refs: FontRefs,
     font_name: &str,
     font_data: &[u8],
     face_index: u32,
     used_chars: &HashSet<char>,
-    alloc: &mut impl FnMut() -> Ref,
+    options: &ConversionOptions,
 ) -> Option<FontMetrics> {
+    let subset = options.subset_fonts;
+    let level = options.compression_level.min(10);
     let face = Face::parse(font_data, face_index).ok()?;
     let units = face.units_per_em() as f32;
     let to_1000 = |v: f32| v / units * 1000.0;
@@ -56,10 +58,14 @@ pub(super) fn embed_truetype(
     let mut char_to_gid = HashMap::new();
     let mut char_widths_1000 = HashMap::new();
 
-    for &ch in used_chars {
+    // Glyph ids are handed out in character order so the subset is the same every run
+    let mut sorted_chars: Vec<char> = used_chars.iter().copied().collect();
+    sorted_chars.sort_unstable();
+    for ch in sorted_chars {
         let gid = resolve_glyph(&face, ch);
         if let Some(gid) = gid {
-            let new_gid = remapper.remap(gid.0);
+            // A complete font keeps its own glyph ids
+            let new_gid = if subset { remapper.remap(gid.0) } else { gid.0 };
             char_to_gid.insert(ch, new_gid);
             char_widths_1000.insert(ch, advance_1000(gid));
         }
@@ -82,13 +88,19 @@ pub(super) fn embed_truetype(
         );
     }
 
-    let subset_data = subsetter::subset(font_data, face_index, &remapper).unwrap_or_else(|e| {
-        log::warn!("Font subsetting failed for {font_name}: {e} — embedding full font");
-        font_data.to_vec()
-    });
+    let subset_data = if subset {
+        subsetter::subset(font_data, face_index, &remapper).unwrap_or_else(|e| {
+            log::warn!("Font subsetting failed for {font_name}: {e} — embedding full font");
+            font_data.to_vec()
+        })
+    } else {
+        collection_face(font_data, face_index).unwrap_or_else(|| font_data.to_vec())
+    };
 
     let data_len = i32::try_from(subset_data.len()).ok()?;
-    pdf.stream(data_ref, &subset_data)
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&subset_data, level);
+    pdf.stream(refs.data, &compressed)
+        .filter(Filter::FlateDecode)
         .pair(Name(b"Length1"), data_len);
 
     let ps_name = font_name.replace(' ', "");
@@ -99,7 +111,7 @@ pub(super) fn embed_truetype(
         supplement: 0,
     };
 
-    pdf.font_descriptor(descriptor_ref)
+    pdf.font_descriptor(refs.descriptor)
         .name(ps_name_ref)
         .flags(FontFlags::NON_SYMBOLIC)
         .bbox(bbox)
@@ -108,15 +120,14 @@ pub(super) fn embed_truetype(
         .descent(descent)
         .cap_height(cap_height)
         .stem_v(80.0)
-        .font_file2(data_ref);
+        .font_file2(refs.data);
 
-    let cid_font_ref = alloc();
     {
-        let mut cid = pdf.cid_font(cid_font_ref);
+        let mut cid = pdf.cid_font(refs.cid_font);
         cid.subtype(CidFontType::Type2);
         cid.base_font(ps_name_ref);
         cid.system_info(system_info);
-        cid.font_descriptor(descriptor_ref);
+        cid.font_descriptor(refs.descriptor);
         cid.default_width(0.0);
         cid.cid_to_gid_map_predefined(Name(b"Identity"));
 
@@ -135,19 +146,32 @@ pub(super) fn embed_truetype(
         }
     }
 
-    let tounicode_ref = alloc();
     let cmap_name = format!("{}-UTF16", ps_name);
     let mut cmap = UnicodeCmap::new(Name(cmap_name.as_bytes()), system_info);
-    for (&ch, &new_gid) in &char_to_gid {
-        cmap.pair(new_gid, ch);
+    // One entry per glyph: where characters share a glyph (space and no-break
+    // space, symbol-font PUA aliases) the lowest code point is what text copies as
+    let mut gid_chars: Vec<(u16, char)> = char_to_gid.iter().map(|(&ch, &gid)| (gid, ch)).collect();
+    gid_chars.sort_unstable();
+    gid_chars.dedup_by_key(|&mut (gid, _)| gid);
+    for (gid, ch) in gid_chars {
+        match ligature_components(ch) {
+            Some(parts) => {
+                cmap.pair_with_multiple(gid, parts.chars());
+            }
+            None => {
+                cmap.pair(gid, ch);
+            }
+        }
     }
-    pdf.stream(tounicode_ref, cmap.finish().as_slice());
+    let cmap = miniz_oxide::deflate::compress_to_vec_zlib(cmap.finish().as_slice(), level);
+    pdf.stream(refs.to_unicode, &cmap)
+        .filter(Filter::FlateDecode);
 
-    pdf.type0_font(font_ref)
+    pdf.type0_font(refs.font)
         .base_font(ps_name_ref)
         .encoding_predefined(Name(b"Identity-H"))
-        .descendant_font(cid_font_ref)
-        .to_unicode(tounicode_ref);
+        .descendant_font(refs.cid_font)
+        .to_unicode(refs.to_unicode);
 
     let (line_h_ratio, ascender_ratio) = compute_line_metrics(&face, units);
 
@@ -162,6 +186,51 @@ pub(super) fn embed_truetype(
     })
 }
 
+/// Copies one face out of a TrueType collection (`ttcf`) into a standalone font
+/// file; `None` for fonts that are not collections.
+fn collection_face(data: &[u8], face_index: u32) -> Option<Vec<u8>> {
+    let be_u16 = |at: usize| Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?));
+    let be_u32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    if data.get(..4)? != b"ttcf" {
+        return None;
+    }
+    let face = be_u32(12 + 4 * face_index as usize)? as usize;
+    let num_tables = be_u16(face + 4)? as usize;
+
+    let mut font = data.get(face..face + 12)?.to_vec();
+    let mut offset = 12 + num_tables * 16;
+    let mut tables = Vec::with_capacity(num_tables);
+    for i in 0..num_tables {
+        let record = face + 12 + i * 16;
+        let start = be_u32(record + 8)? as usize;
+        let len = be_u32(record + 12)? as usize;
+        font.extend_from_slice(data.get(record..record + 8)?);
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(len as u32).to_be_bytes());
+        tables.push(data.get(start..start + len)?);
+        offset += len.next_multiple_of(4);
+    }
+    for table in tables {
+        font.extend_from_slice(table);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    Some(font)
+}
+
+/// Letters of a Unicode presentation-form ligature, so searching and copying
+/// the text finds the plain letters.
+fn ligature_components(ch: char) -> Option<&'static str> {
+    match ch {
+        '\u{FB00}' => Some("ff"),
+        '\u{FB01}' => Some("fi"),
+        '\u{FB02}' => Some("fl"),
+        '\u{FB03}' => Some("ffi"),
+        '\u{FB04}' => Some("ffl"),
+        '\u{FB05}' | '\u{FB06}' => Some("st"),
+        _ => None,
+    }
+}
+
 fn resolve_glyph(face: &Face, ch: char) -> Option<ttf_parser::GlyphId> {
     face.glyph_index(ch)
         .or_else(|| {
@@ -3,10 +3,10 @@ mod discovery;
 mod embed;
 mod encoding;
 
+use pdf_writer::{Chunk, Name, Ref};
 use std::collections::{HashMap, HashSet};
 
-use pdf_writer::{Name, Pdf, Ref};
-
+use crate::ConversionOptions;
 use crate::model::{FontFamily, FontTable, Run};
 
 pub(crate) use encoding::{encode_as_gids, to_winansi_bytes};
@@ -33,9 +33,20 @@ pub(crate) struct FontEntry {
     pub(crate) char_widths_1000: Option<HashMap<char, f32>>,
     pub(crate) kern_pairs: Option<HashMap<(u16, u16), f32>>,
     pub(crate) synthetic_bold: bool,
+    /// Key of the font drawing the characters this one has no glyph for
+    pub(crate) fallback: Option<String>,
 }
 
 impl FontEntry {
+    /// Whether the font can draw `ch`: it is in the embedded subset, or in
+    /// WinAnsi for a standard font.
+    pub(crate) fn has_glyph(&self, ch: char) -> bool {
+        match &self.char_to_gid {
+            Some(map) => map.contains_key(&ch),
+            None => encoding::char_to_winansi(ch) != 0,
+        }
+    }
+
     /// Width of a single character in 1000-units. Uses the per-char cache (covers
     /// all Unicode chars seen in the document), falls back to the WinAnsi table.
     pub(crate) fn char_width_1000(&self, ch: char) -> f32 {
@@ -110,32 +121,53 @@ pub(crate) fn font_key(run: &Run) -> String {
     buf
 }
 
+/// Font name, bold and italic of a key built by `font_key`.
+pub(crate) fn split_font_key(key: &str) -> (&str, bool, bool) {
+    match key.rsplit_once('/') {
+        Some((name, "B")) => (name, true, false),
+        Some((name, "I")) => (name, false, true),
+        Some((name, "BI")) => (name, true, true),
+        _ => (key, false, false),
+    }
+}
+
 pub(crate) type EmbeddedFonts = HashMap<(String, bool, bool), Vec<u8>>;
 
+/// Object ids of one font, allocated before it is registered so fonts can be
+/// embedded off the main thread.
+#[derive(Clone, Copy)]
+pub(crate) struct FontRefs {
+    font: Ref,
+    descriptor: Ref,
+    data: Ref,
+    cid_font: Ref,
+    to_unicode: Ref,
+}
+
+impl FontRefs {
+    pub(crate) fn alloc(alloc: &mut impl FnMut() -> Ref) -> Self {
+        Self {
+            font: alloc(),
+            descriptor: alloc(),
+            data: alloc(),
+            cid_font: alloc(),
+            to_unicode: alloc(),
+        }
+    }
+}
+
 fn try_font(
-    pdf: &mut Pdf,
+    pdf: &mut Chunk,
     candidate: &str,
     bold: bool,
     italic: bool,
-    font_ref: Ref,
-    descriptor_ref: Ref,
-    data_ref: Ref,
-    alloc: &mut impl FnMut() -> Ref,
+    refs: FontRefs,
     embedded_fonts: &EmbeddedFonts,
     used_chars: &HashSet<char>,
+    options: &ConversionOptions,
 ) -> Option<FontMetrics> {
     let mut embed = |data: &[u8], face_index: u32| {
-        embed::embed_truetype(
-            pdf,
-            font_ref,
-            descriptor_ref,
-            data_ref,
-            candidate,
-            data,
-            face_index,
-            used_chars,
-            alloc,
-        )
+        embed::embed_truetype(pdf, refs, candidate, data, face_index, used_chars, options)
     };
 
     let embedded_key = (candidate.to_lowercase(), bold, italic);
@@ -144,7 +176,11 @@ fn try_font(
         return Some(metrics);
     }
 
-    let (path, face_index, exact_match) = discovery::find_font_file(candidate, bold, italic)?;
+    let (path, face_index, exact_match) = options
+        .font_dirs
+        .iter()
+        .find_map(|dir| discovery::find_font_file_in(dir, candidate, bold, italic))
+        .or_else(|| discovery::find_font_file(candidate, bold, italic))?;
     let data = std::fs::read(&path).ok()?;
     let mut metrics = embed(&data, face_index)?;
     metrics.synthetic_bold = bold && !exact_match;
@@ -174,20 +210,19 @@ fn family_fallback(family: FontFamily) -> Option<&'static str> {
 }
 
 pub(crate) fn register_font(
-    pdf: &mut Pdf,
+    pdf: &mut Chunk,
     font_name: &str,
     bold: bool,
     italic: bool,
     pdf_name: String,
-    alloc: &mut impl FnMut() -> Ref,
+    refs: FontRefs,
     embedded_fonts: &EmbeddedFonts,
     used_chars: &HashSet<char>,
     font_table: &FontTable,
+    options: &ConversionOptions,
 ) -> FontEntry {
     let t0 = std::time::Instant::now();
-    let font_ref = alloc();
-    let descriptor_ref = alloc();
-    let data_ref = alloc();
+    let font_ref = refs.font;
 
     let primary = primary_font_name(font_name);
 
@@ -197,12 +232,10 @@ pub(crate) fn register_font(
             name,
             bold,
             italic,
-            font_ref,
-            descriptor_ref,
-            data_ref,
-            alloc,
+            refs,
             embedded_fonts,
             used_chars,
+            options,
         )
     };
 
@@ -242,6 +275,7 @@ pub(crate) fn register_font(
                 Some(m.kern_pairs)
             },
             synthetic_bold: m.synthetic_bold,
+            fallback: None,
         },
         None => {
             log::warn!("Font not found: {font_name} bold={bold} italic={italic} — using Helvetica");
@@ -258,6 +292,7 @@ pub(crate) fn register_font(
                 char_widths_1000: None,
                 kern_pairs: None,
                 synthetic_bold: false,
+                fallback: None,
             }
         }
     };
@@ -269,3 +304,138 @@ pub(crate) fn register_font(
 
     entry
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(name: &str) -> (FontEntry, Vec<u8>) {
+        register_with_text(name, "Hello ")
+    }
+
+    fn register_with_text(name: &str, text: &str) -> (FontEntry, Vec<u8>) {
+        register_with_options(name, text, true)
+    }
+
+    fn register_with_options(name: &str, text: &str, subset: bool) -> (FontEntry, Vec<u8>) {
+        let mut pdf = pdf_writer::Pdf::new();
+        let mut next_id = 1;
+        let mut alloc = || {
+            next_id += 1;
+            Ref::new(next_id)
+        };
+        let used: HashSet<char> = text.chars().collect();
+        let entry = register_font(
+            &mut pdf,
+            name,
+            false,
+            false,
+            "F1".to_string(),
+            FontRefs::alloc(&mut alloc),
+            &EmbeddedFonts::new(),
+            &used,
+            &FontTable::new(),
+            // Stored streams, so tests can read the ToUnicode CMap
+            &ConversionOptions {
+                subset_fonts: subset,
+                compression_level: 0,
+                font_dirs: vec![fixture_dir()],
+                ..ConversionOptions::default()
+            },
+        );
+        (entry, pdf.finish())
+    }
+
+    /// Family of the TrueType font committed under `tests/fixtures/fonts`,
+    /// found through `font_dirs` whatever the machine has installed.
+    const FIXTURE_FAMILY: &str = "Source Code Pro";
+
+    fn fixture_dir() -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fonts")
+    }
+
+    /// Glyph id to text mappings of the `bfchar` entries in a ToUnicode CMap.
+    fn to_unicode_map(pdf: &[u8]) -> HashMap<u16, String> {
+        let text = String::from_utf8_lossy(pdf);
+        let mut map = HashMap::new();
+        for section in text.split("beginbfchar").skip(1) {
+            let section = section.split("endbfchar").next().unwrap_or("");
+            for line in section.lines() {
+                let hex: Vec<&str> = line
+                    .split(['<', '>'])
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                let [gid, units] = hex[..] else {
+                    continue;
+                };
+                let units: Vec<u16> = (0..units.len())
+                    .step_by(4)
+                    .map(|i| u16::from_str_radix(&units[i..i + 4], 16).unwrap())
+                    .collect();
+                map.insert(
+                    u16::from_str_radix(gid, 16).unwrap(),
+                    String::from_utf16(&units).unwrap(),
+                );
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn test_to_unicode_recovers_source_text() {
+        let source = "Searchable text, \u{FB01}ne print \u{A0}100%";
+        let (entry, bytes) = register_with_text(FIXTURE_FAMILY, source);
+        let char_to_gid = entry.char_to_gid.expect("embedded font");
+        let to_unicode = to_unicode_map(&bytes);
+        let extracted: String = source
+            .chars()
+            .filter_map(|ch| char_to_gid.get(&ch))
+            .map(|gid| to_unicode[gid].as_str())
+            .collect();
+        let expected: String = source
+            .chars()
+            .filter(|ch| char_to_gid.contains_key(ch))
+            .collect::<String>()
+            .replace('\u{FB01}', "fi");
+        // A no-break space drawn with the space glyph copies as a space
+        let shares_space = char_to_gid.get(&' ') == char_to_gid.get(&'\u{A0}');
+        if shares_space {
+            assert_eq!(extracted, expected.replace('\u{A0}', " "));
+        } else {
+            assert_eq!(extracted, expected);
+        }
+    }
+
+    /// `/Length1` of the embedded font program.
+    fn font_program_len(pdf: &[u8]) -> usize {
+        let text = String::from_utf8_lossy(pdf);
+        let rest = &text[text.find("/Length1 ").expect("font program") + 9..];
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().unwrap()
+    }
+
+    #[test]
+    fn test_full_font_embedded_without_subsetting() {
+        let file = std::fs::read(fixture_dir().join("SourceCodePro-Regular.ttf")).unwrap();
+        let (full_entry, full) = register_with_options(FIXTURE_FAMILY, "Hello ", false);
+        let (_, subset) = register_with_options(FIXTURE_FAMILY, "Hello ", true);
+        assert_eq!(font_program_len(&full), file.len());
+        assert!(font_program_len(&subset) < file.len());
+        // Text is encoded with the font's own glyph ids
+        let ttf = ttf_parser::Face::parse(&file, 0).unwrap();
+        let h_gid = ttf.glyph_index('H').unwrap().0;
+        assert_eq!(full_entry.char_to_gid.unwrap()[&'H'], h_gid);
+    }
+
+    #[test]
+    fn test_font_file_embedded_instead_of_standard_font() {
+        let (entry, bytes) = register(FIXTURE_FAMILY);
+        assert!(entry.char_to_gid.is_some_and(|m| m.contains_key(&'H')));
+        assert!(bytes.windows(13).any(|w| w == b"/CIDFontType2"));
+
+        let (entry, bytes) = register("No Such Font Family");
+        assert!(entry.char_to_gid.is_none());
+        assert!(bytes.windows(10).any(|w| w == b"/Helvetica"));
+    }
+}
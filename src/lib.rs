@@ -7,36 +7,199 @@ mod pdf;
 
 pub use error::Error;
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+/// Settings for a conversion. The defaults match `convert_docx_to_pdf`.
+#[derive(Clone, Debug)]
+pub struct ConversionOptions {
+    /// `/Producer` written to the PDF document information; defaults to "docxide-pdf <version>".
+    pub producer: Option<String>,
+    /// Show review comments as PDF note annotations at their anchors (default: on).
+    pub render_comments: bool,
+    /// Accept all tracked changes instead of showing them as markup (default: off).
+    pub accept_changes: bool,
+    /// Fill pages with the document's page color (`w:background`), as on screen (default: on).
+    pub render_background: bool,
+    /// Hyphenate words at line ends even when the document doesn't turn on
+    /// `w:autoHyphenation` (default: off). Needs the `hyphenation` feature.
+    pub hyphenate: bool,
+    /// Font for text when the document has no theme fonts to fall back on
+    /// (default: Word's own, Aptos).
+    pub default_font: Option<String>,
+    /// Font for characters the run's own font has no glyph for, such as emoji
+    /// or CJK in a Latin face (default: none, leaving them blank).
+    pub fallback_font: Option<String>,
+    /// Directories of extra fonts, searched after the document's embedded fonts
+    /// and before the system's (default: none).
+    pub font_dirs: Vec<PathBuf>,
+    /// Embed only the glyphs the document uses (default: on). Off embeds complete
+    /// font programs, for debugging or for PDFs that will be edited later.
+    pub subset_fonts: bool,
+    /// Same output for the same input: without `creation_date`, DATE and TIME
+    /// fields show when the document was last saved instead of today
+    /// (default: off).
+    pub deterministic: bool,
+    /// W3CDTF timestamp ("2024-03-05T14:30:00Z") written as the PDF's creation and
+    /// modification dates in place of the document's own, and shown by DATE and
+    /// TIME fields (default: none).
+    pub creation_date: Option<String>,
+    /// Minutes east of UTC for the times DATE and TIME fields show, as Word
+    /// shows local time (default: 0, UTC). The system time zone isn't consulted.
+    pub utc_offset_minutes: i32,
+    /// Values for MERGEFIELD fields by field name, for filling a template at
+    /// convert time (default: none, showing each field as «Name»).
+    pub merge_fields: HashMap<String, String>,
+    /// zlib level, 0 (store) to 10, for page content, image and font streams (default: 6).
+    pub compression_level: u8,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            producer: None,
+            render_comments: true,
+            accept_changes: false,
+            render_background: true,
+            hyphenate: false,
+            default_font: None,
+            fallback_font: None,
+            font_dirs: Vec::new(),
+            subset_fonts: true,
+            deterministic: false,
+            creation_date: None,
+            utc_offset_minutes: 0,
+            merge_fields: HashMap::new(),
+            compression_level: 6,
+        }
+    }
+}
+
+/// Document properties from `docProps/core.xml`, plus the page count from layout.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    /// W3CDTF timestamp, e.g. "2026-02-18T19:42:00Z"
+    pub created: Option<String>,
+    /// W3CDTF timestamp, e.g. "2026-02-18T19:42:00Z"
+    pub modified: Option<String>,
+    pub page_count: usize,
+}
+
 pub fn convert_docx_to_pdf(input: impl AsRef<Path>, path: impl AsRef<Path>) -> Result<(), Error> {
-    let doc = docx::parse(input.as_ref())?;
-    render_and_write(&doc, path)
+    convert_docx_to_pdf_with_options(input, path, &ConversionOptions::default())
+}
+
+pub fn convert_docx_to_pdf_with_options(
+    input: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+    options: &ConversionOptions,
+) -> Result<(), Error> {
+    let bytes = convert_docx_to_pdf_bytes_with_options(input, options)?;
+    write_pdf(path, &bytes)
+}
+
+/// Like `convert_docx_to_pdf_with_options`, calling `progress(pages_done, total_pages)`
+/// as pages are written so callers can drive a progress bar.
+pub fn convert_docx_to_pdf_with_progress(
+    input: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+    options: &ConversionOptions,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<(), Error> {
+    let doc = docx::parse(input.as_ref(), options)?;
+    let bytes = pdf::render_with_progress(&doc, options, progress)?;
+    write_pdf(path, &bytes)
 }
 
 pub fn convert_docx_bytes_to_pdf(input: &[u8], path: impl AsRef<Path>) -> Result<(), Error> {
-    let doc = docx::parse_bytes(input)?;
-    render_and_write(&doc, path)
+    let bytes = convert_docx_bytes_to_pdf_bytes(input)?;
+    write_pdf(path, &bytes)
 }
 
-fn render_and_write(doc: &model::Document, path: impl AsRef<Path>) -> Result<(), Error> {
-    let path = path.as_ref().with_extension("pdf");
-    let t0 = Instant::now();
+/// Converts a .docx file to PDF in memory, without writing an output file.
+pub fn convert_docx_to_pdf_bytes(input: impl AsRef<Path>) -> Result<Vec<u8>, Error> {
+    convert_docx_to_pdf_bytes_with_options(input, &ConversionOptions::default())
+}
 
-    let bytes = pdf::render(doc)?;
-    let t_render = t0.elapsed();
+pub fn convert_docx_to_pdf_bytes_with_options(
+    input: impl AsRef<Path>,
+    options: &ConversionOptions,
+) -> Result<Vec<u8>, Error> {
+    let doc = docx::parse(input.as_ref(), options)?;
+    render(&doc, options)
+}
 
-    std::fs::write(&path, &bytes)?;
-    let t_total = t0.elapsed();
+/// Converts .docx bytes (e.g. an upload) to PDF bytes without touching the filesystem.
+pub fn convert_docx_bytes_to_pdf_bytes(input: &[u8]) -> Result<Vec<u8>, Error> {
+    convert_docx_bytes_to_pdf_bytes_with_options(input, &ConversionOptions::default())
+}
+
+pub fn convert_docx_bytes_to_pdf_bytes_with_options(
+    input: &[u8],
+    options: &ConversionOptions,
+) -> Result<Vec<u8>, Error> {
+    let doc = docx::parse_bytes(input, options)?;
+    render(&doc, options)
+}
+
+/// Converts a .docx read from any seekable source (a file, a `Cursor`, ...) to PDF bytes.
+pub fn convert_docx_reader_to_pdf_bytes(
+    input: impl Read + Seek,
+    options: &ConversionOptions,
+) -> Result<Vec<u8>, Error> {
+    let doc = docx::parse_reader(input, options)?;
+    render(&doc, options)
+}
+
+/// Number of pages the converted PDF has, from the layout alone without building it.
+/// Pass the options the document will be converted with: fonts and hyphenation
+/// change where pages break.
+pub fn page_count(input: impl AsRef<Path>, options: &ConversionOptions) -> Result<usize, Error> {
+    let doc = docx::parse(input.as_ref(), options)?;
+    Ok(pdf::page_count(&doc, options))
+}
 
+/// Reads a document's properties and page count without producing a PDF.
+/// The page count is for a conversion with `options`.
+pub fn read_metadata(
+    input: impl AsRef<Path>,
+    options: &ConversionOptions,
+) -> Result<DocumentMetadata, Error> {
+    let doc = docx::parse(input.as_ref(), options)?;
+    let page_count = pdf::page_count(&doc, options);
+    let props = doc.core_properties;
+    Ok(DocumentMetadata {
+        title: props.title,
+        author: props.creator,
+        subject: props.subject,
+        keywords: props.keywords,
+        created: props.created,
+        modified: props.modified,
+        page_count,
+    })
+}
+
+fn render(doc: &model::Document, options: &ConversionOptions) -> Result<Vec<u8>, Error> {
+    let t0 = Instant::now();
+    let bytes = pdf::render(doc, options)?;
     log::info!(
-        "Timing: render={:.1}ms, write={:.1}ms, total={:.1}ms (output {} bytes)",
-        t_render.as_secs_f64() * 1000.0,
-        (t_total - t_render).as_secs_f64() * 1000.0,
-        t_total.as_secs_f64() * 1000.0,
+        "Timing: render={:.1}ms (output {} bytes)",
+        t0.elapsed().as_secs_f64() * 1000.0,
         bytes.len(),
     );
+    Ok(bytes)
+}
 
+fn write_pdf(path: impl AsRef<Path>, bytes: &[u8]) -> Result<(), Error> {
+    let path = path.as_ref().with_extension("pdf");
+    let t0 = Instant::now();
+    std::fs::write(&path, bytes)?;
+    log::info!("Timing: write={:.1}ms", t0.elapsed().as_secs_f64() * 1000.0);
     Ok(())
 }
@@ -10,6 +10,8 @@ pub enum Alignment {
     Center,
     Right,
     Justify,
+    /// Justified on every line, the last included (`w:jc="distribute"`)
+    Distribute,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -35,8 +37,99 @@ pub enum VertAlign {
     Subscript,
 }
 
+/// `w:u` underline pattern; the heavy variants draw at double thickness.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum UnderlineStyle {
+    #[default]
+    None,
+    Single,
+    Words,
+    Double,
+    Thick,
+    Dotted,
+    DottedHeavy,
+    Dash,
+    DashedHeavy,
+    DashLong,
+    DashLongHeavy,
+    DotDash,
+    DashDotHeavy,
+    DotDotDash,
+    DashDotDotHeavy,
+    Wave,
+    WavyHeavy,
+    WavyDouble,
+}
+
+impl UnderlineStyle {
+    pub fn from_ooxml(val: &str) -> Self {
+        match val {
+            "none" => Self::None,
+            "words" => Self::Words,
+            "double" => Self::Double,
+            "thick" => Self::Thick,
+            "dotted" => Self::Dotted,
+            "dottedHeavy" => Self::DottedHeavy,
+            "dash" => Self::Dash,
+            "dashedHeavy" => Self::DashedHeavy,
+            "dashLong" => Self::DashLong,
+            "dashLongHeavy" => Self::DashLongHeavy,
+            "dotDash" => Self::DotDash,
+            "dashDotHeavy" => Self::DashDotHeavy,
+            "dotDotDash" => Self::DotDotDash,
+            "dashDotDotHeavy" => Self::DashDotDotHeavy,
+            "wave" => Self::Wave,
+            "wavyHeavy" => Self::WavyHeavy,
+            "wavyDouble" => Self::WavyDouble,
+            _ => Self::Single,
+        }
+    }
+
+    pub fn is_some(self) -> bool {
+        self != Self::None
+    }
+
+    pub fn is_heavy(self) -> bool {
+        matches!(
+            self,
+            Self::Thick
+                | Self::DottedHeavy
+                | Self::DashedHeavy
+                | Self::DashLongHeavy
+                | Self::DashDotHeavy
+                | Self::DashDotDotHeavy
+                | Self::WavyHeavy
+        )
+    }
+}
+
+/// `w:outline`, `w:shadow`, `w:emboss` and `w:imprint` run effects.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextEffects {
+    pub outline: bool,
+    pub shadow: bool,
+    pub emboss: bool,
+    pub imprint: bool,
+}
+
 pub struct HeaderFooter {
     pub blocks: Vec<Block>,
+    /// Watermark shape anchored in this header
+    pub watermark: Option<Watermark>,
+}
+
+/// A header watermark, drawn centered behind the content of every page using the header
+pub enum Watermark {
+    /// WordArt text (`v:textpath`) stretched across `width`; `run` carries font and color
+    Text {
+        run: Box<Run>,
+        width: f32,
+        /// Clockwise, in degrees
+        rotation: f32,
+        opacity: f32,
+    },
+    /// A picture, drawn at `opacity` (Word's "washout" fades it)
+    Image { image: EmbeddedImage, opacity: f32 },
 }
 
 pub struct Footnote {
@@ -88,6 +181,45 @@ pub struct SectionProperties {
     pub break_type: SectionBreakType,
     pub columns: Option<ColumnsConfig>,
     pub page_num_start: Option<u32>,
+    /// Format of PAGE fields and page references (`w:pgNumType/@w:fmt`)
+    pub page_num_format: NumberFormat,
+    pub page_borders: Option<PageBorders>,
+    pub line_numbering: Option<LineNumbering>,
+    pub v_align: PageVAlign,
+}
+
+/// Vertical alignment of a section's text on its pages (`w:vAlign`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PageVAlign {
+    #[default]
+    Top,
+    Center,
+    /// Justified: laid out like `Top`
+    Both,
+    Bottom,
+}
+
+/// When line numbers start over (`w:restart`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LineNumberRestart {
+    #[default]
+    NewPage,
+    NewSection,
+    Continuous,
+}
+
+/// Margin line numbers of a section (`w:lnNumType`)
+#[derive(Clone)]
+pub struct LineNumbering {
+    /// Only every `count_by`-th line shows its number
+    pub count_by: u32,
+    /// Number of the first line
+    pub start: u32,
+    pub restart: LineNumberRestart,
+    /// Gap between the number's right edge and the text
+    pub distance: f32,
+    /// Font and color of the numbers (the `LineNumber` style)
+    pub run: Run,
 }
 
 pub struct Section {
@@ -95,6 +227,271 @@ pub struct Section {
     pub blocks: Vec<Block>,
 }
 
+/// Where a bookmark starts: the block of its section holding the paragraph
+/// (for bookmarks inside a table, the table itself).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BookmarkLocation {
+    pub section: usize,
+    pub block: usize,
+}
+
+/// What separates a list label from the text (`w:suff`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LabelSuffix {
+    #[default]
+    Tab,
+    Space,
+    Nothing,
+}
+
+/// Counter format (`w:numFmt`) for list labels, notes and page numbers
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NumberFormat {
+    #[default]
+    Decimal,
+    DecimalZero,
+    LowerLetter,
+    UpperLetter,
+    LowerRoman,
+    UpperRoman,
+    /// `*`, `†`, `‡`, `§`, then doubled, tripled, ...
+    Chicago,
+    /// 1st, 2nd, 3rd, ...
+    Ordinal,
+    /// One, Two, Three, ...
+    CardinalText,
+    /// First, Second, Third, ...
+    OrdinalText,
+    None,
+}
+
+impl NumberFormat {
+    pub fn from_ooxml(val: &str) -> Self {
+        match val {
+            "decimalZero" => Self::DecimalZero,
+            "lowerLetter" => Self::LowerLetter,
+            "upperLetter" => Self::UpperLetter,
+            "lowerRoman" => Self::LowerRoman,
+            "upperRoman" => Self::UpperRoman,
+            "chicago" => Self::Chicago,
+            "ordinal" => Self::Ordinal,
+            "cardinalText" => Self::CardinalText,
+            "ordinalText" => Self::OrdinalText,
+            "none" => Self::None,
+            _ => Self::Decimal,
+        }
+    }
+
+    pub fn format(self, value: u32) -> String {
+        match self {
+            Self::Decimal => value.to_string(),
+            Self::DecimalZero => format!("{value:02}"),
+            Self::LowerLetter => to_letter(value, b'a'),
+            Self::UpperLetter => to_letter(value, b'A'),
+            Self::LowerRoman => to_roman(value),
+            Self::UpperRoman => to_roman(value).to_uppercase(),
+            Self::Chicago => to_chicago(value),
+            Self::Ordinal => format!("{value}{}", ordinal_suffix(value)),
+            Self::CardinalText => capitalize(&to_cardinal_text(value)),
+            Self::OrdinalText => capitalize(&to_ordinal_text(value)),
+            Self::None => String::new(),
+        }
+    }
+
+    /// Every character `format` can produce.
+    pub fn symbols(self) -> &'static str {
+        match self {
+            Self::Decimal | Self::DecimalZero => "0123456789",
+            Self::LowerLetter => "abcdefghijklmnopqrstuvwxyz",
+            Self::UpperLetter => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Self::LowerRoman => "ivxlcdm",
+            Self::UpperRoman => "IVXLCDM",
+            Self::Chicago => "*\u{2020}\u{2021}\u{00A7}",
+            Self::Ordinal => "0123456789dhnrst",
+            Self::CardinalText | Self::OrdinalText => {
+                "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ- "
+            }
+            Self::None => "",
+        }
+    }
+}
+
+fn to_roman(mut n: u32) -> String {
+    const TABLE: &[(u32, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut result = String::new();
+    for &(value, numeral) in TABLE {
+        while n >= value {
+            result.push_str(numeral);
+            n -= value;
+        }
+    }
+    result
+}
+
+fn to_letter(value: u32, base: u8) -> String {
+    if value == 0 {
+        return String::new();
+    }
+    let mut n = value - 1;
+    let mut result = String::new();
+    loop {
+        result.insert(0, (base + (n % 26) as u8) as char);
+        if n < 26 {
+            break;
+        }
+        n = n / 26 - 1;
+    }
+    result
+}
+
+fn ordinal_suffix(value: u32) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// English number words, lowercase: "one hundred twenty-three".
+fn to_cardinal_text(value: u32) -> String {
+    const ONES: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    fn below_thousand(n: u32, words: &mut Vec<String>) {
+        if n >= 100 {
+            words.push(format!("{} hundred", ONES[(n / 100) as usize]));
+        }
+        match n % 100 {
+            0 => {}
+            r @ 1..=19 => words.push(ONES[r as usize].to_string()),
+            r if r % 10 == 0 => words.push(TENS[(r / 10) as usize].to_string()),
+            r => words.push(format!(
+                "{}-{}",
+                TENS[(r / 10) as usize],
+                ONES[(r % 10) as usize]
+            )),
+        }
+    }
+
+    if value == 0 {
+        return ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    for (scale, name) in [
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1000, "thousand"),
+    ] {
+        if !(value / scale).is_multiple_of(1000) {
+            below_thousand(value / scale % 1000, &mut words);
+            words.push(name.to_string());
+        }
+    }
+    below_thousand(value % 1000, &mut words);
+    words.join(" ")
+}
+
+/// The cardinal words with the last one made ordinal: "twenty-first".
+fn to_ordinal_text(value: u32) -> String {
+    let cardinal = to_cardinal_text(value);
+    let split = cardinal.rfind([' ', '-']).map_or(0, |i| i + 1);
+    let (head, last) = cardinal.split_at(split);
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        w if w.ends_with('y') => format!("{}ieth", &w[..w.len() - 1]),
+        w => format!("{w}th"),
+    };
+    format!("{head}{last}")
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn to_chicago(value: u32) -> String {
+    const SYMBOLS: [char; 4] = ['*', '\u{2020}', '\u{2021}', '\u{00A7}'];
+    if value == 0 {
+        return String::new();
+    }
+    let symbol = SYMBOLS[((value - 1) % 4) as usize];
+    std::iter::repeat_n(symbol, ((value - 1) / 4 + 1) as usize).collect()
+}
+
+/// When note numbering starts over (`w:numRestart`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NoteRestart {
+    #[default]
+    Continuous,
+    EachSection,
+    EachPage,
+}
+
+/// Document-wide note numbering (`w:footnotePr`/`w:endnotePr` in settings)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoteProperties {
+    pub num_fmt: NumberFormat,
+    pub num_start: u32,
+    pub restart: NoteRestart,
+}
+
+impl Default for NoteProperties {
+    fn default() -> Self {
+        Self {
+            num_fmt: NumberFormat::Decimal,
+            num_start: 1,
+            restart: NoteRestart::Continuous,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FontFamily {
     Auto,
@@ -120,16 +517,96 @@ pub struct Document {
     /// Key: (lowercase_font_name, bold, italic)
     pub embedded_fonts: HashMap<(String, bool, bool), Vec<u8>>,
     pub footnotes: HashMap<u32, Footnote>,
+    /// Endnotes by id, rendered after the body in reference order
+    pub endnotes: HashMap<u32, Footnote>,
+    pub footnote_properties: NoteProperties,
+    /// Review comments by id, anchored by `Run::comment_id`
+    pub comments: HashMap<u32, Comment>,
+    /// Page color (`w:background`)
+    pub background: Option<[u8; 3]>,
     pub font_table: FontTable,
     pub even_and_odd_headers: bool,
+    /// Interval of the implicit tab stops past the last custom one (`w:defaultTabStop`)
+    pub default_tab_stop: f32,
+    /// Left and right margins swap on even pages (`w:mirrorMargins`)
+    pub mirror_margins: bool,
+    /// Words may be hyphenated at line ends (`w:autoHyphenation`)
+    pub auto_hyphenation: bool,
+    /// Document language (`w:themeFontLang/@w:val`), e.g. "de-DE"
+    pub lang: Option<String>,
     /// Maps style IDs to display names (for STYLEREF resolution)
     pub style_id_to_name: HashMap<String, String>,
+    /// Source file, when converting from a path (for FILENAME fields)
+    pub file_path: Option<std::path::PathBuf>,
+    pub core_properties: CoreProperties,
+    /// Body bookmarks by name (`_GoBack` excluded)
+    #[allow(dead_code)]
+    pub bookmarks: HashMap<String, BookmarkLocation>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RevisionKind {
+    /// `w:ins`, `w:moveTo`
+    Insertion,
+    /// `w:del`, `w:moveFrom`
+    Deletion,
+}
+
+/// A tracked change and the reviewer who made it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Revision {
+    pub kind: RevisionKind,
+    pub author: Option<String>,
+}
+
+/// A review comment from `word/comments.xml`
+#[derive(Clone, Debug, Default)]
+pub struct Comment {
+    pub author: Option<String>,
+    /// `w:date`, as a W3CDTF timestamp
+    pub date: Option<String>,
+    /// Plain text, one line per paragraph
+    pub text: String,
+}
+
+/// Document metadata from `docProps/core.xml`
+#[derive(Clone, Debug, Default)]
+pub struct CoreProperties {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub creator: Option<String>,
+    pub keywords: Option<String>,
+    pub last_modified_by: Option<String>,
+    /// `dcterms:created`, as a W3CDTF timestamp
+    pub created: Option<String>,
+    /// `dcterms:modified`, as a W3CDTF timestamp
+    pub modified: Option<String>,
+}
+
+impl CoreProperties {
+    /// Value for a document-information field (AUTHOR, TITLE, ...), if it is one.
+    pub fn field_value(&self, code: &FieldCode) -> Option<&str> {
+        match code {
+            FieldCode::Author => self.creator.as_deref(),
+            FieldCode::Title => self.title.as_deref(),
+            FieldCode::Subject => self.subject.as_deref(),
+            FieldCode::Keywords => self.keywords.as_deref(),
+            FieldCode::LastSavedBy => self.last_modified_by.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ImageFormat {
     Jpeg,
     Png,
+    Gif,
+    Bmp,
+    Tiff,
+    /// Vector metafiles; rendered as a placeholder box since they are not rasterized
+    Emf,
+    Wmf,
 }
 
 #[derive(Clone)]
@@ -141,8 +618,32 @@ pub struct EmbeddedImage {
     pub display_width: f32,  // points
     pub display_height: f32, // points
     pub jpeg_components: u8,
+    /// CMYK/YCCK JPEG written by Adobe software (APP14 marker), whose samples are stored inverted
+    pub jpeg_adobe_inverted: bool,
     /// Extra vertical space from wp:effectExtent + wp:inline distT/distB (points)
     pub layout_extra_height: f32,
+    pub crop: ImageCrop,
+    /// Clockwise rotation in degrees from `a:xfrm@rot`, applied around the image center
+    pub rotation: f32,
+    /// Accessible description from `wp:docPr@descr`, falling back to `@title`
+    pub alt_text: Option<String>,
+    /// Link target of a clickable picture, "#name" for a bookmark
+    pub hyperlink_url: Option<String>,
+}
+
+/// Fractional insets from `a:srcRect` (0.25 = a quarter of the source trimmed from that edge)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImageCrop {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl ImageCrop {
+    pub fn is_empty(&self) -> bool {
+        self.left == 0.0 && self.top == 0.0 && self.right == 0.0 && self.bottom == 0.0
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -185,6 +686,16 @@ pub enum WrapType {
     TopAndBottom,
 }
 
+/// Side of a wrapped object text flows on (`wrapText` of `wp:wrapSquare`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WrapSide {
+    #[default]
+    BothSides,
+    Left,
+    Right,
+    Largest,
+}
+
 #[derive(Clone)]
 pub struct FloatingImage {
     pub image: EmbeddedImage,
@@ -193,7 +704,15 @@ pub struct FloatingImage {
     pub v_position: VerticalPosition,
     pub v_relative_from: VRelativeFrom,
     pub wrap_type: WrapType,
+    pub wrap_side: WrapSide,
+    /// Gap kept between the image and wrapped text (`distT` etc., points)
+    pub dist_top: f32,
+    pub dist_bottom: f32,
+    pub dist_left: f32,
+    pub dist_right: f32,
     pub behind_doc: bool,
+    /// Stacking order among overlapping objects (`relativeHeight`); higher is on top
+    pub z_order: u32,
 }
 
 /// Geometry definition for a shape — either a preset name or custom paths.
@@ -358,6 +877,25 @@ pub struct Textbox {
     pub no_text_wrap: bool,
 }
 
+/// Line style of a character border; other `w:val` styles draw as `Single`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BorderLineStyle {
+    #[default]
+    Single,
+    Double,
+    Dotted,
+    Dashed,
+}
+
+/// Box drawn around a run's text (`w:bdr` in `w:rPr`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CharBorder {
+    pub width_pt: f32,
+    pub space_pt: f32, // gap between text and the box sides
+    pub color: [u8; 3],
+    pub style: BorderLineStyle,
+}
+
 #[derive(Clone)]
 pub struct ParagraphBorder {
     pub width_pt: f32,  // line thickness in points
@@ -365,6 +903,34 @@ pub struct ParagraphBorder {
     pub color: [u8; 3], // RGB
 }
 
+/// What page border `space` is measured from (`w:offsetFrom`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PageBorderOffset {
+    #[default]
+    Text,
+    Page,
+}
+
+/// Pages of a section that show its page borders (`w:display`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PageBorderDisplay {
+    #[default]
+    AllPages,
+    FirstPage,
+    NotFirstPage,
+}
+
+/// Section page borders (`w:pgBorders`)
+#[derive(Clone, Default)]
+pub struct PageBorders {
+    pub top: Option<ParagraphBorder>,
+    pub bottom: Option<ParagraphBorder>,
+    pub left: Option<ParagraphBorder>,
+    pub right: Option<ParagraphBorder>,
+    pub offset_from: PageBorderOffset,
+    pub display: PageBorderDisplay,
+}
+
 #[derive(Clone, Default)]
 pub struct ParagraphBorders {
     pub top: Option<ParagraphBorder>,
@@ -391,9 +957,12 @@ pub struct Paragraph {
     pub list_label_font_size: Option<f32>,
     pub list_label_bold: bool,
     pub list_label_color: Option<[u8; 3]>,
+    pub list_label_suffix: LabelSuffix,
     pub contextual_spacing: bool,
     pub keep_next: bool,
     pub keep_lines: bool,
+    /// Excluded from automatic hyphenation (`w:suppressAutoHyphens`)
+    pub suppress_auto_hyphens: bool,
     pub line_spacing: Option<LineSpacing>,
     pub image: Option<EmbeddedImage>,
     pub borders: ParagraphBorders,
@@ -408,6 +977,26 @@ pub struct Paragraph {
     pub inline_chart: Option<InlineChart>,
     pub smartart: Option<SmartArtDiagram>,
     pub is_section_break: bool,
+    /// Names of bookmarks starting in this paragraph
+    pub bookmarks: Vec<String>,
+    /// Right-to-left paragraph (`w:bidi`)
+    pub rtl: bool,
+    /// Heading level 1–9 for the PDF outline (heading style or `w:outlineLvl`)
+    pub outline_level: Option<u8>,
+    /// Enlarged initial from the drop cap frame paragraph before this one
+    pub drop_cap: Option<DropCap>,
+}
+
+/// A drop cap (`w:framePr/@w:dropCap`): Word stores it as a frame paragraph
+/// holding the enlarged letter, placed before the paragraph it starts.
+pub struct DropCap {
+    pub runs: Vec<Run>,
+    /// Number of text lines the cap drops through (`w:lines`)
+    pub lines: u32,
+    /// `dropCap="margin"`: the cap hangs in the margin instead of indenting the text
+    pub in_margin: bool,
+    /// Gap between the cap and the text (`w:hSpace`)
+    pub h_space: f32,
 }
 
 #[derive(Clone)]
@@ -416,18 +1005,26 @@ pub struct Run {
     pub font_size: f32,
     pub font_name: String,
     pub east_asia_font_name: Option<String>,
+    pub cs_font_name: Option<String>, // complex-script (Arabic, Hebrew, Thai) font
+    pub cs_font_size: Option<f32>,
     pub bold: bool,
     pub italic: bool,
-    pub underline: bool,
+    pub underline: UnderlineStyle,
+    pub underline_color: Option<[u8; 3]>, // None = text color
     pub strikethrough: bool,
     pub dstrike: bool,
     pub char_spacing: f32,
     pub text_scale: f32, // percentage, 100.0 = normal
+    pub position: f32,   // baseline raise in points, negative lowers
+    /// Language of the text (`w:lang`), which picks the hyphenation dictionary
+    pub lang: Option<String>,
     pub caps: bool,
     pub small_caps: bool,
     pub vanish: bool,
+    pub effects: TextEffects,
     pub color: Option<[u8; 3]>, // None = automatic (black)
     pub highlight: Option<[u8; 3]>,
+    pub char_border: Option<CharBorder>,
     pub is_tab: bool,
     pub is_line_break: bool,
     pub vertical_align: VertAlign,
@@ -435,6 +1032,12 @@ pub struct Run {
     pub hyperlink_url: Option<String>,
     pub inline_image: Option<EmbeddedImage>,
     pub footnote_id: Option<u32>,
+    pub endnote_id: Option<u32>,
+    /// Review comment anchored at the end of this run (`w:commentReference`)
+    pub comment_id: Option<u32>,
+    /// Tracked change this run belongs to
+    pub revision: Option<Revision>,
+    /// The note's own number (`w:footnoteRef`/`w:endnoteRef`) inside note text
     pub is_footnote_ref_mark: bool,
     pub kern_threshold: Option<f32>,
     pub char_style_id: Option<String>,
@@ -447,18 +1050,25 @@ impl Default for Run {
             font_size: 0.0,
             font_name: String::new(),
             east_asia_font_name: None,
+            cs_font_name: None,
+            cs_font_size: None,
             bold: false,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
             strikethrough: false,
             dstrike: false,
             char_spacing: 0.0,
             text_scale: 100.0,
+            position: 0.0,
+            lang: None,
             caps: false,
             small_caps: false,
             vanish: false,
+            effects: TextEffects::default(),
             color: None,
             highlight: None,
+            char_border: None,
             is_tab: false,
             is_line_break: false,
             vertical_align: VertAlign::Baseline,
@@ -466,6 +1076,9 @@ impl Default for Run {
             hyperlink_url: None,
             inline_image: None,
             footnote_id: None,
+            endnote_id: None,
+            comment_id: None,
+            revision: None,
             is_footnote_ref_mark: false,
             kern_threshold: None,
             char_style_id: None,
@@ -477,7 +1090,58 @@ impl Default for Run {
 pub enum FieldCode {
     Page,
     NumPages,
-    StyleRef(String),
+    /// SECTION / SECTIONPAGES: number of the page's section and its page count,
+    /// resolved per page in headers and footers
+    Section,
+    SectionPages,
+    /// STYLEREF; `\l` takes the last match on the page instead of the first
+    StyleRef {
+        style_name: String,
+        last_on_page: bool,
+    },
+    /// DATE / TIME with their `\@` picture; resolved to text while parsing
+    Date(String),
+    Time(String),
+    /// FILENAME; `\p` asks for the full path
+    FileName {
+        full_path: bool,
+    },
+    /// Document information from core.xml; missing properties render empty
+    Author,
+    Title,
+    Subject,
+    Keywords,
+    LastSavedBy,
+    /// REF: text of the named bookmark, resolved after parsing
+    Ref(String),
+    /// PAGEREF: page the named bookmark lands on, resolved after layout
+    PageRef(String),
+    /// DOCVARIABLE: value of a `w:docVar` from settings.xml, resolved after parsing
+    DocVariable(String),
+    /// MERGEFIELD: value supplied in `ConversionOptions::merge_fields`, resolved after parsing
+    MergeField(String),
+    /// SEQ: caption counter; `\r` resets it, `\c` repeats the current value and
+    /// `\h` advances it without showing the number
+    Seq {
+        identifier: String,
+        restart: Option<u32>,
+        repeat: bool,
+        hidden: bool,
+        num_fmt: String,
+    },
+    /// TOC over heading levels `min_level..=max_level` (`\o "1-3"`)
+    Toc {
+        min_level: u8,
+        max_level: u8,
+    },
+    /// End of a field begun in an earlier paragraph, or of a TOC field.
+    /// Marks where a multi-paragraph field result stops.
+    FieldEnd,
+    /// SYMBOL character code in an optional font (`\f "Wingdings"`)
+    Symbol {
+        char_code: u32,
+        font: Option<String>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -535,6 +1199,10 @@ pub struct CellBorders {
     pub bottom: CellBorder,
     pub left: CellBorder,
     pub right: CellBorder,
+    /// Diagonal from the top-left to the bottom-right corner (`w:tl2br`)
+    pub tl2br: CellBorder,
+    /// Diagonal from the top-right to the bottom-left corner (`w:tr2bl`)
+    pub tr2bl: CellBorder,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -563,23 +1231,75 @@ pub struct TablePosition {
     pub v_anchor: &'static str, // "page", "margin", or "text"
 }
 
+/// A preferred width (`w:tblW`, `w:tcW`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PreferredWidth {
+    /// No preference: the grid decides (`auto`, `nil` or absent)
+    #[default]
+    Auto,
+    Points(f32),
+    /// Percentage of the available width
+    Percent(f32),
+}
+
 pub struct Table {
     pub col_widths: Vec<f32>, // points
+    /// Preferred table width (`w:tblW`)
+    pub width: PreferredWidth,
+    /// Columns sized to their content (`w:tblLayout` autofit) instead of the grid
+    pub autofit: bool,
     pub rows: Vec<TableRow>,
     pub table_indent: f32,
     pub cell_margins: CellMargins,
     pub position: Option<TablePosition>,
 }
 
+impl Table {
+    /// Paragraphs of every cell, each cell's nested tables following its own.
+    pub fn paragraphs(&self) -> Box<dyn Iterator<Item = &Paragraph> + '_> {
+        Box::new(
+            self.rows
+                .iter()
+                .flat_map(|row| row.cells.iter())
+                .flat_map(|cell| {
+                    cell.paragraphs.iter().chain(
+                        cell.nested_tables
+                            .iter()
+                            .flat_map(|nt| nt.table.paragraphs()),
+                    )
+                }),
+        )
+    }
+
+    pub fn paragraphs_mut(&mut self) -> Box<dyn Iterator<Item = &mut Paragraph> + '_> {
+        Box::new(
+            self.rows
+                .iter_mut()
+                .flat_map(|row| row.cells.iter_mut())
+                .flat_map(|cell| {
+                    cell.paragraphs.iter_mut().chain(
+                        cell.nested_tables
+                            .iter_mut()
+                            .flat_map(|nt| nt.table.paragraphs_mut()),
+                    )
+                }),
+        )
+    }
+}
+
 pub struct TableRow {
     pub cells: Vec<TableCell>,
     pub height: Option<f32>,
     pub height_exact: bool,
     pub is_header: bool,
+    /// Row is kept on one page (`w:cantSplit`), unless taller than a page
+    pub cant_split: bool,
+    /// Gap around each cell (`w:tblCellSpacing` of the row, else of the table)
+    pub cell_spacing: f32,
 }
 
 pub struct TableCell {
-    pub width: f32, // points
+    pub width: PreferredWidth,
     pub paragraphs: Vec<Paragraph>,
     pub borders: CellBorders,
     pub shading: Option<[u8; 3]>,
@@ -588,6 +1308,15 @@ pub struct TableCell {
     pub v_align: CellVAlign,
     pub text_direction: TextDirection,
     pub cell_margins: Option<CellMargins>,
+    /// Tables inside the cell (`w:tbl` within `w:tc`)
+    pub nested_tables: Vec<NestedTable>,
+}
+
+/// A table nested in a cell, placed before the cell paragraph at `para_idx`
+/// (after the last one when `para_idx` is past the end).
+pub struct NestedTable {
+    pub para_idx: usize,
+    pub table: Table,
 }
 
 pub enum Block {
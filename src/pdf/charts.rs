@@ -251,12 +251,14 @@ pub(super) fn render_chart(
     let h = chart.display_height;
 
     let font_size = 10.0;
+    // The first plain sans font by name, so every run picks the same one
     let label_font_key = seen_fonts
         .keys()
-        .find(|k| {
+        .filter(|k| {
             let lower = k.to_lowercase();
             !lower.contains("symbol") && !lower.contains("serif") && !lower.contains("/")
         })
+        .min()
         .map(|s| s.as_str())
         .unwrap_or(default_font_name);
     let has_font = seen_fonts.contains_key(label_font_key);
@@ -2,20 +2,21 @@ use std::collections::HashMap;
 
 use pdf_writer::Content;
 
-use crate::model::{Footnote, LineSpacing, Run};
+use crate::model::{Block, Document, Footnote, LineSpacing, NoteProperties, NoteRestart, Run};
 
-use super::RenderContext;
+use super::header_footer::{compute_effective_margin_bottom, effective_slot_top};
 use super::layout::{
     TextLine, build_paragraph_lines, is_text_empty, render_paragraph_lines, tallest_run_metrics,
 };
 use super::resolve_line_h;
+use super::{PageBuilder, RenderContext};
 
-fn substitute_ref_marks(runs: &[Run], display_num: u32) -> Vec<Run> {
+fn substitute_ref_marks(runs: &[Run], mark: &str) -> Vec<Run> {
     runs.iter()
         .map(|run| {
             if run.is_footnote_ref_mark {
                 let mut r = run.clone();
-                r.text = display_num.to_string();
+                r.text = mark.to_string();
                 r
             } else {
                 run.clone()
@@ -74,7 +75,7 @@ pub(super) fn render_page_footnotes(
     content: &mut Content,
     fn_ids: &[u32],
     footnotes: &HashMap<u32, Footnote>,
-    footnote_display_order: &HashMap<u32, u32>,
+    footnote_marks: &HashMap<u32, String>,
     ctx: &RenderContext,
     margin_left: f32,
     margin_bottom: f32,
@@ -109,10 +110,10 @@ pub(super) fn render_page_footnotes(
         let Some(footnote) = footnotes.get(fn_id) else {
             continue;
         };
-        let display_num = footnote_display_order.get(fn_id).copied().unwrap_or(1);
+        let mark = footnote_marks.get(fn_id).map_or("1", String::as_str);
 
         for para in &footnote.paragraphs {
-            let runs = substitute_ref_marks(&para.runs, display_num);
+            let runs = substitute_ref_marks(&para.runs, mark);
             let ls = para.line_spacing.unwrap_or(LineSpacing::Auto(1.0));
 
             let Some(layout) = layout_paragraph(&runs, ls, ctx, text_width) else {
@@ -141,3 +142,194 @@ pub(super) fn render_page_footnotes(
         }
     }
 }
+
+fn block_runs(block: &Block) -> Box<dyn Iterator<Item = &Run> + '_> {
+    match block {
+        Block::Paragraph(p) => Box::new(p.runs.iter()),
+        Block::Table(t) => Box::new(t.paragraphs().flat_map(|p| p.runs.iter())),
+    }
+}
+
+/// Footnote marks in the order the body references them, counting from
+/// `w:numStart` and starting over in each section for `w:numRestart="eachSect"`.
+pub(super) fn number_footnotes(doc: &Document) -> HashMap<u32, String> {
+    let props = doc.footnote_properties;
+    let mut marks = HashMap::new();
+    let mut next = props.num_start;
+    for section in &doc.sections {
+        if props.restart == NoteRestart::EachSection {
+            next = props.num_start;
+        }
+        for run in section.blocks.iter().flat_map(block_runs) {
+            if let Some(id) = run.footnote_id
+                && !marks.contains_key(&id)
+            {
+                marks.insert(id, props.num_fmt.format(next));
+                next += 1;
+            }
+        }
+    }
+    marks
+}
+
+/// Footnote marks for `w:numRestart="eachPage"`, from the pages a layout pass
+/// placed each reference on.
+pub(super) fn number_footnotes_per_page(
+    page_footnote_ids: &[Vec<u32>],
+    props: NoteProperties,
+) -> HashMap<u32, String> {
+    page_footnote_ids
+        .iter()
+        .flat_map(|ids| {
+            ids.iter()
+                .enumerate()
+                .map(move |(i, &id)| (id, props.num_fmt.format(props.num_start + i as u32)))
+        })
+        .collect()
+}
+
+/// Endnote ids in the order the body first references them.
+fn endnote_order(doc: &Document) -> Vec<u32> {
+    let mut order = Vec::new();
+    for block in doc.sections.iter().flat_map(|s| s.blocks.iter()) {
+        for id in block_runs(block).filter_map(|r| r.endnote_id) {
+            if doc.endnotes.contains_key(&id) && !order.contains(&id) {
+                order.push(id);
+            }
+        }
+    }
+    order
+}
+
+/// Endnotes as one list after the body of the last section: a short separator,
+/// then each note in reference order, continuing onto new pages as needed.
+pub(super) fn render_endnotes(
+    pb: &mut PageBuilder,
+    doc: &Document,
+    ctx: &RenderContext,
+    margin_bottom: &mut f32,
+) {
+    let order = endnote_order(doc);
+    if order.is_empty() {
+        return;
+    }
+    let sect_idx = doc.sections.len() - 1;
+    let sp = &doc.sections[sect_idx].properties;
+    let text_width = sp.page_width - sp.margin_left - sp.margin_right;
+    let new_page = |pb: &mut PageBuilder, margin_bottom: &mut f32| {
+        pb.flush_full_page(sect_idx);
+        let even = pb.is_even_page(sect_idx);
+        pb.slot_top = effective_slot_top(sp, false, even, ctx);
+        *margin_bottom = compute_effective_margin_bottom(sp, false, even, ctx);
+        pb.is_first_page_of_section = false;
+    };
+
+    // Same separator as footnotes: 0.5pt black, ~1/3 page width
+    let separator_gap = 12.0f32;
+    if pb.slot_top - separator_gap < *margin_bottom {
+        new_page(pb, margin_bottom);
+    }
+    let sep_y = pb.slot_top - 3.0;
+    let sep_width = 144.0f32.min(text_width);
+    pb.content.save_state();
+    pb.content.set_line_width(0.5);
+    pb.content.move_to(sp.margin_left, sep_y);
+    pb.content.line_to(sp.margin_left + sep_width, sep_y);
+    pb.content.stroke();
+    pb.content.restore_state();
+    pb.slot_top = sep_y - 9.0;
+
+    for para in order
+        .iter()
+        .filter_map(|id| doc.endnotes.get(id))
+        .flat_map(|note| note.paragraphs.iter())
+    {
+        let ls = para.line_spacing.unwrap_or(LineSpacing::Auto(1.0));
+        let Some(layout) = layout_paragraph(&para.runs, ls, ctx, text_width) else {
+            continue;
+        };
+        let line_count = layout.lines.len();
+        let mut first = 0;
+        while first < line_count {
+            let room = ((pb.slot_top - *margin_bottom) / layout.line_height).floor();
+            if room < 1.0
+                && first == 0
+                && pb.slot_top < effective_slot_top(sp, false, pb.is_even_page(sect_idx), ctx)
+            {
+                new_page(pb, margin_bottom);
+                continue;
+            }
+            let count = (room.max(1.0) as usize).min(line_count - first);
+            let baseline_y = pb.slot_top - layout.font_size * layout.ascender_ratio;
+            render_paragraph_lines(
+                &mut pb.content,
+                &layout.lines[first..first + count],
+                &para.alignment,
+                sp.margin_left,
+                text_width,
+                baseline_y,
+                layout.line_height,
+                line_count,
+                first,
+                &mut pb.links,
+                0.0,
+                ctx.fonts,
+            );
+            pb.slot_top -= count as f32 * layout.line_height;
+            first += count;
+            if first < line_count {
+                new_page(pb, margin_bottom);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConversionOptions;
+    use crate::docx::testing;
+    use crate::model::NumberFormat;
+
+    #[test]
+    fn test_note_properties_set_format_start_and_restart() {
+        let settings = testing::part(
+            "w:settings",
+            r#"<w:footnotePr><w:numFmt w:val="lowerLetter"/><w:numRestart w:val="eachSect"/></w:footnotePr>
+            <w:endnotePr><w:numFmt w:val="upperLetter"/><w:numStart w:val="3"/></w:endnotePr>"#,
+        );
+        let endnotes = testing::part(
+            "w:endnotes",
+            r#"<w:endnote w:id="1"><w:p><w:r><w:t>Note</w:t></w:r></w:p></w:endnote>"#,
+        );
+        let footnote = |id: u32| format!(r#"<w:r><w:footnoteReference w:id="{id}"/></w:r>"#);
+        let body = format!(
+            r#"<w:p>{}{}<w:r><w:endnoteReference w:id="1"/></w:r></w:p>
+            <w:p><w:pPr><w:sectPr/></w:pPr>{}</w:p>
+            <w:p>{}</w:p><w:sectPr/>"#,
+            footnote(1),
+            footnote(2),
+            footnote(3),
+            footnote(4),
+        );
+        let doc = testing::parse_with(
+            &body,
+            &[
+                ("word/settings.xml", settings.as_bytes()),
+                ("word/endnotes.xml", endnotes.as_bytes()),
+            ],
+            &ConversionOptions::default(),
+        );
+        assert_eq!(doc.footnote_properties.num_fmt, NumberFormat::LowerLetter);
+
+        let marks = number_footnotes(&doc);
+        let mark = |id: u32| marks[&id].as_str();
+        assert_eq!([mark(1), mark(2), mark(3), mark(4)], ["a", "b", "c", "a"]);
+        let endnote_mark = testing::paragraphs(&doc)[0]
+            .runs
+            .iter()
+            .find(|r| r.endnote_id.is_some())
+            .map(|r| r.text.clone());
+        assert_eq!(endnote_mark.as_deref(), Some("C"));
+    }
+}
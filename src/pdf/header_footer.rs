@@ -1,24 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use pdf_writer::{Content, Name};
+use pdf_writer::{Content, Name, Str};
 
+use crate::fonts::{encode_as_gids, font_key, to_winansi_bytes};
 use crate::model::{
-    Alignment, Block, FieldCode, HeaderFooter, Paragraph, Run, SectionProperties, VRelativeFrom,
-    VerticalPosition, WrapType,
+    Alignment, Block, FieldCode, HeaderFooter, PageBorderDisplay, PageBorderOffset, Paragraph,
+    ParagraphBorder, Run, SectionProperties, TabAlignment, TabStop, VRelativeFrom,
+    VerticalPosition, Watermark, WrapType,
 };
 
 use super::layout::{
-    TextLine, build_paragraph_lines, build_tabbed_line, is_text_empty, render_paragraph_lines,
-    tallest_run_metrics,
+    TextLine, build_indented_paragraph_lines, build_tabbed_line, is_text_empty,
+    render_paragraph_lines, tallest_run_metrics,
 };
 use super::table;
-use super::{RenderContext, resolve_line_h};
+use super::{RenderContext, page_field_text, resolve_line_h};
+
+/// STYLEREF values visible from a page's header or footer.
+pub(super) struct StyleRefValues<'a> {
+    /// First match on the page, else the last match before it
+    pub(super) first: &'a HashMap<String, String>,
+    /// Last match on or before the page, for `\l`
+    pub(super) last: &'a HashMap<String, String>,
+}
+
+/// Page-dependent field values for one page's header and footer.
+pub(super) struct PageFields<'a> {
+    /// Formatted number of the page, for PAGE
+    pub(super) page_text: &'a str,
+    /// Pages in the document, for NUMPAGES
+    pub(super) total_pages: usize,
+    /// 1-based number of the page's section, for SECTION
+    pub(super) section: usize,
+    /// Pages in the page's section, for SECTIONPAGES
+    pub(super) section_pages: usize,
+}
 
 pub(super) fn substitute_hf_runs(
     runs: &[Run],
-    page_num: usize,
-    total_pages: usize,
-    styleref_values: &HashMap<String, String>,
+    page: &PageFields,
+    styleref_values: &StyleRefValues,
+    ctx: &RenderContext,
 ) -> Vec<Run> {
     runs.iter()
         .map(|run| {
@@ -26,11 +48,40 @@ pub(super) fn substitute_hf_runs(
             if let Some(ref fc) = run.field_code {
                 r.field_code = None;
                 r.text = match fc {
-                    FieldCode::Page => page_num.to_string(),
-                    FieldCode::NumPages => total_pages.to_string(),
-                    FieldCode::StyleRef(name) => {
-                        styleref_values.get(name).cloned().unwrap_or_default()
+                    FieldCode::Page => page.page_text.to_string(),
+                    FieldCode::NumPages => page.total_pages.to_string(),
+                    FieldCode::Section => page.section.to_string(),
+                    FieldCode::SectionPages => page.section_pages.to_string(),
+                    FieldCode::StyleRef {
+                        style_name,
+                        last_on_page,
+                    } => {
+                        let values = if *last_on_page {
+                            styleref_values.last
+                        } else {
+                            styleref_values.first
+                        };
+                        values.get(style_name).cloned().unwrap_or_default()
+                    }
+                    FieldCode::PageRef(_) => {
+                        page_field_text(run, ctx).unwrap_or_else(|| run.text.clone())
                     }
+                    // Resolved while parsing
+                    FieldCode::Ref(_)
+                    | FieldCode::DocVariable(_)
+                    | FieldCode::MergeField(_)
+                    | FieldCode::Seq { .. }
+                    | FieldCode::Toc { .. }
+                    | FieldCode::FieldEnd
+                    | FieldCode::Symbol { .. }
+                    | FieldCode::Date(_)
+                    | FieldCode::Time(_)
+                    | FieldCode::FileName { .. }
+                    | FieldCode::Author
+                    | FieldCode::Title
+                    | FieldCode::Subject
+                    | FieldCode::Keywords
+                    | FieldCode::LastSavedBy => run.text.clone(),
                 };
             }
             r
@@ -38,7 +89,11 @@ pub(super) fn substitute_hf_runs(
         .collect()
 }
 
-pub(super) fn compute_header_height(hf: &HeaderFooter, ctx: &RenderContext) -> f32 {
+pub(super) fn compute_header_height(
+    hf: &HeaderFooter,
+    sp: &SectionProperties,
+    ctx: &RenderContext,
+) -> f32 {
     let mut height = 0.0f32;
     let mut prev_space_after = 0.0f32;
     for block in &hf.blocks {
@@ -62,7 +117,7 @@ pub(super) fn compute_header_height(hf: &HeaderFooter, ctx: &RenderContext) -> f
                             VerticalPosition::Offset(o) => o + fi.image.display_height,
                             _ => fi.image.display_height,
                         };
-                        content_h = content_h.max(fi_h);
+                        content_h = content_h.max(fi_h + fi.dist_bottom);
                     }
                 }
 
@@ -84,7 +139,7 @@ pub(super) fn compute_header_height(hf: &HeaderFooter, ctx: &RenderContext) -> f
                 prev_space_after = para.space_after;
             }
             Block::Table(table) => {
-                height += table::compute_hf_table_height(table, ctx);
+                height += table::compute_hf_table_height(table, sp, ctx);
                 prev_space_after = 0.0;
             }
         }
@@ -95,18 +150,21 @@ pub(super) fn compute_header_height(hf: &HeaderFooter, ctx: &RenderContext) -> f
 pub(super) fn effective_slot_top(
     sp: &SectionProperties,
     is_first: bool,
+    even: bool,
     ctx: &RenderContext,
 ) -> f32 {
     let header = select_hf(
+        sp,
         is_first,
-        sp.different_first_page,
+        even && ctx.even_and_odd_headers,
         &sp.header_first,
+        &sp.header_even,
         &sp.header_default,
     );
     let base = sp.page_height - sp.margin_top;
     match header {
         Some(hf) => {
-            base.min(sp.page_height - sp.header_margin - compute_header_height(hf, ctx))
+            base.min(sp.page_height - sp.header_margin - compute_header_height(hf, sp, ctx))
         }
         None => base,
     }
@@ -115,31 +173,38 @@ pub(super) fn effective_slot_top(
 pub(super) fn compute_effective_margin_bottom(
     sp: &SectionProperties,
     is_first: bool,
+    even: bool,
     ctx: &RenderContext,
 ) -> f32 {
     let footer = select_hf(
+        sp,
         is_first,
-        sp.different_first_page,
+        even && ctx.even_and_odd_headers,
         &sp.footer_first,
+        &sp.footer_even,
         &sp.footer_default,
     );
     let base = sp.margin_bottom;
     match footer {
-        Some(hf) => {
-            base.max(sp.footer_margin + compute_header_height(hf, ctx))
-        }
+        Some(hf) => base.max(sp.footer_margin + compute_header_height(hf, sp, ctx)),
         None => base,
     }
 }
 
+/// The first-page, even-page or default header (or footer) of a page. Even
+/// pages without an even variant use the default one.
 fn select_hf<'a>(
+    sp: &SectionProperties,
     is_first: bool,
-    different_first_page: bool,
+    even: bool,
     first: &'a Option<HeaderFooter>,
+    even_hf: &'a Option<HeaderFooter>,
     default: &'a Option<HeaderFooter>,
 ) -> Option<&'a HeaderFooter> {
-    if is_first && different_first_page {
+    if is_first && sp.different_first_page {
         first.as_ref()
+    } else if even && even_hf.is_some() {
+        even_hf.as_ref()
     } else {
         default.as_ref()
     }
@@ -150,12 +215,7 @@ pub(super) fn hf_paragraphs(hf: &HeaderFooter) -> Vec<&Paragraph> {
         .iter()
         .flat_map(|block| match block {
             Block::Paragraph(p) => vec![p],
-            Block::Table(t) => t
-                .rows
-                .iter()
-                .flat_map(|row| row.cells.iter())
-                .flat_map(|cell| cell.paragraphs.iter())
-                .collect(),
+            Block::Table(t) => t.paragraphs().collect(),
         })
         .collect()
 }
@@ -189,18 +249,203 @@ fn emit_image_xobject(
     content.restore_state();
 }
 
+/// Strokes the section's page borders (`w:pgBorders`) on the pages its
+/// `display` setting selects. Each border sits `space` points inside the page
+/// edge or outside the text area, depending on `offsetFrom`. On `mirrored`
+/// pages the text area has the left and right margins swapped.
+pub(super) fn render_page_borders(
+    content: &mut Content,
+    sp: &SectionProperties,
+    is_first: bool,
+    mirrored: bool,
+) {
+    let Some(borders) = &sp.page_borders else {
+        return;
+    };
+    let shown = match borders.display {
+        PageBorderDisplay::AllPages => true,
+        PageBorderDisplay::FirstPage => is_first,
+        PageBorderDisplay::NotFirstPage => !is_first,
+    };
+    if !shown {
+        return;
+    }
+    let (margin_left, margin_right) = if mirrored {
+        (sp.margin_right, sp.margin_left)
+    } else {
+        (sp.margin_left, sp.margin_right)
+    };
+
+    // Edge position of a border line's center, measured inward from the page edge
+    let inset = |b: &ParagraphBorder, margin: f32| match borders.offset_from {
+        PageBorderOffset::Page => b.space_pt + b.width_pt / 2.0,
+        PageBorderOffset::Text => margin - b.space_pt - b.width_pt / 2.0,
+    };
+    let width = |b: &Option<ParagraphBorder>| b.as_ref().map_or(0.0, |b| b.width_pt);
+    let top = borders
+        .top
+        .as_ref()
+        .map_or(sp.page_height, |b| sp.page_height - inset(b, sp.margin_top));
+    let bottom = borders
+        .bottom
+        .as_ref()
+        .map_or(0.0, |b| inset(b, sp.margin_bottom));
+    let left = borders.left.as_ref().map_or(0.0, |b| inset(b, margin_left));
+    let right = borders
+        .right
+        .as_ref()
+        .map_or(sp.page_width, |b| sp.page_width - inset(b, margin_right));
+
+    // Horizontal lines run across the vertical ones so the corners close
+    let half_left = width(&borders.left) / 2.0;
+    let half_right = width(&borders.right) / 2.0;
+    let sides = [
+        (
+            &borders.top,
+            (left - half_left, top),
+            (right + half_right, top),
+        ),
+        (
+            &borders.bottom,
+            (left - half_left, bottom),
+            (right + half_right, bottom),
+        ),
+        (&borders.left, (left, bottom), (left, top)),
+        (&borders.right, (right, bottom), (right, top)),
+    ];
+    for (border, (x1, y1), (x2, y2)) in sides {
+        let Some(b) = border else {
+            continue;
+        };
+        let [r, g, bl] = b.color;
+        content.save_state();
+        content.set_line_width(b.width_pt);
+        content.set_stroke_rgb(r as f32 / 255.0, g as f32 / 255.0, bl as f32 / 255.0);
+        content.move_to(x1, y1);
+        content.line_to(x2, y2);
+        content.stroke();
+        content.restore_state();
+    }
+}
+
+/// Draws a header watermark centered on the text area: text stretched to its
+/// shape width and rotated, pictures at their size, both at the shape's opacity.
+pub(super) fn render_watermark(
+    content: &mut Content,
+    watermark: &Watermark,
+    sp: &SectionProperties,
+    ctx: &RenderContext,
+    image_name: Option<&str>,
+    alpha_states: &mut HashSet<u8>,
+) {
+    let cx = sp.margin_left + (sp.page_width - sp.margin_left - sp.margin_right) / 2.0;
+    let cy = sp.margin_bottom + (sp.page_height - sp.margin_top - sp.margin_bottom) / 2.0;
+    let opacity = match watermark {
+        Watermark::Text { opacity, .. } | Watermark::Image { opacity, .. } => *opacity,
+    };
+
+    content.save_state();
+    if opacity < 1.0 {
+        let pct = (opacity * 100.0).round().clamp(0.0, 100.0) as u8;
+        alpha_states.insert(pct);
+        content.set_parameters(Name(format!("GSa{pct}").as_bytes()));
+    }
+    match watermark {
+        Watermark::Text {
+            run,
+            width,
+            rotation,
+            ..
+        } => {
+            let Some(entry) = ctx.fonts.get(&font_key(run)) else {
+                content.restore_state();
+                return;
+            };
+            let unit_width = entry.word_width(&run.text, 1.0, false);
+            if unit_width > 0.0 {
+                let font_size = width / unit_width;
+                // VML rotates clockwise
+                let (sin, cos) = (-rotation.to_radians()).sin_cos();
+                content.transform([cos, sin, -sin, cos, cx, cy]);
+                let [r, g, b] = run.color.unwrap_or([0xC0, 0xC0, 0xC0]);
+                content.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+                let bytes = match &entry.char_to_gid {
+                    Some(map) => encode_as_gids(&run.text, map),
+                    None => to_winansi_bytes(&run.text),
+                };
+                content
+                    .begin_text()
+                    .set_font(Name(entry.pdf_name.as_bytes()), font_size)
+                    .next_line(-width / 2.0, -font_size * 0.35)
+                    .show(Str(&bytes))
+                    .end_text();
+            }
+        }
+        Watermark::Image { image, .. } => {
+            if let Some(name) = image_name {
+                let (w, h) = (image.display_width, image.display_height);
+                emit_image_xobject(content, name, cx - w / 2.0, cy - h / 2.0, w, h);
+            }
+        }
+    }
+    content.restore_state();
+}
+
+/// Tab stops of a header or footer paragraph. Without stops of its own (the
+/// Header and Footer styles usually supply them) tabs go to the center and
+/// then the right margin, for the classic "title / page / date" layout.
+fn hf_tab_stops(tab_stops: &[TabStop], text_width: f32) -> Vec<TabStop> {
+    if !tab_stops.is_empty() {
+        return tab_stops.to_vec();
+    }
+    [
+        (text_width / 2.0, TabAlignment::Center),
+        (text_width, TabAlignment::Right),
+    ]
+    .into_iter()
+    .map(|(position, alignment)| TabStop {
+        position,
+        alignment,
+        leader: None,
+    })
+    .collect()
+}
+
 fn build_lines(
     runs: &[Run],
     fonts: &HashMap<String, crate::fonts::FontEntry>,
-    tab_stops: &[crate::model::TabStop],
+    tab_stops: &[TabStop],
+    default_tab: f32,
     text_width: f32,
     inline_images: &HashMap<usize, String>,
+    rtl: bool,
 ) -> Vec<TextLine> {
     let has_tabs = runs.iter().any(|r| r.is_tab);
     if has_tabs {
-        build_tabbed_line(runs, fonts, tab_stops, 0.0, text_width, 0.0, inline_images)
+        build_tabbed_line(
+            runs,
+            fonts,
+            tab_stops,
+            default_tab,
+            0.0,
+            text_width,
+            0.0,
+            inline_images,
+            rtl,
+        )
     } else {
-        build_paragraph_lines(runs, fonts, text_width, 0.0, inline_images)
+        build_indented_paragraph_lines(
+            runs,
+            fonts,
+            text_width,
+            0.0,
+            inline_images,
+            0..0,
+            0.0,
+            0.0,
+            None,
+            rtl,
+        )
     }
 }
 
@@ -210,19 +455,18 @@ pub(super) fn render_header_footer(
     ctx: &RenderContext,
     sp: &SectionProperties,
     is_header: bool,
-    page_num: usize,
-    total_pages: usize,
+    page: &PageFields,
     para_image_names: &HashMap<usize, String>,
     inline_image_names: &HashMap<(usize, usize), String>,
     floating_image_names: &HashMap<(usize, usize), String>,
-    styleref_values: &HashMap<String, String>,
+    styleref_values: &StyleRefValues,
     gradient_specs: &mut Vec<super::GradientSpec>,
 ) {
     let text_width = sp.page_width - sp.margin_left - sp.margin_right;
     let mut cursor_y = if is_header {
         sp.page_height - sp.header_margin
     } else {
-        sp.footer_margin + compute_header_height(hf, ctx)
+        sp.footer_margin + compute_header_height(hf, sp, ctx)
     };
 
     let mut pi = 0usize;
@@ -236,8 +480,7 @@ pub(super) fn render_header_footer(
                     ctx,
                     content,
                     &mut cursor_y,
-                    page_num,
-                    total_pages,
+                    page,
                     styleref_values,
                 );
                 prev_space_after = 0.0;
@@ -249,8 +492,7 @@ pub(super) fn render_header_footer(
 
                 cursor_y -= prev_space_after.max(para.space_before);
 
-                let substituted_runs =
-                    substitute_hf_runs(&para.runs, page_num, total_pages, styleref_values);
+                let substituted_runs = substitute_hf_runs(&para.runs, page, styleref_values, ctx);
 
                 let (font_size, tallest_lhr, tallest_ar) =
                     tallest_run_metrics(&substituted_runs, ctx.fonts);
@@ -298,8 +540,10 @@ pub(super) fn render_header_footer(
                             &tp.runs,
                             ctx.fonts,
                             &tp.tab_stops,
+                            ctx.default_tab_stop,
                             content_w,
                             &empty_inline_imgs,
+                            tp.rtl,
                         );
                         if tb_lines.is_empty() {
                             let (fs, _, _) = tallest_run_metrics(&tp.runs, ctx.fonts);
@@ -436,9 +680,11 @@ pub(super) fn render_header_footer(
                 let lines = build_lines(
                     &substituted_runs,
                     ctx.fonts,
-                    &para.tab_stops,
+                    &hf_tab_stops(&para.tab_stops, text_width),
+                    ctx.default_tab_stop,
                     text_width,
                     &block_inline_images,
+                    para.rtl,
                 );
 
                 render_paragraph_lines(
@@ -468,3 +714,75 @@ pub(super) fn render_header_footer(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_part_footer_without_tab_stops() {
+        let fonts = HashMap::from([(
+            "Latin".to_string(),
+            crate::fonts::FontEntry {
+                pdf_name: "F1".to_string(),
+                font_ref: pdf_writer::Ref::new(1),
+                widths_1000: vec![500.0; 224],
+                line_h_ratio: None,
+                ascender_ratio: None,
+                char_to_gid: None,
+                char_widths_1000: None,
+                kern_pairs: None,
+                synthetic_bold: false,
+                fallback: None,
+            },
+        )]);
+        let run = |text: &str| Run {
+            text: text.to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let tab = Run {
+            is_tab: true,
+            ..run("")
+        };
+        let runs = [run("Title"), tab.clone(), run("Page"), tab, run("Date")];
+        let lines = build_lines(
+            &runs,
+            &fonts,
+            &hf_tab_stops(&[], 400.0),
+            36.0,
+            400.0,
+            &HashMap::new(),
+            false,
+        );
+        let placed: Vec<(&str, f32)> = lines[0]
+            .chunks
+            .iter()
+            .map(|c| (c.text.as_str(), c.x_offset))
+            .collect();
+        assert_eq!(placed, [("Title", 0.0), ("Page", 190.0), ("Date", 380.0)]);
+    }
+
+    #[test]
+    fn test_page_border_measured_from_the_page_edge() {
+        let doc = crate::docx::testing::parse_body(
+            r#"<w:p/><w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440"/>
+            <w:pgBorders w:offsetFrom="page" w:display="notFirstPage"><w:left w:val="single" w:sz="8" w:space="24" w:color="FF0000"/></w:pgBorders></w:sectPr>"#,
+        );
+        let sp = &doc.sections[0].properties;
+        let draw = |is_first: bool| {
+            let mut content = Content::new();
+            render_page_borders(&mut content, sp, is_first, false);
+            String::from_utf8(content.finish().to_vec()).unwrap()
+        };
+
+        assert_eq!(draw(true), "");
+        // A 1pt line whose center is 24pt of space plus half its width in from the edge
+        let ops = draw(false);
+        assert!(
+            ops.contains("1 w\n1 0 0 RG\n24.5 0 m\n24.5 792 l\nS"),
+            "{ops}"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+// Automatic hyphenation (`w:autoHyphenation`). Break points come from the TeX
+// patterns of the `hyphenation` crate, so without that feature no word is split.
+
+#[cfg_attr(not(feature = "hyphenation"), allow(dead_code))]
+pub(crate) struct Hyphenator {
+    /// One dictionary per language the document uses
+    #[cfg(feature = "hyphenation")]
+    dicts: Vec<(hyphenation::Language, hyphenation::Standard)>,
+    /// Language of text without its own `w:lang`
+    default_lang: String,
+}
+
+#[cfg(feature = "hyphenation")]
+fn language(lang: &str) -> Option<hyphenation::Language> {
+    use hyphenation::Language;
+    let lang = lang.to_ascii_lowercase();
+    let primary = lang.split(['-', '_']).next().unwrap_or("");
+    Some(match primary {
+        "en" if lang == "en-gb" => Language::EnglishGB,
+        "en" => Language::EnglishUS,
+        "de" if lang == "de-ch" => Language::GermanSwiss,
+        "de" => Language::German1996,
+        "fr" => Language::French,
+        "es" => Language::Spanish,
+        "it" => Language::Italian,
+        "nl" => Language::Dutch,
+        "da" => Language::Danish,
+        "sv" => Language::Swedish,
+        "nb" | "no" => Language::NorwegianBokmal,
+        "nn" => Language::NorwegianNynorsk,
+        "fi" => Language::Finnish,
+        "pl" => Language::Polish,
+        "pt" => Language::Portuguese,
+        _ => return None,
+    })
+}
+
+impl Hyphenator {
+    /// Dictionaries for `default_lang` and the other language tags in `langs`
+    /// (such as "de-DE"), or `None` when none of them has one.
+    pub(super) fn new<'a>(
+        default_lang: &str,
+        langs: impl IntoIterator<Item = &'a str>,
+    ) -> Option<Self> {
+        #[cfg(feature = "hyphenation")]
+        {
+            use hyphenation::Load;
+            let mut dicts: Vec<(hyphenation::Language, hyphenation::Standard)> = Vec::new();
+            for lang in std::iter::once(default_lang).chain(langs) {
+                let Some(language) = language(lang) else {
+                    log::warn!("No hyphenation dictionary for language {lang}");
+                    continue;
+                };
+                if dicts.iter().any(|(l, _)| *l == language) {
+                    continue;
+                }
+                match hyphenation::Standard::from_embedded(language) {
+                    Ok(dict) => dicts.push((language, dict)),
+                    Err(e) => log::warn!("Failed to load hyphenation dictionary for {lang}: {e}"),
+                }
+            }
+            (!dicts.is_empty()).then(|| Self {
+                dicts,
+                default_lang: default_lang.to_string(),
+            })
+        }
+        #[cfg(not(feature = "hyphenation"))]
+        {
+            let _ = langs;
+            log::warn!(
+                "Hyphenation ({default_lang}) requires the `hyphenation` feature; words are not split"
+            );
+            None
+        }
+    }
+
+    /// Byte offsets where `word`, in language `lang` (the document's when
+    /// `None`), may be split with a hyphen. Leading and trailing punctuation is
+    /// ignored; words mixing letters with digits or inner punctuation (URLs,
+    /// existing compounds) and words in languages without a dictionary are
+    /// never split.
+    pub(super) fn breaks(&self, word: &str, lang: Option<&str>) -> Vec<usize> {
+        let Some(start) = word.find(char::is_alphabetic) else {
+            return Vec::new();
+        };
+        let end = word
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_alphabetic())
+            .map_or(word.len(), |(i, c)| i + c.len_utf8());
+        let core = &word[start..end];
+        if !core.chars().all(char::is_alphabetic) {
+            return Vec::new();
+        }
+        self.core_breaks(core, lang.unwrap_or(&self.default_lang))
+            .into_iter()
+            .map(|b| b + start)
+            .collect()
+    }
+
+    #[cfg(feature = "hyphenation")]
+    fn core_breaks(&self, word: &str, lang: &str) -> Vec<usize> {
+        use hyphenation::Hyphenator as _;
+        let dict =
+            language(lang).and_then(|language| self.dicts.iter().find(|(l, _)| *l == language));
+        match dict {
+            Some((_, dict)) => dict.hyphenate(word).breaks,
+            None => Vec::new(),
+        }
+    }
+
+    #[cfg(not(feature = "hyphenation"))]
+    fn core_breaks(&self, _word: &str, _lang: &str) -> Vec<usize> {
+        Vec::new()
+    }
+}
+
+#[cfg(all(test, feature = "hyphenation"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_hyphenate_in_their_own_language() {
+        let hyphenator = Hyphenator::new("en-US", ["de-DE"]).expect("dictionaries");
+        assert!(
+            !hyphenator
+                .breaks("Silbentrennung", Some("de-DE"))
+                .is_empty()
+        );
+        assert!(!hyphenator.breaks("hyphenation", None).is_empty());
+        // No dictionary: the word stays whole rather than take English breaks
+        assert!(hyphenator.breaks("hyphenation", Some("tlh")).is_empty());
+    }
+}
@@ -2,10 +2,16 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 
 use pdf_writer::types::TextRenderingMode;
-use pdf_writer::{Content, Name, Rect, Str};
+use pdf_writer::{Content, Name, Rect, Str, TextStr};
+use unicode_bidi::{BidiClass, BidiInfo, Level, bidi_class};
 
 use crate::fonts::{FontEntry, encode_as_gids, font_key, font_key_buf, to_winansi_bytes};
-use crate::model::{Alignment, Run, TabAlignment, TabStop, VertAlign};
+use crate::model::{
+    Alignment, BorderLineStyle, CharBorder, Run, TabAlignment, TabStop, TextEffects,
+    UnderlineStyle, VertAlign,
+};
+
+use super::hyphenate::Hyphenator;
 
 fn set_fill_color(content: &mut Content, color: Option<[u8; 3]>) {
     if let Some([r, g, b]) = color {
@@ -48,28 +54,79 @@ fn resolve_tab_aligned_start(
     }
 }
 
-/// Split text into (preceding_space_count, word) pairs.
+/// Ideographs, kana and CJK punctuation, which break lines between characters.
+/// Hangul is left out: Korean text breaks at spaces like Latin text.
+pub(super) fn is_cjk_break_char(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x2FDF     // CJK and Kangxi radicals
+        | 0x3000..=0x30FF   // CJK punctuation, Hiragana, Katakana
+        | 0x3100..=0x312F   // Bopomofo
+        | 0x31A0..=0x31FF   // Bopomofo Extended, Katakana Phonetic Extensions
+        | 0x3200..=0x4DBF   // Enclosed CJK, CJK Compatibility, Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0xFE30..=0xFE4F   // CJK Compatibility Forms
+        | 0xFF00..=0xFFEF   // Halfwidth and Fullwidth Forms
+        | 0x20000..=0x2FFFF // Extensions B and beyond
+    )
+}
+
+/// Kinsoku: characters that may not start a line.
+const NO_BREAK_BEFORE: &str = "、。，．・：；？！ー」』）】〕〉》〗〙〛｝］’”ゝゞヽヾぁぃぅぇぉっゃゅょゎァィゥェォッャュョヮヵヶ々〻゠〜～,.!?:;)]}%";
+/// Kinsoku: characters that may not end a line.
+const NO_BREAK_AFTER: &str = "「『（【〔〈《〖〘〚｛［‘“([{";
+
+/// Splits a space-free word at CJK line-break opportunities: between any two
+/// characters where one is CJK, but not before closing or after opening
+/// punctuation. Words without CJK characters come back whole.
+fn cjk_break_units(word: &str) -> Vec<&str> {
+    if !word.chars().any(is_cjk_break_char) {
+        return vec![word];
+    }
+    let mut units = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    for (i, c) in word.char_indices() {
+        if let Some(p) = prev
+            && (is_cjk_break_char(p) || is_cjk_break_char(c))
+            && !NO_BREAK_BEFORE.contains(c)
+            && !NO_BREAK_AFTER.contains(p)
+        {
+            units.push(&word[start..i]);
+            start = i;
+        }
+        prev = Some(c);
+    }
+    units.push(&word[start..]);
+    units
+}
+
+/// Split text into (preceding_space_count, byte_offset, word) triples.
 /// Leading and inter-word spaces are counted. Trailing spaces (after the last word)
 /// are handled separately by the caller.
-fn split_preserving_spaces(text: &str) -> Vec<(usize, &str)> {
+fn split_preserving_spaces(text: &str) -> Vec<(usize, usize, &str)> {
     let mut result = Vec::new();
     let mut chars = text.char_indices().peekable();
     let mut space_count: usize = 0;
 
     while let Some(&(i, c)) = chars.peek() {
-        if c.is_whitespace() {
+        if is_break_space(c) {
             space_count += 1;
             chars.next();
         } else {
             let start = i;
             while let Some(&(_, c)) = chars.peek() {
-                if c.is_whitespace() {
+                if is_break_space(c) {
                     break;
                 }
                 chars.next();
             }
             let end = chars.peek().map(|&(i, _)| i).unwrap_or(text.len());
-            result.push((space_count, &text[start..end]));
+            let mut offset = start;
+            for (k, unit) in cjk_break_units(&text[start..end]).into_iter().enumerate() {
+                result.push((if k == 0 { space_count } else { 0 }, offset, unit));
+                offset += unit.len();
+            }
             space_count = 0;
         }
     }
@@ -77,24 +134,67 @@ fn split_preserving_spaces(text: &str) -> Vec<(usize, &str)> {
     result
 }
 
+/// Whitespace a line may break at; no-break spaces keep their words together.
+fn is_break_space(c: char) -> bool {
+    c.is_whitespace() && !matches!(c, '\u{A0}' | '\u{2007}' | '\u{202F}')
+}
+
+/// `word` without its soft hyphens, and the byte offsets in it where they were.
+fn split_soft_hyphens(word: &str) -> (Cow<'_, str>, Vec<usize>) {
+    if !word.contains('\u{AD}') {
+        return (Cow::Borrowed(word), Vec::new());
+    }
+    let mut visible = String::with_capacity(word.len());
+    let mut breaks = Vec::new();
+    for c in word.chars() {
+        if c == '\u{AD}' {
+            breaks.push(visible.len());
+        } else {
+            visible.push(c);
+        }
+    }
+    breaks.retain(|&b| b > 0 && b < visible.len());
+    breaks.dedup();
+    (Cow::Owned(visible), breaks)
+}
+
+/// Bidi levels of the bytes of `word` once [`split_soft_hyphens`] drops its
+/// soft hyphens, from `levels` covering `word` as written (empty when the
+/// paragraph has no levels).
+fn visible_levels(word: &str, levels: &[u8]) -> Vec<u8> {
+    if levels.is_empty() {
+        return Vec::new();
+    }
+    word.char_indices()
+        .filter(|&(_, c)| c != '\u{AD}')
+        .flat_map(|(i, c)| std::iter::repeat_n(levels[i], c.len_utf8()))
+        .collect()
+}
+
 pub(super) struct WordChunk {
     pub(super) pdf_font: String,
     pub(super) text: String,
     pub(super) font_size: f32,
     pub(super) color: Option<[u8; 3]>,
     pub(super) highlight: Option<[u8; 3]>,
+    pub(super) char_border: Option<CharBorder>,
     pub(super) x_offset: f32, // x relative to line start
     pub(super) width: f32,
-    pub(super) underline: bool,
+    pub(super) underline: UnderlineStyle,
+    pub(super) underline_color: Option<[u8; 3]>,
     pub(super) strikethrough: bool,
     pub(super) dstrike: bool,
     pub(super) char_spacing: f32,
     pub(super) text_scale: f32, // percentage, 100.0 = normal
-    pub(super) y_offset: f32,   // vertical offset for superscript/subscript
+    pub(super) effects: TextEffects,
+    pub(super) y_offset: f32, // vertical offset for superscript/subscript
     pub(super) hyperlink_url: Option<String>,
+    pub(super) comment_id: Option<u32>,
     pub(super) inline_image_name: Option<String>,
     pub(super) inline_image_height: f32,
     pub(super) synthetic_bold: bool,
+    /// Unicode bidi embedding level; odd levels run right to left
+    pub(super) bidi_level: u8,
 }
 
 impl WordChunk {
@@ -114,23 +214,29 @@ impl WordChunk {
             font_size: eff_fs,
             color: run.color,
             highlight: run.highlight,
+            char_border: run.char_border,
             x_offset,
             width,
             underline: run.underline,
+            underline_color: run.underline_color,
             strikethrough: run.strikethrough,
             dstrike: run.dstrike,
             char_spacing,
             text_scale: run.text_scale,
+            effects: run.effects,
             y_offset,
             hyperlink_url: run.hyperlink_url.clone(),
+            comment_id: run.comment_id,
             inline_image_name: None,
             inline_image_height: 0.0,
             synthetic_bold: entry.synthetic_bold,
+            bidi_level: 0,
         }
     }
 
     fn image(
         pdf_name: &str,
+        hyperlink_url: Option<String>,
         font_size: f32,
         x_offset: f32,
         display_width: f32,
@@ -142,18 +248,23 @@ impl WordChunk {
             font_size,
             color: None,
             highlight: None,
+            char_border: None,
             x_offset,
             width: display_width,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
             strikethrough: false,
             dstrike: false,
             char_spacing: 0.0,
             text_scale: 100.0,
+            effects: TextEffects::default(),
             y_offset: 0.0,
-            hyperlink_url: None,
+            hyperlink_url,
+            comment_id: None,
             inline_image_name: Some(pdf_name.to_string()),
             inline_image_height: display_height,
             synthetic_bold: false,
+            bidi_level: 0,
         }
     }
 
@@ -171,31 +282,193 @@ impl WordChunk {
             font_size,
             color,
             highlight: None,
+            char_border: None,
             x_offset,
             width,
-            underline: false,
+            underline: UnderlineStyle::None,
+            underline_color: None,
             strikethrough: false,
             dstrike: false,
             char_spacing: 0.0,
             text_scale: 100.0,
+            effects: TextEffects::default(),
             y_offset: 0.0,
             hyperlink_url: None,
+            comment_id: None,
             inline_image_name: None,
             inline_image_height: 0.0,
             synthetic_bold: false,
+            bidi_level: 0,
         }
     }
 }
 
+pub(crate) enum LinkTarget {
+    Url(String),
+    /// Review comment, shown as a note at the end of the commented text
+    Comment(u32),
+}
+
 pub(crate) struct LinkAnnotation {
     pub(super) rect: Rect,
-    pub(super) url: String,
+    pub(super) target: LinkTarget,
 }
 
 pub(super) struct TextLine {
     pub(super) chunks: Vec<WordChunk>,
     pub(super) total_width: f32,
     pub(super) ends_with_break: bool,
+    /// Text of the line in logical order, when its chunks were reordered for display
+    pub(super) actual_text: Option<String>,
+}
+
+/// Unicode bidi embedding level (UAX #9) of every byte of each run's text,
+/// resolved over the whole paragraph with tabs, breaks and pictures standing
+/// in as the characters they act as. Empty for a left-to-right paragraph
+/// without right-to-left text, where every level is zero.
+fn bidi_levels(runs: &[Run], rtl: bool) -> Vec<Vec<u8>> {
+    let has_rtl_text = runs.iter().filter(|r| !r.vanish).any(|r| {
+        r.text.chars().any(|c| {
+            matches!(
+                bidi_class(c),
+                BidiClass::R
+                    | BidiClass::AL
+                    | BidiClass::AN
+                    | BidiClass::RLE
+                    | BidiClass::RLO
+                    | BidiClass::RLI
+            )
+        })
+    });
+    if !rtl && !has_rtl_text {
+        return Vec::new();
+    }
+    let mut text = String::new();
+    let mut spans = Vec::with_capacity(runs.len());
+    for run in runs {
+        let start = text.len();
+        if !run.vanish {
+            match run {
+                r if r.is_tab => text.push('\t'),
+                r if r.is_line_break => text.push('\u{2028}'),
+                r if r.inline_image.is_some() => text.push('\u{FFFC}'),
+                r => text.push_str(&effective_text(r)),
+            }
+        }
+        spans.push(start..text.len());
+    }
+    let para_level = if rtl { Level::rtl() } else { Level::ltr() };
+    let info = BidiInfo::new(&text, Some(para_level));
+    spans
+        .into_iter()
+        .map(|span| info.levels[span].iter().map(|l| l.number()).collect())
+        .collect()
+}
+
+/// `text` split where the bidi level of its bytes changes, with the level of
+/// each piece.
+fn level_pieces<'a>(text: &'a str, levels: &[u8]) -> Vec<(&'a str, u8)> {
+    let level_at = |i: usize| levels.get(i).copied().unwrap_or(0);
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for (i, _) in text.char_indices() {
+        if level_at(i) != level_at(start) {
+            pieces.push((&text[start..i], level_at(start)));
+            start = i;
+        }
+    }
+    pieces.push((&text[start..], level_at(start)));
+    pieces
+}
+
+fn mirror_bracket(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        _ => c,
+    }
+}
+
+/// Puts the chunks of each line in visual order (UAX #9 rule L2): from the
+/// highest embedding level down to the lowest odd one, every sequence of
+/// chunks at that level or higher is reversed. Chunks at odd levels get their
+/// characters reversed, with paired brackets mirrored.
+fn reorder_bidi_lines(lines: &mut [TextLine]) {
+    for line in lines {
+        let n = line.chunks.len();
+        let levels: Vec<u8> = line.chunks.iter().map(|c| c.bidi_level).collect();
+        let max_level = levels.iter().copied().max().unwrap_or(0);
+        if max_level == 0 {
+            continue;
+        }
+        let lowest_odd = levels.iter().copied().min().unwrap_or(0) | 1;
+        let mut order: Vec<usize> = (0..n).collect();
+        for level in (lowest_odd..=max_level).rev() {
+            let mut k = 0;
+            while k < n {
+                if levels[order[k]] < level {
+                    k += 1;
+                    continue;
+                }
+                let end = (k..n).find(|&e| levels[order[e]] < level).unwrap_or(n);
+                order[k..end].reverse();
+                k = end;
+            }
+        }
+        if order.iter().enumerate().all(|(k, &idx)| k == idx) && levels.iter().all(|l| l % 2 == 0) {
+            continue;
+        }
+
+        // Gap before each chunk in logical order, reused between visual neighbours
+        let gaps: Vec<f32> = (0..n)
+            .map(|k| {
+                if k == 0 {
+                    0.0
+                } else {
+                    let prev = &line.chunks[k - 1];
+                    (line.chunks[k].x_offset - prev.x_offset - prev.width).max(0.0)
+                }
+            })
+            .collect();
+        let start_x = line.chunks[0].x_offset;
+
+        // Extraction reads the chunks in visual order, so keep what they say
+        let mut logical = String::new();
+        for (k, chunk) in line.chunks.iter().enumerate() {
+            if !logical.is_empty() && gaps[k] > 0.0 {
+                logical.push(' ');
+            }
+            logical.push_str(&chunk.text);
+        }
+        line.actual_text = Some(logical);
+
+        let mut chunks: Vec<Option<WordChunk>> = std::mem::take(&mut line.chunks)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut x = start_x;
+        let mut prev: Option<usize> = None;
+        for &idx in &order {
+            let mut chunk = chunks[idx].take().expect("each chunk placed once");
+            if let Some(p) = prev {
+                x += gaps[p.max(idx)];
+            }
+            chunk.x_offset = x;
+            x += chunk.width;
+            if levels[idx] % 2 == 1 {
+                chunk.text = chunk.text.chars().rev().map(mirror_bracket).collect();
+            }
+            line.chunks.push(chunk);
+            prev = Some(idx);
+        }
+        line.total_width = x;
+    }
 }
 
 /// True when a paragraph has no visible text (may still have phantom font-info runs).
@@ -226,11 +499,40 @@ fn effective_text(run: &Run) -> Cow<'_, str> {
 }
 
 fn vert_y_offset(run: &Run) -> f32 {
-    match run.vertical_align {
+    let shift = match run.vertical_align {
         VertAlign::Superscript => run.font_size * 0.35,
         VertAlign::Subscript => -run.font_size * 0.14,
         VertAlign::Baseline => 0.0,
+    };
+    shift + run.position
+}
+
+/// Splits `text` into pieces drawn by one font each: `entry`, or its fallback
+/// for the characters only the fallback has a glyph for.
+fn font_pieces<'a>(
+    text: &'a str,
+    entry: &'a FontEntry,
+    seen_fonts: &'a HashMap<String, FontEntry>,
+) -> Vec<(&'a str, &'a FontEntry)> {
+    let Some(fallback) = entry.fallback.as_ref().and_then(|k| seen_fonts.get(k)) else {
+        return vec![(text, entry)];
+    };
+    let mut pieces: Vec<(&str, &FontEntry)> = Vec::new();
+    let mut start = 0;
+    let mut uses_fallback = false;
+    for (i, ch) in text.char_indices() {
+        let want_fallback = !entry.has_glyph(ch) && fallback.has_glyph(ch);
+        if i > start && want_fallback != uses_fallback {
+            pieces.push((
+                &text[start..i],
+                if uses_fallback { fallback } else { entry },
+            ));
+            start = i;
+        }
+        uses_fallback = want_fallback;
     }
+    pieces.push((&text[start..], if uses_fallback { fallback } else { entry }));
+    pieces
 }
 
 const DEFAULT_TAB_INTERVAL: f32 = 36.0; // 0.5 inches
@@ -241,6 +543,7 @@ fn finish_line(chunks: &mut Vec<WordChunk>) -> TextLine {
         chunks: std::mem::take(chunks),
         total_width,
         ends_with_break: false,
+        actual_text: None,
     }
 }
 
@@ -250,7 +553,7 @@ fn finish_line_with_break(chunks: &mut Vec<WordChunk>) -> TextLine {
     line
 }
 
-/// Layout runs into wrapped lines.
+/// Layout runs of a left-to-right paragraph into wrapped lines, in visual order.
 /// Handles cross-run contiguous text correctly: no space is inserted between
 /// runs unless the preceding text ended with whitespace or the new run starts
 /// with whitespace (e.g., "bold" + ", " → "bold," not "bold ,").
@@ -261,21 +564,72 @@ pub(super) fn build_paragraph_lines(
     first_line_hanging: f32,
     inline_image_names: &HashMap<usize, String>,
 ) -> Vec<TextLine> {
+    build_indented_paragraph_lines(
+        runs,
+        seen_fonts,
+        max_width,
+        first_line_hanging,
+        inline_image_names,
+        0..0,
+        0.0,
+        0.0,
+        None,
+        false,
+    )
+}
+
+/// Like [`build_paragraph_lines`], with the `indented_lines` lines
+/// pushed right by `indent_left` and narrowed by it and `indent_right` (text
+/// wrapping around a drop cap or a floating image), words split across lines
+/// where `hyphenator` allows and a right-to-left base direction when `rtl`.
+/// Bidi levels are resolved over the whole paragraph before it is broken into
+/// lines, and each line is then reordered on its own.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn build_indented_paragraph_lines(
+    runs: &[Run],
+    seen_fonts: &HashMap<String, FontEntry>,
+    max_width: f32,
+    first_line_hanging: f32,
+    inline_image_names: &HashMap<usize, String>,
+    indented_lines: std::ops::Range<usize>,
+    indent_left: f32,
+    indent_right: f32,
+    hyphenator: Option<&Hyphenator>,
+    rtl: bool,
+) -> Vec<TextLine> {
+    let bidi = bidi_levels(runs, rtl);
+    let line_max = |line_idx: usize| {
+        let width = if line_idx == 0 {
+            max_width + first_line_hanging
+        } else {
+            max_width
+        };
+        if indented_lines.contains(&line_idx) {
+            width - indent_left - indent_right
+        } else {
+            width
+        }
+    };
     let mut lines: Vec<TextLine> = Vec::new();
     let mut current_chunks: Vec<WordChunk> = Vec::new();
     let mut current_x: f32 = 0.0;
     let mut pending_space_w: f32 = 0.0;
+    // Spaces starting the paragraph or a line after a break indent it; spaces
+    // at a wrap are dropped
+    let mut at_hard_line_start = true;
     let mut key_buf = String::new();
 
     for (run_idx, run) in runs.iter().enumerate() {
         if run.vanish || run.is_tab {
             continue; // vanished runs hidden; tabs handled in build_tabbed_line
         }
+        let run_levels = bidi.get(run_idx).map_or(&[][..], Vec::as_slice);
 
         if run.is_line_break {
             lines.push(finish_line_with_break(&mut current_chunks));
             current_x = 0.0;
             pending_space_w = 0.0;
+            at_hard_line_start = true;
             continue;
         }
 
@@ -283,33 +637,33 @@ pub(super) fn build_paragraph_lines(
         if let Some(img) = &run.inline_image {
             if let Some(pdf_name) = inline_image_names.get(&run_idx) {
                 let img_w = img.display_width;
-                let need_space = !current_chunks.is_empty() && pending_space_w > 0.0;
+                let need_space =
+                    (!current_chunks.is_empty() || at_hard_line_start) && pending_space_w > 0.0;
                 let proposed_x = if need_space {
                     current_x + pending_space_w
                 } else {
                     current_x
                 };
 
-                let line_max = if lines.is_empty() {
-                    max_width + first_line_hanging
-                } else {
-                    max_width
-                };
-                if !current_chunks.is_empty() && proposed_x + img_w > line_max {
+                if !current_chunks.is_empty() && proposed_x + img_w > line_max(lines.len()) {
                     lines.push(finish_line(&mut current_chunks));
                     current_x = 0.0;
                 } else {
                     current_x = proposed_x;
                 }
                 pending_space_w = 0.0;
+                at_hard_line_start = false;
 
-                current_chunks.push(WordChunk::image(
+                let mut chunk = WordChunk::image(
                     pdf_name,
+                    run.hyperlink_url.clone(),
                     run.font_size,
                     current_x,
                     img_w,
                     img.display_height,
-                ));
+                );
+                chunk.bidi_level = run_levels.first().copied().unwrap_or(0);
+                current_chunks.push(chunk);
                 current_x += img_w;
             }
             continue;
@@ -326,42 +680,109 @@ pub(super) fn build_paragraph_lines(
         let ts = run.text_scale / 100.0;
         let space_w_cs = space_w * ts + cs;
 
-        for (space_count, word) in split_preserving_spaces(&text) {
+        let kern = run.kern_threshold.is_some_and(|t| eff_fs >= t);
+        let width_of = |w: &str| {
+            font_pieces(w, entry, seen_fonts)
+                .iter()
+                .map(|(piece, e)| {
+                    e.word_width(piece, eff_fs, kern) * ts + cs * piece.chars().count() as f32
+                })
+                .sum::<f32>()
+        };
+        // `levels` holds the bidi level of each byte of `word`
+        let push_text = |chunks: &mut Vec<WordChunk>, word: &str, levels: &[u8], x: f32| {
+            let mut x = x;
+            let mut at = 0;
+            for (piece, e) in font_pieces(word, entry, seen_fonts) {
+                let piece_levels = levels.get(at..at + piece.len()).unwrap_or(&[]);
+                for (part, level) in level_pieces(piece, piece_levels) {
+                    let w =
+                        e.word_width(part, eff_fs, kern) * ts + cs * part.chars().count() as f32;
+                    let mut chunk = WordChunk::text(e, run, part, eff_fs, cs, y_off, x, w);
+                    chunk.bidi_level = level;
+                    chunks.push(chunk);
+                    x += w;
+                }
+                at += piece.len();
+            }
+        };
+
+        for (space_count, offset, word) in split_preserving_spaces(&text) {
             pending_space_w += space_count as f32 * space_w_cs;
 
-            let char_count = word.chars().count();
-            let kern = run.kern_threshold.is_some_and(|t| eff_fs >= t);
-            let ww = entry.word_width(word, eff_fs, kern) * ts + cs * char_count as f32;
+            // Soft hyphens are invisible unless the word is split at one
+            let (full, soft_breaks) = split_soft_hyphens(word);
+            let word_levels = run_levels.get(offset..offset + word.len()).unwrap_or(&[]);
+            let full_levels = visible_levels(word, word_levels);
+            let mut word: &str = &full;
+            let mut levels: &[u8] = &full_levels;
+            let mut ww = width_of(word);
 
-            let need_space = !current_chunks.is_empty() && pending_space_w > 0.0;
+            let need_space =
+                (!current_chunks.is_empty() || at_hard_line_start) && pending_space_w > 0.0;
 
-            let proposed_x = if need_space {
+            let mut proposed_x = if need_space {
                 current_x + pending_space_w
             } else {
                 current_x
             };
 
-            let line_max = if lines.is_empty() {
-                max_width + first_line_hanging
-            } else {
-                max_width
+            // A word running past the line end is split at the last break point
+            // that still fits with its hyphen, as often as it takes. Soft hyphens
+            // the author placed take the place of the dictionary's break points.
+            let overflows = proposed_x + ww > line_max(lines.len());
+            let breaks = match hyphenator {
+                _ if !soft_breaks.is_empty() => soft_breaks,
+                Some(hyphenator) if overflows => hyphenator.breaks(&full, run.lang.as_deref()),
+                _ => Vec::new(),
             };
-            if !current_chunks.is_empty() && proposed_x + ww > line_max {
+            if overflows && !breaks.is_empty() {
+                let mut start = 0;
+                while proposed_x + ww > line_max(lines.len()) {
+                    let avail = line_max(lines.len()) - proposed_x;
+                    let Some((end, head)) = breaks
+                        .iter()
+                        .rev()
+                        .filter(|&&b| b > start)
+                        .map(|&b| (b, format!("{}-", &full[start..b])))
+                        .find(|(_, head)| width_of(head) <= avail)
+                    else {
+                        break;
+                    };
+                    // The hyphen takes the level of the letter before it
+                    let mut head_levels = full_levels.get(start..end).unwrap_or(&[]).to_vec();
+                    if let Some(&last) = head_levels.last() {
+                        head_levels.push(last);
+                    }
+                    push_text(&mut current_chunks, &head, &head_levels, proposed_x);
+                    lines.push(finish_line(&mut current_chunks));
+                    start = end;
+                    proposed_x = 0.0;
+                    ww = width_of(&full[start..]);
+                }
+                word = &full[start..];
+                levels = full_levels.get(start..).unwrap_or(&[]);
+            }
+
+            if !current_chunks.is_empty() && proposed_x + ww > line_max(lines.len()) {
                 lines.push(finish_line(&mut current_chunks));
                 current_x = 0.0;
             } else {
                 current_x = proposed_x;
             }
             pending_space_w = 0.0;
+            at_hard_line_start = false;
 
-            current_chunks.push(WordChunk::text(
-                entry, run, word, eff_fs, cs, y_off, current_x, ww,
-            ));
+            push_text(&mut current_chunks, word, levels, current_x);
             current_x += ww;
         }
 
         // Accumulate trailing whitespace for the next run
-        let trailing_spaces = text.chars().rev().take_while(|c| c.is_whitespace()).count();
+        let trailing_spaces = text
+            .chars()
+            .rev()
+            .take_while(|&c| is_break_space(c))
+            .count();
         pending_space_w += trailing_spaces as f32 * space_w_cs;
     }
 
@@ -374,24 +795,49 @@ pub(super) fn build_paragraph_lines(
             chunks: vec![],
             total_width: 0.0,
             ends_with_break: false,
+            actual_text: None,
         });
     }
+    reorder_bidi_lines(&mut lines);
+    for line in lines
+        .iter_mut()
+        .take(indented_lines.end)
+        .skip(indented_lines.start)
+    {
+        for chunk in &mut line.chunks {
+            chunk.x_offset += indent_left;
+        }
+        // Counting the room on the right keeps aligned text clear of it too
+        line.total_width += indent_left + indent_right;
+    }
     lines
 }
 
-fn find_next_tab_stop(current_x: f32, tab_stops: &[TabStop], indent_left: f32) -> TabStop {
+/// First implicit tab stop, every `interval` points from the margin, past `x`.
+pub(super) fn next_default_tab(x: f32, interval: f32) -> f32 {
+    let interval = if interval > 0.0 {
+        interval
+    } else {
+        DEFAULT_TAB_INTERVAL
+    };
+    ((x / interval).floor() + 1.0) * interval
+}
+
+fn find_next_tab_stop(
+    current_x: f32,
+    tab_stops: &[TabStop],
+    default_tab: f32,
+    indent_left: f32,
+) -> TabStop {
     let abs_x = current_x + indent_left;
     tab_stops
         .iter()
         .find(|s| s.position > abs_x + 0.5)
         .cloned()
-        .unwrap_or_else(|| {
-            let next = ((abs_x / DEFAULT_TAB_INTERVAL).floor() + 1.0) * DEFAULT_TAB_INTERVAL;
-            TabStop {
-                position: next,
-                alignment: TabAlignment::Left,
-                leader: None,
-            }
+        .unwrap_or_else(|| TabStop {
+            position: next_default_tab(abs_x, default_tab),
+            alignment: TabAlignment::Left,
+            leader: None,
         })
 }
 
@@ -403,15 +849,18 @@ fn segment_width(runs: &[&Run], seen_fonts: &HashMap<String, FontEntry>) -> f32
         let key = font_key_buf(run, &mut key_buf);
         let entry = seen_fonts.get(key).expect("font registered");
         let eff_fs = effective_font_size(run);
+        let cs = run.char_spacing;
         let ts = run.text_scale / 100.0;
-        let space_w = entry.space_width(eff_fs) * ts;
+        let space_w = entry.space_width(eff_fs) * ts + cs;
         let text = effective_text(run);
-        for (i, word) in text.split_whitespace().enumerate() {
+        let words = text.split(is_break_space).filter(|w| !w.is_empty());
+        for (i, word) in words.enumerate() {
             if !first || i > 0 {
                 w += space_w;
             }
+            let (word, _) = split_soft_hyphens(word);
             let kern = run.kern_threshold.is_some_and(|t| eff_fs >= t);
-            w += entry.word_width(word, eff_fs, kern) * ts;
+            w += entry.word_width(&word, eff_fs, kern) * ts + cs * word.chars().count() as f32;
             first = false;
         }
     }
@@ -442,7 +891,8 @@ fn decimal_before_width(runs: &[&Run], seen_fonts: &HashMap<String, FontEntry>)
             s
         };
         let kern = run.kern_threshold.is_some_and(|t| eff_fs >= t);
-        w += entry.word_width(text_to_measure, eff_fs, kern);
+        w += entry.word_width(text_to_measure, eff_fs, kern) * run.text_scale / 100.0
+            + run.char_spacing * text_to_measure.chars().count() as f32;
         if chars_remaining == 0 {
             break;
         }
@@ -450,17 +900,22 @@ fn decimal_before_width(runs: &[&Run], seen_fonts: &HashMap<String, FontEntry>)
     w
 }
 
-/// Build TextLines for a paragraph that contains tab characters.
+/// Build TextLines for a paragraph that contains tab characters, in visual
+/// order for the paragraph direction `rtl`.
 /// Wraps to new lines when content exceeds `max_width`.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn build_tabbed_line(
     runs: &[Run],
     seen_fonts: &HashMap<String, FontEntry>,
     tab_stops: &[TabStop],
+    default_tab: f32,
     indent_left: f32,
     max_width: f32,
     first_line_hanging: f32,
     inline_image_names: &HashMap<usize, String>,
+    rtl: bool,
 ) -> Vec<TextLine> {
+    let bidi = bidi_levels(runs, rtl);
     // Split runs into segments at tab markers, tracking original run indices
     let mut segments: Vec<(Vec<&Run>, Vec<usize>, Option<TabStop>)> = Vec::new();
     let mut current_seg: Vec<&Run> = Vec::new();
@@ -512,7 +967,7 @@ pub(super) fn build_tabbed_line(
         };
 
         if seg_idx > 0 {
-            let stop = find_next_tab_stop(current_x, tab_stops, line_indent);
+            let stop = find_next_tab_stop(current_x, tab_stops, default_tab, line_indent);
             let tab_target = stop.position - line_indent;
             let mut seg_start =
                 resolve_tab_aligned_start(&stop, tab_target, seg_runs, seen_fonts, current_x);
@@ -521,7 +976,7 @@ pub(super) fn build_tabbed_line(
                 result_lines.push(finish_line(&mut all_chunks));
                 current_x = 0.0;
                 is_first_line = false;
-                let new_stop = find_next_tab_stop(0.0, tab_stops, indent_left);
+                let new_stop = find_next_tab_stop(0.0, tab_stops, default_tab, indent_left);
                 let new_target = new_stop.position - indent_left;
                 seg_start =
                     resolve_tab_aligned_start(&new_stop, new_target, seg_runs, seen_fonts, 0.0);
@@ -556,14 +1011,17 @@ pub(super) fn build_tabbed_line(
                                     std::iter::repeat_n(leader_char, count).collect();
                                 let leader_w = count as f32 * char_w;
                                 let leader_start = seg_start - leader_w;
-                                all_chunks.push(WordChunk::leader(
+                                let mut chunk = WordChunk::leader(
                                     entry,
                                     leader_text,
                                     eff_fs,
                                     run.color,
                                     leader_start,
                                     leader_w,
-                                ));
+                                );
+                                // Tabs are at the paragraph's level
+                                chunk.bidi_level = u8::from(rtl);
+                                all_chunks.push(chunk);
                             }
                         }
                     }
@@ -576,6 +1034,9 @@ pub(super) fn build_tabbed_line(
         // Layout text in this segment from current_x
         let mut prev_ws = false;
         for (local_idx, run) in seg_runs.iter().enumerate() {
+            let run_levels = bidi
+                .get(seg_indices[local_idx])
+                .map_or(&[][..], Vec::as_slice);
             if run.is_line_break {
                 result_lines.push(finish_line_with_break(&mut all_chunks));
                 current_x = 0.0;
@@ -587,13 +1048,16 @@ pub(super) fn build_tabbed_line(
             // Handle inline images (same pattern as build_paragraph_lines)
             if let Some(img) = &run.inline_image {
                 if let Some(pdf_name) = inline_image_names.get(&seg_indices[local_idx]) {
-                    all_chunks.push(WordChunk::image(
+                    let mut chunk = WordChunk::image(
                         pdf_name,
+                        run.hyperlink_url.clone(),
                         run.font_size,
                         current_x,
                         img.display_width,
                         img.display_height,
-                    ));
+                    );
+                    chunk.bidi_level = run_levels.first().copied().unwrap_or(0);
+                    all_chunks.push(chunk);
                     current_x += img.display_width;
                 }
                 continue;
@@ -608,13 +1072,19 @@ pub(super) fn build_tabbed_line(
 
             let cs = run.char_spacing;
             let ts = run.text_scale / 100.0;
-            for (i, word) in text.split_whitespace().enumerate() {
-                let char_count = word.chars().count();
+            let units = split_preserving_spaces(&text);
+            for (k, &(space_count, offset, word)) in units.iter().enumerate() {
                 let kern = run.kern_threshold.is_some_and(|t| eff_fs >= t);
-                let ww = entry.word_width(word, eff_fs, kern) * ts + cs * char_count as f32;
-                if !all_chunks.is_empty()
-                    && (i > 0 || prev_ws || text.starts_with(char::is_whitespace))
-                {
+                let word_levels = run_levels.get(offset..offset + word.len()).unwrap_or(&[]);
+                let levels = visible_levels(word, word_levels);
+                let (word, _) = split_soft_hyphens(word);
+                let pieces = font_pieces(&word, entry, seen_fonts);
+                let piece_w = |piece: &str, e: &FontEntry| {
+                    e.word_width(piece, eff_fs, kern) * ts + cs * piece.chars().count() as f32
+                };
+                let ww: f32 = pieces.iter().map(|&(piece, e)| piece_w(piece, e)).sum();
+                // Any run of spaces before a word takes one space's width
+                if !all_chunks.is_empty() && (space_count > 0 || (k == 0 && prev_ws)) {
                     current_x += space_w * ts + cs;
                 }
                 let cur_line_max = if is_first_line {
@@ -628,12 +1098,21 @@ pub(super) fn build_tabbed_line(
                     current_x = 0.0;
                     is_first_line = false;
                 }
-                all_chunks.push(WordChunk::text(
-                    entry, run, word, eff_fs, cs, y_off, current_x, ww,
-                ));
-                current_x += ww;
+                let mut at = 0;
+                for (piece, e) in pieces {
+                    let piece_levels = levels.get(at..at + piece.len()).unwrap_or(&[]);
+                    for (part, level) in level_pieces(piece, piece_levels) {
+                        let w = piece_w(part, e);
+                        let mut chunk =
+                            WordChunk::text(e, run, part, eff_fs, cs, y_off, current_x, w);
+                        chunk.bidi_level = level;
+                        all_chunks.push(chunk);
+                        current_x += w;
+                    }
+                    at += piece.len();
+                }
             }
-            prev_ws = text.ends_with(char::is_whitespace);
+            prev_ws = text.ends_with(is_break_space);
         }
     }
 
@@ -645,9 +1124,11 @@ pub(super) fn build_tabbed_line(
             chunks: vec![],
             total_width: 0.0,
             ends_with_break: false,
+            actual_text: None,
         });
     }
 
+    reorder_bidi_lines(&mut result_lines);
     result_lines
 }
 
@@ -667,6 +1148,32 @@ pub(super) fn encode_text_for_pdf(
 
 /// Render pre-built lines applying the paragraph alignment.
 /// `total_line_count` is the full paragraph line count (for justify: last line stays left-aligned).
+/// Distance of each line's baseline below the first one, accounting for
+/// inline images making lines taller.
+pub(super) fn line_y_offsets(lines: &[TextLine], line_pitch: f32) -> Vec<f32> {
+    let mut line_y_offsets: Vec<f32> = Vec::with_capacity(lines.len());
+    let mut cumulative_y = 0.0f32;
+    for (i, line) in lines.iter().enumerate() {
+        line_y_offsets.push(cumulative_y);
+        let img_h = line
+            .chunks
+            .iter()
+            .map(|c| c.inline_image_height)
+            .fold(0.0f32, f32::max);
+        cumulative_y += if img_h > line_pitch {
+            img_h
+        } else {
+            line_pitch
+        };
+        // First line offset is always 0
+        if i == 0 {
+            cumulative_y = line_pitch.max(img_h);
+            line_y_offsets[0] = 0.0;
+        }
+    }
+    line_y_offsets
+}
+
 pub(super) fn render_paragraph_lines(
     content: &mut Content,
     lines: &[TextLine],
@@ -693,37 +1200,21 @@ pub(super) fn render_paragraph_lines(
         .map(|e| (e.pdf_name.as_str(), e))
         .collect();
 
-    // Pre-compute per-line y offsets accounting for inline images making lines taller
-    let mut line_y_offsets: Vec<f32> = Vec::with_capacity(lines.len());
-    let mut cumulative_y = 0.0f32;
-    for (i, line) in lines.iter().enumerate() {
-        line_y_offsets.push(cumulative_y);
-        let img_h = line
-            .chunks
-            .iter()
-            .map(|c| c.inline_image_height)
-            .fold(0.0f32, f32::max);
-        cumulative_y += if img_h > line_pitch {
-            img_h
-        } else {
-            line_pitch
-        };
-        // First line offset is always 0
-        if i == 0 {
-            cumulative_y = line_pitch.max(img_h);
-            line_y_offsets[0] = 0.0;
-        }
-    }
+    let line_y_offsets = line_y_offsets(lines, line_pitch);
 
     let last_line_idx = total_line_count.saturating_sub(1);
     for (line_num, line) in lines.iter().enumerate() {
         let y = first_baseline_y - line_y_offsets[line_num];
         let global_line_idx = first_line_index + line_num;
 
-        let is_justified = *alignment == Alignment::Justify
-            && global_line_idx != last_line_idx
-            && !line.ends_with_break
-            && line.chunks.len() > 1;
+        // Distributed text spreads every line, including the last and those
+        // ending in a manual break; CJK text is split per character, so its
+        // gaps fall between characters
+        let is_justified = match alignment {
+            Alignment::Justify => global_line_idx != last_line_idx && !line.ends_with_break,
+            Alignment::Distribute => true,
+            _ => false,
+        } && line.chunks.len() > 1;
 
         let (eff_margin, eff_width) = if global_line_idx == 0 && first_line_hanging.abs() > 0.001 {
             (
@@ -737,7 +1228,7 @@ pub(super) fn render_paragraph_lines(
         let line_start_x = match alignment {
             Alignment::Center => eff_margin + (eff_width - line.total_width) / 2.0,
             Alignment::Right => eff_margin + eff_width - line.total_width,
-            Alignment::Left | Alignment::Justify => eff_margin,
+            Alignment::Left | Alignment::Justify | Alignment::Distribute => eff_margin,
         };
 
         let extra_per_gap = if is_justified {
@@ -747,6 +1238,7 @@ pub(super) fn render_paragraph_lines(
         };
 
         let mut decorations: Vec<(f32, f32, f32, f32, Option<[u8; 3]>)> = Vec::new();
+        let mut underlines: Vec<UnderlineSpan> = Vec::new();
 
         // Draw run highlights as merged spans (contiguous same-color highlights)
         {
@@ -796,6 +1288,12 @@ pub(super) fn render_paragraph_lines(
             .any(|c| c.inline_image_name.is_none() && !c.text.is_empty());
 
         if has_text_chunks {
+            if let Some(actual) = &line.actual_text {
+                content
+                    .begin_marked_content_with_properties(Name(b"Span"))
+                    .properties()
+                    .pair(Name(b"ActualText"), TextStr(actual));
+            }
             content.begin_text();
             let mut td_x = 0.0_f32;
             let mut td_y = 0.0_f32;
@@ -845,29 +1343,76 @@ pub(super) fn render_paragraph_lines(
 
                 let text_bytes =
                     encode_text_for_pdf(&chunk.text, &chunk.pdf_font, &pdf_name_to_entry);
-                content.show(Str(&text_bytes));
+                let fx = chunk.effects;
+                if fx.shadow || fx.emboss || fx.imprint {
+                    // Offset gray copies behind the glyphs: a drop shadow below
+                    // right, a light relief for emboss, a dark edge for imprint
+                    let d = chunk.font_size * 0.04;
+                    for (on, dx, dy, gray) in [
+                        (fx.shadow, d, -d, 0.55),
+                        (fx.emboss, d, -d, 0.8),
+                        (fx.imprint, -d, d, 0.35),
+                    ] {
+                        if on {
+                            content.set_fill_gray(gray);
+                            content.next_line(dx, dy);
+                            content.show(Str(&text_bytes));
+                            content.next_line(-dx, -dy);
+                        }
+                    }
+                    set_fill_color(content, chunk.color);
+                }
+                if fx.outline {
+                    content.set_line_width(chunk.font_size * 0.03);
+                    set_stroke_color(content, chunk.color);
+                    content.set_text_rendering_mode(TextRenderingMode::Stroke);
+                    content.show(Str(&text_bytes));
+                    if cur_synthetic_bold {
+                        content.set_line_width(chunk.font_size * 0.02);
+                        content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+                    } else {
+                        content.set_text_rendering_mode(TextRenderingMode::Fill);
+                    }
+                } else {
+                    content.show(Str(&text_bytes));
+                }
 
-                if chunk.underline {
-                    let thick = (chunk.font_size * 0.05).max(0.5);
+                if chunk.underline.is_some() {
+                    let mut thick = (chunk.font_size * 0.05).max(0.5);
+                    if chunk.underline.is_heavy() {
+                        thick *= 2.0;
+                    }
                     let ul_y = if chunk.hyperlink_url.is_some() {
                         y - chunk.font_size * 0.08
                     } else {
                         y - chunk.font_size * 0.12
                     };
-                    let ul_top = ul_y - thick;
-                    // Merge with previous underline decoration if same y and color
-                    let merged = decorations.last_mut().filter(|(_, dy, _, dh, dc)| {
-                        (*dy - ul_top).abs() < 0.01
-                            && (*dh - thick).abs() < 0.01
-                            && *dc == chunk.color
+                    let ul_bottom = ul_y - thick;
+                    let ul_color = chunk.underline_color.or(chunk.color);
+                    // Merge with previous underline if same style, y and color;
+                    // "words" underlines leave the gaps between chunks open
+                    let merged = underlines.last_mut().filter(|prev| {
+                        chunk.underline != UnderlineStyle::Words
+                            && prev.style == chunk.underline
+                            && (prev.y - ul_bottom).abs() < 0.01
+                            && (prev.thick - thick).abs() < 0.01
+                            && prev.color == ul_color
                     });
                     if let Some(prev) = merged {
-                        prev.2 = (x + chunk.width) - prev.0;
+                        prev.width = (x + chunk.width) - prev.x;
                     } else {
-                        decorations.push((x, ul_top, chunk.width, thick, chunk.color));
+                        underlines.push(UnderlineSpan {
+                            style: chunk.underline,
+                            x,
+                            y: ul_bottom,
+                            width: chunk.width,
+                            thick,
+                            color: ul_color,
+                        });
                     }
                 }
-                if chunk.strikethrough {
+                // A double strike replaces the single one when both are set
+                if chunk.strikethrough && !chunk.dstrike {
                     let thick = (chunk.font_size * 0.05).max(0.5);
                     let st_y = y + chunk.font_size * 0.3;
                     decorations.push((x, st_y, chunk.width, thick, chunk.color));
@@ -880,21 +1425,36 @@ pub(super) fn render_paragraph_lines(
                     decorations.push((x, mid_y + gap / 2.0, chunk.width, thick, chunk.color));
                 }
 
+                let bottom = y - chunk.font_size * 0.2;
+                let top = y + chunk.font_size * 0.8;
                 if let Some(ref url) = chunk.hyperlink_url {
-                    let bottom = y - chunk.font_size * 0.2;
-                    let top = y + chunk.font_size * 0.8;
-                    let merged = links
-                        .last_mut()
-                        .filter(|prev| prev.url == *url && (prev.rect.y1 - bottom).abs() < 1.0);
+                    let merged = links.last_mut().filter(|prev| {
+                        matches!(&prev.target, LinkTarget::Url(u) if u == url)
+                            && (prev.rect.y1 - bottom).abs() < 1.0
+                    });
                     if let Some(prev) = merged {
                         prev.rect.x2 = x + chunk.width;
                     } else {
                         links.push(LinkAnnotation {
                             rect: Rect::new(x, bottom, x + chunk.width, top),
-                            url: url.clone(),
+                            target: LinkTarget::Url(url.clone()),
                         });
                     }
                 }
+                // A comment's anchor moves along to the last chunk of its run
+                if let Some(id) = chunk.comment_id {
+                    let rect = Rect::new(x, bottom, x + chunk.width, top);
+                    let existing = links
+                        .iter_mut()
+                        .find(|prev| matches!(prev.target, LinkTarget::Comment(c) if c == id));
+                    match existing {
+                        Some(prev) => prev.rect = rect,
+                        None => links.push(LinkAnnotation {
+                            rect,
+                            target: LinkTarget::Comment(id),
+                        }),
+                    }
+                }
             }
             if cur_synthetic_bold {
                 content.set_text_rendering_mode(TextRenderingMode::Fill);
@@ -909,6 +1469,9 @@ pub(super) fn render_paragraph_lines(
                 cur_text_scale = 100.0;
             }
             content.end_text();
+            if line.actual_text.is_some() {
+                content.end_marked_content();
+            }
         }
 
         // Draw inline images outside text block
@@ -927,6 +1490,17 @@ pub(super) fn render_paragraph_lines(
                 ]);
                 content.x_object(Name(img_name.as_bytes()));
                 content.restore_state();
+                if let Some(url) = &chunk.hyperlink_url {
+                    links.push(LinkAnnotation {
+                        rect: Rect::new(
+                            x,
+                            img_bottom,
+                            x + chunk.width,
+                            img_bottom + chunk.inline_image_height,
+                        ),
+                        target: LinkTarget::Url(url.clone()),
+                    });
+                }
             }
         }
 
@@ -937,12 +1511,156 @@ pub(super) fn render_paragraph_lines(
             }
             content.rect(dx, dy, dw, dh).fill_nonzero();
         }
+        for ul in &underlines {
+            if ul.color != current_color {
+                set_fill_color(content, ul.color);
+                current_color = ul.color;
+            }
+            draw_underline(content, ul);
+        }
+
+        // Boxes around bordered runs, one per stretch of chunks sharing a border
+        let mut boxes: Vec<(CharBorder, f32, f32, f32)> = Vec::new(); // border, x0, x1, font size
+        let mut prev_border = None;
+        for (chunk_idx, chunk) in line.chunks.iter().enumerate() {
+            let x = line_start_x + chunk.x_offset + chunk_idx as f32 * extra_per_gap;
+            if let Some(border) = chunk.char_border {
+                if prev_border == Some(border)
+                    && let Some(last) = boxes.last_mut()
+                {
+                    last.2 = x + chunk.width;
+                    last.3 = last.3.max(chunk.font_size);
+                } else {
+                    boxes.push((border, x, x + chunk.width, chunk.font_size));
+                }
+            }
+            prev_border = chunk.char_border;
+        }
+        for (border, x0, x1, fs) in &boxes {
+            draw_char_border(
+                content,
+                border,
+                x0 - border.space_pt,
+                x1 + border.space_pt,
+                y,
+                *fs,
+            );
+        }
     }
     if current_color.is_some() {
         content.set_fill_gray(0.0);
     }
 }
 
+struct UnderlineSpan {
+    style: UnderlineStyle,
+    x: f32,
+    y: f32, // bottom edge of a single underline
+    width: f32,
+    thick: f32,
+    color: Option<[u8; 3]>,
+}
+
+/// Draws an underline in its `w:u` pattern. Solid lines are filled rectangles in
+/// the current fill color; patterned lines are stroked with a dash array or as a
+/// wave of cubic arcs.
+fn draw_underline(content: &mut Content, ul: &UnderlineSpan) {
+    let t = ul.thick;
+    let mid = ul.y + t / 2.0;
+    let dashes: &[f32] = match ul.style {
+        UnderlineStyle::None => return,
+        UnderlineStyle::Single | UnderlineStyle::Words | UnderlineStyle::Thick => {
+            content.rect(ul.x, ul.y, ul.width, t).fill_nonzero();
+            return;
+        }
+        UnderlineStyle::Double => {
+            content.rect(ul.x, ul.y, ul.width, t).fill_nonzero();
+            content
+                .rect(ul.x, ul.y - 2.0 * t, ul.width, t)
+                .fill_nonzero();
+            return;
+        }
+        UnderlineStyle::Wave | UnderlineStyle::WavyHeavy | UnderlineStyle::WavyDouble => {
+            content.save_state();
+            set_stroke_color(content, ul.color);
+            content.set_line_width(t * 0.75);
+            draw_wave(content, ul.x, ul.width, mid, t);
+            if ul.style == UnderlineStyle::WavyDouble {
+                draw_wave(content, ul.x, ul.width, mid - 2.5 * t, t);
+            }
+            content.restore_state();
+            return;
+        }
+        UnderlineStyle::Dotted | UnderlineStyle::DottedHeavy => &[1.0, 1.0],
+        UnderlineStyle::Dash | UnderlineStyle::DashedHeavy => &[4.0, 2.0],
+        UnderlineStyle::DashLong | UnderlineStyle::DashLongHeavy => &[8.0, 3.0],
+        UnderlineStyle::DotDash | UnderlineStyle::DashDotHeavy => &[4.0, 2.0, 1.0, 2.0],
+        UnderlineStyle::DotDotDash | UnderlineStyle::DashDotDotHeavy => {
+            &[4.0, 2.0, 1.0, 2.0, 1.0, 2.0]
+        }
+    };
+    content.save_state();
+    set_stroke_color(content, ul.color);
+    content.set_line_width(t);
+    content.set_dash_pattern(dashes.iter().map(|d| d * t), 0.0);
+    content.move_to(ul.x, mid);
+    content.line_to(ul.x + ul.width, mid);
+    content.stroke();
+    content.restore_state();
+}
+
+/// Strokes the box of a character border around text from `x0` to `x1`,
+/// spanning the same height as a highlight.
+fn draw_char_border(content: &mut Content, border: &CharBorder, x0: f32, x1: f32, y: f32, fs: f32) {
+    let w = border.width_pt;
+    let bottom = y - fs * 0.2;
+    let height = fs * 1.15;
+    content.save_state();
+    set_stroke_color(content, Some(border.color));
+    content.set_line_width(w);
+    match border.style {
+        BorderLineStyle::Dotted => {
+            content.set_dash_pattern([w, w], 0.0);
+        }
+        BorderLineStyle::Dashed => {
+            content.set_dash_pattern([3.0 * w, 2.0 * w], 0.0);
+        }
+        BorderLineStyle::Single | BorderLineStyle::Double => {}
+    }
+    if border.style == BorderLineStyle::Double {
+        // Two lines a line width apart, centred on the single box
+        content.rect(x0 - w, bottom - w, x1 - x0 + 2.0 * w, height + 2.0 * w);
+        content.rect(x0 + w, bottom + w, x1 - x0 - 2.0 * w, height - 2.0 * w);
+    } else {
+        content.rect(x0, bottom, x1 - x0, height);
+    }
+    content.stroke();
+    content.restore_state();
+}
+
+/// Strokes a wave centred on `mid`, alternating arcs of amplitude `t`.
+fn draw_wave(content: &mut Content, x: f32, width: f32, mid: f32, t: f32) {
+    let half = 2.0 * t;
+    let end = x + width;
+    let mut x0 = x;
+    let mut up = true;
+    content.move_to(x0, mid);
+    while x0 < end {
+        let x1 = (x0 + half).min(end);
+        // Both control points at 4/3 amplitude put the arc's peak at `t`
+        let cy = if up {
+            mid + t * 4.0 / 3.0
+        } else {
+            mid - t * 4.0 / 3.0
+        };
+        let cx = (x0 + x1) / 2.0;
+        content.cubic_to(cx, cy, cx, cy, x1, mid);
+        x0 = x1;
+        up = !up;
+    }
+    content.stroke();
+}
+
 pub(super) fn font_metric(
     runs: &[Run],
     seen_fonts: &HashMap<String, FontEntry>,
@@ -964,19 +1682,674 @@ pub(super) fn tallest_run_metrics(
     let mut best_ascent = 0.0f32;
     let mut best_line_h_ratio: Option<f32> = None;
     let mut best_ascender_ratio: Option<f32> = None;
+    let mut lowest_descent = 0.0f32;
     let mut key_buf = String::new();
 
     for run in runs {
         let key = font_key_buf(run, &mut key_buf);
         let entry = seen_fonts.get(key);
         let ar = entry.and_then(|e| e.ascender_ratio).unwrap_or(0.75);
-        let ascent = run.font_size * ar;
+        // Raised text (w:position) needs the line as tall as a font reaching its top
+        let ascent = run.font_size * ar + run.position.max(0.0);
         if ascent > best_ascent {
             best_ascent = ascent;
-            best_font_size = run.font_size;
+            best_font_size = if run.position > 0.0 {
+                ascent / ar
+            } else {
+                run.font_size
+            };
             best_ascender_ratio = entry.and_then(|e| e.ascender_ratio);
             best_line_h_ratio = entry.and_then(|e| e.line_h_ratio);
         }
+        // Lowered text needs room below the baseline
+        if run.position < 0.0 {
+            let lhr = entry.and_then(|e| e.line_h_ratio).unwrap_or(1.2);
+            let descent = run.font_size * (lhr - ar) - run.position;
+            lowest_descent = lowest_descent.max(descent);
+        }
+    }
+    let below = best_line_h_ratio.unwrap_or(1.2) - best_ascender_ratio.unwrap_or(0.75);
+    if below > 0.0 && lowest_descent > best_font_size * below {
+        best_font_size = lowest_descent / below;
     }
     (best_font_size, best_line_h_ratio, best_ascender_ratio)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(pdf_name: &str, chars: &str, fallback: Option<&str>) -> FontEntry {
+        FontEntry {
+            pdf_name: pdf_name.to_string(),
+            font_ref: pdf_writer::Ref::new(1),
+            widths_1000: vec![0.0; 224],
+            line_h_ratio: None,
+            ascender_ratio: None,
+            char_to_gid: Some(chars.chars().zip(1..).collect()),
+            char_widths_1000: Some(chars.chars().map(|c| (c, 500.0)).collect()),
+            kern_pairs: None,
+            synthetic_bold: false,
+            fallback: fallback.map(String::from),
+        }
+    }
+
+    /// Strings shown by a content stream, in stream order.
+    fn shown_strings(content: Content) -> Vec<String> {
+        let bytes = content.finish();
+        let text = String::from_utf8_lossy(&bytes);
+        text.split('(')
+            .skip(1)
+            .filter_map(|s| s.split_once(')'))
+            .map(|(shown, _)| shown.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_bordered_words_share_one_box() {
+        let mut latin = font("F1", "", None);
+        latin.char_to_gid = None;
+        latin.char_widths_1000 = None;
+        latin.widths_1000 = vec![500.0; 224];
+        let seen_fonts = HashMap::from([("Latin".to_string(), latin)]);
+        let run = |text: &str| Run {
+            text: text.to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let key = Run {
+            char_border: Some(CharBorder {
+                width_pt: 0.5,
+                space_pt: 1.0,
+                color: [0, 0, 0],
+                style: BorderLineStyle::Single,
+            }),
+            ..run("Ctrl S")
+        };
+        let runs = [run("Press "), key, run(" now")];
+        let lines = build_paragraph_lines(&runs, &seen_fonts, 450.0, 0.0, &HashMap::new());
+
+        let mut content = Content::new();
+        render_paragraph_lines(
+            &mut content,
+            &lines,
+            &Alignment::Left,
+            72.0,
+            450.0,
+            700.0,
+            12.0,
+            lines.len(),
+            0,
+            &mut Vec::new(),
+            0.0,
+            &seen_fonts,
+        );
+        let ops = String::from_utf8_lossy(&content.finish()).into_owned();
+        // "Ctrl" and "S" (5pt per character) plus the 1pt gap on each side
+        assert_eq!(ops.matches(" re").count(), 1, "{ops}");
+        assert!(ops.contains("101 698 32 11.5 re"), "{ops}");
+    }
+
+    #[test]
+    fn test_text_effects_draw_extra_passes() {
+        let mut latin = font("F1", "", None);
+        latin.char_to_gid = None;
+        latin.char_widths_1000 = None;
+        latin.widths_1000 = vec![500.0; 224];
+        let seen_fonts = HashMap::from([("Latin".to_string(), latin)]);
+        let ops = |effects: TextEffects| {
+            let runs = [Run {
+                text: "Relief".to_string(),
+                font_name: "Latin".to_string(),
+                font_size: 10.0,
+                effects,
+                ..Run::default()
+            }];
+            let lines = build_paragraph_lines(&runs, &seen_fonts, 450.0, 0.0, &HashMap::new());
+            let mut content = Content::new();
+            render_paragraph_lines(
+                &mut content,
+                &lines,
+                &Alignment::Left,
+                72.0,
+                450.0,
+                700.0,
+                12.0,
+                lines.len(),
+                0,
+                &mut Vec::new(),
+                0.0,
+                &seen_fonts,
+            );
+            String::from_utf8_lossy(&content.finish()).into_owned()
+        };
+
+        let plain = ops(TextEffects::default());
+        assert_eq!(plain.matches(" Tj").count(), 1, "{plain}");
+        let shadowed = ops(TextEffects {
+            shadow: true,
+            ..TextEffects::default()
+        });
+        assert_eq!(shadowed.matches(" Tj").count(), 2, "{shadowed}");
+        assert!(shadowed.contains("0.55 g"), "{shadowed}");
+        let outlined = ops(TextEffects {
+            outline: true,
+            ..TextEffects::default()
+        });
+        assert!(outlined.contains("1 Tr"), "{outlined}");
+        assert!(outlined.contains("0 Tr"), "{outlined}");
+    }
+
+    #[test]
+    fn test_tabbed_justified_line_keeps_reading_order() {
+        let mut latin = font("F1", "", None);
+        latin.char_to_gid = None;
+        latin.char_widths_1000 = None;
+        latin.widths_1000 = vec![500.0; 224];
+        let seen_fonts = HashMap::from([("Latin".to_string(), latin)]);
+        let run = |text: &str| Run {
+            text: text.to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let tab = Run {
+            is_tab: true,
+            ..run("")
+        };
+        let source = ["Item one", "1234.50", "Total due now"];
+        let runs = [
+            run(source[0]),
+            tab.clone(),
+            run(source[1]),
+            tab,
+            run(source[2]),
+        ];
+        let tab_stops = [
+            TabStop {
+                position: 150.0,
+                alignment: TabAlignment::Decimal,
+                leader: Some('.'),
+            },
+            TabStop {
+                position: 400.0,
+                alignment: TabAlignment::Right,
+                leader: None,
+            },
+        ];
+        let lines = build_tabbed_line(
+            &runs,
+            &seen_fonts,
+            &tab_stops,
+            DEFAULT_TAB_INTERVAL,
+            0.0,
+            450.0,
+            0.0,
+            &HashMap::new(),
+            false,
+        );
+
+        let mut content = Content::new();
+        render_paragraph_lines(
+            &mut content,
+            &lines,
+            &Alignment::Justify,
+            72.0,
+            450.0,
+            700.0,
+            12.0,
+            lines.len() + 1,
+            0,
+            &mut Vec::new(),
+            0.0,
+            &seen_fonts,
+        );
+        let words: Vec<String> = shown_strings(content)
+            .into_iter()
+            .filter(|s| !s.chars().all(|c| c == '.'))
+            .collect();
+        assert_eq!(words, source.join(" ").split(' ').collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_lines_break_at_soft_hyphens_but_not_at_no_break_characters() {
+        let seen_fonts =
+            HashMap::from([("Latin".to_string(), font("F1", "ab -\u{A0}\u{AD}", None))]);
+        let line_texts = |text: &str, max_width: f32| -> Vec<Vec<String>> {
+            let runs = [Run {
+                text: text.to_string(),
+                font_name: "Latin".to_string(),
+                font_size: 10.0,
+                ..Run::default()
+            }];
+            build_paragraph_lines(&runs, &seen_fonts, max_width, 0.0, &HashMap::new())
+                .iter()
+                .map(|l| l.chunks.iter().map(|c| c.text.clone()).collect())
+                .collect()
+        };
+        // Every character is 5pt wide
+        assert_eq!(
+            line_texts("ab\u{A0}ab ab-ab", 20.0),
+            [["ab\u{A0}ab"], ["ab-ab"]]
+        );
+        assert_eq!(
+            line_texts("ab\u{A0}ab ab\u{AD}ab\u{AD}ab", 45.0),
+            [vec!["ab\u{A0}ab", "ab-"], vec!["abab"]]
+        );
+        assert_eq!(line_texts("ab ab\u{AD}ab", 80.0), [["ab", "abab"]]);
+    }
+
+    #[cfg(feature = "hyphenation")]
+    #[test]
+    fn test_words_split_with_the_dictionary_of_their_language() {
+        let letters: String = ('a'..='z').chain(['-']).collect();
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", &letters, None))]);
+        let hyphenator = Hyphenator::new("en-US", std::iter::empty()).unwrap();
+        let line_texts = |lang: Option<&str>| -> Vec<String> {
+            let runs = [Run {
+                text: "hyphenation".to_string(),
+                font_name: "Latin".to_string(),
+                font_size: 10.0,
+                lang: lang.map(String::from),
+                ..Run::default()
+            }];
+            // 55pt of text on 40pt lines
+            build_indented_paragraph_lines(
+                &runs,
+                &seen_fonts,
+                40.0,
+                0.0,
+                &HashMap::new(),
+                0..0,
+                0.0,
+                0.0,
+                Some(&hyphenator),
+                false,
+            )
+            .iter()
+            .flat_map(|l| l.chunks.iter().map(|c| c.text.clone()))
+            .collect()
+        };
+        let split = line_texts(None);
+        assert!(split.len() > 1 && split[0].ends_with('-'), "{split:?}");
+        assert_eq!(split.concat().replace('-', ""), "hyphenation");
+        assert_eq!(line_texts(Some("tlh")), ["hyphenation"]);
+    }
+
+    #[test]
+    fn test_lines_beside_wrapped_image_leave_room_on_both_sides() {
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", "ab ", None))]);
+        let runs = [Run {
+            text: "ab ab ab ab ab ab ab ab ab ab".to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        }];
+        // Each word is 10pt wide; the second and third lines share 60pt with 15pt
+        // taken each side
+        let lines = build_indented_paragraph_lines(
+            &runs,
+            &seen_fonts,
+            60.0,
+            0.0,
+            &HashMap::new(),
+            1..3,
+            15.0,
+            15.0,
+            None,
+            false,
+        );
+        let words: Vec<usize> = lines.iter().map(|l| l.chunks.len()).collect();
+        assert_eq!(words, [4, 2, 2, 2]);
+        assert_eq!(lines[0].chunks[0].x_offset, 0.0);
+        assert_eq!(lines[1].chunks[0].x_offset, 15.0);
+        assert_eq!(lines[3].chunks[0].x_offset, 0.0);
+        assert_eq!(lines[1].total_width, 55.0);
+    }
+
+    #[test]
+    fn test_shifted_runs_make_room_above_and_below() {
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", "x", None))]);
+        let run = |position: f32| Run {
+            text: "x".to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            position,
+            ..Run::default()
+        };
+        let (plain, _, _) = tallest_run_metrics(&[run(0.0)], &seen_fonts);
+        let (raised, _, _) = tallest_run_metrics(&[run(0.0), run(3.0)], &seen_fonts);
+        let (lowered, _, _) = tallest_run_metrics(&[run(0.0), run(-3.0)], &seen_fonts);
+        assert_eq!(plain, 10.0);
+        // 3pt above a 7.5pt ascent, and 3pt below a 4.5pt descent
+        assert!((raised - 14.0).abs() < 1e-4, "{raised}");
+        assert!((lowered - 10.0 * 7.5 / 4.5).abs() < 1e-4, "{lowered}");
+    }
+
+    #[test]
+    fn test_underline_styles_draw_their_patterns() {
+        let drawn = |val: &str| {
+            let style = UnderlineStyle::from_ooxml(val);
+            let mut content = Content::new();
+            draw_underline(
+                &mut content,
+                &UnderlineSpan {
+                    style,
+                    x: 0.0,
+                    y: 0.0,
+                    width: 50.0,
+                    thick: if style.is_heavy() { 2.0 } else { 1.0 },
+                    color: Some([255, 0, 0]),
+                },
+            );
+            String::from_utf8(content.finish().as_slice().to_vec()).unwrap()
+        };
+        assert_eq!(drawn("single").matches(" re").count(), 1);
+        assert_eq!(drawn("double").matches(" re").count(), 2);
+        assert!(drawn("dash").contains("[4 2] 0 d"), "{}", drawn("dash"));
+        assert!(drawn("dashedHeavy").contains("[8 4] 0 d"));
+        assert!(drawn("dotDotDash").contains("[4 2 1 2 1 2] 0 d"));
+        assert!(drawn("wave").contains(" c\n"));
+        assert!(drawn("none").is_empty());
+        assert_eq!(
+            UnderlineStyle::from_ooxml("unknown"),
+            UnderlineStyle::Single
+        );
+    }
+
+    #[test]
+    fn test_words_underline_skips_the_spaces() {
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", "ab ", None))]);
+        let underlines = |underline: UnderlineStyle| {
+            let runs = [Run {
+                text: "ab ba ab".to_string(),
+                font_name: "Latin".to_string(),
+                font_size: 10.0,
+                underline,
+                ..Run::default()
+            }];
+            let lines = build_paragraph_lines(&runs, &seen_fonts, 450.0, 0.0, &HashMap::new());
+            let mut content = Content::new();
+            render_paragraph_lines(
+                &mut content,
+                &lines,
+                &Alignment::Left,
+                72.0,
+                450.0,
+                700.0,
+                12.0,
+                lines.len(),
+                0,
+                &mut Vec::new(),
+                0.0,
+                &seen_fonts,
+            );
+            let ops = String::from_utf8_lossy(&content.finish()).into_owned();
+            ops.matches(" re").count()
+        };
+        assert_eq!(underlines(UnderlineStyle::Single), 1);
+        assert_eq!(underlines(UnderlineStyle::Words), 3);
+    }
+
+    #[test]
+    fn test_distributed_last_line_spans_the_width() {
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", "ab ", None))]);
+        let runs = [Run {
+            text: "ab ab ab".to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        }];
+        let lines = build_paragraph_lines(&runs, &seen_fonts, 450.0, 0.0, &HashMap::new());
+        // Where each chunk starts, adding up the relative `Td` moves
+        let starts = |alignment: Alignment| {
+            let mut content = Content::new();
+            render_paragraph_lines(
+                &mut content,
+                &lines,
+                &alignment,
+                72.0,
+                450.0,
+                700.0,
+                12.0,
+                lines.len(),
+                0,
+                &mut Vec::new(),
+                0.0,
+                &seen_fonts,
+            );
+            let ops = String::from_utf8_lossy(&content.finish()).into_owned();
+            let mut x = 0.0;
+            ops.lines()
+                .filter_map(|line| {
+                    line.strip_suffix(" Td")?
+                        .split(' ')
+                        .next()?
+                        .parse::<f32>()
+                        .ok()
+                })
+                .map(|dx| {
+                    x += dx;
+                    x
+                })
+                .collect::<Vec<f32>>()
+        };
+        // The only line is also the last: justified text leaves it alone
+        assert_eq!(starts(Alignment::Justify), [72.0, 87.0, 102.0]);
+        assert_eq!(starts(Alignment::Distribute), [72.0, 292.0, 512.0]);
+    }
+
+    #[test]
+    fn test_cjk_text_breaks_between_characters_with_kinsoku() {
+        // No break after an opening bracket or before closing punctuation
+        assert_eq!(
+            cjk_break_units("「東京」です。"),
+            ["「東", "京」", "で", "す。"]
+        );
+        assert_eq!(cjk_break_units("Rust言語"), ["Rust", "言", "語"]);
+        assert_eq!(cjk_break_units("한국어"), ["한국어"]);
+        assert_eq!(cjk_break_units("plain"), ["plain"]);
+    }
+
+    #[test]
+    fn test_tab_past_custom_stops_uses_document_interval() {
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", "ab ", None))]);
+        let run = |text: &str| Run {
+            text: text.to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let tab = Run {
+            is_tab: true,
+            ..run("")
+        };
+        let runs = [run("a"), tab.clone(), run("b"), tab, run("a")];
+        let stops = [TabStop {
+            position: 20.0,
+            alignment: TabAlignment::Left,
+            leader: None,
+        }];
+        let lines = build_tabbed_line(
+            &runs,
+            &seen_fonts,
+            &stops,
+            72.0,
+            0.0,
+            450.0,
+            0.0,
+            &HashMap::new(),
+            false,
+        );
+        let offsets: Vec<f32> = lines[0].chunks.iter().map(|c| c.x_offset).collect();
+        assert_eq!(offsets, [0.0, 20.0, 72.0]);
+    }
+
+    #[test]
+    fn test_character_spacing_widens_right_and_decimal_tab_segments() {
+        let seen_fonts = HashMap::from([("Latin".to_string(), font("F1", "ab.", None))]);
+        let run = |text: &str| Run {
+            text: text.to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            char_spacing: 2.0,
+            ..Run::default()
+        };
+        let tab = Run {
+            is_tab: true,
+            ..run("")
+        };
+        let runs = [tab.clone(), run("ab"), tab, run("ab.a")];
+        let stops = [
+            TabStop {
+                position: 200.0,
+                alignment: TabAlignment::Right,
+                leader: None,
+            },
+            TabStop {
+                position: 300.0,
+                alignment: TabAlignment::Decimal,
+                leader: None,
+            },
+        ];
+        let lines = build_tabbed_line(
+            &runs,
+            &seen_fonts,
+            &stops,
+            72.0,
+            0.0,
+            450.0,
+            0.0,
+            &HashMap::new(),
+            false,
+        );
+        // Two 5pt letters, each followed by 2pt of spacing, end at the stop
+        // or at the decimal point
+        let offsets: Vec<f32> = lines[0].chunks.iter().map(|c| c.x_offset).collect();
+        assert_eq!(offsets, [186.0, 286.0]);
+    }
+
+    #[test]
+    fn test_reordered_line_carries_logical_text() {
+        let seen_fonts = HashMap::from([("Hebrew".to_string(), font("F1", "שלוםעו 2", None))]);
+        let run = Run {
+            text: "שלום עולם 2".to_string(),
+            font_name: "Hebrew".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let lines = build_indented_paragraph_lines(
+            &[run],
+            &seen_fonts,
+            500.0,
+            0.0,
+            &HashMap::new(),
+            0..0,
+            0.0,
+            0.0,
+            None,
+            true,
+        );
+        assert_eq!(lines[0].actual_text.as_deref(), Some("שלום עולם 2"));
+        assert_ne!(lines[0].chunks[0].text, "שלום");
+
+        let mut content = Content::new();
+        render_paragraph_lines(
+            &mut content,
+            &lines,
+            &Alignment::Right,
+            72.0,
+            450.0,
+            700.0,
+            12.0,
+            1,
+            0,
+            &mut Vec::new(),
+            0.0,
+            &seen_fonts,
+        );
+        let bytes = content.finish();
+        assert!(bytes.windows(11).any(|w| w == b"/ActualText"));
+    }
+
+    #[test]
+    fn test_mixed_direction_words_reorder_by_embedding_level() {
+        let seen_fonts = HashMap::from([(
+            "Arabic".to_string(),
+            font("F1", "صدرiPhone15في2024م ", None),
+        )]);
+        // "iPhone 15 came out in 2024 AD", a year with its era letter attached
+        let run = Run {
+            text: "صدر iPhone 15 في 2024م".to_string(),
+            font_name: "Arabic".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let lines = build_indented_paragraph_lines(
+            &[run],
+            &seen_fonts,
+            500.0,
+            0.0,
+            &HashMap::new(),
+            0..0,
+            0.0,
+            0.0,
+            None,
+            true,
+        );
+        let chunks: Vec<(&str, f32)> = lines[0]
+            .chunks
+            .iter()
+            .map(|c| (c.text.as_str(), c.x_offset))
+            .collect();
+        // Numbers and the Latin name keep their order inside the right-to-left
+        // line, and the era letter stays on the year's left without a gap
+        assert_eq!(
+            chunks,
+            [
+                ("م", 0.0),
+                ("2024", 5.0),
+                ("يف", 30.0),
+                ("iPhone", 45.0),
+                ("15", 80.0),
+                ("ردص", 95.0),
+            ]
+        );
+        assert_eq!(
+            lines[0].actual_text.as_deref(),
+            Some("صدر iPhone 15 في 2024م")
+        );
+    }
+
+    #[test]
+    fn test_missing_glyphs_use_fallback_font() {
+        let seen_fonts = HashMap::from([
+            (
+                "Latin".to_string(),
+                font("F1", "Hi! ", Some("Emoji#fallback")),
+            ),
+            ("Emoji#fallback".to_string(), font("F2", "\u{1F600} ", None)),
+        ]);
+        let run = Run {
+            text: "Hi \u{1F600}!".to_string(),
+            font_name: "Latin".to_string(),
+            font_size: 10.0,
+            ..Run::default()
+        };
+        let lines = build_paragraph_lines(&[run], &seen_fonts, 500.0, 0.0, &HashMap::new());
+        let chunks: Vec<(&str, &str, f32)> = lines[0]
+            .chunks
+            .iter()
+            .map(|c| (c.text.as_str(), c.pdf_font.as_str(), c.x_offset))
+            .collect();
+        assert_eq!(
+            chunks,
+            [
+                ("Hi", "F1", 0.0),
+                ("\u{1F600}", "F2", 15.0),
+                ("!", "F1", 20.0)
+            ]
+        );
+    }
+}
@@ -3,42 +3,69 @@ mod charts;
 mod charts_radial;
 mod footnotes;
 mod header_footer;
+mod hyphenate;
 mod layout;
 mod smartart;
 mod table;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use pdf_writer::{Content, Filter, Name, Pdf, Rect, Ref, Str};
+use pdf_writer::{Chunk, Content, Date, Filter, Name, Pdf, Rect, Ref, Str, TextStr};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
+use crate::ConversionOptions;
 use crate::error::Error;
 use crate::fonts::{
-    FontEntry, encode_as_gids, font_key, font_key_buf, register_font, to_winansi_bytes,
+    FontEntry, FontRefs, encode_as_gids, font_key, font_key_buf, register_font, split_font_key,
+    to_winansi_bytes,
 };
 use crate::model::{
-    Alignment, Block, ConnectorShape, ConnectorType, Document, EmbeddedImage, FieldCode,
-    FloatingImage, HRelativeFrom, HeaderFooter, HorizontalPosition, ImageFormat, LineSpacing,
-    Paragraph, ParagraphBorder, ParagraphBorders, Run, SectionBreakType, SectionProperties,
-    ShapeFill, ShapeGeometry, Table, TextAnchor, Textbox, VRelativeFrom, VerticalPosition,
-    WrapType,
+    Alignment, Block, ConnectorShape, ConnectorType, CoreProperties, Document, DropCap,
+    EmbeddedImage, FieldCode, FloatingImage, HRelativeFrom, HeaderFooter, HorizontalPosition,
+    ImageFormat, LabelSuffix, LineNumberRestart, LineNumbering, LineSpacing, NoteRestart,
+    PageVAlign, Paragraph, ParagraphBorder, ParagraphBorders, Run, SectionBreakType,
+    SectionProperties, ShapeFill, ShapeGeometry, Table, TextAnchor, Textbox, VRelativeFrom,
+    VerticalPosition, Watermark, WrapSide, WrapType,
 };
 
-use footnotes::{compute_footnote_height, render_page_footnotes};
+use footnotes::{
+    compute_footnote_height, number_footnotes, number_footnotes_per_page, render_endnotes,
+    render_page_footnotes,
+};
 use header_footer::{
-    compute_effective_margin_bottom, effective_slot_top, hf_paragraphs, render_header_footer,
+    PageFields, StyleRefValues, compute_effective_margin_bottom, effective_slot_top, hf_paragraphs,
+    render_header_footer, render_page_borders, render_watermark,
 };
+use hyphenate::Hyphenator;
 use layout::{
-    LinkAnnotation, build_paragraph_lines, build_tabbed_line, is_text_empty,
-    render_paragraph_lines, tallest_run_metrics,
+    LinkAnnotation, LinkTarget, TextLine, build_indented_paragraph_lines, build_tabbed_line,
+    is_text_empty, line_y_offsets, next_default_tab, render_paragraph_lines, tallest_run_metrics,
 };
 use smartart::draw_shape_path;
 use table::render_table;
 
+/// Side of the note icon drawn for a review comment.
+const COMMENT_ICON_SIZE: f32 = 12.0;
+
+/// Layout passes spent settling a page total shown in body text.
+const MAX_PAGE_TOTAL_PASSES: usize = 3;
+
 pub(super) struct RenderContext<'a> {
     pub(super) fonts: &'a HashMap<String, FontEntry>,
     pub(super) doc_line_spacing: LineSpacing,
+    /// Even pages use the `even` header and footer (`w:evenAndOddHeaders`)
+    pub(super) even_and_odd_headers: bool,
+    /// Interval of the implicit tab stops (`w:defaultTabStop`)
+    pub(super) default_tab_stop: f32,
     /// Image names for inline images in table cells, keyed by Arc data pointer address.
     pub(super) table_cell_image_names: &'a HashMap<usize, String>,
+    /// Formatted page number of each bookmark, known once a layout pass has run.
+    pub(super) bookmark_pages: &'a HashMap<String, String>,
+    /// Page total from an earlier layout pass, for NUMPAGES in body text.
+    pub(super) total_pages: Option<usize>,
+    /// Set when words may be hyphenated at line ends
+    pub(super) hyphenator: Option<&'a Hyphenator>,
 }
 
 pub(super) struct GradientSpec {
@@ -133,6 +160,72 @@ fn resolve_fi_x(
     )
 }
 
+/// Room a square-wrapped image anchored to a paragraph takes from its text.
+struct SquareWrap {
+    /// Lines beside the image, counted from the top of the paragraph
+    lines: std::ops::Range<usize>,
+    indent_left: f32,
+    indent_right: f32,
+    /// Image bottom below the top of the paragraph
+    bottom: f32,
+}
+
+/// Square wrap of the first paragraph-anchored image text flows around,
+/// narrowing the lines from the image's top to its bottom. Text takes one
+/// side of the image: the one `wrapText` names, else the wider one (also for
+/// `bothSides`, which Word fills on both), and stays the image's wrap
+/// distances away from it. Images nearly as wide as the text push it below
+/// instead.
+#[allow(clippy::too_many_arguments)]
+fn square_wrap(
+    para: &Paragraph,
+    sp: &SectionProperties,
+    col_x: f32,
+    col_w: f32,
+    text_width: f32,
+    para_text_x: f32,
+    para_text_width: f32,
+    line_h: f32,
+) -> Option<SquareWrap> {
+    para.floating_images.iter().find_map(|fi| {
+        let wraps = matches!(
+            fi.wrap_type,
+            WrapType::Square | WrapType::Tight | WrapType::Through
+        );
+        if !wraps || fi.behind_doc || fi.image.display_width >= text_width * 0.9 {
+            return None;
+        }
+        let (VRelativeFrom::Paragraph, VerticalPosition::Offset(top)) =
+            (fi.v_relative_from, fi.v_position)
+        else {
+            return None;
+        };
+        let x = resolve_fi_x(fi, sp, col_x, col_w, text_width);
+        let left_room = (x - fi.dist_left - para_text_x).max(0.0);
+        let right_room =
+            (para_text_x + para_text_width - x - fi.image.display_width - fi.dist_right).max(0.0);
+        let text_left = match fi.wrap_side {
+            WrapSide::Left => true,
+            WrapSide::Right => false,
+            WrapSide::BothSides | WrapSide::Largest => left_room >= right_room,
+        };
+        let top = top.max(0.0);
+        let bottom = top + fi.image.display_height + fi.dist_bottom;
+        let (indent_left, indent_right) = if text_left {
+            (0.0, para_text_width - left_room)
+        } else {
+            (para_text_width - right_room, 0.0)
+        };
+        Some(SquareWrap {
+            lines: ((top - fi.dist_top).max(0.0) / line_h.max(1.0)).floor() as usize
+                ..(bottom / line_h.max(1.0)).ceil() as usize,
+            indent_left,
+            indent_right,
+            bottom,
+        })
+    })
+}
+
 fn resolve_fi_y_top(fi: &FloatingImage, sp: &SectionProperties, slot_top: f32) -> f32 {
     let img = &fi.image;
     match fi.v_position {
@@ -241,7 +334,7 @@ fn render_single_textbox(
                 let tp_ls = tp.line_spacing.unwrap_or(ctx.doc_line_spacing);
                 let tp_text_w = (content_w - tp.indent_left - tp.indent_right).max(1.0);
                 let text_hanging = if !tp.list_label.is_empty() {
-                    0.0
+                    list_text_hanging(tp, ctx)
                 } else if tp.indent_hanging > 0.0 {
                     tp.indent_hanging
                 } else {
@@ -250,12 +343,28 @@ fn render_single_textbox(
                 let has_tabs = tp.runs.iter().any(|r| r.is_tab);
                 let lines = if has_tabs {
                     build_tabbed_line(
-                        &tp.runs, ctx.fonts, &tp.tab_stops, tp.indent_left,
-                        tp_text_w, text_hanging, &empty_inline_imgs_pre,
+                        &tp.runs,
+                        ctx.fonts,
+                        &tp.tab_stops,
+                        ctx.default_tab_stop,
+                        tp.indent_left,
+                        tp_text_w,
+                        text_hanging,
+                        &empty_inline_imgs_pre,
+                        tp.rtl,
                     )
                 } else {
-                    build_paragraph_lines(
-                        &tp.runs, ctx.fonts, tp_text_w, text_hanging, &empty_inline_imgs_pre,
+                    build_indented_paragraph_lines(
+                        &tp.runs,
+                        ctx.fonts,
+                        tp_text_w,
+                        text_hanging,
+                        &empty_inline_imgs_pre,
+                        0..0,
+                        0.0,
+                        0.0,
+                        ctx.hyphenator.filter(|_| !tp.suppress_auto_hyphens),
+                        tp.rtl,
                     )
                 };
                 let (fs, lhr, _) = tallest_run_metrics(&tp.runs, ctx.fonts);
@@ -280,7 +389,7 @@ fn render_single_textbox(
         let tp_text_x = content_x + tp.indent_left;
         let tp_text_w = (content_w - tp.indent_left - tp.indent_right).max(1.0);
         let text_hanging = if !tp.list_label.is_empty() {
-            0.0
+            list_text_hanging(tp, ctx)
         } else if tp.indent_hanging > 0.0 {
             tp.indent_hanging
         } else {
@@ -292,18 +401,25 @@ fn render_single_textbox(
                 &tp.runs,
                 ctx.fonts,
                 &tp.tab_stops,
+                ctx.default_tab_stop,
                 tp.indent_left,
                 tp_text_w,
                 text_hanging,
                 &empty_inline_imgs,
+                tp.rtl,
             )
         } else {
-            build_paragraph_lines(
+            build_indented_paragraph_lines(
                 &tp.runs,
                 ctx.fonts,
                 tp_text_w,
                 text_hanging,
                 &empty_inline_imgs,
+                0..0,
+                0.0,
+                0.0,
+                ctx.hyphenator.filter(|_| !tp.suppress_auto_hyphens),
+                tp.rtl,
             )
         };
         if tb_lines.is_empty() {
@@ -342,9 +458,11 @@ fn render_single_textbox(
     }
 }
 
-fn render_floating_images(
+/// Positions a paragraph's floating images on the current page. They are
+/// drawn when the page is flushed, so each layer is stacked page-wide.
+#[allow(clippy::too_many_arguments)]
+fn place_floating_images(
     floating_images: &[FloatingImage],
-    behind_doc: bool,
     global_block_idx: usize,
     pdf_names: &HashMap<(usize, usize), String>,
     sp: &SectionProperties,
@@ -352,28 +470,30 @@ fn render_floating_images(
     col_w: f32,
     text_width: f32,
     slot_top: f32,
-    content: &mut Content,
+    pb: &mut PageBuilder,
 ) {
     for (fi_idx, fi) in floating_images.iter().enumerate() {
-        if fi.behind_doc != behind_doc {
-            continue;
-        }
         if let Some(pdf_name) = pdf_names.get(&(global_block_idx, fi_idx)) {
             let img = &fi.image;
             let fi_x = resolve_fi_x(fi, sp, col_x, col_w, text_width);
             let fi_y_top = resolve_fi_y_top(fi, sp, slot_top);
             let fi_y_bottom = fi_y_top - img.display_height;
-            content.save_state();
-            content.transform([
-                img.display_width,
-                0.0,
-                0.0,
-                img.display_height,
-                fi_x,
-                fi_y_bottom,
-            ]);
-            content.x_object(Name(pdf_name.as_bytes()));
-            content.restore_state();
+            let placed = PlacedImage {
+                z_order: fi.z_order,
+                pdf_name: pdf_name.clone(),
+                rect: [fi_x, fi_y_bottom, img.display_width, img.display_height],
+            };
+            if fi.behind_doc {
+                pb.behind_images.push(placed);
+            } else {
+                pb.front_images.push(placed);
+            }
+            if let Some(url) = &img.hyperlink_url {
+                pb.links.push(LinkAnnotation {
+                    rect: Rect::new(fi_x, fi_y_bottom, fi_x + img.display_width, fi_y_top),
+                    target: LinkTarget::Url(url.clone()),
+                });
+            }
         }
     }
 }
@@ -560,6 +680,9 @@ fn resolve_line_h(ls: LineSpacing, font_size: f32, tallest_lhr: Option<f32>) ->
 
 fn para_runs_with_textboxes(para: &Paragraph) -> Vec<&Run> {
     let mut out: Vec<&Run> = para.runs.iter().collect();
+    if let Some(dc) = &para.drop_cap {
+        out.extend(&dc.runs);
+    }
     for tb in &para.textboxes {
         for tp in &tb.paragraphs {
             out.extend(para_runs_with_textboxes(tp));
@@ -578,25 +701,77 @@ fn collect_paras(para: &Paragraph) -> Vec<&Paragraph> {
     out
 }
 
-struct EmbeddedImages {
+/// Image XObjects planned during layout. Pictures are only decoded and
+/// compressed by `write_image_xobjects`, once the pages are assembled.
+#[derive(Default)]
+struct ImageXObjects<'a> {
+    /// Resource names and the objects they draw
+    placed: Vec<(String, Ref)>,
+    /// Image XObject per distinct picture file, so a reused picture is written once
+    by_data: HashMap<&'a [u8], Ref>,
+    /// Resource name of each image XObject drawn without a crop/rotation wrapper
+    plain_names: HashMap<Ref, String>,
+    /// Wrapped placements of each image XObject, reused when one recurs unchanged
+    wrapped_names: HashMap<Ref, Vec<(&'a EmbeddedImage, String)>>,
+    /// Pictures still to be written, with their XObject ids
+    pending: Vec<(&'a EmbeddedImage, Ref)>,
+}
+
+struct EmbeddedImages<'a> {
     image_pdf_names: HashMap<usize, String>,
     inline_image_pdf_names: HashMap<(usize, usize), String>,
     floating_image_pdf_names: HashMap<(usize, usize), String>,
-    image_xobjects: Vec<(String, Ref)>,
+    image_xobjects: ImageXObjects<'a>,
     hf_image_names: HashMap<(usize, u8, usize), String>,
     hf_inline_image_names: HashMap<(usize, u8, usize, usize), String>,
     hf_floating_image_names: HashMap<(usize, u8, usize, usize), String>,
+    /// Picture watermarks by (section, header type)
+    hf_watermark_names: HashMap<(usize, u8), String>,
     /// Images in table cell paragraphs, keyed by Arc data pointer address.
     table_cell_image_names: HashMap<usize, String>,
 }
 
+/// A heading placed in the body, for the PDF document outline.
+struct OutlineEntry {
+    level: u8,
+    title: String,
+    page_idx: usize,
+    top: f32,
+}
+
+/// A floating image waiting for its page to be complete.
+struct PlacedImage {
+    z_order: u32,
+    pdf_name: String,
+    /// Left, bottom, width and height
+    rect: [f32; 4],
+}
+
+/// Draws one layer of a page's floating images, lower `relativeHeight`
+/// first so higher ones cover them; ties keep document order.
+fn draw_placed_images(content: &mut Content, mut images: Vec<PlacedImage>) {
+    images.sort_by_key(|img| img.z_order);
+    for img in images {
+        let [x, y, w, h] = img.rect;
+        content.save_state();
+        content.transform([w, 0.0, 0.0, h, x, y]);
+        content.x_object(Name(img.pdf_name.as_bytes()));
+        content.restore_state();
+    }
+}
+
 pub(super) struct PageBuilder {
     // Current page state
     pub(super) content: Content,
+    /// Floating images behind and in front of the page's text
+    behind_images: Vec<PlacedImage>,
+    front_images: Vec<PlacedImage>,
     pub(super) links: Vec<LinkAnnotation>,
     pub(super) footnote_ids: Vec<u32>,
     pub(super) alpha_states: HashSet<u8>,
     pub(super) gradient_specs: Vec<GradientSpec>,
+    /// Body line baselines with the left edge of their column, for line numbering
+    line_marks: Vec<(f32, f32)>,
 
     // Cross-page running state
     styleref_running: HashMap<String, String>,
@@ -605,42 +780,73 @@ pub(super) struct PageBuilder {
     // Layout position state
     pub(super) slot_top: f32,
     pub(super) is_first_page_of_section: bool,
+    /// The page being flushed ran out of room
+    page_full: bool,
 
     // Accumulated pages
     all_contents: Vec<Content>,
+    /// Behind-text images of each page, drawn before its body content
+    all_behind_contents: Vec<Content>,
     all_links: Vec<Vec<LinkAnnotation>>,
     all_footnote_ids: Vec<Vec<u32>>,
     all_alpha_states: Vec<HashSet<u8>>,
     all_gradient_specs: Vec<Vec<GradientSpec>>,
+    all_line_marks: Vec<Vec<(f32, f32)>>,
+    /// Where the text of each page ends, for pages that didn't run out of room
+    all_content_bottoms: Vec<Option<f32>>,
     page_section_indices: Vec<(usize, bool)>,
     all_styleref: Vec<HashMap<String, String>>,
     all_first_styleref: Vec<HashMap<String, String>>,
+    /// Page index and slot top where each bookmark first lands.
+    bookmark_positions: HashMap<String, (usize, f32)>,
+    /// Headings in document order, with where they land.
+    outline: Vec<OutlineEntry>,
+    /// `pgNumType` start of each section
+    section_page_starts: Vec<Option<u32>>,
 }
 
 impl PageBuilder {
-    fn new(slot_top: f32) -> Self {
+    fn new(slot_top: f32, doc: &Document) -> Self {
         PageBuilder {
             content: Content::new(),
+            behind_images: Vec::new(),
+            front_images: Vec::new(),
             links: Vec::new(),
             footnote_ids: Vec::new(),
             alpha_states: HashSet::new(),
             gradient_specs: Vec::new(),
+            line_marks: Vec::new(),
             styleref_running: HashMap::new(),
             styleref_page_first: HashMap::new(),
             slot_top,
             is_first_page_of_section: true,
+            page_full: false,
             all_contents: Vec::new(),
+            all_behind_contents: Vec::new(),
             all_links: Vec::new(),
             all_footnote_ids: Vec::new(),
             all_alpha_states: Vec::new(),
             all_gradient_specs: Vec::new(),
+            all_line_marks: Vec::new(),
+            all_content_bottoms: Vec::new(),
             page_section_indices: Vec::new(),
             all_styleref: Vec::new(),
             all_first_styleref: Vec::new(),
+            bookmark_positions: HashMap::new(),
+            outline: Vec::new(),
+            section_page_starts: doc
+                .sections
+                .iter()
+                .map(|s| s.properties.page_num_start)
+                .collect(),
         }
     }
 
     pub(super) fn flush_page(&mut self, sect_idx: usize) {
+        draw_placed_images(&mut self.content, std::mem::take(&mut self.front_images));
+        let mut behind = Content::new();
+        draw_placed_images(&mut behind, std::mem::take(&mut self.behind_images));
+        self.all_behind_contents.push(behind);
         self.all_contents
             .push(std::mem::replace(&mut self.content, Content::new()));
         self.all_links.push(std::mem::take(&mut self.links));
@@ -650,6 +856,11 @@ impl PageBuilder {
             .push(std::mem::take(&mut self.alpha_states));
         self.all_gradient_specs
             .push(std::mem::take(&mut self.gradient_specs));
+        self.all_line_marks
+            .push(std::mem::take(&mut self.line_marks));
+        let page_full = std::mem::take(&mut self.page_full);
+        self.all_content_bottoms
+            .push((!page_full).then_some(self.slot_top));
         self.page_section_indices
             .push((sect_idx, self.is_first_page_of_section));
         self.all_styleref.push(self.styleref_running.clone());
@@ -657,22 +868,175 @@ impl PageBuilder {
             .push(std::mem::take(&mut self.styleref_page_first));
     }
 
+    /// Flushes a page whose content overflows onto the next one.
+    pub(super) fn flush_full_page(&mut self, sect_idx: usize) {
+        self.page_full = true;
+        self.flush_page(sect_idx);
+    }
+
+    /// Moves the content of pages in `w:vAlign` center/bottom sections down
+    /// by their leftover space, returning each page's shift. Pages that ran
+    /// out of room keep their place.
+    fn align_pages_vertically(&mut self, doc: &Document, ctx: &RenderContext) -> Vec<f32> {
+        let mut shifts = vec![0.0; self.all_contents.len()];
+        for (page_idx, shift) in shifts.iter_mut().enumerate() {
+            let (si, is_first) = self.page_section_indices[page_idx];
+            let sp = &doc.sections[si].properties;
+            let Some(bottom) = self.all_content_bottoms[page_idx] else {
+                continue;
+            };
+            let even = self.display_page_number(page_idx).is_multiple_of(2);
+            let leftover = bottom - compute_effective_margin_bottom(sp, is_first, even, ctx);
+            *shift = match sp.v_align {
+                PageVAlign::Center => leftover / 2.0,
+                PageVAlign::Bottom => leftover,
+                PageVAlign::Top | PageVAlign::Both => 0.0,
+            }
+            .max(0.0);
+            if *shift == 0.0 {
+                continue;
+            }
+            for link in &mut self.all_links[page_idx] {
+                link.rect.y1 -= *shift;
+                link.rect.y2 -= *shift;
+            }
+            for mark in &mut self.all_line_marks[page_idx] {
+                mark.1 -= *shift;
+            }
+            for (pi, top) in self.bookmark_positions.values_mut() {
+                if *pi == page_idx {
+                    *top -= *shift;
+                }
+            }
+            for entry in &mut self.outline {
+                if entry.page_idx == page_idx {
+                    entry.top -= *shift;
+                }
+            }
+        }
+        shifts
+    }
+
+    /// With mirror margins, moves the content of even pages right or left so
+    /// their left and right margins swap, returning each page's shift. Layout
+    /// is unaffected since the text width stays the same.
+    fn mirror_pages(&mut self, doc: &Document) -> Vec<f32> {
+        let mut shifts = vec![0.0; self.all_contents.len()];
+        if !doc.mirror_margins {
+            return shifts;
+        }
+        for (page_idx, shift) in shifts.iter_mut().enumerate() {
+            if !self.display_page_number(page_idx).is_multiple_of(2) {
+                continue;
+            }
+            let sp = &doc.sections[self.page_section_indices[page_idx].0].properties;
+            *shift = sp.margin_right - sp.margin_left;
+            for link in &mut self.all_links[page_idx] {
+                link.rect.x1 += *shift;
+                link.rect.x2 += *shift;
+            }
+        }
+        shifts
+    }
+
     fn push_blank_page(&mut self, sect_idx: usize) {
         self.all_contents.push(Content::new());
+        self.all_behind_contents.push(Content::new());
         self.all_links.push(Vec::new());
         self.all_footnote_ids.push(Vec::new());
         self.all_alpha_states.push(HashSet::new());
         self.all_gradient_specs.push(Vec::new());
+        self.all_line_marks.push(Vec::new());
+        self.all_content_bottoms.push(None);
         self.page_section_indices.push((sect_idx, false));
         self.all_styleref.push(self.styleref_running.clone());
         self.all_first_styleref
             .push(std::mem::take(&mut self.styleref_page_first));
     }
 
+    fn record_lines(
+        &mut self,
+        lines: &[TextLine],
+        first_baseline_y: f32,
+        line_pitch: f32,
+        col_x: f32,
+    ) {
+        for offset in line_y_offsets(lines, line_pitch) {
+            self.line_marks.push((col_x, first_baseline_y - offset));
+        }
+    }
+
     fn page_count(&self) -> usize {
         self.all_contents.len()
     }
 
+    fn record_bookmarks(&mut self, para: &Paragraph) {
+        for name in &para.bookmarks {
+            self.bookmark_positions
+                .entry(name.clone())
+                .or_insert((self.all_contents.len(), self.slot_top));
+        }
+        if let Some(level) = para.outline_level {
+            let mut title = para.list_label.clone();
+            title.push(' ');
+            title.extend(
+                para.runs
+                    .iter()
+                    .filter(|r| !r.vanish && r.field_code.is_none())
+                    .map(|r| r.text.as_str()),
+            );
+            let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !title.is_empty() {
+                self.outline.push(OutlineEntry {
+                    level,
+                    title,
+                    page_idx: self.all_contents.len(),
+                    top: self.slot_top,
+                });
+            }
+        }
+    }
+
+    /// Page number of page `page_idx` in section `sect_idx`: the section's
+    /// `pgNumType` start on its first page, else one more than the page before.
+    /// `page_idx` may be the page being laid out.
+    fn page_number(&self, page_idx: usize, sect_idx: usize) -> usize {
+        let sections = self.page_section_indices[..page_idx]
+            .iter()
+            .map(|&(si, _)| si)
+            .chain(std::iter::once(sect_idx));
+        let mut number = 0;
+        let mut prev_section = None;
+        for si in sections {
+            number = match self.section_page_starts[si] {
+                Some(start) if prev_section != Some(si) => start as usize,
+                _ => number + 1,
+            };
+            prev_section = Some(si);
+        }
+        number
+    }
+
+    /// Page number as printed on the page, honoring per-section restarts.
+    fn display_page_number(&self, page_idx: usize) -> usize {
+        self.page_number(page_idx, self.page_section_indices[page_idx].0)
+    }
+
+    /// Page number as printed on the page, in its section's `pgNumType` format.
+    fn page_number_text(&self, doc: &Document, page_idx: usize) -> String {
+        let (si, _) = self.page_section_indices[page_idx];
+        doc.sections[si]
+            .properties
+            .page_num_format
+            .format(self.display_page_number(page_idx) as u32)
+    }
+
+    /// Whether the page being laid out in section `sect_idx` gets an even page number.
+    pub(super) fn is_even_page(&self, sect_idx: usize) -> bool {
+        self.page_number(self.page_count(), sect_idx)
+            .is_multiple_of(2)
+    }
+
     fn is_at_page_top(&self, sp: &SectionProperties) -> bool {
         (self.slot_top - (sp.page_height - sp.margin_top)).abs() < 1.0
     }
@@ -689,26 +1053,94 @@ impl PageBuilder {
     ) {
         if *current_col + 1 < col_count {
             *current_col += 1;
-            self.slot_top = effective_slot_top(sp, false, ctx);
+            self.slot_top = effective_slot_top(sp, false, self.is_even_page(sect_idx), ctx);
         } else {
             *current_col = 0;
-            self.flush_page(sect_idx);
-            self.slot_top = effective_slot_top(sp, false, ctx);
-            *effective_margin_bottom = compute_effective_margin_bottom(sp, false, ctx);
+            self.flush_full_page(sect_idx);
+            let even = self.is_even_page(sect_idx);
+            self.slot_top = effective_slot_top(sp, false, even, ctx);
+            *effective_margin_bottom = compute_effective_margin_bottom(sp, false, even, ctx);
             self.is_first_page_of_section = false;
         }
     }
 }
 
-fn embed_single_image(
-    img: &EmbeddedImage,
-    image_xobjects: &mut Vec<(String, Ref)>,
+/// Reserves the XObject for an image and returns its resource name. Identical
+/// picture data shares one image XObject; its pixels are written later.
+fn embed_single_image<'a>(
+    img: &'a EmbeddedImage,
+    images: &mut ImageXObjects<'a>,
     pdf: &mut Pdf,
     alloc: &mut impl FnMut() -> Ref,
 ) -> String {
-    let xobj_ref = alloc();
-    let pdf_name = format!("Im{}", image_xobjects.len() + 1);
+    let xobj_ref = match images.by_data.get(img.data.as_slice()) {
+        Some(&xobj_ref) => xobj_ref,
+        None => {
+            let xobj_ref = alloc();
+            images.by_data.insert(img.data.as_slice(), xobj_ref);
+            images.pending.push((img, xobj_ref));
+            xobj_ref
+        }
+    };
+
+    let plain = img.crop.is_empty() && img.rotation == 0.0 && img.alt_text.is_none();
+    let existing = if plain {
+        images.plain_names.get(&xobj_ref)
+    } else {
+        images
+            .wrapped_names
+            .get(&xobj_ref)
+            .and_then(|placements| placements.iter().find(|(p, _)| same_placement(p, img)))
+            .map(|(_, name)| name)
+    };
+    if let Some(name) = existing {
+        return name.clone();
+    }
+    let pdf_name = format!("Im{}", images.placed.len() + 1);
+    let placed_ref = if plain {
+        images.plain_names.insert(xobj_ref, pdf_name.clone());
+        xobj_ref
+    } else {
+        images
+            .wrapped_names
+            .entry(xobj_ref)
+            .or_default()
+            .push((img, pdf_name.clone()));
+        wrap_placed_image(img, xobj_ref, pdf, alloc)
+    };
+    images.placed.push((pdf_name.clone(), placed_ref));
+    pdf_name
+}
+
+/// Whether two placements of a picture produce the same `wrap_placed_image` form.
+fn same_placement(a: &EmbeddedImage, b: &EmbeddedImage) -> bool {
+    a.crop == b.crop
+        && a.rotation == b.rotation
+        && a.display_width == b.display_width
+        && a.display_height == b.display_height
+        && a.alt_text == b.alt_text
+}
+
+/// Decodes and writes the planned image XObjects one at a time, so only one
+/// decoded picture is held in memory, zlib-compressing rasters at `level`.
+fn write_image_xobjects(
+    pending: Vec<(&EmbeddedImage, Ref)>,
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+    level: u8,
+) {
+    for (img, xobj_ref) in pending {
+        write_image_xobject(img, xobj_ref, pdf, alloc, level);
+    }
+}
 
+fn write_image_xobject(
+    img: &EmbeddedImage,
+    xobj_ref: Ref,
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+    level: u8,
+) {
     match img.format {
         ImageFormat::Jpeg => {
             let mut xobj = pdf.image_xobject(xobj_ref, &*img.data);
@@ -720,41 +1152,54 @@ fn embed_single_image(
                 4 => xobj.color_space().device_cmyk(),
                 _ => xobj.color_space().device_rgb(),
             };
+            if img.jpeg_adobe_inverted {
+                xobj.decode([1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+            }
             xobj.bits_per_component(8);
             xobj.interpolate(true);
         }
-        ImageFormat::Png => {
-            let cursor = std::io::Cursor::new(img.data.as_slice());
-            let reader = image::ImageReader::with_format(
-                std::io::BufReader::new(cursor),
-                image::ImageFormat::Png,
+        ImageFormat::Emf | ImageFormat::Wmf => {
+            // No metafile rasterizer: a light gray pixel stretched to the display box
+            // keeps the layout and marks where the graphic belongs.
+            log::warn!(
+                "{:?} metafile not rasterized — drawing placeholder box",
+                img.format
             );
-            let decoded = match reader.decode() {
+            let mut xobj = pdf.image_xobject(xobj_ref, &[217]);
+            xobj.width(1);
+            xobj.height(1);
+            xobj.color_space().device_gray();
+            xobj.bits_per_component(8);
+        }
+        ImageFormat::Png | ImageFormat::Gif | ImageFormat::Bmp | ImageFormat::Tiff => {
+            let decoded = match decode_raster(&img.data, img.format) {
                 Ok(d) => d,
                 Err(e) => {
-                    log::warn!("PNG decode failed: {e} — writing 1x1 placeholder");
+                    log::warn!(
+                        "{:?} decode failed: {e} — writing 1x1 placeholder",
+                        img.format
+                    );
                     let mut xobj = pdf.image_xobject(xobj_ref, &[255, 255, 255]);
                     xobj.width(1);
                     xobj.height(1);
                     xobj.color_space().device_rgb();
                     xobj.bits_per_component(8);
-                    image_xobjects.push((pdf_name.clone(), xobj_ref));
-                    return pdf_name;
+                    return;
                 }
             };
-            let rgba: image::RgbaImage = decoded.to_rgba8();
-            let (w, h) = (rgba.width(), rgba.height());
-            let has_alpha = rgba.pixels().any(|p| p.0[3] < 255);
-
-            let rgb_data: Vec<u8> = rgba
-                .pixels()
-                .flat_map(|p| [p.0[0], p.0[1], p.0[2]])
-                .collect();
-            let compressed_rgb = miniz_oxide::deflate::compress_to_vec_zlib(&rgb_data, 6);
-
-            let smask_ref = if has_alpha {
-                let alpha_data: Vec<u8> = rgba.pixels().map(|p| p.0[3]).collect();
-                let compressed_alpha = miniz_oxide::deflate::compress_to_vec_zlib(&alpha_data, 6);
+            let (w, h) = (decoded.width(), decoded.height());
+            // Gray sources stay DeviceGray; 16-bit channels are reduced to 8 bits
+            let gray = !decoded.color().has_color();
+            let (color_data, alpha_data) = if gray {
+                split_alpha(decoded.to_luma_alpha8().as_raw(), 2)
+            } else {
+                split_alpha(decoded.to_rgba8().as_raw(), 4)
+            };
+            let compressed_color = miniz_oxide::deflate::compress_to_vec_zlib(&color_data, level);
+
+            let smask_ref = if let Some(alpha_data) = alpha_data {
+                let compressed_alpha =
+                    miniz_oxide::deflate::compress_to_vec_zlib(&alpha_data, level);
                 let mask_ref = alloc();
                 let mut mask = pdf.image_xobject(mask_ref, &compressed_alpha);
                 mask.filter(Filter::FlateDecode);
@@ -767,11 +1212,15 @@ fn embed_single_image(
                 None
             };
 
-            let mut xobj = pdf.image_xobject(xobj_ref, &compressed_rgb);
+            let mut xobj = pdf.image_xobject(xobj_ref, &compressed_color);
             xobj.filter(Filter::FlateDecode);
             xobj.width(w as i32);
             xobj.height(h as i32);
-            xobj.color_space().device_rgb();
+            if gray {
+                xobj.color_space().device_gray();
+            } else {
+                xobj.color_space().device_rgb();
+            }
             xobj.bits_per_component(8);
             xobj.interpolate(true);
             if let Some(mask_ref) = smask_ref {
@@ -779,9 +1228,121 @@ fn embed_single_image(
             }
         }
     }
+}
 
-    image_xobjects.push((pdf_name.clone(), xobj_ref));
-    pdf_name
+/// Decodes a raster image. Palette PNGs are expanded by the decoder, including
+/// 1/2/4-bit indices and per-index `tRNS` alpha; BMP row order is normalized;
+/// GIFs and multi-page TIFFs yield their first frame/page.
+fn decode_raster(data: &[u8], format: ImageFormat) -> image::ImageResult<image::DynamicImage> {
+    let decode_format = match format {
+        ImageFormat::Gif => image::ImageFormat::Gif,
+        ImageFormat::Bmp => image::ImageFormat::Bmp,
+        ImageFormat::Tiff => image::ImageFormat::Tiff,
+        _ => image::ImageFormat::Png,
+    };
+    let cursor = std::io::Cursor::new(data);
+    let reader = image::ImageReader::with_format(std::io::BufReader::new(cursor), decode_format);
+    reader.decode()
+}
+
+/// Splits interleaved 8-bit samples whose last channel is alpha into a color
+/// plane and an alpha plane for the SMask. The alpha plane is omitted when
+/// every pixel is opaque.
+fn split_alpha(samples: &[u8], channels: usize) -> (Vec<u8>, Option<Vec<u8>>) {
+    let color: Vec<u8> = samples
+        .chunks_exact(channels)
+        .flat_map(|px| &px[..channels - 1])
+        .copied()
+        .collect();
+    let alpha = samples
+        .chunks_exact(channels)
+        .any(|px| px[channels - 1] < 255)
+        .then(|| {
+            samples
+                .chunks_exact(channels)
+                .map(|px| px[channels - 1])
+                .collect()
+        });
+    (color, alpha)
+}
+
+/// Wraps an image XObject in a unit-square form XObject that applies `srcRect`
+/// cropping and `xfrm` rotation, so callers keep mapping the unit square to the
+/// display box. Alt text is attached as a `/Figure` marked-content sequence.
+fn wrap_placed_image(
+    img: &EmbeddedImage,
+    image_ref: Ref,
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+) -> Ref {
+    let crop = img.crop;
+    let sx = 1.0 / (1.0 - crop.left - crop.right).max(0.01);
+    let sy = 1.0 / (1.0 - crop.top - crop.bottom).max(0.01);
+
+    let mut content = Content::new();
+    let mut bbox = Rect::new(0.0, 0.0, 1.0, 1.0);
+    if img.rotation != 0.0 {
+        // Rotate in page space around the center: the form is later scaled by
+        // (w, h), so conjugate the rotation with that scale. Word's rotation is
+        // clockwise, PDF's counter-clockwise.
+        let w = img.display_width.max(0.01);
+        let h = img.display_height.max(0.01);
+        let (sin, cos) = (-img.rotation).to_radians().sin_cos();
+        let (a, b, c, d) = (cos, sin * w / h, -sin * h / w, cos);
+        let e = 0.5 - 0.5 * a - 0.5 * c;
+        let f = 0.5 - 0.5 * b - 0.5 * d;
+        content.transform([a, b, c, d, e, f]);
+        let half_w = 0.5 * (a.abs() + c.abs());
+        let half_h = 0.5 * (b.abs() + d.abs());
+        bbox = Rect::new(0.5 - half_w, 0.5 - half_h, 0.5 + half_w, 0.5 + half_h);
+    }
+    content.rect(0.0, 0.0, 1.0, 1.0);
+    content.clip_nonzero();
+    content.end_path();
+    content.transform([sx, 0.0, 0.0, sy, -crop.left * sx, -crop.bottom * sy]);
+    if let Some(ref alt) = img.alt_text {
+        content
+            .begin_marked_content_with_properties(Name(b"Figure"))
+            .properties()
+            .pair(Name(b"Alt"), TextStr(alt));
+        content.x_object(Name(b"Src"));
+        content.end_marked_content();
+    } else {
+        content.x_object(Name(b"Src"));
+    }
+    let data = content.finish();
+
+    let form_ref = alloc();
+    let mut form = pdf.form_xobject(form_ref, data.as_slice());
+    form.bbox(bbox);
+    form.resources().x_objects().pair(Name(b"Src"), image_ref);
+    form_ref
+}
+
+/// Text of a PAGEREF or NUMPAGES run known from an earlier layout pass: the
+/// page its bookmark landed on, or the document's page total.
+pub(super) fn page_field_text(run: &Run, ctx: &RenderContext) -> Option<String> {
+    match run.field_code {
+        Some(FieldCode::PageRef(ref name)) => ctx.bookmark_pages.get(name).cloned(),
+        Some(FieldCode::NumPages) => ctx.total_pages.map(|n| n.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether body text (outside headers and footers) shows the page total.
+fn body_has_page_total(doc: &Document) -> bool {
+    let has_total = |para: &Paragraph| {
+        para.runs
+            .iter()
+            .any(|r| matches!(r.field_code, Some(FieldCode::NumPages)))
+    };
+    doc.sections
+        .iter()
+        .flat_map(|s| &s.blocks)
+        .any(|block| match block {
+            Block::Paragraph(para) => has_total(para),
+            Block::Table(table) => table.paragraphs().any(has_total),
+        })
 }
 
 fn collect_all_runs(doc: &Document) -> Vec<&Run> {
@@ -796,13 +1357,28 @@ fn collect_all_runs(doc: &Document) -> Vec<&Run> {
         ]
         .into_iter()
         .filter_map(|hf| hf.as_ref())
-        .flat_map(|hf| hf_paragraphs(hf))
-        .flat_map(|p| p.runs.iter())
+        .flat_map(|hf| {
+            let watermark_run = match &hf.watermark {
+                Some(Watermark::Text { run, .. }) => Some(run.as_ref()),
+                _ => None,
+            };
+            hf_paragraphs(hf)
+                .into_iter()
+                .flat_map(|p| p.runs.iter())
+                .chain(watermark_run)
+        })
     });
 
+    let line_number_runs = doc
+        .sections
+        .iter()
+        .filter_map(|s| s.properties.line_numbering.as_ref())
+        .map(|ln| &ln.run);
+
     let footnote_runs = doc
         .footnotes
         .values()
+        .chain(doc.endnotes.values())
         .flat_map(|fn_| fn_.paragraphs.iter())
         .flat_map(|p| p.runs.iter());
 
@@ -813,22 +1389,57 @@ fn collect_all_runs(doc: &Document) -> Vec<&Run> {
             match block {
                 Block::Paragraph(para) => para_runs_with_textboxes(para),
                 Block::Table(table) => table
-                    .rows
-                    .iter()
-                    .flat_map(|row| row.cells.iter())
-                    .flat_map(|cell| cell.paragraphs.iter())
+                    .paragraphs()
                     .flat_map(|para| para_runs_with_textboxes(para))
                     .collect(),
             }
         })
         .chain(hf_runs)
         .chain(footnote_runs)
+        .chain(line_number_runs)
         .collect()
 }
 
-fn collect_used_chars(doc: &Document, all_runs: &[&Run]) -> HashMap<String, HashSet<char>> {
+/// Dictionaries for the document language and every run's own `w:lang`, when
+/// the document or the options turn hyphenation on.
+fn load_hyphenator(doc: &Document, options: &ConversionOptions) -> Option<Hyphenator> {
+    if !(doc.auto_hyphenation || options.hyphenate) {
+        return None;
+    }
+    let langs: BTreeSet<&str> = collect_all_runs(doc)
+        .into_iter()
+        .filter_map(|r| r.lang.as_deref())
+        .collect();
+    Hyphenator::new(doc.lang.as_deref().unwrap_or("en-US"), langs)
+}
+
+fn collect_used_chars(
+    doc: &Document,
+    all_runs: &[&Run],
+    hyphenate: bool,
+) -> HashMap<String, HashSet<char>> {
     let mut used: HashMap<String, HashSet<char>> = HashMap::new();
     let mut key_buf = String::new();
+    // Footnote marks are only formatted at layout time
+    let fn_props = doc.footnote_properties;
+    let mut footnote_mark_chars = HashSet::new();
+    for i in 0..doc.footnotes.len() as u32 {
+        footnote_mark_chars.extend(fn_props.num_fmt.format(fn_props.num_start + i).chars());
+    }
+    // Page numbers follow their section's `pgNumType` format
+    let page_number_chars: HashSet<char> = doc
+        .sections
+        .iter()
+        .flat_map(|s| s.properties.page_num_format.symbols().chars())
+        .collect();
+
+    for ln in doc
+        .sections
+        .iter()
+        .filter_map(|s| s.properties.line_numbering.as_ref())
+    {
+        used.entry(font_key(&ln.run)).or_default().extend('0'..='9');
+    }
 
     for run in all_runs {
         let key = font_key_buf(run, &mut key_buf);
@@ -840,14 +1451,35 @@ fn collect_used_chars(doc: &Document, all_runs: &[&Run]) -> HashMap<String, Hash
         }
         if let Some(ref fc) = run.field_code {
             match fc {
-                FieldCode::Page | FieldCode::NumPages => {
+                FieldCode::Page
+                | FieldCode::NumPages
+                | FieldCode::PageRef(_)
+                | FieldCode::Section
+                | FieldCode::SectionPages => {
                     chars.extend('0'..='9');
+                    chars.extend(&page_number_chars);
                 }
-                FieldCode::StyleRef(_) => {}
+                FieldCode::StyleRef { .. }
+                | FieldCode::Ref(_)
+                | FieldCode::DocVariable(_)
+                | FieldCode::MergeField(_)
+                | FieldCode::Seq { .. }
+                | FieldCode::Toc { .. }
+                | FieldCode::FieldEnd
+                | FieldCode::Symbol { .. }
+                | FieldCode::Date(_)
+                | FieldCode::Time(_)
+                | FieldCode::FileName { .. }
+                | FieldCode::Author
+                | FieldCode::Title
+                | FieldCode::Subject
+                | FieldCode::Keywords
+                | FieldCode::LastSavedBy => {}
             }
         }
         if run.footnote_id.is_some() || run.is_footnote_ref_mark {
             chars.extend('0'..='9');
+            chars.extend(&footnote_mark_chars);
         }
     }
 
@@ -858,13 +1490,7 @@ fn collect_used_chars(doc: &Document, all_runs: &[&Run]) -> HashMap<String, Hash
         .flat_map(|block| -> Vec<&Paragraph> {
             match block {
                 Block::Paragraph(p) => collect_paras(p),
-                Block::Table(t) => t
-                    .rows
-                    .iter()
-                    .flat_map(|row| row.cells.iter())
-                    .flat_map(|cell| cell.paragraphs.iter())
-                    .flat_map(|p| collect_paras(p))
-                    .collect(),
+                Block::Table(t) => t.paragraphs().flat_map(|p| collect_paras(p)).collect(),
             }
         })
         .collect();
@@ -922,15 +1548,35 @@ fn collect_used_chars(doc: &Document, all_runs: &[&Run]) -> HashMap<String, Hash
                     }
                     if let Some(ref fc) = run.field_code {
                         match fc {
-                            FieldCode::Page | FieldCode::NumPages => {
+                            FieldCode::Page
+                            | FieldCode::NumPages
+                            | FieldCode::PageRef(_)
+                            | FieldCode::Section
+                            | FieldCode::SectionPages => {
                                 chars.extend('0'..='9');
+                                chars.extend(&page_number_chars);
                             }
-                            FieldCode::StyleRef(_) => {
+                            FieldCode::StyleRef { .. } => {
                                 chars.extend('0'..='9');
                                 chars.extend('A'..='Z');
                                 chars.extend('a'..='z');
                                 chars.extend([' ', '.', ',', '/', '-', '(', ')']);
                             }
+                            FieldCode::Ref(_)
+                            | FieldCode::DocVariable(_)
+                            | FieldCode::MergeField(_)
+                            | FieldCode::Seq { .. }
+                            | FieldCode::Toc { .. }
+                            | FieldCode::FieldEnd
+                            | FieldCode::Symbol { .. }
+                            | FieldCode::Date(_)
+                            | FieldCode::Time(_)
+                            | FieldCode::FileName { .. }
+                            | FieldCode::Author
+                            | FieldCode::Title
+                            | FieldCode::Subject
+                            | FieldCode::Keywords
+                            | FieldCode::LastSavedBy => {}
                         }
                     }
                 }
@@ -940,6 +1586,12 @@ fn collect_used_chars(doc: &Document, all_runs: &[&Run]) -> HashMap<String, Hash
 
     for chars in used.values_mut() {
         chars.insert(' ');
+        // Soft hyphens are never drawn; a line broken at one ends in '-'
+        let soft_hyphen = chars.remove(&'\u{AD}');
+        // Hyphens are only added when words are split at layout time
+        if hyphenate || soft_hyphen {
+            chars.insert('-');
+        }
     }
 
     used
@@ -949,55 +1601,74 @@ fn collect_and_register_fonts(
     doc: &Document,
     pdf: &mut Pdf,
     alloc: &mut impl FnMut() -> Ref,
+    hyphenate: bool,
+    options: &ConversionOptions,
 ) -> (HashMap<String, FontEntry>, Vec<String>) {
-    let mut seen_fonts: HashMap<String, FontEntry> = HashMap::new();
-    let mut font_order: Vec<String> = Vec::new();
     let all_runs = collect_all_runs(doc);
-    let used_chars_per_font = collect_used_chars(doc, &all_runs);
-    let mut key_buf = String::new();
+    let used_chars_per_font = collect_used_chars(doc, &all_runs, hyphenate);
+    let no_chars = HashSet::new();
 
+    // Fonts in order of first use, then those only labels, fields and the like use
+    let mut jobs: Vec<(String, &str, bool, bool)> = Vec::new();
+    let mut queued: HashSet<String> = HashSet::new();
+    let mut key_buf = String::new();
     for run in &all_runs {
         let key = font_key_buf(run, &mut key_buf);
-        if !seen_fonts.contains_key(key) {
-            let key_owned = key.to_string();
-            let pdf_name = format!("F{}", font_order.len() + 1);
-            let used = used_chars_per_font
-                .get(&key_owned)
-                .cloned()
-                .unwrap_or_default();
-            let entry = register_font(
-                pdf,
-                &run.font_name,
+        if !queued.contains(key) {
+            queued.insert(key.to_string());
+            jobs.push((
+                key.to_string(),
+                run.font_name.as_str(),
                 run.bold,
                 run.italic,
-                pdf_name,
-                alloc,
-                &doc.embedded_fonts,
-                &used,
-                &doc.font_table,
-            );
-            font_order.push(key_owned.clone());
-            seen_fonts.insert(key_owned, entry);
+            ));
         }
     }
+    let mut rest: Vec<&String> = used_chars_per_font
+        .keys()
+        .filter(|k| !queued.contains(*k))
+        .collect();
+    rest.sort();
+    for key in rest {
+        let (font_name, bold, italic) = split_font_key(key);
+        jobs.push((key.clone(), font_name, bold, italic));
+    }
 
-    for (key, used) in &used_chars_per_font {
-        if !seen_fonts.contains_key(key) {
-            let pdf_name = format!("F{}", font_order.len() + 1);
+    // Subsetting is the slow part, so fonts are embedded into chunks with ids
+    // handed out up front (in parallel with the `parallel` feature), then
+    // written in order
+    let refs: Vec<FontRefs> = jobs.iter().map(|_| FontRefs::alloc(alloc)).collect();
+    #[cfg(feature = "parallel")]
+    let job_iter = jobs.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let job_iter = jobs.iter();
+    let registered: Vec<(Chunk, FontEntry)> = job_iter
+        .zip(refs)
+        .enumerate()
+        .map(|(i, ((key, font_name, bold, italic), refs))| {
+            let mut chunk = Chunk::new();
             let entry = register_font(
-                pdf,
-                key,
-                false,
-                false,
-                pdf_name,
-                alloc,
+                &mut chunk,
+                font_name,
+                *bold,
+                *italic,
+                format!("F{}", i + 1),
+                refs,
                 &doc.embedded_fonts,
-                used,
+                used_chars_per_font.get(key).unwrap_or(&no_chars),
                 &doc.font_table,
+                options,
             );
-            seen_fonts.insert(key.clone(), entry);
-            font_order.push(key.clone());
-        }
+            (chunk, entry)
+        })
+        .collect();
+
+    let mut seen_fonts: HashMap<String, FontEntry> = HashMap::new();
+    let mut font_order: Vec<String> = Vec::new();
+    for ((key, ..), (chunk, entry)) in jobs.into_iter().zip(registered) {
+        pdf.extend(&chunk);
+        seen_fonts.insert(key.clone(), entry);
+        font_order.push(key);
     }
 
     if seen_fonts.is_empty() {
@@ -1008,26 +1679,104 @@ fn collect_and_register_fonts(
             false,
             false,
             pdf_name,
-            alloc,
+            FontRefs::alloc(alloc),
             &doc.embedded_fonts,
             &HashSet::new(),
             &doc.font_table,
+            options,
         );
         seen_fonts.insert("Helvetica".to_string(), entry);
         font_order.push("Helvetica".to_string());
     }
 
+    if let Some(fallback) = &options.fallback_font {
+        register_fallback_fonts(
+            doc,
+            pdf,
+            alloc,
+            fallback,
+            &used_chars_per_font,
+            &mut seen_fonts,
+            &mut font_order,
+            options,
+        );
+    }
+
     (seen_fonts, font_order)
 }
 
-fn embed_all_images(
+/// Registers `fallback` for the characters registered fonts have no glyph for,
+/// once per style so bold and italic text keeps its weight and slant, and
+/// points each font lacking glyphs at the one for its style.
+#[allow(clippy::too_many_arguments)]
+fn register_fallback_fonts(
     doc: &Document,
     pdf: &mut Pdf,
     alloc: &mut impl FnMut() -> Ref,
-) -> EmbeddedImages {
+    fallback: &str,
+    used_chars_per_font: &HashMap<String, HashSet<char>>,
+    seen_fonts: &mut HashMap<String, FontEntry>,
+    font_order: &mut Vec<String>,
+    options: &ConversionOptions,
+) {
+    let mut missing: BTreeMap<String, HashSet<char>> = BTreeMap::new();
+    let mut lacking: Vec<(String, String)> = Vec::new();
+    for (key, used) in used_chars_per_font {
+        let Some(entry) = seen_fonts.get(key) else {
+            continue;
+        };
+        let chars: HashSet<char> = used
+            .iter()
+            .copied()
+            .filter(|&c| !c.is_whitespace() && !entry.has_glyph(c))
+            .collect();
+        if chars.is_empty() {
+            continue;
+        }
+        let (name, _, _) = split_font_key(key);
+        let fallback_key = format!("{fallback}{}#fallback", &key[name.len()..]);
+        missing
+            .entry(fallback_key.clone())
+            .or_default()
+            .extend(chars);
+        lacking.push((key.clone(), fallback_key));
+    }
+
+    for (fallback_key, mut chars) in missing {
+        chars.insert(' ');
+        let (_, bold, italic) = split_font_key(fallback_key.trim_end_matches("#fallback"));
+        let entry = register_font(
+            pdf,
+            fallback,
+            bold,
+            italic,
+            format!("F{}", font_order.len() + 1),
+            FontRefs::alloc(alloc),
+            &doc.embedded_fonts,
+            &chars,
+            &doc.font_table,
+            options,
+        );
+        seen_fonts.insert(fallback_key.clone(), entry);
+        font_order.push(fallback_key);
+    }
+    for (key, fallback_key) in lacking {
+        if let Some(entry) = seen_fonts.get_mut(&key) {
+            entry.fallback = Some(fallback_key);
+        }
+    }
+}
+
+/// Names every image in the document, writing only the crop/rotation wrappers;
+/// the pictures themselves are left pending in `image_xobjects`.
+fn embed_all_images<'a>(
+    doc: &'a Document,
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+) -> EmbeddedImages<'a> {
     let mut image_pdf_names: HashMap<usize, String> = HashMap::new();
     let mut inline_image_pdf_names: HashMap<(usize, usize), String> = HashMap::new();
-    let mut image_xobjects: Vec<(String, Ref)> = Vec::new();
+    let mut image_xobjects = ImageXObjects::default();
     let mut floating_image_pdf_names: HashMap<(usize, usize), String> = HashMap::new();
 
     {
@@ -1058,6 +1807,7 @@ fn embed_all_images(
     let mut hf_image_names: HashMap<(usize, u8, usize), String> = HashMap::new();
     let mut hf_inline_image_names: HashMap<(usize, u8, usize, usize), String> = HashMap::new();
     let mut hf_floating_image_names: HashMap<(usize, u8, usize, usize), String> = HashMap::new();
+    let mut hf_watermark_names: HashMap<(usize, u8), String> = HashMap::new();
     {
         let hf_variants: [(u8, fn(&SectionProperties) -> Option<&HeaderFooter>); 6] = [
             (0, |sp| sp.header_default.as_ref()),
@@ -1070,6 +1820,10 @@ fn embed_all_images(
         for (si, section) in doc.sections.iter().enumerate() {
             for &(hf_type, accessor) in &hf_variants {
                 if let Some(hf) = accessor(&section.properties) {
+                    if let Some(Watermark::Image { image, .. }) = &hf.watermark {
+                        let name = embed_single_image(image, &mut image_xobjects, pdf, alloc);
+                        hf_watermark_names.insert((si, hf_type), name);
+                    }
                     let mut pi = 0usize;
                     for block in &hf.blocks {
                         if let Block::Paragraph(para) = block {
@@ -1129,17 +1883,12 @@ fn embed_all_images(
             }
         }
         for table in tables {
-            for row in &table.rows {
-                for cell in &row.cells {
-                    for para in &cell.paragraphs {
-                        if let Some(img) = &para.image {
-                            let key = std::sync::Arc::as_ptr(&img.data) as usize;
-                            if !table_cell_image_names.contains_key(&key) {
-                                let name =
-                                    embed_single_image(img, &mut image_xobjects, pdf, alloc);
-                                table_cell_image_names.insert(key, name);
-                            }
-                        }
+            for para in table.paragraphs() {
+                if let Some(img) = &para.image {
+                    let key = std::sync::Arc::as_ptr(&img.data) as usize;
+                    if !table_cell_image_names.contains_key(&key) {
+                        let name = embed_single_image(img, &mut image_xobjects, pdf, alloc);
+                        table_cell_image_names.insert(key, name);
                     }
                 }
             }
@@ -1154,6 +1903,7 @@ fn embed_all_images(
         hf_image_names,
         hf_inline_image_names,
         hf_floating_image_names,
+        hf_watermark_names,
         table_cell_image_names,
     }
 }
@@ -1174,6 +1924,76 @@ fn srgb_to_linear_rgb(c: [u8; 3]) -> [f32; 3] {
     ]
 }
 
+/// Writes the document outline (bookmarks pane), nesting each heading under the
+/// closest preceding heading of a lower level. Returns `None` without headings.
+fn write_outline(
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+    page_ids: &[Ref],
+    entries: &[OutlineEntry],
+) -> Option<Ref> {
+    if entries.is_empty() {
+        return None;
+    }
+    let root_id = alloc();
+    let ids: Vec<Ref> = entries.iter().map(|_| alloc()).collect();
+
+    let mut parents: Vec<Option<usize>> = Vec::with_capacity(entries.len());
+    let mut stack: Vec<usize> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        while stack
+            .last()
+            .is_some_and(|&j| entries[j].level >= entry.level)
+        {
+            stack.pop();
+        }
+        parents.push(stack.last().copied());
+        stack.push(i);
+    }
+    let children = |parent: Option<usize>| -> Vec<usize> {
+        (0..entries.len())
+            .filter(|&i| parents[i] == parent)
+            .collect()
+    };
+    let descendants = |i: usize| {
+        let mut count = 0;
+        let mut j = i + 1;
+        while j < entries.len() && entries[j].level > entries[i].level {
+            count += 1;
+            j += 1;
+        }
+        count
+    };
+
+    let top_level = children(None);
+    pdf.outline(root_id)
+        .first(ids[top_level[0]])
+        .last(ids[*top_level.last().unwrap()])
+        .count(entries.len() as i32);
+
+    for (i, entry) in entries.iter().enumerate() {
+        let siblings = children(parents[i]);
+        let pos = siblings.iter().position(|&k| k == i).unwrap();
+        let kids = children(Some(i));
+        let mut item = pdf.outline_item(ids[i]);
+        item.title(TextStr(&entry.title))
+            .parent(parents[i].map_or(root_id, |p| ids[p]));
+        if pos > 0 {
+            item.prev(ids[siblings[pos - 1]]);
+        }
+        if let Some(&next) = siblings.get(pos + 1) {
+            item.next(ids[next]);
+        }
+        if let (Some(&first), Some(&last)) = (kids.first(), kids.last()) {
+            item.first(ids[first]).last(ids[last]).count(descendants(i));
+        }
+        item.dest()
+            .page(page_ids[entry.page_idx])
+            .xyz(0.0, entry.top, None);
+    }
+    Some(root_id)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn assemble_pdf_pages(
     pdf: &mut Pdf,
@@ -1181,43 +2001,104 @@ fn assemble_pdf_pages(
     catalog_id: Ref,
     pages_id: Ref,
     all_contents: Vec<Content>,
+    all_behind_contents: Vec<Content>,
     all_hf_contents: &mut Vec<Option<Content>>,
     all_page_links: &[Vec<LinkAnnotation>],
     all_page_alpha_states: &[HashSet<u8>],
     all_page_gradient_specs: &[Vec<GradientSpec>],
     page_section_indices: &[(usize, bool)],
+    page_offsets: &[(f32, f32)],
     seen_fonts: &HashMap<String, FontEntry>,
     font_order: &[String],
     image_xobjects: &[(String, Ref)],
+    bookmark_positions: &HashMap<String, (usize, f32)>,
+    outline: &[OutlineEntry],
     doc: &Document,
+    options: &ConversionOptions,
+    progress: &mut dyn FnMut(usize, usize),
 ) {
     let n = all_contents.len();
     let page_ids: Vec<Ref> = (0..n).map(|_| alloc()).collect();
     let content_ids: Vec<Ref> = (0..n).map(|_| alloc()).collect();
 
+    // A comment whose run breaks across pages gets one note, on the page it ends
+    let comment_pages: HashMap<u32, usize> = all_page_links
+        .iter()
+        .enumerate()
+        .flat_map(|(page_idx, links)| {
+            links.iter().filter_map(move |link| match link.target {
+                LinkTarget::Comment(id) => Some((id, page_idx)),
+                LinkTarget::Url(_) => None,
+            })
+        })
+        .collect();
+
     let page_annot_refs: Vec<Vec<Ref>> = all_page_links
         .iter()
-        .map(|links| {
+        .enumerate()
+        .map(|(page_idx, links)| {
             links
                 .iter()
-                .map(|link| {
-                    let annot_ref = alloc();
-                    let mut annot = pdf.annotation(annot_ref);
-                    annot
-                        .subtype(pdf_writer::types::AnnotationType::Link)
-                        .rect(link.rect)
-                        .border(0.0, 0.0, 0.0, None);
-                    annot
-                        .action()
-                        .action_type(pdf_writer::types::ActionType::Uri)
-                        .uri(Str(link.url.as_bytes()));
-                    annot_ref
+                .filter_map(|link| match &link.target {
+                    LinkTarget::Url(url) => {
+                        // "#name" targets a bookmark; drop links to bookmarks that were never placed
+                        let target = match url.strip_prefix('#') {
+                            Some(name) => Some(*bookmark_positions.get(name)?),
+                            None => None,
+                        };
+                        let annot_ref = alloc();
+                        let mut annot = pdf.annotation(annot_ref);
+                        annot
+                            .subtype(pdf_writer::types::AnnotationType::Link)
+                            .rect(link.rect)
+                            .border(0.0, 0.0, 0.0, None);
+                        let mut action = annot.action();
+                        if let Some((page_idx, top)) = target {
+                            action
+                                .action_type(pdf_writer::types::ActionType::GoTo)
+                                .destination()
+                                .page(page_ids[page_idx])
+                                .xyz(0.0, top, None);
+                        } else {
+                            action
+                                .action_type(pdf_writer::types::ActionType::Uri)
+                                .uri(Str(url.as_bytes()));
+                        }
+                        Some(annot_ref)
+                    }
+                    LinkTarget::Comment(id) => {
+                        if !options.render_comments || comment_pages.get(id) != Some(&page_idx) {
+                            return None;
+                        }
+                        let comment = doc.comments.get(id)?;
+                        let annot_ref = alloc();
+                        let mut annot = pdf.annotation(annot_ref);
+                        // Note icon just after the end of the commented text
+                        let Rect { x2, y2, .. } = link.rect;
+                        annot
+                            .subtype(pdf_writer::types::AnnotationType::Text)
+                            .rect(Rect::new(
+                                x2,
+                                y2 - COMMENT_ICON_SIZE,
+                                x2 + COMMENT_ICON_SIZE,
+                                y2,
+                            ))
+                            .contents(TextStr(&comment.text))
+                            .icon(pdf_writer::types::AnnotationIcon::Comment);
+                        if let Some(author) = &comment.author {
+                            annot.author(TextStr(author));
+                        }
+                        if let Some(date) = comment.date.as_deref().and_then(pdf_date) {
+                            annot.modified(date);
+                        }
+                        Some(annot_ref)
+                    }
                 })
                 .collect()
         })
         .collect();
 
-    let all_alpha_values: HashSet<u8> = all_page_alpha_states
+    let all_alpha_values: BTreeSet<u8> = all_page_alpha_states
         .iter()
         .flat_map(|s| s.iter().copied())
         .collect();
@@ -1319,25 +2200,64 @@ fn assemble_pdf_pages(
         })
         .collect();
 
-    for (i, c) in all_contents.into_iter().enumerate() {
-        let body_raw = c.finish();
+    let background = doc.background.filter(|_| options.render_background);
+    for (i, (c, behind)) in all_contents
+        .into_iter()
+        .zip(all_behind_contents)
+        .enumerate()
+    {
+        let mut combined = Vec::new();
+        if let Some([r, g, b]) = background {
+            let sp = &doc.sections[page_section_indices[i].0].properties;
+            let mut bg = Content::new();
+            bg.save_state();
+            bg.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+            bg.rect(0.0, 0.0, sp.page_width, sp.page_height);
+            bg.fill_nonzero();
+            bg.restore_state();
+            combined.extend_from_slice(bg.finish().as_slice());
+            combined.push(b'\n');
+        }
         if let Some(hf) = all_hf_contents[i].take() {
-            let hf_raw = hf.finish();
-            let mut combined = Vec::with_capacity(hf_raw.len() + 1 + body_raw.len());
-            combined.extend_from_slice(hf_raw.as_slice());
+            combined.extend_from_slice(hf.finish().as_slice());
             combined.push(b'\n');
-            combined.extend_from_slice(body_raw.as_slice());
-            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&combined, 6);
-            pdf.stream(content_ids[i], &compressed)
-                .filter(Filter::FlateDecode);
-        } else {
-            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(body_raw.as_slice(), 6);
-            pdf.stream(content_ids[i], &compressed)
-                .filter(Filter::FlateDecode);
         }
-    }
-
-    pdf.catalog(catalog_id).pages(pages_id);
+        let (dx, dy) = page_offsets[i];
+        if dx != 0.0 || dy != 0.0 {
+            let mut shift = Content::new();
+            shift.save_state();
+            shift.transform([1.0, 0.0, 0.0, 1.0, dx, dy]);
+            combined.extend_from_slice(shift.finish().as_slice());
+            combined.push(b'\n');
+            combined.extend_from_slice(behind.finish().as_slice());
+            combined.push(b'\n');
+            combined.extend_from_slice(c.finish().as_slice());
+            let mut restore = Content::new();
+            restore.restore_state();
+            combined.push(b'\n');
+            combined.extend_from_slice(restore.finish().as_slice());
+        } else {
+            combined.extend_from_slice(behind.finish().as_slice());
+            combined.push(b'\n');
+            combined.extend_from_slice(c.finish().as_slice());
+        }
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(
+            &combined,
+            options.compression_level.min(10),
+        );
+        pdf.stream(content_ids[i], &compressed)
+            .filter(Filter::FlateDecode);
+        progress(i + 1, n);
+    }
+
+    let outline_id = write_outline(pdf, alloc, &page_ids, outline);
+    {
+        let mut catalog = pdf.catalog(catalog_id);
+        catalog.pages(pages_id);
+        if let Some(outline_id) = outline_id {
+            catalog.outlines(outline_id);
+        }
+    }
     pdf.pages(pages_id)
         .kids(page_ids.iter().copied())
         .count(n as i32);
@@ -1372,8 +2292,10 @@ fn assemble_pdf_pages(
                 }
             }
             if let Some(alpha_set) = all_page_alpha_states.get(i).filter(|s| !s.is_empty()) {
+                let mut alpha_values: Vec<u8> = alpha_set.iter().copied().collect();
+                alpha_values.sort_unstable();
                 let mut gs_dict = resources.ext_g_states();
-                for &pct in alpha_set {
+                for pct in alpha_values {
                     let gs_name = format!("GSa{pct}");
                     let gs_ref = alpha_gs_refs[&pct];
                     gs_dict.pair(Name(gs_name.as_bytes()), gs_ref);
@@ -1389,7 +2311,78 @@ fn assemble_pdf_pages(
     }
 }
 
-pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
+/// Converts a W3CDTF timestamp from core.xml ("2024-03-05T14:30:00Z") to a PDF date.
+fn pdf_date(w3cdtf: &str) -> Option<Date> {
+    let s = w3cdtf.trim();
+    let num = |start: usize| s.get(start..start + 2)?.parse::<u8>().ok();
+    let mut date = Date::new(s.get(0..4)?.parse().ok()?);
+    if let (Some(month), Some(day)) = (num(5), num(8)) {
+        date = date.month(month).day(day);
+    }
+    if s.get(10..11) != Some("T") {
+        return Some(date);
+    }
+    let (Some(hour), Some(minute), Some(second)) = (num(11), num(14), num(17)) else {
+        return Some(date);
+    };
+    date = date.hour(hour).minute(minute).second(second);
+    if s.ends_with('Z') {
+        date = date.utc_offset_hour(0);
+    } else if let Some(offset) = s.get(s.len().saturating_sub(6)..)
+        && let Some(sign) = offset.chars().next().filter(|c| matches!(c, '+' | '-'))
+        && let (Some(h), Some(m)) = (
+            offset.get(1..3).and_then(|v| v.parse::<i8>().ok()),
+            offset.get(4..6).and_then(|v| v.parse::<u8>().ok()),
+        )
+    {
+        let h = if sign == '-' { -h } else { h };
+        date = date.utc_offset_hour(h).utc_offset_minute(m);
+    }
+    Some(date)
+}
+
+fn write_document_info(
+    pdf: &mut Pdf,
+    info_id: Ref,
+    props: &CoreProperties,
+    options: &ConversionOptions,
+) {
+    let default_producer = concat!("docxide-pdf ", env!("CARGO_PKG_VERSION"));
+    let mut info = pdf.document_info(info_id);
+    if let Some(title) = &props.title {
+        info.title(TextStr(title));
+    }
+    if let Some(author) = &props.creator {
+        info.author(TextStr(author));
+    }
+    if let Some(subject) = &props.subject {
+        info.subject(TextStr(subject));
+    }
+    if let Some(keywords) = &props.keywords {
+        info.keywords(TextStr(keywords));
+    }
+    info.producer(TextStr(
+        options.producer.as_deref().unwrap_or(default_producer),
+    ));
+    let fixed = options.creation_date.as_deref();
+    if let Some(date) = fixed.or(props.created.as_deref()).and_then(pdf_date) {
+        info.creation_date(date);
+    }
+    if let Some(date) = fixed.or(props.modified.as_deref()).and_then(pdf_date) {
+        info.modified_date(date);
+    }
+}
+
+pub fn render(doc: &Document, options: &ConversionOptions) -> Result<Vec<u8>, Error> {
+    render_with_progress(doc, options, &mut |_, _| {})
+}
+
+/// `render`, calling `progress(pages_done, total_pages)` as each page is written.
+pub fn render_with_progress(
+    doc: &Document,
+    options: &ConversionOptions,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<Vec<u8>, Error> {
     let t0 = std::time::Instant::now();
     let mut pdf = Pdf::new();
     let mut next_id = 1i32;
@@ -1402,7 +2395,9 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
     let catalog_id = alloc();
     let pages_id = alloc();
 
-    let (seen_fonts, font_order) = collect_and_register_fonts(doc, &mut pdf, &mut alloc);
+    let hyphenator = load_hyphenator(doc, options);
+    let (seen_fonts, font_order) =
+        collect_and_register_fonts(doc, &mut pdf, &mut alloc, hyphenator.is_some(), options);
     let smartart_font_key = font_order.first().map(|s| s.as_str()).unwrap_or("");
     let t_fonts = t0.elapsed();
 
@@ -1414,138 +2409,512 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
         hf_image_names,
         hf_inline_image_names,
         hf_floating_image_names,
+        hf_watermark_names,
         table_cell_image_names,
     } = embed_all_images(doc, &mut pdf, &mut alloc);
 
-    let ctx = RenderContext {
+    let mut ctx = RenderContext {
         fonts: &seen_fonts,
         doc_line_spacing: doc.line_spacing,
+        even_and_odd_headers: doc.even_and_odd_headers,
+        default_tab_stop: doc.default_tab_stop,
         table_cell_image_names: &table_cell_image_names,
+        bookmark_pages: &HashMap::new(),
+        total_pages: None,
+        hyphenator: hyphenator.as_ref(),
     };
 
     let t_images = t0.elapsed();
 
-    // Pre-compute footnote display order: scan body runs for footnote_id, assign sequential numbers
-    let mut footnote_display_order: HashMap<u32, u32> = HashMap::new();
-    {
-        let mut next_fn_num = 1u32;
-        for section in &doc.sections {
-            for block in &section.blocks {
-                let runs: Box<dyn Iterator<Item = &Run>> = match block {
-                    Block::Paragraph(p) => Box::new(p.runs.iter()),
-                    Block::Table(t) => Box::new(
-                        t.rows
-                            .iter()
-                            .flat_map(|row| row.cells.iter())
-                            .flat_map(|cell| cell.paragraphs.iter())
-                            .flat_map(|p| p.runs.iter()),
-                    ),
-                };
-                for run in runs {
-                    if let Some(id) = run.footnote_id {
-                        if !footnote_display_order.contains_key(&id) {
-                            footnote_display_order.insert(id, next_fn_num);
-                            next_fn_num += 1;
-                        }
+    // Phase 2: build multi-page content streams (section-aware)
+    let (mut pb, footnote_marks, bookmark_pages) = paginate(
+        doc,
+        &ctx,
+        &image_pdf_names,
+        &inline_image_pdf_names,
+        &floating_image_pdf_names,
+        smartart_font_key,
+    );
+    ctx.bookmark_pages = &bookmark_pages;
+
+    let t_layout = t0.elapsed();
+
+    // Phase 2a: vertical page alignment and mirror margins. Body content is
+    // drawn shifted by both; what's added to it below undoes the vertical shift.
+    let page_shifts = pb.align_pages_vertically(doc, &ctx);
+    let mirror_shifts = pb.mirror_pages(doc);
+
+    // Phase 2b: column separator lines
+    for (page_idx, content) in pb.all_contents.iter_mut().enumerate() {
+        let (si, _) = pb.page_section_indices[page_idx];
+        let sp = &doc.sections[si].properties;
+        let shift = page_shifts[page_idx];
+
+        if let Some(cfg) = &sp.columns {
+            if cfg.sep {
+                let mut x = sp.margin_left;
+                for (i, col) in cfg.columns.iter().enumerate() {
+                    x += col.width;
+                    if i < cfg.columns.len() - 1 {
+                        let mid_x = x + col.space / 2.0;
+                        content.save_state();
+                        content.transform([1.0, 0.0, 0.0, 1.0, 0.0, shift]);
+                        content.set_line_width(0.5);
+                        content.move_to(mid_x, sp.margin_bottom);
+                        content.line_to(mid_x, sp.page_height - sp.margin_top);
+                        content.stroke();
+                        content.restore_state();
+                        x += col.space;
                     }
                 }
             }
         }
     }
 
-    // Phase 2: build multi-page content streams (section-aware)
-    let first_sp = &doc.sections[0].properties;
-    let mut cur_sp = first_sp;
-    let initial_slot_top = effective_slot_top(cur_sp, true, &ctx);
-    let mut pb = PageBuilder::new(initial_slot_top);
-    let mut prev_space_after: f32 = 0.0;
-    let mut effective_margin_bottom: f32 = compute_effective_margin_bottom(cur_sp, true, &ctx);
-    let mut global_block_idx: usize = 0;
+    // Phase 2c: render footnotes at page bottom
+    for (page_idx, content) in pb.all_contents.iter_mut().enumerate() {
+        let (si, _) = pb.page_section_indices[page_idx];
+        let sp = &doc.sections[si].properties;
+        let text_width = sp.page_width - sp.margin_left - sp.margin_right;
+        content.save_state();
+        content.transform([1.0, 0.0, 0.0, 1.0, 0.0, page_shifts[page_idx]]);
+        render_page_footnotes(
+            content,
+            &pb.all_footnote_ids[page_idx],
+            &doc.footnotes,
+            &footnote_marks,
+            &ctx,
+            sp.margin_left,
+            sp.margin_bottom,
+            text_width,
+        );
+        content.restore_state();
+    }
 
-    for (sect_idx, section) in doc.sections.iter().enumerate() {
-        let sp = &section.properties;
+    let t_headers = t0.elapsed();
 
-        // Section break handling (not for the first section)
-        if sect_idx > 0 {
-            match sp.break_type {
-                SectionBreakType::NextPage
-                | SectionBreakType::OddPage
-                | SectionBreakType::EvenPage => {
-                    pb.flush_page(sect_idx - 1);
+    // Phase 2d: render headers/footers into separate content streams (behind body)
+    let total_pages = pb.all_contents.len();
+    let mut section_pages = vec![0; doc.sections.len()];
+    for &(si, _) in &pb.page_section_indices {
+        section_pages[si] += 1;
+    }
+    let build_hf_maps = |si: usize,
+                         hf_type: u8|
+     -> (
+        HashMap<usize, String>,
+        HashMap<(usize, usize), String>,
+        HashMap<(usize, usize), String>,
+    ) {
+        let pi_map: HashMap<usize, String> = hf_image_names
+            .iter()
+            .filter(|((s, t, _), _)| *s == si && *t == hf_type)
+            .map(|((_, _, pi), name)| (*pi, name.clone()))
+            .collect();
+        let ii_map: HashMap<(usize, usize), String> = hf_inline_image_names
+            .iter()
+            .filter(|((s, t, _, _), _)| *s == si && *t == hf_type)
+            .map(|((_, _, pi, ri), name)| ((*pi, *ri), name.clone()))
+            .collect();
+        let fi_map: HashMap<(usize, usize), String> = hf_floating_image_names
+            .iter()
+            .filter(|((s, t, _, _), _)| *s == si && *t == hf_type)
+            .map(|((_, _, pi, fi), name)| ((*pi, *fi), name.clone()))
+            .collect();
+        (pi_map, ii_map, fi_map)
+    };
 
-                    // Insert blank page for odd/even page alignment
-                    let need_odd = match sp.break_type {
-                        SectionBreakType::OddPage => true,
-                        _ if doc.even_and_odd_headers && sp.page_num_start.is_some() => {
-                            sp.page_num_start.unwrap() % 2 == 1
-                        }
-                        _ => false,
-                    };
-                    let need_even = match sp.break_type {
-                        SectionBreakType::EvenPage => true,
-                        _ if doc.even_and_odd_headers && sp.page_num_start.is_some() => {
-                            sp.page_num_start.unwrap() % 2 == 0
-                        }
-                        _ => false,
-                    };
-                    if need_odd || need_even {
-                        let next_phys = pb.page_count() + 1;
-                        let next_is_odd = next_phys % 2 == 1;
-                        if (need_odd && !next_is_odd) || (need_even && next_is_odd) {
-                            pb.push_blank_page(sect_idx - 1);
-                        }
-                    }
+    let empty_styleref: HashMap<String, String> = HashMap::new();
+    let mut next_line_number: Option<u32> = None;
+    let mut all_hf_contents: Vec<Option<Content>> = (0..total_pages).map(|_| None).collect();
+    for (page_idx, hf_content) in all_hf_contents.iter_mut().enumerate() {
+        let (si, is_first) = pb.page_section_indices[page_idx];
+        let sp = &doc.sections[si].properties;
 
-                    pb.slot_top = effective_slot_top(sp, true, &ctx);
-                    effective_margin_bottom = compute_effective_margin_bottom(sp, true, &ctx);
-                }
-                SectionBreakType::Continuous => {
-                    // No forced break; geometry updates on next page
-                }
-            }
-            pb.is_first_page_of_section = true;
+        let page_num = pb.display_page_number(page_idx);
+        let page_text = pb.page_number_text(doc, page_idx);
+        let page_fields = PageFields {
+            page_text: &page_text,
+            total_pages,
+            section: si + 1,
+            section_pages: section_pages[si],
+        };
+
+        // Per spec §17.16.5.59: in headers/footers of a printed document, STYLEREF
+        // searches the current page top-to-bottom first, then backward to doc start.
+        let page_first = pb
+            .all_first_styleref
+            .get(page_idx)
+            .unwrap_or(&empty_styleref);
+        let prev_running = if page_idx > 0 {
+            pb.all_styleref.get(page_idx - 1).unwrap_or(&empty_styleref)
+        } else {
+            &empty_styleref
+        };
+        let mut page_styleref_merged = prev_running.clone();
+        // Current-page first occurrences take priority (top-to-bottom search)
+        for (k, v) in page_first {
+            page_styleref_merged.insert(k.clone(), v.clone());
         }
+        let page_styleref = &StyleRefValues {
+            first: &page_styleref_merged,
+            last: pb.all_styleref.get(page_idx).unwrap_or(&empty_styleref),
+        };
 
-        cur_sp = sp;
-        let text_width = sp.page_width - sp.margin_left - sp.margin_right;
+        let mut hf = Content::new();
+        let mirror_shift = mirror_shifts[page_idx];
+        let mut has_hf = sp.page_borders.is_some();
+        render_page_borders(&mut hf, sp, is_first, mirror_shift != 0.0);
+        hf.save_state();
+        hf.transform([1.0, 0.0, 0.0, 1.0, mirror_shift, 0.0]);
+
+        if let Some(ln) = &sp.line_numbering {
+            let section_start = page_idx == 0 || pb.page_section_indices[page_idx - 1].0 != si;
+            let first = match (next_line_number, ln.restart) {
+                (Some(n), LineNumberRestart::Continuous) => n,
+                (Some(n), LineNumberRestart::NewSection) if !section_start => n,
+                _ => ln.start,
+            };
+            let marks = &pb.all_line_marks[page_idx];
+            render_line_numbers(&mut hf, ln, marks, first, ctx.fonts);
+            next_line_number = Some(first + marks.len() as u32);
+            has_hf = true;
+        } else {
+            next_line_number = None;
+        }
 
-        // Column geometry: vec of (x_offset, width) for each column
-        let col_config = sp.columns.as_ref();
-        let col_count = col_config.map(|c| c.columns.len()).unwrap_or(1);
-        let col_geometry: Vec<(f32, f32)> = if let Some(cfg) = col_config {
-            let mut x = sp.margin_left;
-            cfg.columns
-                .iter()
-                .map(|col| {
-                    let result = (x, col.width);
-                    x += col.width + col.space;
-                    result
-                })
-                .collect()
+        let (header, hdr_type) = if is_first && sp.different_first_page {
+            (sp.header_first.as_ref(), 1u8)
+        } else if doc.even_and_odd_headers && page_num % 2 == 0 && sp.header_even.is_some() {
+            (sp.header_even.as_ref(), 4u8)
         } else {
-            vec![(sp.margin_left, text_width)]
+            (sp.header_default.as_ref(), 0u8)
         };
-        let mut current_col: usize = 0;
-
-        let adjacent_para = |idx: usize| -> Option<&Paragraph> {
-            match section.blocks.get(idx)? {
-                Block::Paragraph(p) => Some(p),
-                Block::Table(_) => None,
+        if let Some(header_data) = header {
+            if let Some(watermark) = &header_data.watermark {
+                render_watermark(
+                    &mut hf,
+                    watermark,
+                    sp,
+                    &ctx,
+                    hf_watermark_names.get(&(si, hdr_type)).map(String::as_str),
+                    &mut pb.all_alpha_states[page_idx],
+                );
             }
+            let (pi_map, ii_map, fi_map) = build_hf_maps(si, hdr_type);
+            render_header_footer(
+                &mut hf,
+                header_data,
+                &ctx,
+                sp,
+                true,
+                &page_fields,
+                &pi_map,
+                &ii_map,
+                &fi_map,
+                page_styleref,
+                &mut pb.all_gradient_specs[page_idx],
+            );
+            has_hf = true;
+        }
+
+        let (footer, ftr_type) = if is_first && sp.different_first_page {
+            (sp.footer_first.as_ref(), 3u8)
+        } else if doc.even_and_odd_headers && page_num % 2 == 0 && sp.footer_even.is_some() {
+            (sp.footer_even.as_ref(), 5u8)
+        } else {
+            (sp.footer_default.as_ref(), 2u8)
         };
+        if let Some(footer_data) = footer {
+            let (pi_map, ii_map, fi_map) = build_hf_maps(si, ftr_type);
+            render_header_footer(
+                &mut hf,
+                footer_data,
+                &ctx,
+                sp,
+                false,
+                &page_fields,
+                &pi_map,
+                &ii_map,
+                &fi_map,
+                page_styleref,
+                &mut pb.all_gradient_specs[page_idx],
+            );
+            has_hf = true;
+        }
 
-        for (block_idx, block) in section.blocks.iter().enumerate() {
-            match block {
-                Block::Paragraph(para) => {
-                    // Skip empty section-break paragraphs — Word gives these zero height
-                    if para.is_section_break
-                        && is_text_empty(&para.runs)
-                        && para.image.is_none()
-                        && para.inline_chart.is_none()
-                        && para.smartart.is_none()
-                        && para.floating_images.is_empty()
-                        && para.textboxes.is_empty()
-                    {
+        hf.restore_state();
+        if has_hf {
+            *hf_content = Some(hf);
+        }
+    }
+
+    // Body content moves by both the mirror-margin and vertical-alignment shifts
+    let page_offsets: Vec<(f32, f32)> = mirror_shifts
+        .iter()
+        .zip(&page_shifts)
+        .map(|(&dx, &dy)| (dx, -dy))
+        .collect();
+
+    assemble_pdf_pages(
+        &mut pdf,
+        &mut alloc,
+        catalog_id,
+        pages_id,
+        pb.all_contents,
+        pb.all_behind_contents,
+        &mut all_hf_contents,
+        &pb.all_links,
+        &pb.all_alpha_states,
+        &pb.all_gradient_specs,
+        &pb.page_section_indices,
+        &page_offsets,
+        ctx.fonts,
+        &font_order,
+        &image_xobjects.placed,
+        &pb.bookmark_positions,
+        &pb.outline,
+        doc,
+        options,
+        progress,
+    );
+    write_image_xobjects(
+        image_xobjects.pending,
+        &mut pdf,
+        &mut alloc,
+        options.compression_level.min(10),
+    );
+    write_document_info(&mut pdf, alloc(), &doc.core_properties, options);
+
+    let t_assembly = t0.elapsed();
+
+    log::info!(
+        "Render phases: fonts={:.1}ms, images={:.1}ms, layout={:.1}ms, headers={:.1}ms, assembly={:.1}ms",
+        t_fonts.as_secs_f64() * 1000.0,
+        (t_images - t_fonts).as_secs_f64() * 1000.0,
+        (t_layout - t_images).as_secs_f64() * 1000.0,
+        (t_headers - t_layout).as_secs_f64() * 1000.0,
+        (t_assembly - t_headers).as_secs_f64() * 1000.0,
+    );
+
+    Ok(pdf.finish())
+}
+
+/// Lay out the body into pages, running it again when footnote numbers restart on
+/// each page or PAGEREF fields need the pages their bookmarks land on. Returns the
+/// final pages with the footnote marks and bookmark page numbers they were built with.
+fn paginate(
+    doc: &Document,
+    ctx: &RenderContext,
+    image_pdf_names: &HashMap<usize, String>,
+    inline_image_pdf_names: &HashMap<(usize, usize), String>,
+    floating_image_pdf_names: &HashMap<(usize, usize), String>,
+    smartart_font_key: &str,
+) -> (PageBuilder, HashMap<u32, String>, HashMap<String, String>) {
+    let layout_pass = |ctx: &RenderContext, footnote_marks: &HashMap<u32, String>| {
+        layout_body(
+            doc,
+            ctx,
+            image_pdf_names,
+            inline_image_pdf_names,
+            floating_image_pdf_names,
+            footnote_marks,
+            smartart_font_key,
+        )
+    };
+
+    // Footnote marks in reference order; per-page numbering needs the pages from a
+    // first layout pass
+    let mut footnote_marks = number_footnotes(doc);
+    let mut pb = layout_pass(ctx, &footnote_marks);
+    if doc.footnote_properties.restart == NoteRestart::EachPage {
+        footnote_marks = number_footnotes_per_page(&pb.all_footnote_ids, doc.footnote_properties);
+        pb = layout_pass(ctx, &footnote_marks);
+    }
+    let has_page_refs = collect_all_runs(doc)
+        .iter()
+        .any(|r| matches!(r.field_code, Some(FieldCode::PageRef(_))));
+    let bookmark_page_text = |pb: &PageBuilder| -> HashMap<String, String> {
+        pb.bookmark_positions
+            .iter()
+            .map(|(name, &(page_idx, _))| (name.clone(), pb.page_number_text(doc, page_idx)))
+            .collect()
+    };
+    let mut bookmark_pages = HashMap::new();
+    if has_page_refs {
+        bookmark_pages = bookmark_page_text(&pb);
+        let ctx = RenderContext {
+            bookmark_pages: &bookmark_pages,
+            ..*ctx
+        };
+        pb = layout_pass(&ctx, &footnote_marks);
+    }
+    // NUMPAGES in body text shows the total of the previous pass; a total
+    // with more digits can rewrap lines and move pages, so repeat until the
+    // page count settles
+    if body_has_page_total(doc) {
+        for _ in 0..MAX_PAGE_TOTAL_PASSES {
+            let total = pb.all_contents.len();
+            let ctx = RenderContext {
+                bookmark_pages: &bookmark_pages,
+                total_pages: Some(total),
+                ..*ctx
+            };
+            pb = layout_pass(&ctx, &footnote_marks);
+            if has_page_refs {
+                bookmark_pages = bookmark_page_text(&pb);
+            }
+            if pb.all_contents.len() == total {
+                break;
+            }
+        }
+    }
+    (pb, footnote_marks, bookmark_pages)
+}
+
+/// Number of pages `render` produces, from the same layout passes but without
+/// drawing headers and footers or assembling the PDF.
+pub fn page_count(doc: &Document, options: &ConversionOptions) -> usize {
+    let mut pdf = Pdf::new();
+    let mut next_id = 1i32;
+    let mut alloc = || {
+        let r = Ref::new(next_id);
+        next_id += 1;
+        r
+    };
+    let hyphenator = load_hyphenator(doc, options);
+    let (seen_fonts, font_order) =
+        collect_and_register_fonts(doc, &mut pdf, &mut alloc, hyphenator.is_some(), options);
+    // Layout only needs the image names; the pictures are never written
+    let images = embed_all_images(doc, &mut pdf, &mut alloc);
+    let ctx = RenderContext {
+        fonts: &seen_fonts,
+        doc_line_spacing: doc.line_spacing,
+        even_and_odd_headers: doc.even_and_odd_headers,
+        default_tab_stop: doc.default_tab_stop,
+        table_cell_image_names: &images.table_cell_image_names,
+        bookmark_pages: &HashMap::new(),
+        total_pages: None,
+        hyphenator: hyphenator.as_ref(),
+    };
+    let (pb, _, _) = paginate(
+        doc,
+        &ctx,
+        &images.image_pdf_names,
+        &images.inline_image_pdf_names,
+        &images.floating_image_pdf_names,
+        font_order.first().map(|s| s.as_str()).unwrap_or(""),
+    );
+    pb.all_contents.len()
+}
+
+/// Lay out the body of every section into pages, recording where bookmarked
+/// paragraphs land so PAGEREF fields can be resolved by a later pass.
+#[allow(clippy::too_many_arguments)]
+fn layout_body(
+    doc: &Document,
+    ctx: &RenderContext,
+    image_pdf_names: &HashMap<usize, String>,
+    inline_image_pdf_names: &HashMap<(usize, usize), String>,
+    floating_image_pdf_names: &HashMap<(usize, usize), String>,
+    footnote_marks: &HashMap<u32, String>,
+    smartart_font_key: &str,
+) -> PageBuilder {
+    let first_sp = &doc.sections[0].properties;
+    let mut cur_sp = first_sp;
+    let first_page_even = cur_sp.page_num_start.is_some_and(|n| n % 2 == 0);
+    let initial_slot_top = effective_slot_top(cur_sp, true, first_page_even, ctx);
+    let mut pb = PageBuilder::new(initial_slot_top, doc);
+    let mut prev_space_after: f32 = 0.0;
+    let mut effective_margin_bottom: f32 =
+        compute_effective_margin_bottom(cur_sp, true, first_page_even, ctx);
+    let mut global_block_idx: usize = 0;
+
+    for (sect_idx, section) in doc.sections.iter().enumerate() {
+        let sp = &section.properties;
+
+        // Section break handling (not for the first section)
+        if sect_idx > 0 {
+            match sp.break_type {
+                SectionBreakType::NextPage
+                | SectionBreakType::OddPage
+                | SectionBreakType::EvenPage => {
+                    pb.flush_page(sect_idx - 1);
+
+                    // Insert blank page for odd/even page alignment
+                    let need_odd = match sp.break_type {
+                        SectionBreakType::OddPage => true,
+                        _ if doc.even_and_odd_headers && sp.page_num_start.is_some() => {
+                            sp.page_num_start.unwrap() % 2 == 1
+                        }
+                        _ => false,
+                    };
+                    let need_even = match sp.break_type {
+                        SectionBreakType::EvenPage => true,
+                        _ if doc.even_and_odd_headers && sp.page_num_start.is_some() => {
+                            sp.page_num_start.unwrap() % 2 == 0
+                        }
+                        _ => false,
+                    };
+                    if need_odd || need_even {
+                        let next_phys = pb.page_count() + 1;
+                        let next_is_odd = next_phys % 2 == 1;
+                        if (need_odd && !next_is_odd) || (need_even && next_is_odd) {
+                            pb.push_blank_page(sect_idx - 1);
+                        }
+                    }
+
+                    let even = pb.is_even_page(sect_idx);
+                    pb.slot_top = effective_slot_top(sp, true, even, ctx);
+                    effective_margin_bottom = compute_effective_margin_bottom(sp, true, even, ctx);
+                }
+                SectionBreakType::Continuous => {
+                    // No forced break; geometry updates on next page
+                }
+            }
+            pb.is_first_page_of_section = true;
+        }
+
+        cur_sp = sp;
+        let text_width = sp.page_width - sp.margin_left - sp.margin_right;
+
+        // Column geometry: vec of (x_offset, width) for each column
+        let col_config = sp.columns.as_ref();
+        let col_count = col_config.map(|c| c.columns.len()).unwrap_or(1);
+        let col_geometry: Vec<(f32, f32)> = if let Some(cfg) = col_config {
+            let mut x = sp.margin_left;
+            cfg.columns
+                .iter()
+                .map(|col| {
+                    let result = (x, col.width);
+                    x += col.width + col.space;
+                    result
+                })
+                .collect()
+        } else {
+            vec![(sp.margin_left, text_width)]
+        };
+        let mut current_col: usize = 0;
+
+        let adjacent_para = |idx: usize| -> Option<&Paragraph> {
+            match section.blocks.get(idx)? {
+                Block::Paragraph(p) => Some(p),
+                Block::Table(_) => None,
+            }
+        };
+
+        for (block_idx, block) in section.blocks.iter().enumerate() {
+            match block {
+                Block::Paragraph(para) => {
+                    // Skip empty section-break paragraphs — Word gives these zero height
+                    if para.is_section_break
+                        && is_text_empty(&para.runs)
+                        && para.image.is_none()
+                        && para.inline_chart.is_none()
+                        && para.smartart.is_none()
+                        && para.floating_images.is_empty()
+                        && para.textboxes.is_empty()
+                    {
                         global_block_idx += 1;
                         continue;
                     }
@@ -1555,9 +2924,10 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                         let at_top = pb.is_at_page_top(cur_sp);
                         if !at_top {
                             pb.flush_page(sect_idx);
-                            pb.slot_top = effective_slot_top(cur_sp, false, &ctx);
+                            let even = pb.is_even_page(sect_idx);
+                            pb.slot_top = effective_slot_top(cur_sp, false, even, ctx);
                             effective_margin_bottom =
-                                compute_effective_margin_bottom(cur_sp, false, &ctx);
+                                compute_effective_margin_bottom(cur_sp, false, even, ctx);
                             pb.is_first_page_of_section = false;
                             current_col = 0;
                         }
@@ -1576,7 +2946,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             sect_idx,
                             cur_sp,
                             &mut effective_margin_bottom,
-                            &ctx,
+                            ctx,
                         );
                         prev_space_after = 0.0;
                     }
@@ -1615,25 +2985,39 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                     let para_text_width = (col_w - para.indent_left - para.indent_right).max(1.0);
                     let label_x = col_x + para.indent_left - para.indent_hanging;
                     let text_hanging = if !para.list_label.is_empty() {
-                        0.0
+                        list_text_hanging(para, ctx)
                     } else if para.indent_hanging > 0.0 {
                         para.indent_hanging
                     } else {
                         -para.indent_first_line
                     };
 
-                    // Substitute footnote reference runs with display numbers
+                    // Substitute footnote reference runs with display numbers,
+                    // PAGEREF results with the page their bookmark landed on and
+                    // NUMPAGES with the page total
                     let has_footnote_refs = para.runs.iter().any(|r| r.footnote_id.is_some());
-                    let effective_runs: std::borrow::Cow<'_, Vec<Run>> = if has_footnote_refs {
+                    let has_page_refs = para.runs.iter().any(|r| {
+                        matches!(
+                            r.field_code,
+                            Some(FieldCode::PageRef(_) | FieldCode::NumPages)
+                        )
+                    });
+                    let effective_runs: std::borrow::Cow<'_, Vec<Run>> = if has_footnote_refs
+                        || has_page_refs
+                    {
                         let substituted: Vec<Run> = para
                             .runs
                             .iter()
                             .map(|run| {
                                 if let Some(id) = run.footnote_id {
-                                    let num = footnote_display_order.get(&id).copied().unwrap_or(0);
                                     let mut r = run.clone();
-                                    r.text = num.to_string();
+                                    r.text = footnote_marks.get(&id).cloned().unwrap_or_default();
                                     r
+                                } else if let Some(page) = page_field_text(run, ctx) {
+                                    Run {
+                                        text: page,
+                                        ..run.clone()
+                                    }
                                 } else {
                                     run.clone()
                                 }
@@ -1646,6 +3030,16 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
 
                     let text_empty = is_text_empty(&effective_runs);
                     let has_tabs = effective_runs.iter().any(|r| r.is_tab);
+                    let wrap = square_wrap(
+                        para,
+                        sp,
+                        col_x,
+                        col_w,
+                        text_width,
+                        para_text_x,
+                        para_text_width,
+                        line_h,
+                    );
                     let block_inline_images: HashMap<usize, String> = inline_image_pdf_names
                         .iter()
                         .filter(|((bi, _), _)| *bi == global_block_idx)
@@ -1658,18 +3052,37 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             &effective_runs,
                             ctx.fonts,
                             &para.tab_stops,
+                            ctx.default_tab_stop,
                             para.indent_left,
                             para_text_width,
                             text_hanging,
                             &block_inline_images,
+                            para.rtl,
                         )
                     } else {
-                        build_paragraph_lines(
+                        // Text beside a drop cap or a square-wrapped image wraps
+                        // around it; a margin cap hangs outside the text
+                        let (indented_lines, indent_left, indent_right) = match &para.drop_cap {
+                            Some(dc) if !dc.in_margin => (
+                                0..dc.lines as usize,
+                                drop_cap_width(dc, ctx.fonts) + dc.h_space,
+                                0.0,
+                            ),
+                            _ => wrap.as_ref().map_or((0..0, 0.0, 0.0), |w| {
+                                (w.lines.clone(), w.indent_left, w.indent_right)
+                            }),
+                        };
+                        build_indented_paragraph_lines(
                             &effective_runs,
                             ctx.fonts,
                             para_text_width,
                             text_hanging,
                             &block_inline_images,
+                            indented_lines,
+                            indent_left,
+                            indent_right,
+                            ctx.hyphenator.filter(|_| !para.suppress_auto_hyphens),
+                            para.rtl,
                         )
                     };
 
@@ -1718,6 +3131,16 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             first_line_h + (num_lines - 1) as f32 * line_h
                         }
                     };
+                    // A short paragraph still makes room for its drop cap
+                    if let Some(dc) = &para.drop_cap
+                        && !text_empty
+                    {
+                        content_h = content_h.max(dc.lines as f32 * line_h);
+                    }
+                    // Text flowing beside an image ends no higher than the image
+                    if let Some(w) = &wrap {
+                        content_h = content_h.max(w.bottom);
+                    }
 
                     for fi in &para.floating_images {
                         let reserve = match fi.wrap_type {
@@ -1732,7 +3155,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                                 VerticalPosition::Offset(o) => o + fi.image.display_height,
                                 _ => fi.image.display_height,
                             };
-                            content_h = content_h.max(fi_h);
+                            content_h = content_h.max(fi_h + fi.dist_bottom);
                         }
                     }
 
@@ -1829,6 +3252,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                         if lines_that_fit >= 2 && lines_that_fit < lines.len() {
                             let first_part = &lines[..lines_that_fit];
                             pb.slot_top -= inter_gap;
+                            pb.record_bookmarks(para);
                             let ascender_ratio = tallest_ar.unwrap_or(0.75);
                             let baseline_y = pb.slot_top - font_size * ascender_ratio;
 
@@ -1855,6 +3279,17 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                                 text_hanging,
                                 ctx.fonts,
                             );
+                            pb.record_lines(first_part, baseline_y, line_h, col_x);
+                            if let Some(dc) = &para.drop_cap {
+                                render_drop_cap(
+                                    &mut pb.content,
+                                    dc,
+                                    para_text_x,
+                                    baseline_y,
+                                    line_h,
+                                    ctx.fonts,
+                                );
+                            }
 
                             pb.advance_column_or_page(
                                 &mut current_col,
@@ -1862,7 +3297,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                                 sect_idx,
                                 cur_sp,
                                 &mut effective_margin_bottom,
-                                &ctx,
+                                ctx,
                             );
 
                             let rest = &lines[lines_that_fit..];
@@ -1888,6 +3323,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                                 text_hanging,
                                 ctx.fonts,
                             );
+                            pb.record_lines(rest, baseline_y2, line_h, rest_col_x);
 
                             pb.slot_top -= rest_content_h;
                             prev_space_after = effective_space_after;
@@ -1901,7 +3337,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             sect_idx,
                             cur_sp,
                             &mut effective_margin_bottom,
-                            &ctx,
+                            ctx,
                         );
                         inter_gap = 0.0;
                     }
@@ -1918,6 +3354,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                     }
 
                     pb.slot_top -= inter_gap;
+                    pb.record_bookmarks(para);
 
                     // Re-fetch column geometry (may have changed after overflow)
                     let (col_x, col_w) = col_geometry[current_col];
@@ -1925,18 +3362,18 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                     let para_text_width = (col_w - para.indent_left - para.indent_right).max(1.0);
                     let label_x = col_x + para.indent_left - para.indent_hanging;
 
-                    // Render behind-doc layer: floating images + textboxes
-                    render_floating_images(
+                    // Floating images join their page's layers; behind-doc
+                    // textboxes render now
+                    place_floating_images(
                         &para.floating_images,
-                        true,
                         global_block_idx,
-                        &floating_image_pdf_names,
+                        floating_image_pdf_names,
                         sp,
                         col_x,
                         col_w,
                         text_width,
                         pb.slot_top,
-                        &mut pb.content,
+                        &mut pb,
                     );
                     for tb in para.textboxes.iter().filter(|t| t.behind_doc) {
                         render_single_textbox(
@@ -1948,7 +3385,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             pb.slot_top,
                             &mut pb.content,
                             &mut pb.gradient_specs,
-                            &ctx,
+                            ctx,
                             &mut pb.links,
                         );
                     }
@@ -1987,19 +3424,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                         pb.content.restore_state();
                     }
 
-                    // Render foreground layer: floating images + textboxes
-                    render_floating_images(
-                        &para.floating_images,
-                        false,
-                        global_block_idx,
-                        &floating_image_pdf_names,
-                        sp,
-                        col_x,
-                        col_w,
-                        text_width,
-                        pb.slot_top,
-                        &mut pb.content,
-                    );
+                    // Render foreground textboxes
                     for tb in para.textboxes.iter().filter(|t| !t.behind_doc) {
                         render_single_textbox(
                             tb,
@@ -2010,7 +3435,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             pb.slot_top,
                             &mut pb.content,
                             &mut pb.gradient_specs,
-                            &ctx,
+                            ctx,
                             &mut pb.links,
                         );
                     }
@@ -2029,7 +3454,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                         let default_font = ctx
                             .fonts
                             .keys()
-                            .next()
+                            .min()
                             .map(|s| s.as_str())
                             .unwrap_or("Helvetica");
                         charts::render_chart(
@@ -2071,6 +3496,17 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             ]);
                             pb.content.x_object(Name(pdf_name.as_bytes()));
                             pb.content.restore_state();
+                            if let Some(url) = &img.hyperlink_url {
+                                pb.links.push(LinkAnnotation {
+                                    rect: Rect::new(
+                                        x,
+                                        y_bottom,
+                                        x + img.display_width,
+                                        pb.slot_top,
+                                    ),
+                                    target: LinkTarget::Url(url.clone()),
+                                });
+                            }
                         } else if para.image.is_some() {
                             pb.content
                                 .set_fill_gray(0.5)
@@ -2105,6 +3541,17 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                             text_hanging,
                             ctx.fonts,
                         );
+                        pb.record_lines(&lines, baseline_y, line_h, col_x);
+                        if let Some(dc) = &para.drop_cap {
+                            render_drop_cap(
+                                &mut pb.content,
+                                dc,
+                                para_text_x,
+                                baseline_y,
+                                line_h,
+                                ctx.fonts,
+                            );
+                        }
                     }
 
                     // Draw paragraph borders — left/right borders extend outward
@@ -2193,7 +3640,7 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                                 pb.footnote_ids.push(id);
                                 if let Some(footnote) = doc.footnotes.get(&id) {
                                     let fn_height =
-                                        compute_footnote_height(footnote, &ctx, text_width);
+                                        compute_footnote_height(footnote, ctx, text_width);
                                     let separator_h = if pb.footnote_ids.len() == 1 {
                                         12.0
                                     } else {
@@ -2214,9 +3661,10 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
 
                     if para.page_break_after {
                         pb.flush_page(sect_idx);
-                        pb.slot_top = effective_slot_top(cur_sp, false, &ctx);
+                        let even = pb.is_even_page(sect_idx);
+                        pb.slot_top = effective_slot_top(cur_sp, false, even, ctx);
                         effective_margin_bottom =
-                            compute_effective_margin_bottom(cur_sp, false, &ctx);
+                            compute_effective_margin_bottom(cur_sp, false, even, ctx);
                         pb.is_first_page_of_section = false;
                         prev_space_after = 0.0;
                         current_col = 0;
@@ -2265,10 +3713,14 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                         };
                         (x, y, restore)
                     });
+                    // Bookmarks inside the table resolve to where the table starts
+                    for p in table.paragraphs() {
+                        pb.record_bookmarks(p);
+                    }
                     render_table(
                         table,
                         sp,
-                        &ctx,
+                        ctx,
                         &mut pb,
                         sect_idx,
                         prev_space_after,
@@ -2276,223 +3728,22 @@ pub fn render(doc: &Document) -> Result<Vec<u8>, Error> {
                     );
                     prev_space_after = 0.0;
 
-                    for row in &table.rows {
-                        for cell in &row.cells {
-                            for p in &cell.paragraphs {
-                                update_styleref_from_para(
-                                    &mut pb.styleref_running,
-                                    &mut pb.styleref_page_first,
-                                    p,
-                                    &doc.style_id_to_name,
-                                );
-                            }
-                        }
+                    for p in table.paragraphs() {
+                        update_styleref_from_para(
+                            &mut pb.styleref_running,
+                            &mut pb.styleref_page_first,
+                            p,
+                            &doc.style_id_to_name,
+                        );
                     }
                 }
             }
             global_block_idx += 1;
         }
     }
+    render_endnotes(&mut pb, doc, ctx, &mut effective_margin_bottom);
     pb.flush_page(doc.sections.len() - 1);
-
-    let t_layout = t0.elapsed();
-
-    // Phase 2b: column separator lines
-    for (page_idx, content) in pb.all_contents.iter_mut().enumerate() {
-        let (si, _) = pb.page_section_indices[page_idx];
-        let sp = &doc.sections[si].properties;
-
-        if let Some(cfg) = &sp.columns {
-            if cfg.sep {
-                let mut x = sp.margin_left;
-                for (i, col) in cfg.columns.iter().enumerate() {
-                    x += col.width;
-                    if i < cfg.columns.len() - 1 {
-                        let mid_x = x + col.space / 2.0;
-                        content.save_state();
-                        content.set_line_width(0.5);
-                        content.move_to(mid_x, sp.margin_bottom);
-                        content.line_to(mid_x, sp.page_height - sp.margin_top);
-                        content.stroke();
-                        content.restore_state();
-                        x += col.space;
-                    }
-                }
-            }
-        }
-    }
-
-    // Phase 2c: render footnotes at page bottom
-    for (page_idx, content) in pb.all_contents.iter_mut().enumerate() {
-        let (si, _) = pb.page_section_indices[page_idx];
-        let sp = &doc.sections[si].properties;
-        let text_width = sp.page_width - sp.margin_left - sp.margin_right;
-        render_page_footnotes(
-            content,
-            &pb.all_footnote_ids[page_idx],
-            &doc.footnotes,
-            &footnote_display_order,
-            &ctx,
-            sp.margin_left,
-            sp.margin_bottom,
-            text_width,
-        );
-    }
-
-    let t_headers = t0.elapsed();
-
-    // Phase 2d: render headers/footers into separate content streams (behind body)
-    let total_pages = pb.all_contents.len();
-    let build_hf_maps = |si: usize,
-                         hf_type: u8|
-     -> (
-        HashMap<usize, String>,
-        HashMap<(usize, usize), String>,
-        HashMap<(usize, usize), String>,
-    ) {
-        let pi_map: HashMap<usize, String> = hf_image_names
-            .iter()
-            .filter(|((s, t, _), _)| *s == si && *t == hf_type)
-            .map(|((_, _, pi), name)| (*pi, name.clone()))
-            .collect();
-        let ii_map: HashMap<(usize, usize), String> = hf_inline_image_names
-            .iter()
-            .filter(|((s, t, _, _), _)| *s == si && *t == hf_type)
-            .map(|((_, _, pi, ri), name)| ((*pi, *ri), name.clone()))
-            .collect();
-        let fi_map: HashMap<(usize, usize), String> = hf_floating_image_names
-            .iter()
-            .filter(|((s, t, _, _), _)| *s == si && *t == hf_type)
-            .map(|((_, _, pi, fi), name)| ((*pi, *fi), name.clone()))
-            .collect();
-        (pi_map, ii_map, fi_map)
-    };
-
-    let empty_styleref: HashMap<String, String> = HashMap::new();
-    let mut all_hf_contents: Vec<Option<Content>> = (0..total_pages).map(|_| None).collect();
-    for (page_idx, hf_content) in all_hf_contents.iter_mut().enumerate() {
-        let (si, is_first) = pb.page_section_indices[page_idx];
-        let sp = &doc.sections[si].properties;
-
-        let page_num = if let Some(start) = sp.page_num_start {
-            // Section specifies explicit start: count pages within this section
-            let pages_before_in_section = pb.page_section_indices[..page_idx]
-                .iter()
-                .filter(|&&(s, _)| s == si)
-                .count();
-            start as usize + pages_before_in_section
-        } else {
-            // No explicit start: continue absolute numbering
-            page_idx + 1
-        };
-
-        // Per spec §17.16.5.59: in headers/footers of a printed document, STYLEREF
-        // searches the current page top-to-bottom first, then backward to doc start.
-        let page_first = pb
-            .all_first_styleref
-            .get(page_idx)
-            .unwrap_or(&empty_styleref);
-        let prev_running = if page_idx > 0 {
-            pb.all_styleref.get(page_idx - 1).unwrap_or(&empty_styleref)
-        } else {
-            &empty_styleref
-        };
-        let mut page_styleref_merged = prev_running.clone();
-        // Current-page first occurrences take priority (top-to-bottom search)
-        for (k, v) in page_first {
-            page_styleref_merged.insert(k.clone(), v.clone());
-        }
-        let page_styleref = &page_styleref_merged;
-
-        let mut hf = Content::new();
-        let mut has_hf = false;
-
-        let (header, hdr_type) = if is_first && sp.different_first_page {
-            (sp.header_first.as_ref(), 1u8)
-        } else if doc.even_and_odd_headers && page_num % 2 == 0 && sp.header_even.is_some() {
-            (sp.header_even.as_ref(), 4u8)
-        } else {
-            (sp.header_default.as_ref(), 0u8)
-        };
-        if let Some(header_data) = header {
-            let (pi_map, ii_map, fi_map) = build_hf_maps(si, hdr_type);
-            render_header_footer(
-                &mut hf,
-                header_data,
-                &ctx,
-                sp,
-                true,
-                page_num,
-                total_pages,
-                &pi_map,
-                &ii_map,
-                &fi_map,
-                page_styleref,
-                &mut pb.all_gradient_specs[page_idx],
-            );
-            has_hf = true;
-        }
-
-        let (footer, ftr_type) = if is_first && sp.different_first_page {
-            (sp.footer_first.as_ref(), 3u8)
-        } else if doc.even_and_odd_headers && page_num % 2 == 0 && sp.footer_even.is_some() {
-            (sp.footer_even.as_ref(), 5u8)
-        } else {
-            (sp.footer_default.as_ref(), 2u8)
-        };
-        if let Some(footer_data) = footer {
-            let (pi_map, ii_map, fi_map) = build_hf_maps(si, ftr_type);
-            render_header_footer(
-                &mut hf,
-                footer_data,
-                &ctx,
-                sp,
-                false,
-                page_num,
-                total_pages,
-                &pi_map,
-                &ii_map,
-                &fi_map,
-                page_styleref,
-                &mut pb.all_gradient_specs[page_idx],
-            );
-            has_hf = true;
-        }
-
-        if has_hf {
-            *hf_content = Some(hf);
-        }
-    }
-
-    assemble_pdf_pages(
-        &mut pdf,
-        &mut alloc,
-        catalog_id,
-        pages_id,
-        pb.all_contents,
-        &mut all_hf_contents,
-        &pb.all_links,
-        &pb.all_alpha_states,
-        &pb.all_gradient_specs,
-        &pb.page_section_indices,
-        ctx.fonts,
-        &font_order,
-        &image_xobjects,
-        doc,
-    );
-
-    let t_assembly = t0.elapsed();
-
-    log::info!(
-        "Render phases: fonts={:.1}ms, images={:.1}ms, layout={:.1}ms, headers={:.1}ms, assembly={:.1}ms",
-        t_fonts.as_secs_f64() * 1000.0,
-        (t_images - t_fonts).as_secs_f64() * 1000.0,
-        (t_layout - t_images).as_secs_f64() * 1000.0,
-        (t_headers - t_layout).as_secs_f64() * 1000.0,
-        (t_assembly - t_headers).as_secs_f64() * 1000.0,
-    );
-
-    Ok(pdf.finish())
+    pb
 }
 
 fn label_font_key(para: &Paragraph) -> Option<String> {
@@ -2529,6 +3780,95 @@ fn label_for_paragraph<'a>(
     (entry.pdf_name.as_str(), bytes)
 }
 
+fn drop_cap_width(dc: &DropCap, fonts: &HashMap<String, FontEntry>) -> f32 {
+    dc.runs
+        .iter()
+        .filter_map(|run| {
+            let entry = fonts.get(&font_key(run))?;
+            Some(entry.word_width(run.text.trim(), run.font_size, false))
+        })
+        .sum()
+}
+
+/// Draws a drop cap with its baseline on the last line it drops through,
+/// starting at the text's left edge or, for a margin cap, ending before it.
+fn render_drop_cap(
+    content: &mut Content,
+    dc: &DropCap,
+    text_x: f32,
+    first_baseline_y: f32,
+    line_h: f32,
+    fonts: &HashMap<String, FontEntry>,
+) {
+    let baseline_y = first_baseline_y - (dc.lines - 1) as f32 * line_h;
+    let mut x = if dc.in_margin {
+        text_x - dc.h_space - drop_cap_width(dc, fonts)
+    } else {
+        text_x
+    };
+    for run in &dc.runs {
+        let Some(entry) = fonts.get(&font_key(run)) else {
+            continue;
+        };
+        let text = run.text.trim();
+        let bytes = match &entry.char_to_gid {
+            Some(map) => encode_as_gids(text, map),
+            None => to_winansi_bytes(text),
+        };
+        if let Some([r, g, b]) = run.color {
+            content.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+        }
+        content
+            .begin_text()
+            .set_font(Name(entry.pdf_name.as_bytes()), run.font_size)
+            .next_line(x, baseline_y)
+            .show(Str(&bytes))
+            .end_text();
+        if run.color.is_some() {
+            content.set_fill_gray(0.0);
+        }
+        x += entry.word_width(text, run.font_size, false);
+    }
+}
+
+/// Draws the numbers of counted lines right-aligned `distance` left of their column.
+fn render_line_numbers(
+    content: &mut Content,
+    ln: &LineNumbering,
+    marks: &[(f32, f32)],
+    first: u32,
+    fonts: &HashMap<String, FontEntry>,
+) {
+    let Some(entry) = fonts.get(&font_key(&ln.run)) else {
+        return;
+    };
+    let font_size = ln.run.font_size;
+    if let Some([r, g, b]) = ln.run.color {
+        content.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    }
+    for (i, &(col_x, baseline_y)) in marks.iter().enumerate() {
+        let number = first + i as u32;
+        if !number.is_multiple_of(ln.count_by) {
+            continue;
+        }
+        let text = number.to_string();
+        let width = entry.word_width(&text, font_size, false);
+        let bytes = match &entry.char_to_gid {
+            Some(map) => encode_as_gids(&text, map),
+            None => to_winansi_bytes(&text),
+        };
+        content
+            .begin_text()
+            .set_font(Name(entry.pdf_name.as_bytes()), font_size)
+            .next_line(col_x - ln.distance - width, baseline_y)
+            .show(Str(&bytes))
+            .end_text();
+    }
+    if ln.run.color.is_some() {
+        content.set_fill_gray(0.0);
+    }
+}
+
 fn render_list_label(
     content: &mut Content,
     para: &Paragraph,
@@ -2558,3 +3898,413 @@ fn render_list_label(
         content.set_fill_gray(0.0);
     }
 }
+
+/// First-line hanging of a list paragraph's text, from where `w:suff` puts
+/// it: at the hanging indent, or the next tab stop when the label runs past
+/// it; one space after the label; or right against it.
+fn list_text_hanging(para: &Paragraph, ctx: &RenderContext) -> f32 {
+    let label_w = label_font_key(para)
+        .and_then(|key| ctx.fonts.get(&key))
+        .map(|entry| {
+            let fs = para
+                .list_label_font_size
+                .or_else(|| para.runs.first().map(|r| r.font_size))
+                .unwrap_or(11.0);
+            let suffix = match para.list_label_suffix {
+                LabelSuffix::Space => " ",
+                LabelSuffix::Tab | LabelSuffix::Nothing => "",
+            };
+            entry.word_width(&format!("{}{suffix}", para.list_label), fs, false)
+        })
+        .unwrap_or(0.0);
+    match para.list_label_suffix {
+        LabelSuffix::Space | LabelSuffix::Nothing => para.indent_hanging - label_w,
+        LabelSuffix::Tab => {
+            let label_end = para.indent_left - para.indent_hanging + label_w;
+            if label_end <= para.indent_left {
+                return 0.0;
+            }
+            let next_stop = para
+                .tab_stops
+                .iter()
+                .map(|t| t.position)
+                .find(|&pos| pos > label_end)
+                .unwrap_or(next_default_tab(label_end, ctx.default_tab_stop));
+            para.indent_left - next_stop
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for &b in bytes {
+            crc ^= b as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    0xEDB8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    fn push_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = out.len();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        let crc = crc32(&out[start..]);
+        out.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    /// Builds a single-row palette PNG (color type 3) at the given bit depth.
+    fn indexed_png(bit_depth: u8, palette: &[[u8; 3]], trns: Option<&[u8]>, row: &[u8]) -> Vec<u8> {
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&(row.len() as u32).to_be_bytes());
+        ihdr.extend_from_slice(&1u32.to_be_bytes());
+        ihdr.extend_from_slice(&[bit_depth, 3, 0, 0, 0]);
+        push_chunk(&mut png, b"IHDR", &ihdr);
+        push_chunk(&mut png, b"PLTE", &palette.concat());
+        if let Some(trns) = trns {
+            push_chunk(&mut png, b"tRNS", trns);
+        }
+
+        let per_byte = 8 / bit_depth as usize;
+        let mut scanline = vec![0u8]; // filter type None
+        for chunk in row.chunks(per_byte) {
+            let mut byte = 0u8;
+            for (i, &idx) in chunk.iter().enumerate() {
+                byte |= idx << (8 - bit_depth as usize * (i + 1));
+            }
+            scanline.push(byte);
+        }
+        let idat = miniz_oxide::deflate::compress_to_vec_zlib(&scanline, 6);
+        push_chunk(&mut png, b"IDAT", &idat);
+        push_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn pdf_date_from_w3cdtf() {
+        assert_eq!(
+            pdf_date("2024-03-05T14:30:09Z"),
+            Some(
+                Date::new(2024)
+                    .month(3)
+                    .day(5)
+                    .hour(14)
+                    .minute(30)
+                    .second(9)
+                    .utc_offset_hour(0)
+            )
+        );
+        assert_eq!(
+            pdf_date("2024-03-05T14:30:09-05:30"),
+            Some(
+                Date::new(2024)
+                    .month(3)
+                    .day(5)
+                    .hour(14)
+                    .minute(30)
+                    .second(9)
+                    .utc_offset_hour(-5)
+                    .utc_offset_minute(30)
+            )
+        );
+        assert_eq!(
+            pdf_date("2024-03-05"),
+            Some(Date::new(2024).month(3).day(5))
+        );
+        assert_eq!(pdf_date("yesterday"), None);
+    }
+
+    #[test]
+    fn soft_hyphen_subsets_the_visible_hyphen() {
+        let doc =
+            crate::docx::testing::parse_body("<w:p><w:r><w:t>co\u{AD}operate</w:t></w:r></w:p>");
+        let runs = collect_all_runs(&doc);
+        let used = collect_used_chars(&doc, &runs, false);
+        let chars = used.values().next().expect("one font");
+        assert!(chars.contains(&'-'));
+        assert!(!chars.contains(&'\u{AD}'));
+    }
+
+    const PALETTE: [[u8; 3]; 4] = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [10, 20, 30]];
+
+    #[test]
+    fn reused_picture_is_written_once() {
+        let png = indexed_png(8, &PALETTE, None, &[0, 1, 2, 3]);
+        // The parser reads each reference to a picture into its own buffer
+        let images: Vec<EmbeddedImage> = (0..50)
+            .map(|_| EmbeddedImage {
+                data: std::sync::Arc::new(png.clone()),
+                format: ImageFormat::Png,
+                pixel_width: 4,
+                pixel_height: 1,
+                display_width: 40.0,
+                display_height: 10.0,
+                jpeg_components: 0,
+                jpeg_adobe_inverted: false,
+                layout_extra_height: 0.0,
+                crop: crate::model::ImageCrop::default(),
+                rotation: 0.0,
+                alt_text: None,
+                hyperlink_url: None,
+            })
+            .collect();
+
+        let mut pdf = Pdf::new();
+        let mut next_id = 1;
+        let mut alloc = || {
+            next_id += 1;
+            Ref::new(next_id)
+        };
+        let mut xobjects = ImageXObjects::default();
+        let names: HashSet<String> = images
+            .iter()
+            .map(|img| embed_single_image(img, &mut xobjects, &mut pdf, &mut alloc))
+            .collect();
+        assert_eq!(names.len(), 1);
+        assert_eq!(xobjects.placed.len(), 1);
+        write_image_xobjects(xobjects.pending, &mut pdf, &mut alloc, 6);
+
+        let bytes = pdf.finish();
+        let marker = b"/Subtype /Image";
+        let count = bytes.windows(marker.len()).filter(|w| *w == marker).count();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn cropped_picture_scales_the_kept_region_to_the_unit_square() {
+        let img = EmbeddedImage {
+            data: std::sync::Arc::new(Vec::new()),
+            format: ImageFormat::Png,
+            pixel_width: 4,
+            pixel_height: 4,
+            display_width: 40.0,
+            display_height: 40.0,
+            jpeg_components: 0,
+            jpeg_adobe_inverted: false,
+            layout_extra_height: 0.0,
+            crop: crate::model::ImageCrop {
+                left: 0.25,
+                top: 0.25,
+                right: 0.25,
+                bottom: 0.25,
+            },
+            rotation: 0.0,
+            alt_text: None,
+            hyperlink_url: None,
+        };
+        let mut pdf = Pdf::new();
+        let mut next_id = 1;
+        let mut alloc = || {
+            next_id += 1;
+            Ref::new(next_id)
+        };
+        wrap_placed_image(&img, Ref::new(1), &mut pdf, &mut alloc);
+
+        let bytes = pdf.finish();
+        let has = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+        assert!(has(b"0 0 1 1 re\nW\nn\n2 0 0 2 -0.5 -0.5 cm\n/Src Do"));
+    }
+
+    #[test]
+    fn indexed_png_with_trns_keeps_per_index_alpha() {
+        let png = indexed_png(8, &PALETTE, Some(&[0, 128]), &[0, 1, 2, 3]);
+        let rgba = decode_raster(&png, ImageFormat::Png).unwrap().to_rgba8();
+        let (rgb, alpha) = split_alpha(rgba.as_raw(), 4);
+        assert_eq!(rgb, PALETTE.concat());
+        assert_eq!(alpha, Some(vec![0, 128, 255, 255]));
+    }
+
+    #[test]
+    fn indexed_png_without_trns_is_opaque() {
+        let png = indexed_png(8, &PALETTE, None, &[3, 2, 1, 0]);
+        let rgba = decode_raster(&png, ImageFormat::Png).unwrap().to_rgba8();
+        let (rgb, alpha) = split_alpha(rgba.as_raw(), 4);
+        assert_eq!(
+            rgb,
+            [PALETTE[3], PALETTE[2], PALETTE[1], PALETTE[0]].concat()
+        );
+        assert_eq!(alpha, None);
+    }
+
+    #[test]
+    fn indexed_png_low_bit_depths_expand() {
+        let row = [1, 0, 1, 1, 0, 0, 1, 0, 1];
+        for bit_depth in [1, 2, 4] {
+            let png = indexed_png(bit_depth, &PALETTE[..2], Some(&[255, 64]), &row);
+            let rgba = decode_raster(&png, ImageFormat::Png).unwrap().to_rgba8();
+            assert_eq!(rgba.width(), row.len() as u32, "bit depth {bit_depth}");
+            let (rgb, alpha) = split_alpha(rgba.as_raw(), 4);
+            let expected_rgb: Vec<u8> = row.iter().flat_map(|&i| PALETTE[i as usize]).collect();
+            let expected_alpha: Vec<u8> = row.iter().map(|&i| [255, 64][i as usize]).collect();
+            assert_eq!(rgb, expected_rgb, "bit depth {bit_depth}");
+            assert_eq!(alpha, Some(expected_alpha), "bit depth {bit_depth}");
+        }
+    }
+
+    fn encoded(image: image::DynamicImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut data = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut data), format)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn gif_decodes_to_its_palette_colors() {
+        let mut rgb = image::RgbImage::new(2, 1);
+        rgb.put_pixel(0, 0, image::Rgb([255, 0, 0]));
+        rgb.put_pixel(1, 0, image::Rgb([0, 0, 255]));
+        let gif = encoded(image::DynamicImage::ImageRgb8(rgb), image::ImageFormat::Gif);
+
+        let decoded = decode_raster(&gif, ImageFormat::Gif).unwrap();
+        assert_eq!(decoded.to_rgb8().as_raw(), &[255, 0, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn bmp_24_and_32_bit_decode() {
+        let mut rgb = image::RgbImage::new(2, 2);
+        rgb.put_pixel(1, 0, image::Rgb([10, 20, 30]));
+        rgb.put_pixel(0, 1, image::Rgb([200, 100, 50]));
+        let bmp24 = encoded(image::DynamicImage::ImageRgb8(rgb), image::ImageFormat::Bmp);
+        let decoded = decode_raster(&bmp24, ImageFormat::Bmp).unwrap().to_rgb8();
+        // Bottom-up rows come back top row first
+        assert_eq!(decoded.get_pixel(1, 0).0, [10, 20, 30]);
+        assert_eq!(decoded.get_pixel(0, 1).0, [200, 100, 50]);
+
+        let mut rgba = image::RgbaImage::new(2, 1);
+        rgba.put_pixel(0, 0, image::Rgba([1, 2, 3, 255]));
+        rgba.put_pixel(1, 0, image::Rgba([4, 5, 6, 128]));
+        let bmp32 = encoded(
+            image::DynamicImage::ImageRgba8(rgba),
+            image::ImageFormat::Bmp,
+        );
+        let decoded = decode_raster(&bmp32, ImageFormat::Bmp).unwrap();
+        let (color, alpha) = split_alpha(decoded.to_rgba8().as_raw(), 4);
+        assert_eq!(color, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(alpha, Some(vec![255, 128]));
+    }
+
+    #[test]
+    fn gray_and_rgb_tiff_decode() {
+        let gray = image::GrayImage::from_raw(2, 1, vec![30, 220]).unwrap();
+        let tiff = encoded(
+            image::DynamicImage::ImageLuma8(gray),
+            image::ImageFormat::Tiff,
+        );
+        let decoded = decode_raster(&tiff, ImageFormat::Tiff).unwrap();
+        assert!(!decoded.color().has_color());
+        assert_eq!(decoded.to_luma8().as_raw(), &[30, 220]);
+
+        let rgb = image::RgbImage::from_raw(1, 2, vec![1, 2, 3, 250, 251, 252]).unwrap();
+        let tiff = encoded(
+            image::DynamicImage::ImageRgb8(rgb),
+            image::ImageFormat::Tiff,
+        );
+        let decoded = decode_raster(&tiff, ImageFormat::Tiff).unwrap();
+        assert_eq!(decoded.to_rgb8().as_raw(), &[1, 2, 3, 250, 251, 252]);
+    }
+
+    #[test]
+    fn gray_png_stays_single_channel() {
+        let mut gray = image::GrayAlphaImage::new(2, 1);
+        gray.put_pixel(0, 0, image::LumaA([40, 255]));
+        gray.put_pixel(1, 0, image::LumaA([200, 90]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageLumaA8(gray)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let decoded = decode_raster(&png, ImageFormat::Png).unwrap();
+        assert!(!decoded.color().has_color());
+        let (color, alpha) = split_alpha(decoded.to_luma_alpha8().as_raw(), 2);
+        assert_eq!(color, vec![40, 200]);
+        assert_eq!(alpha, Some(vec![255, 90]));
+    }
+
+    #[test]
+    fn sixteen_bit_png_reduces_to_eight_bits() {
+        let mut rgb = image::ImageBuffer::<image::Rgb<u16>, Vec<u16>>::new(1, 1);
+        rgb.put_pixel(0, 0, image::Rgb([0xFFFF, 0x8080, 0x0000]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb16(rgb)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let decoded = decode_raster(&png, ImageFormat::Png).unwrap();
+        let (color, alpha) = split_alpha(decoded.to_rgba8().as_raw(), 4);
+        assert_eq!(color, vec![255, 128, 0]);
+        assert_eq!(alpha, None);
+    }
+
+    #[test]
+    fn outline_nests_headings_under_the_previous_lower_level() {
+        let entry = |level, title: &str| OutlineEntry {
+            level,
+            title: title.to_string(),
+            page_idx: 0,
+            top: 700.0,
+        };
+        let entries = [
+            entry(1, "Intro"),
+            entry(2, "Scope"),
+            entry(2, "Terms"),
+            entry(1, "Results"),
+        ];
+        let mut pdf = Pdf::new();
+        let mut next_id = 1;
+        let mut alloc = || {
+            next_id += 1;
+            Ref::new(next_id)
+        };
+        // Root is 2 0 R, then one object per entry: 3 Intro ... 6 Results
+        let root = write_outline(&mut pdf, &mut alloc, &[Ref::new(100)], &entries);
+        assert_eq!(root, Some(Ref::new(2)));
+
+        let bytes = String::from_utf8_lossy(&pdf.finish()).into_owned();
+        let object = |id: i32| {
+            let start = bytes.find(&format!("{id} 0 obj")).unwrap();
+            let len = bytes[start..].find("endobj").unwrap();
+            bytes[start..start + len].to_string()
+        };
+        let root = object(2);
+        assert!(root.contains("/First 3 0 R") && root.contains("/Last 6 0 R"));
+        let intro = object(3);
+        assert!(intro.contains("(Intro)") && intro.contains("/Parent 2 0 R"));
+        assert!(intro.contains("/First 4 0 R") && intro.contains("/Last 5 0 R"));
+        assert!(intro.contains("/Next 6 0 R"));
+        let terms = object(5);
+        assert!(terms.contains("/Parent 3 0 R") && terms.contains("/Prev 4 0 R"));
+        assert!(!object(6).contains("/First"));
+    }
+
+    #[test]
+    fn page_numbers_continue_after_a_restarted_section() {
+        let section = |num_type: &str| {
+            format!(r#"<w:p><w:pPr><w:sectPr>{num_type}</w:sectPr></w:pPr></w:p>"#)
+        };
+        let body = format!(
+            "{}{}{}<w:p/><w:sectPr/>",
+            section(""),
+            section(r#"<w:pgNumType w:start="10"/>"#),
+            section(""),
+        );
+        let doc = crate::docx::testing::parse_body(&body);
+        let mut pb = PageBuilder::new(700.0, &doc);
+        for sect_idx in [0, 0, 1, 1, 2, 3, 3] {
+            pb.push_blank_page(sect_idx);
+        }
+        let numbers: Vec<usize> = (0..7).map(|i| pb.display_page_number(i)).collect();
+        assert_eq!(numbers, [1, 2, 10, 11, 12, 13, 14]);
+    }
+}
@@ -81,9 +81,12 @@ pub(super) fn render_smartart(
     seen_fonts: &HashMap<String, FontEntry>,
     smartart_font_key: &str,
 ) {
-    let sa_font_entry = seen_fonts
-        .get(smartart_font_key)
-        .or_else(|| seen_fonts.values().next());
+    let sa_font_entry = seen_fonts.get(smartart_font_key).or_else(|| {
+        seen_fonts
+            .iter()
+            .min_by_key(|(key, _)| *key)
+            .map(|(_, e)| e)
+    });
     let sa_font_pdf_name = sa_font_entry.map(|e| e.pdf_name.as_str()).unwrap_or("F1");
 
     for shape in &diagram.shapes {
@@ -4,17 +4,22 @@ use pdf_writer::{Content, Name, Str};
 
 use crate::fonts::{FontEntry, encode_as_gids, font_key_buf, to_winansi_bytes};
 use crate::model::{
-    Alignment, CellBorder, CellMargins, CellVAlign, SectionProperties, Table, TableRow,
-    TextDirection, VMerge,
+    Alignment, CellBorder, CellMargins, CellVAlign, NestedTable, PreferredWidth, Run,
+    SectionProperties, Table, TableRow, TextDirection, VMerge,
 };
 
-use super::RenderContext;
-use super::header_footer::substitute_hf_runs;
+use super::header_footer::{PageFields, StyleRefValues, substitute_hf_runs};
 use super::layout::{
-    TextLine, build_paragraph_lines, encode_text_for_pdf, font_metric, is_text_empty,
-    render_paragraph_lines,
+    TextLine, build_indented_paragraph_lines, encode_text_for_pdf, font_metric, is_cjk_break_char,
+    is_text_empty, render_paragraph_lines,
 };
 use super::resolve_line_h;
+use super::{RenderContext, page_field_text};
+
+/// Width between the section's margins, which percentage table widths refer to.
+fn text_width(sp: &SectionProperties) -> f32 {
+    sp.page_width - sp.margin_left - sp.margin_right
+}
 
 fn cell_span_width(col_widths: &[f32], grid_col: usize, span: usize) -> f32 {
     col_widths[grid_col..col_widths.len().min(grid_col + span)]
@@ -62,6 +67,8 @@ fn draw_cell_borders(
     }
     draw_border(content, &borders.left, bx, top, bx, bottom);
     draw_border(content, &borders.right, bx + col_w, top, bx + col_w, bottom);
+    draw_border(content, &borders.tl2br, bx, top, bx + col_w, bottom);
+    draw_border(content, &borders.tr2bl, bx + col_w, top, bx, bottom);
 }
 
 fn draw_cell_shading(
@@ -95,16 +102,19 @@ fn valign_offset(v_align: CellVAlign, available: f32, content_h: f32) -> f32 {
 }
 
 fn para_has_visible_content(para: &CellParagraphLayout) -> bool {
-    !para.lines.is_empty() && para.lines.iter().any(|l| !l.chunks.is_empty())
+    para.nested_table.is_some()
+        || (!para.lines.is_empty() && para.lines.iter().any(|l| !l.chunks.is_empty()))
 }
 
 fn cell_has_visible_content(paragraphs: &[CellParagraphLayout]) -> bool {
     paragraphs.iter().any(|p| para_has_visible_content(p))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_cell_paragraphs(
     content: &mut Content,
     paragraphs: &[CellParagraphLayout],
+    nested_tables: &[NestedTable],
     cell_x: f32,
     col_w: f32,
     cursor_y_start: f32,
@@ -114,6 +124,19 @@ fn render_cell_paragraphs(
     let mut cursor_y = cursor_y_start;
 
     for para in paragraphs {
+        if let Some(nested) = &para.nested_table {
+            cursor_y -= para.space_before;
+            render_nested_table(
+                content,
+                &nested_tables[nested.index].table,
+                nested,
+                cell_x + cm.left,
+                cursor_y,
+                fonts,
+            );
+            cursor_y -= para.content_height;
+            continue;
+        }
         if !para_has_visible_content(para) && para.image_name.is_none() {
             cursor_y -= para.space_before + para.lines.len() as f32 * para.line_h;
             if para.content_height > 0.0 {
@@ -173,6 +196,7 @@ fn render_cell_paragraphs(
 fn render_partial_cell_paragraphs(
     content: &mut Content,
     paragraphs: &[CellParagraphLayout],
+    nested_tables: &[NestedTable],
     start: usize,
     end: usize,
     cell_x: f32,
@@ -187,6 +211,20 @@ fn render_partial_cell_paragraphs(
         let para = &paragraphs[pi];
         let sb = if pi == start { 0.0 } else { para.space_before };
 
+        if let Some(nested) = &para.nested_table {
+            cursor_y -= sb;
+            render_nested_table(
+                content,
+                &nested_tables[nested.index].table,
+                nested,
+                cell_x + cm.left,
+                cursor_y,
+                fonts,
+            );
+            cursor_y -= para.content_height;
+            continue;
+        }
+
         if !para_has_visible_content(para) {
             cursor_y -= sb + para.lines.len() as f32 * para.line_h;
             continue;
@@ -221,16 +259,124 @@ fn render_partial_cell_paragraphs(
     }
 }
 
+/// Grid column widths, stretched to a percentage table width (`tblW`) of
+/// `avail_w` and reshaped by the first row whose cells all give percentages.
+fn resolve_col_widths(table: &Table, avail_w: f32) -> Vec<f32> {
+    let mut widths = table.col_widths.clone();
+    let grid_total: f32 = widths.iter().sum();
+    if let PreferredWidth::Percent(pct) = table.width
+        && grid_total > 0.0
+    {
+        let scale = avail_w * pct / 100.0 / grid_total;
+        for w in &mut widths {
+            *w *= scale;
+        }
+    }
+
+    let total: f32 = widths.iter().sum();
+    let pct_row = table.rows.iter().find(|row| {
+        !row.cells.is_empty()
+            && row
+                .cells
+                .iter()
+                .all(|c| matches!(c.width, PreferredWidth::Percent(_)))
+    });
+    if let Some(row) = pct_row {
+        let mut grid_col = 0usize;
+        for cell in &row.cells {
+            let span = cell.grid_span.max(1) as usize;
+            let cols = grid_col.min(widths.len())..(grid_col + span).min(widths.len());
+            grid_col += span;
+            let PreferredWidth::Percent(pct) = cell.width else {
+                continue;
+            };
+            let share = total * pct / 100.0;
+            let current: f32 = widths[cols.clone()].iter().sum();
+            let n = cols.len() as f32;
+            for w in &mut widths[cols] {
+                *w = if current > 0.0 {
+                    *w / current * share
+                } else {
+                    share / n
+                };
+            }
+        }
+    }
+    widths
+}
+
+/// Column widths of an autofit table: every column gets at least its longest
+/// word (or its `tcW`), and the room up to its widest line is shared out in
+/// proportion to what each column lacks, within the table's preferred width.
+fn fit_columns_to_content(
+    table: &Table,
+    avail_w: f32,
+    min_text: &[f32],
+    max_text: &[f32],
+    margins: &[f32],
+) -> Vec<f32> {
+    let ncols = table.col_widths.len();
+    let mut min_widths: Vec<f32> = (0..ncols).map(|i| min_text[i] + margins[i]).collect();
+    for row in &table.rows {
+        let mut grid_col = 0usize;
+        for cell in &row.cells {
+            let span = cell.grid_span.max(1) as usize;
+            if span == 1
+                && grid_col < ncols
+                && let PreferredWidth::Points(w) = cell.width
+            {
+                min_widths[grid_col] = min_widths[grid_col].max(w);
+            }
+            grid_col += span;
+        }
+    }
+    let max_widths: Vec<f32> = (0..ncols)
+        .map(|i| (max_text[i] + margins[i]).max(min_widths[i]))
+        .collect();
+
+    let target = match table.width {
+        PreferredWidth::Points(w) => Some(w),
+        PreferredWidth::Percent(pct) => Some(avail_w * pct / 100.0),
+        PreferredWidth::Auto => None,
+    };
+    let limit = target.unwrap_or(avail_w);
+    let min_total: f32 = min_widths.iter().sum();
+    let max_total: f32 = max_widths.iter().sum();
+
+    if max_total <= 0.0 {
+        return resolve_col_widths(table, avail_w);
+    }
+    if max_total <= limit {
+        // Everything fits unwrapped; a preferred width stretches the columns
+        return match target {
+            Some(t) => max_widths.iter().map(|w| w * t / max_total).collect(),
+            _ => max_widths,
+        };
+    }
+    if min_total >= limit {
+        return min_widths;
+    }
+    let share = (limit - min_total) / (max_total - min_total);
+    min_widths
+        .iter()
+        .zip(&max_widths)
+        .map(|(min, max)| min + (max - min) * share)
+        .collect()
+}
+
 /// Auto-fit column widths so that the longest non-breakable word in each column
 /// fits within the cell (including padding). Columns that need more space grow;
 /// other columns shrink proportionally. Total width is preserved.
-fn auto_fit_columns(table: &Table, fonts: &HashMap<String, FontEntry>) -> Vec<f32> {
+fn auto_fit_columns(table: &Table, avail_w: f32, fonts: &HashMap<String, FontEntry>) -> Vec<f32> {
     let ncols = table.col_widths.len();
     if ncols == 0 {
         return table.col_widths.clone();
     }
 
     let mut min_widths = vec![0.0f32; ncols];
+    // Autofit only: each column's widest unwrapped line and its cell margins
+    let mut max_widths = vec![0.0f32; ncols];
+    let mut margins = vec![0.0f32; ncols];
 
     for row in &table.rows {
         let mut grid_col = 0usize;
@@ -240,9 +386,18 @@ fn auto_fit_columns(table: &Table, fonts: &HashMap<String, FontEntry>) -> Vec<f3
                 grid_col += span;
                 continue;
             }
+            let cm = cell.cell_margins.as_ref().unwrap_or(&table.cell_margins);
+            margins[grid_col] = margins[grid_col].max(cm.left + cm.right);
             let mut key_buf = String::new();
             for para in &cell.paragraphs {
+                let indent = para.indent_left + para.indent_right;
+                let mut line_w = indent;
                 for run in &para.runs {
+                    if run.is_line_break {
+                        max_widths[grid_col] = max_widths[grid_col].max(line_w);
+                        line_w = indent;
+                        continue;
+                    }
                     let key = font_key_buf(run, &mut key_buf);
                     let Some(entry) = fonts.get(key) else {
                         continue;
@@ -257,19 +412,30 @@ fn auto_fit_columns(table: &Table, fonts: &HashMap<String, FontEntry>) -> Vec<f3
                     } else {
                         run.font_size
                     };
+                    let kern = run.kern_threshold.is_some_and(|t| fs >= t);
+                    let text_w = |s: &str| {
+                        entry.word_width(s, fs, kern) * run.text_scale / 100.0
+                            + run.char_spacing * s.chars().count() as f32
+                    };
                     for word in text.split_whitespace() {
-                        let kern = run.kern_threshold.is_some_and(|t| fs >= t);
-                        let ww = entry.word_width(word, fs, kern);
-                        min_widths[grid_col] = min_widths[grid_col].max(ww);
+                        min_widths[grid_col] = min_widths[grid_col].max(text_w(word));
+                    }
+                    if table.autofit {
+                        line_w += text_w(&text);
                     }
                 }
+                max_widths[grid_col] = max_widths[grid_col].max(line_w);
             }
             grid_col += span;
         }
     }
 
-    let total: f32 = table.col_widths.iter().sum();
-    let mut widths = table.col_widths.clone();
+    if table.autofit {
+        return fit_columns_to_content(table, avail_w, &min_widths, &max_widths, &margins);
+    }
+
+    let mut widths = resolve_col_widths(table, avail_w);
+    let total: f32 = widths.iter().sum();
 
     let mut extra_needed: f32 = 0.0;
     let mut shrinkable: f32 = 0.0;
@@ -302,6 +468,7 @@ fn auto_fit_columns(table: &Table, fonts: &HashMap<String, FontEntry>) -> Vec<f3
     widths
 }
 
+#[derive(Default)]
 struct CellParagraphLayout {
     lines: Vec<TextLine>,
     line_h: f32,
@@ -320,6 +487,25 @@ struct CellParagraphLayout {
     image_width: f32,
     image_height: f32,
     content_height: f32,
+    nested_table: Option<NestedTableLayout>,
+}
+
+impl CellParagraphLayout {
+    fn height(&self) -> f32 {
+        if self.lines.is_empty() && self.content_height > 0.0 {
+            self.content_height
+        } else {
+            self.lines.len() as f32 * self.line_h
+        }
+    }
+}
+
+/// A table nested in a cell (`TableCell::nested_tables[index]`), laid out as
+/// a block between the cell's paragraphs.
+struct NestedTableLayout {
+    index: usize,
+    col_widths: Vec<f32>,
+    rows: Vec<RowLayout>,
 }
 
 struct CellLayout {
@@ -373,9 +559,63 @@ fn draw_cell_label(
 /// When provided, field codes in header/footer table runs are substituted with
 /// their resolved values before layout.
 struct HfSubstitution<'a> {
-    page_num: usize,
-    total_pages: usize,
-    styleref_values: &'a HashMap<String, String>,
+    page: &'a PageFields<'a>,
+    styleref_values: &'a StyleRefValues<'a>,
+}
+
+/// Lays out a nested table as a cell block of its full height. Its columns
+/// keep their grid widths, scaled down when they would overflow the cell.
+fn layout_nested_table(
+    index: usize,
+    table: &Table,
+    cell_text_w: f32,
+    space_before: f32,
+    ctx: &RenderContext,
+    hf_sub: Option<&HfSubstitution>,
+) -> CellParagraphLayout {
+    let ncm = &table.cell_margins;
+    // Like a top-level table, the nested one lines its text up with the
+    // surrounding text, so its own cell margins reach past the cell's
+    let avail = (cell_text_w - table.table_indent + ncm.left + ncm.right).max(0.0);
+    let mut col_widths = auto_fit_columns(table, avail, ctx.fonts);
+    let total: f32 = col_widths.iter().sum();
+    if total > avail && total > 0.0 {
+        for w in &mut col_widths {
+            *w *= avail / total;
+        }
+    }
+    let rows = compute_row_layouts(table, &col_widths, ctx, hf_sub);
+    CellParagraphLayout {
+        space_before,
+        content_height: rows.iter().map(|r| r.height).sum(),
+        nested_table: Some(NestedTableLayout {
+            index,
+            col_widths,
+            rows,
+        }),
+        ..CellParagraphLayout::default()
+    }
+}
+
+fn render_nested_table(
+    content: &mut Content,
+    table: &Table,
+    layout: &NestedTableLayout,
+    text_x: f32,
+    top_y: f32,
+    fonts: &HashMap<String, FontEntry>,
+) {
+    let table_left = text_x + table.table_indent - table.cell_margins.left;
+    let mut cursor_y = top_y;
+    render_rows_at(
+        content,
+        table,
+        &layout.rows,
+        &layout.col_widths,
+        table_left,
+        &mut cursor_y,
+        fonts,
+    );
 }
 
 fn compute_row_layouts(
@@ -396,7 +636,11 @@ fn compute_row_layouts(
                 .iter()
                 .map(|cell| {
                     let span = cell.grid_span.max(1) as usize;
-                    let col_w = cell_span_width(col_widths, grid_col, span).max(cell.width);
+                    let min_w = match cell.width {
+                        PreferredWidth::Points(w) => w,
+                        _ => 0.0,
+                    };
+                    let col_w = cell_span_width(col_widths, grid_col, span).max(min_w);
                     grid_col += span;
 
                     if cell.v_merge == VMerge::Continue {
@@ -408,9 +652,14 @@ fn compute_row_layouts(
                     }
 
                     let ecm = cell.cell_margins.as_ref().unwrap_or(cm);
+                    let col_w = col_w - 2.0 * row.cell_spacing;
                     let is_rotated = cell.text_direction != TextDirection::LrTb;
+                    // Rotated lines run along the row: they wrap at an exact row
+                    // height, otherwise the row grows to the longest line
                     let cell_text_w = if is_rotated {
-                        10000.0
+                        row.height
+                            .filter(|_| row.height_exact)
+                            .map_or(10000.0, |h| (h - ecm.top - ecm.bottom).max(0.0))
                     } else {
                         (col_w - ecm.left - ecm.right).max(0.0)
                     };
@@ -418,18 +667,42 @@ fn compute_row_layouts(
                     let mut max_rotated_line_w: f32 = 0.0;
                     let mut paragraphs = Vec::new();
                     let mut prev_space_after = 0.0f32;
-                    let cell_has_content =
-                        cell.paragraphs.iter().any(|p| !is_text_empty(&p.runs));
+                    let cell_has_content = cell.paragraphs.iter().any(|p| !is_text_empty(&p.runs));
 
                     for (pi, para) in cell.paragraphs.iter().enumerate() {
+                        for (ti, nested) in cell.nested_tables.iter().enumerate() {
+                            if nested.para_idx == pi {
+                                let layout = layout_nested_table(
+                                    ti,
+                                    &nested.table,
+                                    cell_text_w,
+                                    prev_space_after,
+                                    ctx,
+                                    hf_sub,
+                                );
+                                total_h += layout.space_before + layout.content_height;
+                                paragraphs.push(layout);
+                                prev_space_after = 0.0;
+                            }
+                        }
+
                         let substituted;
                         let runs = if let Some(sub) = hf_sub {
-                            substituted = substitute_hf_runs(
-                                &para.runs,
-                                sub.page_num,
-                                sub.total_pages,
-                                sub.styleref_values,
-                            );
+                            substituted =
+                                substitute_hf_runs(&para.runs, sub.page, sub.styleref_values, ctx);
+                            &substituted
+                        } else if !ctx.bookmark_pages.is_empty() || ctx.total_pages.is_some() {
+                            substituted = para
+                                .runs
+                                .iter()
+                                .map(|run| match page_field_text(run, ctx) {
+                                    Some(page) => Run {
+                                        text: page,
+                                        ..run.clone()
+                                    },
+                                    None => run.clone(),
+                                })
+                                .collect();
                             &substituted
                         } else {
                             &para.runs
@@ -457,12 +730,17 @@ fn compute_row_layouts(
                         let lines = if !is_text_empty(runs) {
                             let para_text_w =
                                 (cell_text_w - para.indent_left - para.indent_right).max(0.0);
-                            let lines = build_paragraph_lines(
+                            let lines = build_indented_paragraph_lines(
                                 runs,
                                 ctx.fonts,
                                 para_text_w,
                                 para.indent_hanging,
                                 &std::collections::HashMap::new(),
+                                0..0,
+                                0.0,
+                                0.0,
+                                ctx.hyphenator.filter(|_| !para.suppress_auto_hyphens),
+                                para.rtl,
                             );
                             if is_rotated {
                                 for line in &lines {
@@ -516,10 +794,26 @@ fn compute_row_layouts(
                             image_width,
                             image_height,
                             content_height: para.content_height,
+                            nested_table: None,
                         });
 
                         prev_space_after = para.space_after;
                     }
+                    for (ti, nested) in cell.nested_tables.iter().enumerate() {
+                        if nested.para_idx >= cell.paragraphs.len() {
+                            let layout = layout_nested_table(
+                                ti,
+                                &nested.table,
+                                cell_text_w,
+                                prev_space_after,
+                                ctx,
+                                hf_sub,
+                            );
+                            total_h += layout.space_before + layout.content_height;
+                            paragraphs.push(layout);
+                            prev_space_after = 0.0;
+                        }
+                    }
 
                     total_h += prev_space_after;
                     if is_rotated {
@@ -538,7 +832,7 @@ fn compute_row_layouts(
 
             // Word's row height includes the end-of-cell paragraph mark glyph,
             // adding roughly 0.5pt beyond the content metrics.
-            let content_h = max_h + 0.5;
+            let content_h = max_h + 0.5 + 2.0 * row.cell_spacing;
             let height = match (row.height, row.height_exact) {
                 (Some(h), true) => h,
                 (Some(h), false) => content_h.max(h),
@@ -617,54 +911,69 @@ fn render_table_row(
             continue;
         }
 
+        // Cell spacing (`tblCellSpacing`) leaves a gap around each cell
+        let gap = row.cell_spacing;
+        let (cell_x, col_w) = (cell_x + gap, col_w - 2.0 * gap);
+        let (cell_top, cell_h) = (row_top - gap, row_h - 2.0 * gap);
+        let cell_bottom = cell_top - cell_h;
+
         if let Some(shading) = cell.shading {
             draw_cell_shading(
                 &mut pb.content,
                 shading,
                 &cell.borders,
                 cell_x,
-                row_bottom,
+                cell_bottom,
                 col_w,
-                row_h,
+                cell_h,
             );
         }
 
         let has_content = cell_has_visible_content(&cell_layout.paragraphs);
         let ecm = cell.cell_margins.as_ref().unwrap_or(cm);
 
-        if has_content && cell_layout.text_direction == TextDirection::TbRl {
+        if has_content
+            && cell_layout.text_direction == TextDirection::TbRl
+            && is_east_asian(cell_layout)
+        {
             render_vertical_cjk_cell(
                 &mut pb.content,
                 cell_layout,
                 cell,
                 cell_x,
-                row_top,
-                row_h,
+                cell_top,
+                cell_h,
                 col_w,
                 ecm,
                 ctx,
             );
+        } else if has_content && cell_layout.text_direction != TextDirection::LrTb {
+            render_rotated_cell(
+                &mut pb.content,
+                cell_layout,
+                cell,
+                cell_x,
+                cell_top,
+                cell_h,
+                col_w,
+                ecm,
+                ctx.fonts,
+            );
         } else if has_content {
             let content_h: f32 = cell_layout
                 .paragraphs
                 .iter()
-                .map(|p| {
-                    let para_h = if p.lines.is_empty() && p.content_height > 0.0 {
-                        p.content_height
-                    } else {
-                        p.lines.len() as f32 * p.line_h
-                    };
-                    p.space_before + para_h
-                })
+                .map(|p| p.space_before + p.height())
                 .sum();
 
-            let avail = row_h - ecm.top - ecm.bottom;
+            let avail = cell_h - ecm.top - ecm.bottom;
             let v_offset = valign_offset(cell.v_align, avail, content_h);
-            let cursor_y = row_top - ecm.top - v_offset;
+            let cursor_y = cell_top - ecm.top - v_offset;
 
             render_cell_paragraphs(
                 &mut pb.content,
                 &cell_layout.paragraphs,
+                &cell.nested_tables,
                 cell_x,
                 col_w,
                 cursor_y,
@@ -689,15 +998,16 @@ fn render_table_row(
             .get(&(row_idx, grid_col))
             .copied()
             .unwrap_or(0.0);
-        let effective_bottom = row_bottom - merge_extra;
+        let gap = row.cell_spacing;
+        let effective_bottom = row_bottom - merge_extra + gap;
 
         draw_cell_borders(
             &mut pb.content,
             &cell.borders,
-            bx,
-            row_top,
+            bx + gap,
+            row_top - gap,
             effective_bottom,
-            col_w,
+            col_w - 2.0 * gap,
             true,
             true,
         );
@@ -708,6 +1018,67 @@ fn render_table_row(
     pb.slot_top = row_bottom;
 }
 
+/// Whether a vertical cell holds East Asian text, which stays upright with
+/// its characters stacked instead of being turned with the line.
+fn is_east_asian(cell_layout: &CellLayout) -> bool {
+    cell_layout
+        .paragraphs
+        .iter()
+        .flat_map(|p| &p.lines)
+        .flat_map(|l| &l.chunks)
+        .any(|c| c.text.chars().any(is_cjk_break_char))
+}
+
+/// Cell text turned a quarter turn: `btLr` reads bottom to top with lines
+/// stacking left to right, `tbRl` top to bottom with lines stacking right to
+/// left. Lines run along the row height; the column width holds the block.
+#[allow(clippy::too_many_arguments)]
+fn render_rotated_cell(
+    content: &mut Content,
+    cell_layout: &CellLayout,
+    cell: &crate::model::TableCell,
+    cell_x: f32,
+    row_top: f32,
+    row_h: f32,
+    col_w: f32,
+    cm: &CellMargins,
+    fonts: &HashMap<String, FontEntry>,
+) {
+    let line_len = (row_h - cm.top - cm.bottom).max(0.0);
+    let block_h: f32 = cell_layout
+        .paragraphs
+        .iter()
+        .map(|p| p.space_before + p.height())
+        .sum();
+    let offset = valign_offset(cell.v_align, col_w - cm.left - cm.right, block_h);
+
+    content.save_state();
+    if cell_layout.text_direction == TextDirection::BtLr {
+        let origin_x = cell_x + cm.left + offset;
+        content.transform([0.0, 1.0, -1.0, 0.0, origin_x, row_top - row_h + cm.bottom]);
+    } else {
+        let origin_x = cell_x + col_w - cm.right - offset;
+        content.transform([0.0, -1.0, 1.0, 0.0, origin_x, row_top - cm.top]);
+    }
+    let no_margins = CellMargins {
+        top: 0.0,
+        left: 0.0,
+        bottom: 0.0,
+        right: 0.0,
+    };
+    render_cell_paragraphs(
+        content,
+        &cell_layout.paragraphs,
+        &cell.nested_tables,
+        0.0,
+        line_len,
+        0.0,
+        &no_margins,
+        fonts,
+    );
+    content.restore_state();
+}
+
 fn render_vertical_cjk_cell(
     content: &mut Content,
     cell_layout: &CellLayout,
@@ -798,7 +1169,7 @@ fn find_cell_split(cell: &CellLayout, start: usize, available_h: f32, cm: &CellM
     for pi in start..cell.paragraphs.len() {
         let para = &cell.paragraphs[pi];
         let sb = if pi == start { 0.0 } else { para.space_before };
-        let para_h = sb + para.lines.len() as f32 * para.line_h;
+        let para_h = sb + para.height();
         if h + para_h > available_h && pi > start {
             return pi;
         }
@@ -831,7 +1202,7 @@ fn render_partial_row(
         for pi in start..end {
             let para = &cell_layout.paragraphs[pi];
             let sb = if pi == start { 0.0 } else { para.space_before };
-            h += sb + para.lines.len() as f32 * para.line_h;
+            h += sb + para.height();
         }
         max_h = max_h.max(h);
     }
@@ -873,6 +1244,7 @@ fn render_partial_row(
             render_partial_cell_paragraphs(
                 &mut pb.content,
                 &cell_layout.paragraphs,
+                &cell.nested_tables,
                 start,
                 end,
                 cell_x,
@@ -942,7 +1314,7 @@ pub(super) fn render_table(
     prev_space_after: f32,
     override_pos: Option<(f32, f32, bool)>,
 ) {
-    let col_widths = auto_fit_columns(table, ctx.fonts);
+    let col_widths = auto_fit_columns(table, text_width(sp), ctx.fonts);
     let row_layouts = compute_row_layouts(table, &col_widths, ctx, None);
     let merge_spans = compute_merge_spans(table, &row_layouts);
     let cm = &table.cell_margins;
@@ -965,7 +1337,7 @@ pub(super) fn render_table(
     let header_count = table.rows.iter().take_while(|r| r.is_header).count();
 
     let flush_and_render_headers = |pb: &mut super::PageBuilder, ri: usize| {
-        pb.flush_page(sect_idx);
+        pb.flush_full_page(sect_idx);
         pb.is_first_page_of_section = false;
         pb.slot_top = sp.page_height - sp.margin_top;
         if header_count > 0 && ri >= header_count {
@@ -997,7 +1369,11 @@ pub(super) fn render_table(
         let page_content_h = sp.page_height - sp.margin_top - sp.margin_bottom;
 
         if !is_truly_floating && row_h > available_h && row_h > page_content_h {
-            // Row is too tall for any single page -- split across pages
+            // Row is too tall for any single page -- split across pages. One
+            // that mustn't split (`w:cantSplit`) at least starts on a new page
+            if row.cant_split && !at_page_top {
+                flush_and_render_headers(pb, ri);
+            }
             let ncells = layout.cells.len();
             let mut starts = vec![0usize; ncells];
             let mut is_first_chunk = true;
@@ -1078,8 +1454,12 @@ pub(super) fn render_table(
     }
 }
 
-pub(super) fn compute_hf_table_height(table: &Table, ctx: &RenderContext) -> f32 {
-    let col_widths = auto_fit_columns(table, ctx.fonts);
+pub(super) fn compute_hf_table_height(
+    table: &Table,
+    sp: &SectionProperties,
+    ctx: &RenderContext,
+) -> f32 {
+    let col_widths = auto_fit_columns(table, text_width(sp), ctx.fonts);
     let row_layouts = compute_row_layouts(table, &col_widths, ctx, None);
     row_layouts.iter().map(|r| r.height).sum()
 }
@@ -1090,20 +1470,39 @@ pub(super) fn render_header_footer_table(
     ctx: &RenderContext,
     content: &mut Content,
     cursor_y: &mut f32,
-    page_num: usize,
-    total_pages: usize,
-    styleref_values: &HashMap<String, String>,
+    page: &PageFields,
+    styleref_values: &StyleRefValues,
 ) {
-    let col_widths = auto_fit_columns(table, ctx.fonts);
+    let col_widths = auto_fit_columns(table, text_width(sp), ctx.fonts);
     let hf_sub = HfSubstitution {
-        page_num,
-        total_pages,
+        page,
         styleref_values,
     };
     let row_layouts = compute_row_layouts(table, &col_widths, ctx, Some(&hf_sub));
-    let cm = &table.cell_margins;
-    let table_left = sp.margin_left + table.table_indent - cm.left;
+    let table_left = sp.margin_left + table.table_indent - table.cell_margins.left;
+    render_rows_at(
+        content,
+        table,
+        &row_layouts,
+        &col_widths,
+        table_left,
+        cursor_y,
+        ctx.fonts,
+    );
+}
 
+/// Draws whole rows from `cursor_y` down, without page breaks (header/footer
+/// and nested tables).
+fn render_rows_at(
+    content: &mut Content,
+    table: &Table,
+    row_layouts: &[RowLayout],
+    col_widths: &[f32],
+    table_left: f32,
+    cursor_y: &mut f32,
+    fonts: &HashMap<String, FontEntry>,
+) {
+    let cm = &table.cell_margins;
     for (ri, (row, layout)) in table.rows.iter().zip(row_layouts.iter()).enumerate() {
         let row_h = layout.height;
         let row_top = *cursor_y;
@@ -1112,14 +1511,19 @@ pub(super) fn render_header_footer_table(
         let mut grid_col = 0usize;
         for (cell, cell_layout) in row.cells.iter().zip(layout.cells.iter()) {
             let span = cell.grid_span.max(1) as usize;
-            let col_w = cell_span_width(&col_widths, grid_col, span);
-            let cell_x = cell_x_offset(&col_widths, table_left, grid_col);
+            let col_w = cell_span_width(col_widths, grid_col, span);
+            let cell_x = cell_x_offset(col_widths, table_left, grid_col);
             grid_col += span;
 
             if cell.v_merge == VMerge::Continue {
                 continue;
             }
 
+            let gap = row.cell_spacing;
+            let (cell_x, col_w) = (cell_x + gap, col_w - 2.0 * gap);
+            let (cell_top, cell_h) = (row_top - gap, row_h - 2.0 * gap);
+            let cell_bottom = cell_top - cell_h;
+
             if let Some(shading) = cell.shading {
                 content.save_state();
                 content.set_fill_rgb(
@@ -1127,7 +1531,7 @@ pub(super) fn render_header_footer_table(
                     shading[1] as f32 / 255.0,
                     shading[2] as f32 / 255.0,
                 );
-                content.rect(cell_x, row_bottom, col_w, row_h);
+                content.rect(cell_x, cell_bottom, col_w, cell_h);
                 content.fill_nonzero();
                 content.restore_state();
             }
@@ -1137,21 +1541,22 @@ pub(super) fn render_header_footer_table(
                 let content_h: f32 = cell_layout
                     .paragraphs
                     .iter()
-                    .map(|p| p.space_before + p.lines.len() as f32 * p.line_h)
+                    .map(|p| p.space_before + p.height())
                     .sum();
 
-                let avail = row_h - ecm.top - ecm.bottom;
+                let avail = cell_h - ecm.top - ecm.bottom;
                 let v_offset = valign_offset(cell.v_align, avail, content_h);
-                let cell_cursor_y = row_top - ecm.top - v_offset;
+                let cell_cursor_y = cell_top - ecm.top - v_offset;
 
                 render_cell_paragraphs(
                     content,
                     &cell_layout.paragraphs,
+                    &cell.nested_tables,
                     cell_x,
                     col_w,
                     cell_cursor_y,
                     ecm,
-                    ctx.fonts,
+                    fonts,
                 );
             }
         }
@@ -1159,18 +1564,20 @@ pub(super) fn render_header_footer_table(
         let mut grid_col = 0usize;
         for cell in &row.cells {
             let span = cell.grid_span.max(1) as usize;
-            let col_w = cell_span_width(&col_widths, grid_col, span);
-            let bx = cell_x_offset(&col_widths, table_left, grid_col);
+            let col_w = cell_span_width(col_widths, grid_col, span);
+            let bx = cell_x_offset(col_widths, table_left, grid_col);
             grid_col += span;
 
-            let draw_top = cell.v_merge != VMerge::Continue && ri == 0;
+            // Spaced cells stand apart, each with its own top border
+            let gap = row.cell_spacing;
+            let draw_top = cell.v_merge != VMerge::Continue && (ri == 0 || gap > 0.0);
             draw_cell_borders(
                 content,
                 &cell.borders,
-                bx,
-                row_top,
-                row_bottom,
-                col_w,
+                bx + gap,
+                row_top - gap,
+                row_bottom + gap,
+                col_w - 2.0 * gap,
                 draw_top,
                 true,
             );
@@ -1179,3 +1586,51 @@ pub(super) fn render_header_footer_table(
         *cursor_y = row_bottom;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::testing;
+    use crate::model::Block;
+
+    fn first_table(body: &str) -> Table {
+        let doc = testing::parse_body(body);
+        let blocks = doc.sections.into_iter().flat_map(|s| s.blocks);
+        for block in blocks {
+            if let Block::Table(table) = block {
+                return table;
+            }
+        }
+        panic!("no table");
+    }
+
+    #[test]
+    fn test_percentage_widths_share_out_the_text_width() {
+        let table = first_table(
+            r#"<w:tbl><w:tblPr><w:tblW w:w="2500" w:type="pct"/></w:tblPr><w:tblGrid><w:gridCol w:w="1440"/><w:gridCol w:w="1440"/></w:tblGrid><w:tr><w:tc><w:tcPr><w:tcW w:w="25%" w:type="pct"/></w:tcPr><w:p/></w:tc><w:tc><w:tcPr><w:tcW w:w="75%" w:type="pct"/></w:tcPr><w:p/></w:tc></w:tr></w:tbl>"#,
+        );
+        assert_eq!(table.width, PreferredWidth::Percent(50.0));
+        // Half of 468pt, split a quarter to three quarters
+        assert_eq!(resolve_col_widths(&table, 468.0), [58.5, 175.5]);
+    }
+
+    #[test]
+    fn test_autofit_columns_grow_to_their_content() {
+        let table = first_table(
+            r#"<w:tbl><w:tblPr><w:tblLayout w:type="autofit"/></w:tblPr><w:tblGrid><w:gridCol w:w="1440"/><w:gridCol w:w="1440"/></w:tblGrid><w:tr><w:tc><w:p/></w:tc><w:tc><w:p/></w:tc></w:tr></w:tbl>"#,
+        );
+        assert!(table.autofit);
+        let margins = [10.0, 10.0];
+        // Unwrapped lines fit: each column takes its widest line
+        let widths = fit_columns_to_content(&table, 468.0, &[20.0, 30.0], &[50.0, 100.0], &margins);
+        assert_eq!(widths, [60.0, 110.0]);
+        // Too wide: the room past the longest words is shared out evenly
+        let widths =
+            fit_columns_to_content(&table, 440.0, &[20.0, 20.0], &[400.0, 400.0], &margins);
+        assert_eq!(widths, [220.0, 220.0]);
+        // Not even the longest words fit: they win over the page width
+        let widths =
+            fit_columns_to_content(&table, 100.0, &[80.0, 80.0], &[400.0, 400.0], &margins);
+        assert_eq!(widths, [90.0, 90.0]);
+    }
+}
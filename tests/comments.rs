@@ -0,0 +1,47 @@
+mod common;
+
+use common::{count_bytes, text_run as text};
+use docxide_pdf::{ConversionOptions, convert_docx_bytes_to_pdf_bytes_with_options};
+
+/// A paragraph commented on by Ada, with the comment part alongside.
+fn commented_docx() -> Vec<u8> {
+    let comments = format!(
+        r#"<w:comments xmlns:w="{}"><w:comment w:id="0" w:author="Ada" w:date="2024-03-05T14:30:00Z"><w:p><w:r><w:t>Verify the figure</w:t></w:r></w:p></w:comment></w:comments>"#,
+        common::W_NS
+    );
+    let body = format!(
+        r#"<w:p><w:commentRangeStart w:id="0"/>{}<w:commentRangeEnd w:id="0"/><w:r><w:commentReference w:id="0"/></w:r>{}</w:p>{}"#,
+        text("Sales grew 40%"),
+        text(" last year."),
+        common::letter_section("")
+    );
+    common::docx_package(
+        &common::document_xml(&body),
+        &[],
+        &[("word/comments.xml".to_string(), comments.into_bytes())],
+    )
+}
+
+fn convert(render_comments: bool) -> Vec<u8> {
+    let options = ConversionOptions {
+        render_comments,
+        ..ConversionOptions::default()
+    };
+    convert_docx_bytes_to_pdf_bytes_with_options(&commented_docx(), &options).expect("convert")
+}
+
+#[test]
+fn comment_becomes_a_note_annotation() {
+    let pdf = convert(true);
+    assert_eq!(count_bytes(&pdf, b"/Subtype /Text"), 1);
+    assert_eq!(count_bytes(&pdf, b"(Verify the figure)"), 1);
+    assert_eq!(count_bytes(&pdf, b"/T (Ada)"), 1);
+    assert_eq!(count_bytes(&pdf, b"/M (D:20240305143000"), 1);
+}
+
+#[test]
+fn comments_can_be_left_out() {
+    let pdf = convert(false);
+    assert_eq!(count_bytes(&pdf, b"/Subtype /Text"), 0);
+    assert_eq!(count_bytes(&pdf, b"Verify the figure"), 0);
+}
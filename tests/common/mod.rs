@@ -18,6 +18,8 @@ pub struct Baselines {
     pub text_boundary: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub convert_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_bytes: Option<f64>,
 }
 
 fn load_skiplist() -> HashSet<String> {
@@ -166,6 +168,7 @@ pub fn update_baselines(updates: &HashMap<String, Baselines>) {
         merge_max(&mut entry.ssim, new.ssim);
         merge_max(&mut entry.text_boundary, new.text_boundary);
         merge_min(&mut entry.convert_ms, new.convert_ms);
+        merge_min(&mut entry.pdf_bytes, new.pdf_bytes);
     }
     let sorted: BTreeMap<_, _> = baselines.into_iter().collect();
     let json = serde_json::to_string_pretty(&sorted).expect("Failed to serialize baselines");
@@ -249,3 +252,173 @@ pub fn delta_str(current: f64, previous: Option<f64>) -> String {
         None => String::new(),
     }
 }
+
+pub const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+pub const R_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+pub const REL_TYPE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Letter-sized section properties with one-inch margins. `extra` goes
+/// first inside the `w:sectPr`, for header and footer references.
+pub fn letter_section(extra: &str) -> String {
+    format!(
+        r#"<w:sectPr>{extra}<w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720"/></w:sectPr>"#
+    )
+}
+
+/// A `w:document` part whose body is `body`, as is.
+pub fn document_xml(body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="{W_NS}" xmlns:r="{R_NS}"><w:body>{body}</w:body></w:document>"#
+    )
+}
+
+/// A relationship of the document part to `target`, where `kind` is the
+/// last segment of the relationship type, e.g. `image` or `footer`.
+pub fn relationship(id: &str, kind: &str, target: &str) -> String {
+    let mode = if kind == "hyperlink" {
+        r#" TargetMode="External""#
+    } else {
+        ""
+    };
+    format!(r#"<Relationship Id="{id}" Type="{REL_TYPE}/{kind}" Target="{target}"{mode}/>"#)
+}
+
+/// A relationships part holding `relationships`.
+pub fn relationships_xml(relationships: &[String]) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}</Relationships>"#,
+        relationships.concat()
+    )
+}
+
+/// A .docx with `document` as its main part, `relationships` as the
+/// document's relationships and `parts` added under their package names.
+pub fn docx_package(
+    document: &str,
+    relationships: &[String],
+    parts: &[(String, Vec<u8>)],
+) -> Vec<u8> {
+    let content_types = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/><Default Extension="png" ContentType="image/png"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+</Types>"#;
+    let package_rels =
+        relationships_xml(&[relationship("rId1", "officeDocument", "word/document.xml")]);
+    let document_rels = relationships_xml(relationships);
+
+    let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for (name, content) in [
+        ("[Content_Types].xml", content_types.as_bytes()),
+        ("_rels/.rels", package_rels.as_bytes()),
+        ("word/document.xml", document.as_bytes()),
+        ("word/_rels/document.xml.rels", document_rels.as_bytes()),
+    ]
+    .into_iter()
+    .chain(parts.iter().map(|(n, c)| (n.as_str(), c.as_slice())))
+    {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// A one-section Letter document of `body` with no other parts.
+pub fn simple_docx(body: &str) -> Vec<u8> {
+    docx_package(
+        &document_xml(&format!("{body}{}", letter_section(""))),
+        &[],
+        &[],
+    )
+}
+
+/// A `width` by `height` pixel RGB picture as PNG. The pixel size tells
+/// pictures apart in mutool traces.
+pub fn test_png(width: u32, height: u32) -> Vec<u8> {
+    let picture = image::RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x * 16) as u8, (y * 32) as u8, 90])
+    });
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgb8(picture)
+        .write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+    png
+}
+
+/// A complex field whose cached result is `cached`, as Word leaves it when
+/// the document was saved without updating fields.
+pub fn field(instr: &str, cached: &str) -> String {
+    format!(
+        r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve"> {instr} </w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>{cached}</w:t></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r>"#
+    )
+}
+
+/// A run of `text` with its spaces kept.
+pub fn text_run(text: &str) -> String {
+    format!(r#"<w:r><w:t xml:space="preserve">{text}</w:t></w:r>"#)
+}
+
+/// Occurrences of `needle` in `haystack`, for checking PDF objects.
+pub fn count_bytes(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .filter(|w| *w == needle)
+        .count()
+}
+
+/// Pages in `pdf`, counted from its page objects.
+pub fn pdf_pages(pdf: &[u8]) -> usize {
+    count_bytes(pdf, b"/Type /Page") - count_bytes(pdf, b"/Type /Pages")
+}
+
+/// What `mutool draw -F <format>` prints for `page` (1-based) of `pdf`.
+/// `name` keeps the temporary files of tests running in parallel apart.
+pub fn mutool_draw(name: &str, pdf: &[u8], format: &str, page: usize) -> String {
+    let path =
+        std::env::temp_dir().join(format!("docxide-{name}-{}-{page}.pdf", std::process::id()));
+    fs::write(&path, pdf).expect("write pdf");
+    let output = std::process::Command::new("mutool")
+        .args([
+            "draw",
+            "-F",
+            format,
+            path.to_str().unwrap(),
+            &page.to_string(),
+        ])
+        .output()
+        .expect("Failed to run mutool draw");
+    fs::remove_file(&path).ok();
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// The words mutool extracts from `page` of `pdf`.
+pub fn page_words(name: &str, pdf: &[u8], page: usize) -> Vec<String> {
+    mutool_draw(name, pdf, "text", page)
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+/// Value of the `name` attribute in an element of mutool's XML output.
+pub fn xml_attr(element: &str, name: &str) -> Option<String> {
+    let start = element.find(&format!(" {name}=\""))? + name.len() + 3;
+    let len = element[start..].find('"')?;
+    Some(element[start..start + len].to_string())
+}
+
+/// Each character mutool finds on `page` with its left edge, in reading order.
+pub fn char_positions(name: &str, pdf: &[u8], page: usize) -> Vec<(char, f32)> {
+    mutool_draw(name, pdf, "stext", page)
+        .split("<char ")
+        .skip(1)
+        .filter_map(|element| {
+            let c = xml_attr(element, "c")?.chars().next()?;
+            let x = xml_attr(element, "x")?.parse().ok()?;
+            Some((c, x))
+        })
+        .collect()
+}
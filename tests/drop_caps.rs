@@ -0,0 +1,53 @@
+mod common;
+
+use common::text_run as text;
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// Each character on page 1 with its left edge and baseline.
+fn chars(pdf: &[u8]) -> Vec<(char, f32, f32)> {
+    common::mutool_draw("drop-cap", pdf, "stext", 1)
+        .split("<char ")
+        .skip(1)
+        .filter_map(|element| {
+            let c = common::xml_attr(element, "c")?.chars().next()?;
+            let x = common::xml_attr(element, "x")?.parse().ok()?;
+            let y = common::xml_attr(element, "y")?.parse().ok()?;
+            Some((c, x, y))
+        })
+        .collect()
+}
+
+#[test]
+fn drop_cap_indents_the_lines_it_drops_through() {
+    let cap = r#"<w:p><w:pPr><w:framePr w:dropCap="drop" w:lines="3" w:wrap="around" w:vAnchor="text" w:hAnchor="text"/></w:pPr><w:r><w:rPr><w:sz w:val="96"/></w:rPr><w:t>W</w:t></w:r></w:p>"#;
+    let words = "once upon a time there lived a baker who made bread ".repeat(12);
+    let body = format!("{cap}<w:p>{}</w:p>", text(&words));
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(&body)).expect("convert");
+    let chars = chars(&pdf);
+
+    let (_, cap_x, _) = *chars.iter().find(|(c, ..)| *c == 'W').expect("drop cap");
+    assert!((cap_x - 72.0).abs() < 2.0, "cap at {cap_x}");
+
+    // Left edge of each text line, top to bottom
+    let mut line_starts: Vec<(f32, f32)> = Vec::new();
+    for &(c, x, y) in &chars {
+        if c == 'W' {
+            continue;
+        }
+        match line_starts
+            .iter_mut()
+            .find(|(line_y, _)| (line_y - y).abs() < 1.0)
+        {
+            Some(line) => line.1 = line.1.min(x),
+            None => line_starts.push((y, x)),
+        }
+    }
+    line_starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+    assert!(line_starts.len() > 4, "{line_starts:?}");
+    for &(_, x) in &line_starts[..3] {
+        assert!(x > 100.0, "{line_starts:?}");
+    }
+    for &(_, x) in &line_starts[3..] {
+        assert!((x - 72.0).abs() < 1.0, "{line_starts:?}");
+    }
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::count_bytes;
+use docxide_pdf::{ConversionOptions, convert_docx_bytes_to_pdf_bytes_with_options};
+
+const FONTS: usize = 10;
+
+/// A document with `FONTS` families embedded in its font table, all copies
+/// of the fixture font, and a paragraph in each.
+fn docx_with_embedded_fonts() -> Vec<u8> {
+    let ttf = std::fs::read("tests/fixtures/fonts/SourceCodePro-Regular.ttf").expect("fixture");
+    let fonts: String = (1..=FONTS)
+        .map(|i| {
+            format!(r#"<w:font w:name="Embedded {i}"><w:embedRegular r:id="rId{i}"/></w:font>"#)
+        })
+        .collect();
+    let font_table = format!(
+        r#"<w:fonts xmlns:w="{}" xmlns:r="{}">{fonts}</w:fonts>"#,
+        common::W_NS,
+        common::R_NS
+    );
+    let font_rels: Vec<String> = (1..=FONTS)
+        .map(|i| common::relationship(&format!("rId{i}"), "font", &format!("fonts/font{i}.ttf")))
+        .collect();
+    let mut parts = vec![
+        ("word/fontTable.xml".to_string(), font_table.into_bytes()),
+        (
+            "word/_rels/fontTable.xml.rels".to_string(),
+            common::relationships_xml(&font_rels).into_bytes(),
+        ),
+    ];
+    parts.extend((1..=FONTS).map(|i| (format!("word/fonts/font{i}.ttf"), ttf.clone())));
+
+    let paragraphs: String = (1..=FONTS)
+        .map(|i| {
+            format!(
+                r#"<w:p><w:r><w:rPr><w:rFonts w:ascii="Embedded {i}" w:hAnsi="Embedded {i}"/></w:rPr><w:t>Font number {i}</w:t></w:r></w:p>"#
+            )
+        })
+        .collect();
+    let body = format!("{paragraphs}{}", common::letter_section(""));
+    common::docx_package(&common::document_xml(&body), &[], &parts)
+}
+
+#[test]
+fn ten_embedded_fonts_are_written_in_a_stable_order() {
+    let docx = docx_with_embedded_fonts();
+    let options = ConversionOptions {
+        deterministic: true,
+        ..ConversionOptions::default()
+    };
+    let convert_on = |threads: usize| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("thread pool")
+            .install(|| convert_docx_bytes_to_pdf_bytes_with_options(&docx, &options))
+            .expect("convert")
+    };
+
+    // One thread embeds the fonts one after another, as a build without the
+    // `parallel` feature does; the bytes must not depend on it
+    let pdf = convert_on(4);
+    assert_eq!(count_bytes(&pdf, b"/FontFile2"), FONTS);
+    assert!(pdf == convert_on(1), "output differs from the serial order");
+    assert!(pdf == convert_on(4), "output differs between runs");
+
+    let words = common::page_words("embedded-fonts", &pdf, 1);
+    let expected: Vec<String> = (1..=FONTS)
+        .flat_map(|i| ["Font".to_string(), "number".to_string(), i.to_string()])
+        .collect();
+    assert_eq!(words, expected);
+}
+
+/// Dictionary and raw data of indirect object `id`, a stream.
+fn stream_object(pdf: &[u8], id: &str) -> (String, Vec<u8>) {
+    let header = format!("\n{id} 0 obj");
+    let start = pdf
+        .windows(header.len())
+        .position(|w| w == header.as_bytes())
+        .unwrap_or_else(|| panic!("no object {id}"));
+    let object = &pdf[start..];
+    let data_start = object
+        .windows(7)
+        .position(|w| w == b"stream\n")
+        .expect("stream")
+        + 7;
+    let data_len = object[data_start..]
+        .windows(10)
+        .position(|w| w == b"\nendstream")
+        .expect("endstream");
+    let dict = String::from_utf8_lossy(&object[..data_start]).into_owned();
+    (dict, object[data_start..data_start + data_len].to_vec())
+}
+
+/// Object number that `key` refers to in the first dictionary naming it.
+fn referenced(pdf: &[u8], key: &str) -> String {
+    let text = String::from_utf8_lossy(pdf);
+    let at = text.find(&format!("{key} ")).expect(key) + key.len() + 1;
+    text[at..].split(' ').next().unwrap().to_string()
+}
+
+#[test]
+fn font_programs_and_cmaps_are_flate_compressed() {
+    let pdf = convert_docx_bytes_to_pdf_bytes_with_options(
+        &docx_with_embedded_fonts(),
+        &ConversionOptions::default(),
+    )
+    .expect("convert");
+
+    let (dict, data) = stream_object(&pdf, &referenced(&pdf, "/FontFile2"));
+    assert!(dict.contains("/Filter /FlateDecode"), "{dict}");
+    let program = miniz_oxide::inflate::decompress_to_vec_zlib(&data).expect("zlib data");
+    // Length1 gives the size of the TrueType program before compression
+    assert!(
+        dict.contains(&format!("/Length1 {}", program.len())),
+        "{dict}"
+    );
+    assert_eq!(program[..4], [0, 1, 0, 0]);
+
+    let (dict, data) = stream_object(&pdf, &referenced(&pdf, "/ToUnicode"));
+    assert!(dict.contains("/Filter /FlateDecode"), "{dict}");
+    let cmap = miniz_oxide::inflate::decompress_to_vec_zlib(&data).expect("zlib data");
+    assert!(String::from_utf8_lossy(&cmap).contains("begincmap"));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{relationship, text_run};
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// Pages of a long document whose default header is one line and whose
+/// even-page header is twenty, with `w:evenAndOddHeaders` on or off.
+fn pages(even_and_odd: bool) -> usize {
+    let header = |lines: usize| {
+        let paragraphs = format!("<w:p>{}</w:p>", text_run("Header line")).repeat(lines);
+        format!(
+            r#"<w:hdr xmlns:w="{}" xmlns:r="{}">{paragraphs}</w:hdr>"#,
+            common::W_NS,
+            common::R_NS
+        )
+    };
+    let settings = format!(
+        r#"<w:settings xmlns:w="{}">{}</w:settings>"#,
+        common::W_NS,
+        if even_and_odd {
+            "<w:evenAndOddHeaders/>"
+        } else {
+            ""
+        }
+    );
+    let body: String = (1..=150)
+        .map(|i| format!("<w:p>{}</w:p>", text_run(&format!("Body line {i}"))))
+        .collect();
+    let section = common::letter_section(
+        r#"<w:headerReference w:type="default" r:id="rIdH1"/><w:headerReference w:type="even" r:id="rIdH2"/>"#,
+    );
+    let docx = common::docx_package(
+        &common::document_xml(&format!("{body}{section}")),
+        &[
+            relationship("rIdH1", "header", "header1.xml"),
+            relationship("rIdH2", "header", "header2.xml"),
+        ],
+        &[
+            ("word/header1.xml".to_string(), header(1).into_bytes()),
+            ("word/header2.xml".to_string(), header(20).into_bytes()),
+            ("word/settings.xml".to_string(), settings.into_bytes()),
+        ],
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx).expect("convert");
+    common::pdf_pages(&pdf)
+}
+
+#[test]
+fn tall_even_page_header_pushes_body_text_onto_more_pages() {
+    let odd_only = pages(false);
+    let even_and_odd = pages(true);
+    // Each even page loses the room of nineteen more header lines
+    assert!(
+        even_and_odd > odd_only,
+        "{even_and_odd} pages with even headers, {odd_only} without"
+    );
+}
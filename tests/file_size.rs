@@ -1,20 +1,31 @@
 mod common;
 
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use common::Baselines;
+
 const SIZE_RATIO_THRESHOLD: f64 = 10.0;
 
+/// Allowed growth of a fixture's PDF over the smallest size it has had.
+/// Output is deterministic up to dates and ids, so anything past this is real.
+const SIZE_REGRESSION_FACTOR: f64 = 1.05;
+
 struct SizeResult {
     name: String,
     gen_bytes: u64,
     ref_bytes: u64,
     ratio: f64,
     pass: bool,
+    previous_bytes: Option<f64>,
 }
 
-fn analyze_fixture(fixture_dir: &Path) -> Option<SizeResult> {
+fn analyze_fixture(
+    fixture_dir: &Path,
+    baselines: &HashMap<String, Baselines>,
+) -> Option<SizeResult> {
     let name = common::display_name(fixture_dir);
     let input_docx = fixture_dir.join("input.docx");
     let reference_pdf = fixture_dir.join("reference.pdf");
@@ -39,12 +50,14 @@ fn analyze_fixture(fixture_dir: &Path) -> Option<SizeResult> {
     };
     let pass = ratio <= SIZE_RATIO_THRESHOLD;
 
+    let previous_bytes = baselines.get(&name).and_then(|b| b.pdf_bytes);
     Some(SizeResult {
         name,
         gen_bytes,
         ref_bytes,
         ratio,
         pass,
+        previous_bytes,
     })
 }
 
@@ -74,10 +87,11 @@ fn file_size_within_threshold() {
     if fixtures.is_empty() {
         return;
     }
+    let baselines = common::read_baselines();
 
     let mut results: Vec<SizeResult> = fixtures
         .par_iter()
-        .filter_map(|f| analyze_fixture(f))
+        .filter_map(|f| analyze_fixture(f, &baselines))
         .collect();
     results.sort_by(|a, b| a.name.cmp(&b.name));
 
@@ -94,8 +108,19 @@ fn file_size_within_threshold() {
         "Case", "Generated", "Reference"
     );
 
+    let mut regressions: Vec<String> = Vec::new();
     for r in &results {
         let status = if r.pass { "Y" } else { "N" };
+        if let Some(prev) = r.previous_bytes
+            && r.gen_bytes as f64 > prev * SIZE_REGRESSION_FACTOR
+        {
+            regressions.push(format!(
+                "{}: {} → {}",
+                r.name,
+                human_size(prev as u64),
+                human_size(r.gen_bytes)
+            ));
+        }
         let ratio_str = format!("{:>5.1}", r.ratio);
         let colored_ratio = color_ratio(r.ratio, &ratio_str);
         println!(
@@ -118,4 +143,23 @@ fn file_size_within_threshold() {
     }
 
     println!("  threshold: generated <= {SIZE_RATIO_THRESHOLD:.0}x reference");
+
+    let updates: HashMap<String, Baselines> = results
+        .iter()
+        .map(|r| {
+            let baseline = Baselines {
+                pdf_bytes: Some(r.gen_bytes as f64),
+                ..Default::default()
+            };
+            (r.name.clone(), baseline)
+        })
+        .collect();
+    common::update_baselines(&updates);
+
+    assert!(
+        regressions.is_empty(),
+        "PDF size grew more than {:.0}% in: {}",
+        (SIZE_REGRESSION_FACTOR - 1.0) * 100.0,
+        regressions.join(", ")
+    );
 }
@@ -0,0 +1,217 @@
+mod common;
+
+use common::{R_NS, char_positions, relationship, xml_attr};
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// EMUs in an inch; Word writes anchor offsets and distances in EMUs
+const INCH: i64 = 914400;
+/// Target of the `rIdLink` hyperlink relationship every test document has
+const LINK_URL: &str = "https://example.com/banner";
+
+/// The `size` pixel picture, one inch square, anchored `x` EMUs from the
+/// column and at the top of its paragraph. `anchor_attrs` and `wrap` go on
+/// the anchor as is.
+fn anchored_picture(size: u32, x: i64, anchor_attrs: &str, wrap: &str) -> String {
+    format!(
+        r#"<w:r><w:drawing><wp:anchor xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" {anchor_attrs}>
+<wp:positionH relativeFrom="column"><wp:posOffset>{x}</wp:posOffset></wp:positionH>
+<wp:positionV relativeFrom="paragraph"><wp:posOffset>0</wp:posOffset></wp:positionV>
+<wp:extent cx="{INCH}" cy="{INCH}"/>{wrap}<wp:docPr id="1" name="Picture"/>
+<a:graphic xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+<a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+<pic:pic xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+<pic:blipFill><a:blip r:embed="rIdPicture{size}" xmlns:r="{R_NS}"/></pic:blipFill>
+</pic:pic></a:graphicData></a:graphic></wp:anchor></w:drawing></w:r>"#
+    )
+}
+
+/// The `size` pixel picture, one inch square, in line with the text.
+/// `doc_pr` goes inside its `wp:docPr`.
+fn inline_picture(size: u32, doc_pr: &str) -> String {
+    format!(
+        r#"<w:r><w:drawing><wp:inline xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing">
+<wp:extent cx="{INCH}" cy="{INCH}"/><wp:docPr id="2" name="Banner">{doc_pr}</wp:docPr>
+<a:graphic xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+<a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+<pic:pic xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+<pic:blipFill><a:blip r:embed="rIdPicture{size}" xmlns:r="{R_NS}"/></pic:blipFill>
+</pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+    )
+}
+
+/// A docx of `body` with a picture of each of `sizes` for it to anchor.
+fn docx(body: &str, sizes: &[u32]) -> Vec<u8> {
+    let mut rels: Vec<String> = sizes
+        .iter()
+        .map(|n| {
+            relationship(
+                &format!("rIdPicture{n}"),
+                "image",
+                &format!("media/picture{n}.png"),
+            )
+        })
+        .collect();
+    rels.push(relationship("rIdLink", "hyperlink", LINK_URL));
+    let pictures: Vec<(String, Vec<u8>)> = sizes
+        .iter()
+        .map(|&n| (format!("word/media/picture{n}.png"), common::test_png(n, n)))
+        .collect();
+    let document = common::document_xml(&format!("{body}{}", common::letter_section("")));
+    common::docx_package(&document, &rels, &pictures)
+}
+
+#[test]
+fn square_wrapped_text_keeps_the_wrap_distance() {
+    // Picture at the left margin with a 0.1" gap on its right
+    let picture = anchored_picture(
+        8,
+        0,
+        &format!(
+            r#"behindDoc="0" distT="0" distB="0" distL="0" distR="{}""#,
+            INCH / 10
+        ),
+        r#"<wp:wrapSquare wrapText="bothSides"/>"#,
+    );
+    let body = format!("<w:p>{picture}<w:r><w:t>Beside the picture</w:t></w:r></w:p>");
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx(&body, &[8])).expect("convert");
+
+    let chars = char_positions("dist", &pdf, 1);
+    let &(c, x) = chars.first().expect("text on the page");
+    assert_eq!(c, 'B');
+    // Margin, picture and gap: 72 + 72 + 7.2
+    assert!((x - 151.2).abs() < 0.5, "text starts at {x}");
+}
+
+#[test]
+fn square_wrap_starts_at_the_picture_top() {
+    // Picture at the left margin, an inch below the top of its paragraph
+    let picture = anchored_picture(8, 0, r#"behindDoc="0""#, "<wp:wrapSquare/>").replace(
+        r#"relativeFrom="paragraph"><wp:posOffset>0<"#,
+        &format!(r#"relativeFrom="paragraph"><wp:posOffset>{INCH}<"#),
+    );
+    let words = "lines run above beside and below the picture ".repeat(30);
+    let body = format!("<w:p>{picture}<w:r><w:t>{words}</w:t></w:r></w:p>");
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx(&body, &[8])).expect("convert");
+
+    // Left edge of each text line, top to bottom
+    let mut line_starts: Vec<(f32, f32)> = Vec::new();
+    let stext = common::mutool_draw("wrap-top", &pdf, "stext", 1);
+    for element in stext.split("<char ").skip(1) {
+        let x: f32 = xml_attr(element, "x").unwrap().parse().unwrap();
+        let y: f32 = xml_attr(element, "y").unwrap().parse().unwrap();
+        match line_starts
+            .iter_mut()
+            .find(|(line_y, _)| (line_y - y).abs() < 1.0)
+        {
+            Some(line) => line.1 = line.1.min(x),
+            None => line_starts.push((y, x)),
+        }
+    }
+    line_starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+    // The paragraph starts at the top margin; the picture spans 144..216
+    let beside = |y: f32| y > 150.0 && y < 210.0;
+    let above = |y: f32| y < 140.0;
+    assert!(
+        line_starts.iter().any(|&(y, _)| above(y)),
+        "{line_starts:?}"
+    );
+    for &(y, x) in &line_starts {
+        if above(y) || y > 230.0 {
+            assert!((x - 72.0).abs() < 1.0, "{line_starts:?}");
+        } else if beside(y) {
+            assert!(x > 144.0, "{line_starts:?}");
+        }
+    }
+}
+
+#[test]
+fn overlapping_pictures_stack_by_relative_height() {
+    let over_text = |size: u32, height: u32| {
+        anchored_picture(
+            size,
+            0,
+            &format!(r#"behindDoc="0" relativeHeight="{height}""#),
+            "<wp:wrapNone/>",
+        )
+    };
+    let behind_text = anchored_picture(
+        2,
+        0,
+        r#"behindDoc="1" relativeHeight="9""#,
+        "<wp:wrapNone/>",
+    );
+    // The topmost picture comes first in the document
+    let body = format!(
+        "<w:p>{}{}{behind_text}<w:r><w:t>Covered</w:t></w:r></w:p>",
+        over_text(8, 2),
+        over_text(4, 1),
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx(&body, &[2, 4, 8])).expect("convert");
+    assert_eq!(layers("z-order", &pdf), ["2", "text", "4", "8"]);
+}
+
+#[test]
+fn pictures_stack_across_the_paragraphs_of_a_page() {
+    let picture = |size: u32, behind: u8, height: u32| {
+        anchored_picture(
+            size,
+            INCH * i64::from(size) / 2,
+            &format!(r#"behindDoc="{behind}" relativeHeight="{height}""#),
+            "<wp:wrapNone/>",
+        )
+    };
+    // The later paragraph's pictures go under the earlier one's and its text
+    let body = format!(
+        "<w:p>{}<w:r><w:t>First</w:t></w:r></w:p><w:p>{}{}<w:r><w:t>Second</w:t></w:r></w:p>",
+        picture(8, 0, 3),
+        picture(4, 0, 2),
+        picture(2, 1, 1),
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx(&body, &[2, 4, 8])).expect("convert");
+    assert_eq!(layers("page-z-order", &pdf), ["2", "text", "4", "8"]);
+}
+
+/// Drawing order on page 1, bottom layer first: pictures by pixel width,
+/// runs of text as "text".
+fn layers(name: &str, pdf: &[u8]) -> Vec<String> {
+    let trace = common::mutool_draw(name, pdf, "trace", 1);
+    let mut layers: Vec<String> = trace
+        .split('<')
+        .filter_map(|element| {
+            if element.starts_with("fill_image ") {
+                xml_attr(element, "width")
+            } else if element.starts_with("fill_text ") {
+                Some("text".to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    layers.dedup();
+    layers
+}
+
+#[test]
+fn linked_pictures_are_clickable() {
+    let banner = inline_picture(
+        8,
+        &format!(
+            r#"<a:hlinkClick xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" r:id="rIdLink" xmlns:r="{R_NS}"/>"#
+        ),
+    );
+    let to_details = anchored_picture(4, INCH * 3, r#"behindDoc="0""#, "<wp:wrapNone/>");
+    let body = format!(
+        r#"<w:p>{banner}</w:p>
+<w:p><w:hyperlink w:anchor="details">{to_details}</w:hyperlink><w:r><w:t>Intro</w:t></w:r></w:p>
+<w:p><w:r><w:br w:type="page"/></w:r></w:p>
+<w:p><w:bookmarkStart w:id="0" w:name="details"/><w:r><w:t>Details</w:t></w:r><w:bookmarkEnd w:id="0"/></w:p>"#
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx(&body, &[4, 8])).expect("convert");
+
+    let count = |needle: &[u8]| common::count_bytes(&pdf, needle);
+    assert_eq!(count(b"/Subtype /Link"), 2);
+    assert_eq!(count(format!("/URI ({LINK_URL})").as_bytes()), 1);
+    assert_eq!(count(b"/S /GoTo"), 1);
+    // The banner's link covers the inch it is drawn in at the top left of the text
+    assert_eq!(count(b"/Rect [72 648 144 720]"), 1);
+}
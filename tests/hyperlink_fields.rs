@@ -0,0 +1,22 @@
+mod common;
+
+use common::{count_bytes, field};
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+#[test]
+fn hyperlink_fields_link_to_urls_and_bookmarks() {
+    let body = format!(
+        r#"<w:p>{}</w:p><w:p>{}</w:p>
+<w:p><w:r><w:br w:type="page"/></w:r></w:p>
+<w:p><w:bookmarkStart w:id="0" w:name="_Toc1"/><w:r><w:t>Details</w:t></w:r><w:bookmarkEnd w:id="0"/></w:p>"#,
+        field(r#"HYPERLINK "https://example.com/docs""#, "Docs"),
+        field(r#"HYPERLINK \l "_Toc1""#, "See details"),
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(&body)).expect("convert");
+
+    assert_eq!(count_bytes(&pdf, b"/Subtype /Link"), 2);
+    assert_eq!(count_bytes(&pdf, b"/URI (https://example.com/docs)"), 1);
+    // The \l anchor jumps to the bookmark instead of opening a URL
+    assert_eq!(count_bytes(&pdf, b"/S /GoTo"), 1);
+    assert_eq!(count_bytes(&pdf, b"(#_Toc1)"), 0);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::text_run as text;
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// Digits mutool finds on `page`, with their left edges.
+fn numbers(pdf: &[u8], page: usize) -> Vec<(char, f32)> {
+    common::char_positions("line-numbers", pdf, page)
+        .into_iter()
+        .filter(|(c, _)| c.is_ascii_digit())
+        .collect()
+}
+
+#[test]
+fn every_second_line_is_numbered_in_the_margin_per_page() {
+    let mut body: String = ["Alpha", "Bravo", "Charlie", "Delta"]
+        .iter()
+        .map(|word| format!("<w:p>{}</w:p>", text(word)))
+        .collect();
+    body += &format!(
+        r#"<w:p>{}<w:r><w:br w:type="page"/></w:r></w:p>"#,
+        text("Echo")
+    );
+    body += &format!("<w:p>{}</w:p><w:p>{}</w:p>", text("Foxtrot"), text("Golf"));
+    body += &common::letter_section(r#"<w:lnNumType w:countBy="2" w:restart="newPage"/>"#);
+    let docx = common::docx_package(&common::document_xml(&body), &[], &[]);
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx).expect("convert");
+
+    let first = numbers(&pdf, 1);
+    assert_eq!(
+        first.iter().map(|(c, _)| *c).collect::<Vec<_>>(),
+        ['2', '4']
+    );
+    // Numbers sit in the left margin, the default 0.25" from the text
+    assert!(first.iter().all(|&(_, x)| x < 72.0 - 18.0), "{first:?}");
+    let second = numbers(&pdf, 2);
+    assert_eq!(second.iter().map(|(c, _)| *c).collect::<Vec<_>>(), ['2']);
+}
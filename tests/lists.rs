@@ -0,0 +1,47 @@
+mod common;
+
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// Left edge of the item text in a one-item "1." list whose level has
+/// `suffix` (a `w:suff` value, or "" for the default tab).
+fn text_start(suffix: &str) -> (f32, f32) {
+    let suff = if suffix.is_empty() {
+        String::new()
+    } else {
+        format!(r#"<w:suff w:val="{suffix}"/>"#)
+    };
+    let numbering = format!(
+        r#"<w:numbering xmlns:w="{}"><w:abstractNum w:abstractNumId="0"><w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="decimal"/>{suff}<w:lvlText w:val="%1."/><w:pPr><w:ind w:left="720" w:hanging="360"/></w:pPr></w:lvl></w:abstractNum><w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num></w:numbering>"#,
+        common::W_NS
+    );
+    let body = format!(
+        r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>Item</w:t></w:r></w:p>{}"#,
+        common::letter_section("")
+    );
+    let docx = common::docx_package(
+        &common::document_xml(&body),
+        &[],
+        &[("word/numbering.xml".to_string(), numbering.into_bytes())],
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx).expect("convert");
+    let chars = common::char_positions(&format!("suff-{suffix}"), &pdf, 1);
+    let label = chars.iter().find(|(c, _)| *c == '1').expect("label").1;
+    let text = chars.iter().find(|(c, _)| *c == 'I').expect("text").1;
+    (label, text)
+}
+
+#[test]
+fn suffix_decides_where_list_text_starts() {
+    let (label, tab) = text_start("");
+    let (_, space) = text_start("space");
+    let (_, nothing) = text_start("nothing");
+    // The label hangs at 0.25in, its text at the 0.5in indent
+    assert!((label - 90.0).abs() < 0.5, "label at {label}");
+    assert!((tab - 108.0).abs() < 0.5, "tab: {tab}");
+    // "1." is about 8pt wide, and a space adds under 3pt
+    assert!(
+        nothing > label + 5.0 && nothing < label + 10.0,
+        "nothing: {nothing}"
+    );
+    assert!(space > nothing + 2.0 && space < tab, "space: {space}");
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::text_run as text;
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+#[test]
+fn even_pages_swap_the_left_and_right_margins() {
+    let body = format!(
+        r#"<w:p>{}<w:r><w:br w:type="page"/></w:r></w:p><w:p>{}</w:p><w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="2880"/></w:sectPr>"#,
+        text("Odd"),
+        text("Even"),
+    );
+    let settings = format!(
+        r#"<w:settings xmlns:w="{}"><w:mirrorMargins/></w:settings>"#,
+        common::W_NS
+    );
+    let docx = common::docx_package(
+        &common::document_xml(&body),
+        &[],
+        &[("word/settings.xml".to_string(), settings.into_bytes())],
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx).expect("convert");
+
+    let first_x = |page| common::char_positions("mirror", &pdf, page)[0].1;
+    // The 2" inside margin is on the left of odd pages and the right of even ones
+    assert!((first_x(1) - 144.0).abs() < 1.0, "{}", first_x(1));
+    assert!((first_x(2) - 72.0).abs() < 1.0, "{}", first_x(2));
+}
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+
+use docxide_pdf::{
+    ConversionOptions, convert_docx_bytes_to_pdf_bytes,
+    convert_docx_bytes_to_pdf_bytes_with_options, convert_docx_reader_to_pdf_bytes,
+    convert_docx_to_pdf_bytes, convert_docx_to_pdf_bytes_with_options,
+    convert_docx_to_pdf_with_options, convert_docx_to_pdf_with_progress, page_count, read_metadata,
+};
+
+fn fixture_input() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cases/case1/input.docx")
+}
+
+fn convert(name: &str, options: &ConversionOptions) -> Vec<u8> {
+    let output = std::env::temp_dir().join(format!("docxide-options-{name}.pdf"));
+    convert_docx_to_pdf_with_options(fixture_input(), &output, options).expect("convert");
+    let bytes = std::fs::read(&output).expect("read output");
+    std::fs::remove_file(&output).ok();
+    bytes
+}
+
+#[test]
+fn compression_level_zero_stores_streams() {
+    let stored = convert(
+        "store",
+        &ConversionOptions {
+            compression_level: 0,
+            ..ConversionOptions::default()
+        },
+    );
+    let compressed = convert("default", &ConversionOptions::default());
+    assert!(stored.starts_with(b"%PDF"));
+    assert!(
+        stored.len() > compressed.len(),
+        "level 0 ({} bytes) should be larger than level 6 ({} bytes)",
+        stored.len(),
+        compressed.len()
+    );
+}
+
+#[test]
+fn deterministic_conversions_match() {
+    let options = ConversionOptions {
+        deterministic: true,
+        ..ConversionOptions::default()
+    };
+    assert_eq!(convert("det-a", &options), convert("det-b", &options));
+}
+
+#[test]
+fn repeated_conversions_are_byte_identical() {
+    let cases = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cases");
+    let options = ConversionOptions {
+        deterministic: true,
+        ..ConversionOptions::default()
+    };
+    // Text, pictures, charts and SmartArt
+    for case in ["case1", "case12", "case16", "case29", "case30", "case37"] {
+        let input = cases.join(case).join("input.docx");
+        let first = convert_docx_to_pdf_bytes_with_options(&input, &options).expect("convert");
+        let second = convert_docx_to_pdf_bytes_with_options(&input, &options).expect("convert");
+        assert!(first == second, "{case} differs between runs");
+    }
+}
+
+#[test]
+fn creation_date_option_overrides_document_dates() {
+    let options = ConversionOptions {
+        creation_date: Some("2020-01-02T03:04:05Z".to_string()),
+        ..ConversionOptions::default()
+    };
+    let pdf = convert("dated", &options);
+    let dated = b"(D:20200102030405";
+    let count = pdf.windows(dated.len()).filter(|w| *w == dated).count();
+    assert_eq!(count, 2, "creation and modification dates");
+}
+
+#[test]
+fn bytes_output_matches_file_output() {
+    let from_path = convert_docx_to_pdf_bytes(fixture_input()).expect("convert path");
+    assert!(from_path.starts_with(b"%PDF"));
+    let input = std::fs::read(fixture_input()).expect("read input");
+    let from_bytes = convert_docx_bytes_to_pdf_bytes(&input).expect("convert bytes");
+    assert!(from_bytes.starts_with(b"%PDF"));
+    assert_eq!(from_path, convert("bytes", &ConversionOptions::default()));
+}
+
+#[test]
+fn bytes_input_takes_options() {
+    let input = std::fs::read(fixture_input()).expect("read input");
+    let options = ConversionOptions {
+        producer: Some("bytes-with-options".to_string()),
+        ..ConversionOptions::default()
+    };
+    let pdf = convert_docx_bytes_to_pdf_bytes_with_options(&input, &options).expect("convert");
+    assert_eq!(pdf, convert("bytes-options", &options));
+    let producer = b"(bytes-with-options)";
+    assert!(pdf.windows(producer.len()).any(|w| w == producer));
+}
+
+#[test]
+fn reader_input_matches_path_input() {
+    let input = std::fs::read(fixture_input()).expect("read input");
+    let options = ConversionOptions::default();
+    let from_reader =
+        convert_docx_reader_to_pdf_bytes(std::io::Cursor::new(input), &options).expect("convert");
+    let file = std::fs::File::open(fixture_input()).expect("open input");
+    assert_eq!(
+        from_reader,
+        convert_docx_reader_to_pdf_bytes(file, &options).expect("convert file")
+    );
+}
+
+#[test]
+fn page_count_matches_rendered_pages() {
+    let cases = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cases");
+    for case in ["case1", "case2", "case3", "case10"] {
+        let input = cases.join(case).join("input.docx");
+        let pdf = convert_docx_to_pdf_bytes(&input).expect("convert");
+        let pages = pdf
+            .windows(b"/Type /Page".len() + 1)
+            .filter(|w| w.starts_with(b"/Type /Page") && !w[w.len() - 1].is_ascii_alphabetic())
+            .count();
+        assert_eq!(
+            page_count(&input, &ConversionOptions::default()).expect("page count"),
+            pages,
+            "{case}"
+        );
+    }
+}
+
+#[test]
+fn metadata_reads_core_properties() {
+    let meta = read_metadata(fixture_input(), &ConversionOptions::default()).expect("metadata");
+    assert_eq!(meta.author.as_deref(), Some("Sverre Johann Bjørke"));
+    assert_eq!(meta.title, None);
+    assert_eq!(meta.created.as_deref(), Some("2026-02-18T19:42:00Z"));
+    assert_eq!(meta.modified.as_deref(), Some("2026-02-18T19:42:00Z"));
+    assert_eq!(meta.page_count, 1);
+}
+
+#[test]
+fn progress_reports_every_page_in_order() {
+    let input =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cases/case10/input.docx");
+    let output = std::env::temp_dir().join("docxide-options-progress.pdf");
+    let mut calls = Vec::new();
+    convert_docx_to_pdf_with_progress(
+        &input,
+        &output,
+        &ConversionOptions::default(),
+        &mut |done, total| calls.push((done, total)),
+    )
+    .expect("convert");
+    std::fs::remove_file(&output).ok();
+
+    let pages = page_count(&input, &ConversionOptions::default()).expect("page count");
+    assert_eq!(calls.len(), pages);
+    assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(calls.last(), Some(&(pages, pages)));
+}
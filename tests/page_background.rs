@@ -0,0 +1,36 @@
+mod common;
+
+use common::{count_bytes, text_run as text};
+use docxide_pdf::{ConversionOptions, convert_docx_bytes_to_pdf_bytes_with_options};
+
+/// A one-page document with `background` before its body, converted with
+/// stored streams so page content can be searched.
+fn convert(background: &str, render_background: bool) -> Vec<u8> {
+    let document = format!(
+        r#"<w:document xmlns:w="{}"><w:background w:color="FFFF00"{background}<w:body><w:p>{}</w:p>{}</w:body></w:document>"#,
+        common::W_NS,
+        text("On yellow"),
+        common::letter_section("")
+    );
+    let options = ConversionOptions {
+        render_background,
+        compression_level: 0,
+        ..ConversionOptions::default()
+    };
+    let docx = common::docx_package(&document, &[], &[]);
+    convert_docx_bytes_to_pdf_bytes_with_options(&docx, &options).expect("convert")
+}
+
+const FILL: &[u8] = b"1 1 0 rg\n0 0 612 792 re\nf";
+
+#[test]
+fn page_color_fills_the_page() {
+    assert_eq!(count_bytes(&convert("/>", true), FILL), 1);
+    assert_eq!(count_bytes(&convert("/>", false), FILL), 0);
+}
+
+#[test]
+fn fill_effect_background_needs_display_background_shape() {
+    let shape = r#"><v:background xmlns:v="urn:schemas-microsoft-com:vml" id="_x0000_s1025" fillcolor="yellow"/></w:background>"#;
+    assert_eq!(count_bytes(&convert(shape, true), FILL), 0);
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use common::{field, relationship, text_run as text};
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// A docx of `body` paragraphs whose sections use the footers in `footers`,
+/// referenced from section properties as `rIdF1`, `rIdF2`, ...
+fn docx(body: &str, footers: &[String]) -> Vec<u8> {
+    let rels: Vec<String> = (1..=footers.len())
+        .map(|i| relationship(&format!("rIdF{i}"), "footer", &format!("footer{i}.xml")))
+        .collect();
+    let parts: Vec<(String, Vec<u8>)> = footers
+        .iter()
+        .enumerate()
+        .map(|(i, footer)| {
+            let xml = format!(
+                r#"<w:ftr xmlns:w="{}" xmlns:r="{}"><w:p>{footer}</w:p></w:ftr>"#,
+                common::W_NS,
+                common::R_NS
+            );
+            (format!("word/footer{}.xml", i + 1), xml.into_bytes())
+        })
+        .collect();
+    common::docx_package(&common::document_xml(body), &rels, &parts)
+}
+
+fn section_properties(footer: usize) -> String {
+    common::letter_section(&format!(
+        r#"<w:footerReference w:type="default" r:id="rIdF{footer}"/>"#
+    ))
+}
+
+/// Text of each page of a converted document, extracted with mutool.
+fn page_texts(name: &str, docx: &[u8]) -> Vec<String> {
+    let pdf = convert_docx_bytes_to_pdf_bytes(docx).expect("convert");
+    (1..=common::pdf_pages(&pdf))
+        .map(|page| common::page_words(name, &pdf, page).join(" "))
+        .collect()
+}
+
+#[test]
+fn page_x_of_y_uses_the_final_page_total() {
+    let break_run = r#"<w:r><w:br w:type="page"/></w:r>"#;
+    let mut body = format!(
+        "<w:p>{}{}{}</w:p>",
+        text("This document has "),
+        field("NUMPAGES", "1"),
+        text(" pages."),
+    );
+    for i in 1..=12 {
+        let brk = if i < 12 { break_run } else { "" };
+        body += &format!("<w:p>{}{brk}</w:p>", text(&format!("Body {i}")));
+    }
+    body += &section_properties(1);
+    let footer = format!(
+        "{}{}{}{}",
+        text("Page "),
+        field("PAGE", "1"),
+        text(" of "),
+        field("NUMPAGES", "1"),
+    );
+
+    let pages = page_texts("numpages", &docx(&body, &[footer]));
+    assert_eq!(pages.len(), 12);
+    assert!(
+        pages[0].contains("This document has 12 pages."),
+        "{}",
+        pages[0]
+    );
+    for (i, page) in pages.iter().enumerate() {
+        assert!(page.contains(&format!("Page {} of 12", i + 1)), "{page}");
+    }
+}
+
+#[test]
+fn section_fields_count_pages_per_section() {
+    let break_run = r#"<w:r><w:br w:type="page"/></w:r>"#;
+    let body = format!(
+        r#"<w:p>{}{break_run}</w:p><w:p><w:pPr>{}</w:pPr>{}</w:p><w:p>{}{break_run}</w:p><w:p>{}{break_run}</w:p><w:p>{}</w:p>{}"#,
+        text("Intro 1"),
+        section_properties(1),
+        text("Intro 2"),
+        text("Main 1"),
+        text("Main 2"),
+        text("Main 3"),
+        section_properties(2),
+    );
+    let footer = |label: &str| {
+        format!(
+            "{}{}{}{}",
+            text(&format!("{label} ")),
+            field("SECTION", "1"),
+            text(" of length "),
+            field("SECTIONPAGES", "1"),
+        )
+    };
+
+    let pages = page_texts("sections", &docx(&body, &[footer("Intro"), footer("Main")]));
+    assert_eq!(pages.len(), 5);
+    for (page, expected) in pages.iter().zip([
+        "Intro 1 of length 2",
+        "Intro 1 of length 2",
+        "Main 2 of length 3",
+        "Main 2 of length 3",
+        "Main 2 of length 3",
+    ]) {
+        assert!(page.contains(expected), "{page}");
+    }
+}
+
+#[test]
+fn ref_and_pageref_follow_the_bookmark() {
+    let break_run = r#"<w:r><w:br w:type="page"/></w:r>"#;
+    let body = format!(
+        r#"<w:p>{}{}{}{}{break_run}</w:p><w:p>{}{break_run}</w:p><w:p><w:bookmarkStart w:id="0" w:name="Results"/>{}<w:bookmarkEnd w:id="0"/></w:p>"#,
+        text("See "),
+        field(r"REF Results \h", "stale heading"),
+        text(" on page "),
+        field(r"PAGEREF Results \h", "9"),
+        text("Filler"),
+        text("Findings"),
+    );
+
+    let pages = page_texts("pageref", &common::simple_docx(&body));
+    assert_eq!(pages.len(), 3);
+    assert!(pages[0].contains("See Findings on page 3"), "{}", pages[0]);
+}
+
+#[test]
+fn page_numbers_use_the_section_number_format() {
+    let break_run = r#"<w:r><w:br w:type="page"/></w:r>"#;
+    let section = |num_type: &str| {
+        common::letter_section(&format!(
+            r#"<w:footerReference w:type="default" r:id="rIdF1"/><w:pgNumType {num_type}/>"#
+        ))
+    };
+    let body = format!(
+        r#"<w:p>{}{break_run}</w:p><w:p><w:pPr>{}</w:pPr>{}</w:p><w:p>{}{break_run}</w:p><w:p>{}</w:p>{}"#,
+        text("Preface"),
+        section(r#"w:fmt="lowerRoman""#),
+        text("Contents"),
+        text("Chapter"),
+        text("More"),
+        section(r#"w:fmt="decimal" w:start="1""#),
+    );
+    let footer = format!("{}{}", text("Page "), field("PAGE", "1"));
+
+    let pages = page_texts("pgnumtype", &docx(&body, &[footer]));
+    assert_eq!(pages.len(), 4);
+    for (page, expected) in pages.iter().zip(["Page i", "Page ii", "Page 1", "Page 2"]) {
+        assert!(
+            format!("{page} ").contains(&format!("{expected} ")),
+            "{page}"
+        );
+    }
+}
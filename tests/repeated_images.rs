@@ -0,0 +1,98 @@
+mod common;
+
+use common::{R_NS, count_bytes as count, relationship};
+use docxide_pdf::{
+    ConversionOptions, convert_docx_bytes_to_pdf_bytes,
+    convert_docx_bytes_to_pdf_bytes_with_options,
+};
+
+fn logo_drawing() -> String {
+    format!(
+        r#"<w:r><w:drawing><wp:inline xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing">
+<wp:extent cx="914400" cy="457200"/><wp:docPr id="1" name="Logo" descr="Company logo"/>
+<a:graphic xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+<a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+<pic:pic xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+<pic:blipFill><a:blip r:embed="rIdLogo" xmlns:r="{R_NS}"/></pic:blipFill>
+</pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+    )
+}
+
+/// Ten pages sharing a default and a first-page header, each its own part
+/// referencing its own copy of the same logo.
+fn header_logo_docx() -> Vec<u8> {
+    let pages: Vec<String> = (1..=10)
+        .map(|i| format!("<w:p><w:r><w:t>Page {i}</w:t></w:r></w:p>"))
+        .collect();
+    let section = common::letter_section(
+        r#"<w:headerReference w:type="default" r:id="rIdH1"/><w:headerReference w:type="first" r:id="rIdH2"/><w:titlePg/>"#,
+    );
+    let document = common::document_xml(&format!(
+        "{}{section}",
+        pages.join(r#"<w:p><w:r><w:br w:type="page"/></w:r></w:p>"#)
+    ));
+    let header = format!(
+        r#"<w:hdr xmlns:w="{}" xmlns:r="{R_NS}"><w:p>{}</w:p></w:hdr>"#,
+        common::W_NS,
+        logo_drawing()
+    );
+    let header_rels = |media: &str| {
+        common::relationships_xml(&[relationship("rIdLogo", "image", &format!("media/{media}"))])
+    };
+    let logo = common::test_png(16, 8);
+    let parts = [
+        ("word/header1.xml", header.clone().into_bytes()),
+        ("word/header2.xml", header.into_bytes()),
+        (
+            "word/_rels/header1.xml.rels",
+            header_rels("logo1.png").into_bytes(),
+        ),
+        (
+            "word/_rels/header2.xml.rels",
+            header_rels("logo2.png").into_bytes(),
+        ),
+        ("word/media/logo1.png", logo.clone()),
+        ("word/media/logo2.png", logo),
+    ]
+    .map(|(name, content)| (name.to_string(), content));
+    common::docx_package(
+        &document,
+        &[
+            relationship("rIdH1", "header", "header1.xml"),
+            relationship("rIdH2", "header", "header2.xml"),
+        ],
+        &parts,
+    )
+}
+
+#[test]
+fn header_logo_on_every_page_is_one_image_object() {
+    let pdf = convert_docx_bytes_to_pdf_bytes(&header_logo_docx()).expect("convert");
+    assert_eq!(common::pdf_pages(&pdf), 10);
+    assert_eq!(count(&pdf, b"/Subtype /Image"), 1);
+}
+
+#[test]
+fn picture_description_is_the_figure_alt_text() {
+    let document = common::document_xml(&format!(
+        "<w:p>{}</w:p>{}",
+        logo_drawing(),
+        common::letter_section("")
+    ));
+    let docx = common::docx_package(
+        &document,
+        &[relationship("rIdLogo", "image", "media/logo.png")],
+        &[("word/media/logo.png".to_string(), common::test_png(16, 8))],
+    );
+    let options = ConversionOptions {
+        compression_level: 0,
+        ..ConversionOptions::default()
+    };
+    let pdf = convert_docx_bytes_to_pdf_bytes_with_options(&docx, &options).expect("convert");
+    let text = String::from_utf8_lossy(&pdf);
+    let start = text
+        .find("/Figure")
+        .expect("a /Figure marked-content sequence");
+    let figure = &text[start..start + text[start..].find("BDC").expect("BDC")];
+    assert!(figure.contains("/Alt (Company logo)"), "{figure}");
+}
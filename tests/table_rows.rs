@@ -0,0 +1,25 @@
+mod common;
+
+use common::page_words;
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+#[test]
+fn unsplittable_row_taller_than_a_page_starts_on_a_new_page() {
+    let lines: String = (1..=70)
+        .map(|i| format!("<w:p><w:r><w:t>Line{i}</w:t></w:r></w:p>"))
+        .collect();
+    let body = format!(
+        r#"<w:p><w:r><w:t>Before</w:t></w:r></w:p>
+<w:tbl><w:tblGrid><w:gridCol w:w="9360"/></w:tblGrid>
+<w:tr><w:trPr><w:cantSplit/></w:trPr><w:tc><w:tcPr><w:tcW w:w="9360" w:type="dxa"/></w:tcPr>{lines}</w:tc></w:tr>
+</w:tbl><w:p/>"#
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(&body)).expect("convert");
+
+    let first = page_words("cantsplit", &pdf, 1);
+    assert_eq!(first, ["Before"]);
+    let second = page_words("cantsplit", &pdf, 2);
+    assert_eq!(second.first().map(String::as_str), Some("Line1"));
+    // Still taller than the page, so the rest continues on the next one
+    assert!(!second.iter().any(|w| w == "Line70"));
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use docxide_pdf::{
+    ConversionOptions, convert_docx_bytes_to_pdf_bytes,
+    convert_docx_bytes_to_pdf_bytes_with_options,
+};
+
+/// Each character on page 1 with its left edge and baseline.
+fn chars(name: &str, pdf: &[u8]) -> Vec<(char, f32, f32)> {
+    common::mutool_draw(name, pdf, "stext", 1)
+        .split("<char ")
+        .skip(1)
+        .filter_map(|element| {
+            let c = common::xml_attr(element, "c")?.chars().next()?;
+            let x = common::xml_attr(element, "x")?.parse().ok()?;
+            let y = common::xml_attr(element, "y")?.parse().ok()?;
+            Some((c, x, y))
+        })
+        .collect()
+}
+
+/// Position of the first `c` on the page.
+fn find(chars: &[(char, f32, f32)], c: char) -> (f32, f32) {
+    let &(_, x, y) = chars
+        .iter()
+        .find(|(ch, ..)| *ch == c)
+        .unwrap_or_else(|| panic!("no {c:?} in {chars:?}"));
+    (x, y)
+}
+
+/// A cell `twips` wide holding `content`.
+fn cell(twips: u32, content: &str) -> String {
+    format!(r#"<w:tc><w:tcPr><w:tcW w:w="{twips}" w:type="dxa"/></w:tcPr>{content}</w:tc>"#)
+}
+
+fn para(text: &str) -> String {
+    format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>")
+}
+
+#[test]
+fn nested_table_is_laid_out_inside_its_cell() {
+    let inner = format!(
+        r#"<w:tbl><w:tblGrid><w:gridCol w:w="2000"/><w:gridCol w:w="2000"/></w:tblGrid><w:tr>{}{}</w:tr></w:tbl>"#,
+        cell(2000, &para("Kilo")),
+        cell(2000, &para("Mike")),
+    );
+    let body = format!(
+        r#"<w:tbl><w:tblGrid><w:gridCol w:w="4680"/><w:gridCol w:w="4680"/></w:tblGrid><w:tr>{}{}</w:tr></w:tbl><w:p/>"#,
+        cell(4680, &para("Alpha")),
+        cell(4680, &format!("{}{inner}<w:p/>", para("Bravo"))),
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(&body)).expect("convert");
+    let chars = chars("nested-table", &pdf);
+
+    let (bravo_x, bravo_y) = find(&chars, 'B');
+    let (kilo_x, kilo_y) = find(&chars, 'K');
+    let (mike_x, mike_y) = find(&chars, 'M');
+    // The nested table starts in the second column, below the cell's first paragraph
+    assert!(bravo_x > 306.0, "Bravo at {bravo_x}");
+    assert!(
+        kilo_x >= bravo_x - 1.0,
+        "Kilo at {kilo_x}, Bravo at {bravo_x}"
+    );
+    assert!(
+        kilo_y > bravo_y + 5.0,
+        "Kilo at {kilo_y}, Bravo at {bravo_y}"
+    );
+    // Its own columns are 100pt wide
+    assert!((mike_x - kilo_x - 100.0).abs() < 1.0, "{kilo_x} {mike_x}");
+    assert!((mike_y - kilo_y).abs() < 1.0, "{kilo_y} {mike_y}");
+}
+
+#[test]
+fn vertical_cell_text_runs_along_the_cell() {
+    let vertical = |direction: &str, text: &str| {
+        format!(
+            r#"<w:tc><w:tcPr><w:tcW w:w="1440" w:type="dxa"/><w:textDirection w:val="{direction}"/></w:tcPr>{}</w:tc>"#,
+            para(text)
+        )
+    };
+    let body = format!(
+        r#"<w:tbl><w:tblGrid><w:gridCol w:w="1440"/><w:gridCol w:w="1440"/></w:tblGrid><w:tr><w:trPr><w:trHeight w:val="2880" w:hRule="exact"/></w:trPr>{}{}</w:tr></w:tbl><w:p/>"#,
+        vertical("btLr", "Up"),
+        vertical("tbRl", "Dn"),
+    );
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(&body)).expect("convert");
+    let chars = chars("vertical-cell", &pdf);
+
+    // Bottom to top: each character sits above the previous one
+    let (u_x, u_y) = find(&chars, 'U');
+    let (p_x, p_y) = find(&chars, 'p');
+    assert!((u_x - p_x).abs() < 1.0, "U at {u_x}, p at {p_x}");
+    assert!(p_y < u_y - 3.0, "U at {u_y}, p at {p_y}");
+    // Top to bottom
+    let (d_x, d_y) = find(&chars, 'D');
+    let (n_x, n_y) = find(&chars, 'n');
+    assert!((d_x - n_x).abs() < 1.0, "D at {d_x}, n at {n_x}");
+    assert!(n_y > d_y + 3.0, "D at {d_y}, n at {n_y}");
+}
+
+/// Every straight segment that is neither horizontal nor vertical, as
+/// `[x1, y1, x2, y2]`, from a PDF with stored streams.
+fn slanted_segments(pdf: &[u8]) -> Vec<[f32; 4]> {
+    let text = String::from_utf8_lossy(pdf);
+    let lines: Vec<&str> = text.lines().collect();
+    let point = |line: &str, op: &str| -> Option<(f32, f32)> {
+        let rest = line.strip_suffix(op)?;
+        let (x, y) = rest.trim().split_once(' ')?;
+        Some((x.parse().ok()?, y.parse().ok()?))
+    };
+    lines
+        .windows(2)
+        .filter_map(|pair| {
+            let (x1, y1) = point(pair[0], " m")?;
+            let (x2, y2) = point(pair[1], " l")?;
+            (x1 != x2 && y1 != y2).then_some([x1, y1, x2, y2])
+        })
+        .collect()
+}
+
+#[test]
+fn diagonal_borders_cross_the_cell() {
+    let borders = r#"<w:tcBorders><w:tl2br w:val="single" w:sz="8" w:color="FF0000"/><w:tr2bl w:val="single" w:sz="8" w:color="0000FF"/></w:tcBorders>"#;
+    let body = format!(
+        r#"<w:tbl><w:tblGrid><w:gridCol w:w="1440"/></w:tblGrid><w:tr><w:tc><w:tcPr><w:tcW w:w="1440" w:type="dxa"/>{borders}</w:tcPr>{}</w:tc></w:tr></w:tbl><w:p/>"#,
+        para("X")
+    );
+    let options = ConversionOptions {
+        compression_level: 0,
+        ..ConversionOptions::default()
+    };
+    let pdf = convert_docx_bytes_to_pdf_bytes_with_options(&common::simple_docx(&body), &options)
+        .expect("convert");
+
+    let segments = slanted_segments(&pdf);
+    assert_eq!(segments.len(), 2, "{segments:?}");
+    let [x1, y1, x2, y2] = segments[0];
+    assert!((x2 - x1 - 72.0).abs() < 0.1 && y2 < y1, "{segments:?}");
+    let [x1, y1, x2, y2] = segments[1];
+    assert!((x1 - x2 - 72.0).abs() < 0.1 && y2 < y1, "{segments:?}");
+    assert_eq!(common::count_bytes(&pdf, b"1 0 0 RG\n"), 1);
+    assert_eq!(common::count_bytes(&pdf, b"0 0 1 RG\n"), 1);
+}
+
+#[test]
+fn cell_spacing_moves_cells_apart() {
+    let second_cell_x = |spacing: &str| {
+        let body = format!(
+            r#"<w:tbl><w:tblPr>{spacing}</w:tblPr><w:tblGrid><w:gridCol w:w="2880"/><w:gridCol w:w="2880"/></w:tblGrid><w:tr>{}{}</w:tr></w:tbl><w:p/>"#,
+            cell(2880, &para("Alpha")),
+            cell(2880, &para("Bravo")),
+        );
+        let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(&body)).expect("convert");
+        find(&chars("cell-spacing", &pdf), 'B')
+    };
+    let (plain_x, plain_y) = second_cell_x("");
+    let (spaced_x, spaced_y) = second_cell_x(r#"<w:tblCellSpacing w:w="200" w:type="dxa"/>"#);
+    // A 10pt gap on each side of every cell
+    assert!(
+        (spaced_x - plain_x - 10.0).abs() < 0.5,
+        "{plain_x} {spaced_x}"
+    );
+    assert!(
+        (spaced_y - plain_y - 10.0).abs() < 0.5,
+        "{plain_y} {spaced_y}"
+    );
+}
@@ -327,6 +327,7 @@ fn text_boundaries_match() {
                 ssim: None,
                 text_boundary: Some(r.line_match_pct()),
                 convert_ms: None,
+                pdf_bytes: None,
             },
         );
     }
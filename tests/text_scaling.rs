@@ -0,0 +1,47 @@
+mod common;
+
+use docxide_pdf::{ConversionOptions, convert_docx_bytes_to_pdf_bytes_with_options};
+
+/// Horizontal scale operators (`Tz`) of the converted `body`, in stream
+/// order, with `styles` as the document's style part.
+fn scales(styles: &str, body: &str) -> Vec<String> {
+    let styles = format!(
+        r#"<w:styles xmlns:w="{}">{styles}</w:styles>"#,
+        common::W_NS
+    );
+    let document = common::document_xml(&format!("{body}{}", common::letter_section("")));
+    let docx = common::docx_package(
+        &document,
+        &[],
+        &[("word/styles.xml".to_string(), styles.into_bytes())],
+    );
+    let options = ConversionOptions {
+        compression_level: 0,
+        ..ConversionOptions::default()
+    };
+    let pdf = convert_docx_bytes_to_pdf_bytes_with_options(&docx, &options).expect("convert");
+    String::from_utf8_lossy(&pdf)
+        .lines()
+        .filter_map(|line| line.strip_suffix(" Tz"))
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn runs_are_scaled_by_their_own_or_their_styles_width() {
+    let styles = concat!(
+        r#"<w:style w:type="paragraph" w:styleId="Wide"><w:rPr><w:w w:val="200"/></w:rPr></w:style>"#,
+        r#"<w:style w:type="character" w:styleId="Narrow"><w:rPr><w:w w:val="66"/></w:rPr></w:style>"#,
+    );
+    let body = concat!(
+        r#"<w:p><w:pPr><w:pStyle w:val="Wide"/></w:pPr><w:r><w:t>Wide</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:rPr><w:rStyle w:val="Narrow"/></w:rPr><w:t>Narrow</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:rPr><w:rStyle w:val="Narrow"/><w:w w:val="150%"/></w:rPr><w:t>Direct</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:t>Plain</w:t></w:r></w:p>"#,
+    );
+    // Each scaled run sets its scale and puts it back to 100% after itself
+    assert_eq!(
+        scales(styles, body),
+        ["200", "100", "66", "100", "150", "100"]
+    );
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::text_run as text;
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// Baseline of the first character on page 1, from the top of the page,
+/// for a one-line document whose section has `w:vAlign` set to `align`.
+fn first_baseline(align: &str) -> f32 {
+    let body = format!(
+        "<w:p>{}</w:p>{}",
+        text("Title"),
+        common::letter_section(&format!(r#"<w:vAlign w:val="{align}"/>"#))
+    );
+    let docx = common::docx_package(&common::document_xml(&body), &[], &[]);
+    let pdf = convert_docx_bytes_to_pdf_bytes(&docx).expect("convert");
+    let stext = common::mutool_draw(&format!("valign-{align}"), &pdf, "stext", 1);
+    let element = stext.split("<char ").nth(1).expect("a character");
+    common::xml_attr(element, "y")
+        .and_then(|y| y.parse().ok())
+        .expect("char y")
+}
+
+#[test]
+fn content_moves_down_by_the_leftover_space() {
+    // Letter paper with one-inch margins: text runs from 72pt to 720pt
+    let top = first_baseline("top");
+    let center = first_baseline("center");
+    let bottom = first_baseline("bottom");
+    assert!(top < 100.0, "top {top}");
+    assert!(
+        (center - (top + 720.0) / 2.0).abs() < 20.0,
+        "center {center}"
+    );
+    assert!(bottom > 700.0 && bottom <= 720.0, "bottom {bottom}");
+}
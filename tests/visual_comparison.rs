@@ -581,6 +581,7 @@ fn visual_comparison() {
                 ssim: Some(r.ssim),
                 text_boundary: None,
                 convert_ms: None,
+                pdf_bytes: None,
             },
         );
     }
@@ -0,0 +1,42 @@
+mod common;
+
+use docxide_pdf::convert_docx_bytes_to_pdf_bytes;
+
+/// Left edge of the first character of each line on the first page.
+fn line_starts(name: &str, pdf: &[u8]) -> Vec<(char, f32)> {
+    common::mutool_draw(name, pdf, "stext", 1)
+        .split("<line ")
+        .skip(1)
+        .filter_map(|line| {
+            let first = line.split("<char ").nth(1)?;
+            let c = common::xml_attr(first, "c")?.chars().next()?;
+            let x = common::xml_attr(first, "x")?.parse().ok()?;
+            Some((c, x))
+        })
+        .collect()
+}
+
+#[test]
+fn preserved_leading_spaces_indent_the_line() {
+    let body = r#"<w:p><w:r><w:t xml:space="preserve">    indented()</w:t></w:r></w:p>
+<w:p><w:r><w:t>flush()</w:t></w:r></w:p>
+<w:p><w:r><w:t>first</w:t><w:br/><w:t xml:space="preserve">  second</w:t></w:r></w:p>"#;
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(body)).expect("convert");
+
+    let starts = line_starts("preserve", &pdf);
+    let chars: Vec<char> = starts.iter().map(|&(c, _)| c).collect();
+    assert_eq!(chars, ['i', 'f', 'f', 's']);
+    let margin = starts[1].1;
+    assert!(starts[0].1 > margin + 5.0, "{starts:?}");
+    assert!((starts[2].1 - margin).abs() < 0.5, "{starts:?}");
+    assert!(starts[3].1 > margin + 2.0, "{starts:?}");
+}
+
+#[test]
+fn unpreserved_spaces_around_text_are_dropped() {
+    let body = r#"<w:p><w:r><w:t>  Tight  </w:t></w:r><w:r><w:t xml:space="preserve">ly </w:t></w:r><w:r><w:t>packed</w:t></w:r></w:p>"#;
+    let pdf = convert_docx_bytes_to_pdf_bytes(&common::simple_docx(body)).expect("convert");
+
+    let words = common::page_words("collapse", &pdf, 1);
+    assert_eq!(words, ["Tightly", "packed"]);
+}